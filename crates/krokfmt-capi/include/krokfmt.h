@@ -0,0 +1,56 @@
+/*
+ * C interface to krokfmt, for embedding the formatter in editors and tooling.
+ *
+ * All strings are UTF-8 buffers passed as pointer + length, never
+ * NUL-terminated, so that source files containing NUL survive the round trip.
+ */
+
+#ifndef KROKFMT_H
+#define KROKFMT_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* Formatting succeeded; the result holds the formatted source. */
+#define KROK_OK 0
+/* A required pointer was null. */
+#define KROK_INVALID_ARGUMENT 1
+/* The source or filename was not valid UTF-8. */
+#define KROK_INVALID_UTF8 2
+/* The source could not be formatted (usually a syntax error). */
+#define KROK_FORMAT_ERROR 3
+/* krokfmt panicked. This is always a bug in krokfmt, never in the caller. */
+#define KROK_PANIC 4
+
+/*
+ * Outcome of krok_format. On success `data` holds the formatted source,
+ * otherwise an error message. Owned by krokfmt until krok_result_free.
+ */
+typedef struct KrokResult {
+    int32_t code;
+    uint8_t *data;
+    size_t len;
+} KrokResult;
+
+/*
+ * Format TypeScript/TSX source. `filename` only selects the syntax
+ * (.ts vs .tsx); nothing is read from or written to disk.
+ */
+KrokResult krok_format(const uint8_t *source, size_t source_len,
+                       const uint8_t *filename, size_t filename_len);
+
+/* Release a result returned by krok_format. Call exactly once per result. */
+void krok_result_free(KrokResult result);
+
+/* The krokfmt version as a static NUL-terminated string. */
+const char *krok_version(void);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* KROKFMT_H */
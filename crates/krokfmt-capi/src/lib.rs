@@ -0,0 +1,169 @@
+use std::ffi::c_char;
+use std::panic::{self, AssertUnwindSafe};
+use std::ptr;
+use std::slice;
+
+/// Formatting succeeded; the result holds the formatted source.
+pub const KROK_OK: i32 = 0;
+/// A required pointer was null.
+pub const KROK_INVALID_ARGUMENT: i32 = 1;
+/// The source or filename was not valid UTF-8.
+pub const KROK_INVALID_UTF8: i32 = 2;
+/// The source could not be formatted (usually a syntax error).
+pub const KROK_FORMAT_ERROR: i32 = 3;
+/// krokfmt panicked. This is always a bug in krokfmt, never in the caller.
+pub const KROK_PANIC: i32 = 4;
+
+/// Outcome of [`krok_format`].
+///
+/// On success `data` holds the formatted source, otherwise a human-readable
+/// error message. Either way it's UTF-8, `len` bytes long, not NUL-terminated,
+/// and owned by krokfmt until passed to [`krok_result_free`]. Lengths instead of
+/// C strings because source files can legitimately contain NUL characters.
+#[repr(C)]
+pub struct KrokResult {
+    pub code: i32,
+    pub data: *mut u8,
+    pub len: usize,
+}
+
+impl KrokResult {
+    fn new(code: i32, message: String) -> Self {
+        let bytes = message.into_bytes().into_boxed_slice();
+        let len = bytes.len();
+        Self {
+            code,
+            data: Box::into_raw(bytes).cast::<u8>(),
+            len,
+        }
+    }
+}
+
+/// Format TypeScript/TSX source, exactly like the `krokfmt` CLI would.
+///
+/// `filename` only selects the syntax (`.ts` vs `.tsx`); nothing is read from
+/// or written to disk, so editors can format unsaved buffers in-process.
+///
+/// # Safety
+///
+/// `source` must point to `source_len` readable bytes and `filename` to
+/// `filename_len` readable bytes, both valid for the duration of the call. The
+/// returned result must be released with [`krok_result_free`] exactly once.
+#[no_mangle]
+pub unsafe extern "C" fn krok_format(
+    source: *const u8,
+    source_len: usize,
+    filename: *const u8,
+    filename_len: usize,
+) -> KrokResult {
+    if (source.is_null() && source_len > 0) || (filename.is_null() && filename_len > 0) {
+        return KrokResult::new(KROK_INVALID_ARGUMENT, "null pointer passed".to_string());
+    }
+
+    let source = unsafe { bytes_from_raw(source, source_len) };
+    let filename = unsafe { bytes_from_raw(filename, filename_len) };
+
+    let (Ok(source), Ok(filename)) = (std::str::from_utf8(source), std::str::from_utf8(filename))
+    else {
+        return KrokResult::new(KROK_INVALID_UTF8, "input is not valid UTF-8".to_string());
+    };
+
+    // Unwinding across the FFI boundary is undefined behavior, and taking down
+    // the host editor because of a formatter bug is about the worst outcome.
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        krokfmt_core::format_typescript(source, filename)
+    }));
+
+    match result {
+        Ok(Ok(formatted)) => KrokResult::new(KROK_OK, formatted),
+        Ok(Err(err)) => KrokResult::new(KROK_FORMAT_ERROR, format!("{err:#}")),
+        Err(_) => KrokResult::new(KROK_PANIC, "krokfmt panicked while formatting".to_string()),
+    }
+}
+
+/// Release the buffer owned by a [`KrokResult`].
+///
+/// # Safety
+///
+/// `result` must have been returned by [`krok_format`] and not freed before.
+#[no_mangle]
+pub unsafe extern "C" fn krok_result_free(result: KrokResult) {
+    if !result.data.is_null() {
+        drop(unsafe { Box::from_raw(ptr::slice_from_raw_parts_mut(result.data, result.len)) });
+    }
+}
+
+/// The krokfmt version as a static NUL-terminated string.
+///
+/// This crate inherits its version from the workspace, like krokfmt-core, so
+/// it is the version of the formatter doing the work.
+#[no_mangle]
+pub extern "C" fn krok_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr().cast()
+}
+
+/// Borrow a caller-provided buffer, treating null with zero length as empty.
+unsafe fn bytes_from_raw<'a>(data: *const u8, len: usize) -> &'a [u8] {
+    if len == 0 {
+        &[]
+    } else {
+        unsafe { slice::from_raw_parts(data, len) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CStr;
+
+    fn format(source: &str, filename: &str) -> (i32, String) {
+        unsafe {
+            let result = krok_format(
+                source.as_ptr(),
+                source.len(),
+                filename.as_ptr(),
+                filename.len(),
+            );
+            let data = slice::from_raw_parts(result.data, result.len).to_vec();
+            let code = result.code;
+            krok_result_free(result);
+            (code, String::from_utf8(data).unwrap())
+        }
+    }
+
+    #[test]
+    fn test_format_success() {
+        let (code, output) = format("import b from 'b';\nimport a from 'a';\n", "test.ts");
+
+        assert_eq!(code, KROK_OK);
+        assert_eq!(output, "import a from \"a\";\nimport b from \"b\";\n");
+    }
+
+    #[test]
+    fn test_format_error() {
+        let (code, output) = format("import { foo from './bar';", "test.ts");
+
+        assert_eq!(code, KROK_FORMAT_ERROR);
+        assert!(output.contains("parse"), "{output}");
+    }
+
+    #[test]
+    fn test_invalid_arguments() {
+        unsafe {
+            let result = krok_format(ptr::null(), 3, "a.ts".as_ptr(), 4);
+            assert_eq!(result.code, KROK_INVALID_ARGUMENT);
+            krok_result_free(result);
+
+            let bytes = [0xff, 0xfe];
+            let result = krok_format(bytes.as_ptr(), bytes.len(), "a.ts".as_ptr(), 4);
+            assert_eq!(result.code, KROK_INVALID_UTF8);
+            krok_result_free(result);
+        }
+    }
+
+    #[test]
+    fn test_version() {
+        let version = unsafe { CStr::from_ptr(krok_version()) };
+        assert_eq!(version.to_str().unwrap(), env!("CARGO_PKG_VERSION"));
+    }
+}
@@ -0,0 +1,400 @@
+use anyhow::{Context, Result};
+use biome_diagnostics::Diagnostic;
+use biome_formatter::{AttributePosition, IndentStyle, LineWidth, QuoteStyle};
+use biome_js_formatter::context::{ArrowParentheses, JsFormatOptions, Semicolons};
+use biome_js_formatter::format_node;
+use biome_js_parser::{parse, JsParserOptions};
+use biome_js_syntax::JsFileSource;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+use crate::logging::debug_span;
+
+/// Configuration for the Biome formatter
+#[derive(Debug, Clone)]
+pub struct BiomeFormatterConfig {
+    /// Whether to use spaces or tabs for indentation
+    pub indent_style: IndentStyle,
+    /// The width of a single indentation level
+    pub indent_width: u8,
+    /// The line width at which Biome will try to wrap code
+    pub line_width: u16,
+    /// The style for quotes in JavaScript
+    pub quote_style: QuoteStyle,
+    /// The style for quotes in JSX
+    pub jsx_quote_style: QuoteStyle,
+    /// When to use parentheses around arrow functions
+    pub arrow_parentheses: ArrowParentheses,
+    /// Whether to always use semicolons or rely on ASI
+    pub semicolons: Semicolons,
+    /// How to position attributes in JSX/HTML
+    pub attribute_position: AttributePosition,
+}
+
+impl Default for BiomeFormatterConfig {
+    fn default() -> Self {
+        Self {
+            indent_style: IndentStyle::Space,
+            indent_width: 2,
+            line_width: 80,
+            quote_style: QuoteStyle::Double,
+            jsx_quote_style: QuoteStyle::Double,
+            arrow_parentheses: ArrowParentheses::AsNeeded,
+            semicolons: Semicolons::Always,
+            attribute_position: AttributePosition::Multiline,
+        }
+    }
+}
+
+impl BiomeFormatterConfig {
+    /// Indent like `source` already does, if its indentation is recognizable.
+    ///
+    /// Files with no indented lines to go by keep the configured indentation.
+    pub fn with_detected_indentation(mut self, source: &str) -> Self {
+        if let Some((indent_style, indent_width)) = detect_indentation(source) {
+            self.indent_style = indent_style;
+            self.indent_width = indent_width;
+        }
+        self
+    }
+}
+
+/// The dominant indentation of `source`: tabs, or spaces and how many per level.
+///
+/// Space widths are read from the steps between consecutive lines rather than
+/// from absolute indentation, so a file indented by 4 whose deepest lines sit
+/// at 8 isn't mistaken for anything else. Blank lines and the ` * ` lines of
+/// block comments say nothing about the code's indentation and are skipped.
+pub fn detect_indentation(source: &str) -> Option<(IndentStyle, u8)> {
+    let mut tab_lines = 0;
+    let mut space_lines = 0;
+    let mut steps: HashMap<usize, usize> = HashMap::new();
+    let mut previous = 0;
+
+    for line in source.lines() {
+        let content = line.trim_start();
+        if content.is_empty() || content.starts_with('*') {
+            continue;
+        }
+
+        let indent = &line[..line.len() - content.len()];
+        if indent.starts_with('\t') {
+            tab_lines += 1;
+        } else if !indent.is_empty() {
+            space_lines += 1;
+        }
+
+        let width = indent.chars().take_while(|c| *c == ' ').count();
+        if width > previous {
+            *steps.entry(width - previous).or_default() += 1;
+        }
+        previous = width;
+    }
+
+    if tab_lines > space_lines {
+        return Some((IndentStyle::Tab, 2));
+    }
+
+    // Only 2 and 4 are common enough to trust; odd steps are usually
+    // continuation lines aligned to something on the line above
+    let width = [2, 4]
+        .into_iter()
+        .filter(|width| steps.get(width).copied().unwrap_or(0) > 0)
+        .max_by_key(|width| (steps[width], *width))?;
+    Some((IndentStyle::Space, width as u8))
+}
+
+/// Biome formatter wrapper for krokfmt
+pub struct BiomeFormatter {
+    config: BiomeFormatterConfig,
+}
+
+impl BiomeFormatter {
+    /// Create a new BiomeFormatter with default configuration
+    pub fn new() -> Self {
+        Self {
+            config: BiomeFormatterConfig::default(),
+        }
+    }
+
+    /// Create a new BiomeFormatter with custom configuration
+    pub fn with_config(config: BiomeFormatterConfig) -> Self {
+        Self { config }
+    }
+
+    /// Format the given code using Biome
+    ///
+    /// This applies consistent formatting rules to already-organized code.
+    /// The path is used to determine the source type (JS/TS/JSX/TSX).
+    pub fn format(&self, code: &str, path: &Path) -> Result<String> {
+        let source_type = source_type(path)?;
+        Ok(self.format_source(code, source_type)?)
+    }
+
+    /// Format the given code using Biome, falling back to `code` as is when
+    /// Biome can't handle it.
+    ///
+    /// Biome's parser lags swc's on some valid but exotic syntax. By the time
+    /// Biome runs, the code is already organized, which is most of what
+    /// krokfmt is for, so failing the whole file over a layout gap would throw
+    /// that away. The failure is returned alongside for the caller to report.
+    pub fn format_or_fallback(&self, code: &str, path: &Path) -> Result<Formatted> {
+        let source_type = source_type(path)?;
+        Ok(match self.format_source(code, source_type) {
+            Ok(code) => Formatted {
+                code,
+                fallback: None,
+            },
+            Err(failure) => Formatted {
+                code: code.to_string(),
+                fallback: Some(failure),
+            },
+        })
+    }
+
+    fn format_source(
+        &self,
+        code: &str,
+        source_type: JsFileSource,
+    ) -> std::result::Result<String, BiomeFailure> {
+        let _span = debug_span!("biome").entered();
+
+        // Parse the code
+        let parsed = parse(
+            code,
+            source_type,
+            JsParserOptions::default().with_parse_class_parameter_decorators(),
+        );
+
+        // Check for parse errors
+        if parsed.has_errors() {
+            let diagnostics = parsed.diagnostics();
+            let message = format!(
+                "Biome parser errors: {}",
+                diagnostics
+                    .iter()
+                    .map(|e| format!("{e:?}"))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            );
+            let offset = diagnostics
+                .first()
+                .and_then(|diagnostic| diagnostic.location().span)
+                .map(|span| usize::from(span.start()));
+            return Err(BiomeFailure::new(message, code, offset));
+        }
+
+        // Create format options from our configuration
+        let format_options = self.create_format_options(source_type);
+
+        // Format the syntax tree
+        let formatted = format_node(format_options, &parsed.syntax()).map_err(|e| {
+            BiomeFailure::new(format!("Failed to format with Biome: {e}"), code, None)
+        })?;
+
+        // Convert to final code
+        let result = formatted.print().map_err(|e| {
+            BiomeFailure::new(format!("Failed to print formatted code: {e}"), code, None)
+        })?;
+
+        Ok(result.into_code())
+    }
+
+    /// Create Biome format options from our configuration
+    fn create_format_options(&self, source_type: JsFileSource) -> JsFormatOptions {
+        JsFormatOptions::new(source_type)
+            .with_indent_style(self.config.indent_style)
+            .with_indent_width(self.config.indent_width.into())
+            .with_line_width(
+                LineWidth::try_from(self.config.line_width)
+                    .unwrap_or_else(|_| LineWidth::try_from(80).unwrap()),
+            )
+            .with_quote_style(self.config.quote_style)
+            .with_jsx_quote_style(self.config.jsx_quote_style)
+            .with_arrow_parentheses(self.config.arrow_parentheses)
+            .with_semicolons(self.config.semicolons)
+            .with_attribute_position(self.config.attribute_position)
+    }
+}
+
+/// Determine the source type (JS/TS/JSX/TSX) from the file extension
+fn source_type(path: &Path) -> Result<JsFileSource> {
+    JsFileSource::try_from(path)
+        .with_context(|| format!("Failed to determine source type for {path:?}"))
+}
+
+/// What [`BiomeFormatter::format_or_fallback`] produced.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Formatted {
+    pub code: String,
+    /// Why Biome failed, when `code` is the input returned unformatted
+    pub fallback: Option<BiomeFailure>,
+}
+
+/// Biome couldn't format code that krokfmt had already organized.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BiomeFailure {
+    pub message: String,
+    /// 1-based line of the organized code Biome stopped at, when it says
+    pub line: Option<usize>,
+    /// That line's code, to tell users which construct to look at
+    pub construct: Option<String>,
+}
+
+impl BiomeFailure {
+    fn new(message: String, code: &str, offset: Option<usize>) -> Self {
+        let line = offset
+            .and_then(|offset| code.get(..offset))
+            .map(|before| before.matches('\n').count() + 1);
+        let construct = line
+            .and_then(|line| code.lines().nth(line - 1))
+            .map(|text| text.trim().to_string());
+        Self {
+            message,
+            line,
+            construct,
+        }
+    }
+}
+
+impl fmt::Display for BiomeFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)?;
+        if let (Some(line), Some(construct)) = (self.line, &self.construct) {
+            write!(f, " (line {line}: `{construct}`)")?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for BiomeFailure {}
+
+impl Default for BiomeFormatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_basic_formatting() {
+        let formatter = BiomeFormatter::new();
+        let code = r#"const x={a:1,b:2,c:3};function   foo(  )  {return    x.a+x.b}"#;
+        let path = PathBuf::from("test.js");
+
+        let result = formatter.format(code, &path).unwrap();
+
+        // Biome should format this nicely
+        assert!(result.contains("const x = { a: 1, b: 2, c: 3 }"));
+        assert!(result.contains("function foo()"));
+        assert!(result.contains("return x.a + x.b"));
+    }
+
+    #[test]
+    fn test_typescript_formatting() {
+        let formatter = BiomeFormatter::new();
+        let code = r#"interface User{name:string;age:number}const greet=(user:User):string=>{return `Hello ${user.name}`}"#;
+        let path = PathBuf::from("test.ts");
+
+        let result = formatter.format(code, &path).unwrap();
+
+        // Should format TypeScript properly
+        assert!(result.contains("interface User"));
+        assert!(result.contains("name: string"));
+        assert!(result.contains("age: number"));
+        assert!(result.contains("const greet = (user: User): string =>"));
+    }
+
+    #[test]
+    fn test_jsx_formatting() {
+        let formatter = BiomeFormatter::new();
+        let code = r#"const App=()=>{return<div className="app"><h1>Hello</h1><button onClick={()=>alert('clicked')}>Click</button></div>}"#;
+        let path = PathBuf::from("test.jsx");
+
+        let result = formatter.format(code, &path).unwrap();
+
+        // Should format JSX properly
+        assert!(result.contains("const App = () =>"));
+        assert!(result.contains("<div className=\"app\">"));
+        assert!(result.contains("<h1>Hello</h1>"));
+        assert!(result.contains("onClick={() => alert(\"clicked\")}"));
+    }
+
+    #[test]
+    fn test_detect_indentation() {
+        let four = "class A {\n    run() {\n        go();\n    }\n}\n";
+        assert_eq!(detect_indentation(four), Some((IndentStyle::Space, 4)));
+
+        let two = "/**\n * Docs\n */\nfunction f() {\n  if (x) {\n    y();\n  }\n}\n";
+        assert_eq!(detect_indentation(two), Some((IndentStyle::Space, 2)));
+
+        let tabs = "function f() {\n\treturn 1;\n}\n";
+        assert_eq!(
+            detect_indentation(tabs).map(|(style, _)| style),
+            Some(IndentStyle::Tab)
+        );
+
+        assert_eq!(detect_indentation("const a = 1;\n"), None);
+    }
+
+    #[test]
+    fn test_format_with_detected_indentation() {
+        let code = "function greet() {\n    return 'hi';\n}\n";
+        let config = BiomeFormatterConfig::default().with_detected_indentation(code);
+
+        let result = BiomeFormatter::with_config(config)
+            .format(code, &PathBuf::from("test.ts"))
+            .unwrap();
+
+        assert_eq!(result, "function greet() {\n    return \"hi\";\n}\n");
+    }
+
+    #[test]
+    fn test_fallback_keeps_code_biome_cannot_parse() {
+        let formatter = BiomeFormatter::new();
+        let code = "const a = 1;\nconst = ;\n";
+
+        let formatted = formatter
+            .format_or_fallback(code, Path::new("test.ts"))
+            .unwrap();
+        assert_eq!(formatted.code, code);
+        let failure = formatted.fallback.unwrap();
+        assert_eq!(failure.line, Some(2));
+        assert_eq!(failure.construct.as_deref(), Some("const = ;"));
+
+        let formatted = formatter
+            .format_or_fallback("const  b = 2", Path::new("test.ts"))
+            .unwrap();
+        assert_eq!(formatted.code.trim_end(), "const b = 2;");
+        assert!(formatted.fallback.is_none());
+    }
+
+    #[test]
+    fn test_custom_config() {
+        let config = BiomeFormatterConfig {
+            indent_style: IndentStyle::Tab,
+            indent_width: 4,
+            line_width: 120,
+            quote_style: QuoteStyle::Single,
+            jsx_quote_style: QuoteStyle::Single,
+            arrow_parentheses: ArrowParentheses::Always,
+            semicolons: Semicolons::AsNeeded,
+            attribute_position: AttributePosition::Auto,
+        };
+
+        let formatter = BiomeFormatter::with_config(config);
+        let code = r#"const message = "Hello"; const fn = x => x * 2"#;
+        let path = PathBuf::from("test.js");
+
+        let result = formatter.format(code, &path).unwrap();
+
+        // Should use single quotes and no semicolons (based on config)
+        assert!(result.contains("const message = 'Hello'"));
+        assert!(result.contains("const fn = (x) => x * 2"));
+    }
+}
@@ -0,0 +1,164 @@
+use swc_common::{Span, Spanned};
+use swc_ecma_ast::*;
+use swc_ecma_visit::{Visit, VisitWith};
+
+use crate::parser::TypeScriptParser;
+
+/// Maximum consecutive blank lines allowed between top-level module items.
+///
+/// Two lines gives authors room to mark a major section break at module level,
+/// which is where that kind of visual chunking actually reads as intentional.
+const MAX_TOP_LEVEL_BLANK_LINES: usize = 2;
+
+/// Maximum consecutive blank lines allowed inside any declaration body.
+const MAX_NESTED_BLANK_LINES: usize = 1;
+
+/// Caps runs of consecutive blank lines in generated code.
+///
+/// Moving statements around and reinserting comments regularly leaves behind 2-3
+/// blank lines where something used to be. A regex over the output would squash
+/// those too, but it would also rewrite blank lines inside template literals, which
+/// are part of the string value, and inside block comments. So we re-parse the
+/// code and use real spans to decide what is protected and how deep each blank
+/// line sits.
+pub struct BlankLineNormalizer;
+
+impl BlankLineNormalizer {
+    /// Normalize blank lines in `code`, which is parsed using `filename` to pick
+    /// the syntax (TS vs TSX).
+    ///
+    /// If the code doesn't parse, it is returned untouched. This pass is purely
+    /// cosmetic and must never be the reason formatting fails.
+    pub fn normalize(code: &str, filename: &str) -> String {
+        let parser = TypeScriptParser::new();
+        let module = match parser.parse(code, filename) {
+            Ok(module) => module,
+            Err(_) => return code.to_string(),
+        };
+
+        let to_range = |span: Span| {
+            let lo = parser.source_map.lookup_byte_offset(span.lo).pos.0 as usize;
+            let hi = parser.source_map.lookup_byte_offset(span.hi).pos.0 as usize;
+            (lo, hi)
+        };
+
+        let item_ranges: Vec<_> = module
+            .body
+            .iter()
+            .map(|item| to_range(item.span()))
+            .collect();
+
+        let mut collector = ProtectedSpanCollector::default();
+        module.visit_with(&mut collector);
+        // Blank lines inside a block comment are part of its text, just like a
+        // template literal's
+        let (leading, trailing) = parser.comments.borrow_all();
+        let comment_spans = leading
+            .values()
+            .chain(trailing.values())
+            .flatten()
+            .map(|comment| comment.span);
+        let protected_ranges: Vec<_> = collector
+            .spans
+            .into_iter()
+            .chain(comment_spans)
+            .map(to_range)
+            .collect();
+
+        let mut result = String::with_capacity(code.len());
+        let mut offset = 0;
+        let mut blank_run = 0;
+
+        for line in code.split_inclusive('\n') {
+            let line_start = offset;
+            offset += line.len();
+
+            if !line.trim().is_empty() {
+                blank_run = 0;
+                result.push_str(line);
+                continue;
+            }
+
+            blank_run += 1;
+
+            let is_protected = protected_ranges
+                .iter()
+                .any(|&(lo, hi)| line_start > lo && line_start < hi);
+            let is_nested = item_ranges
+                .iter()
+                .any(|&(lo, hi)| line_start > lo && line_start < hi);
+
+            let limit = if is_nested {
+                MAX_NESTED_BLANK_LINES
+            } else {
+                MAX_TOP_LEVEL_BLANK_LINES
+            };
+
+            if is_protected || blank_run <= limit {
+                result.push_str(line);
+            }
+        }
+
+        result
+    }
+}
+
+/// Collects spans whose text is significant and must not be rewritten.
+#[derive(Default)]
+struct ProtectedSpanCollector {
+    spans: Vec<Span>,
+}
+
+impl Visit for ProtectedSpanCollector {
+    fn visit_tpl(&mut self, tpl: &Tpl) {
+        self.spans.push(tpl.span);
+    }
+
+    fn visit_str(&mut self, s: &Str) {
+        // Line continuations let plain strings span lines too
+        self.spans.push(s.span);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_squashes_blank_lines_in_function_body() {
+        let code = "function foo() {\n    const a = 1;\n\n\n\n    return a;\n}\n";
+        let result = BlankLineNormalizer::normalize(code, "test.ts");
+        assert_eq!(
+            result,
+            "function foo() {\n    const a = 1;\n\n    return a;\n}\n"
+        );
+    }
+
+    #[test]
+    fn test_allows_two_blank_lines_at_top_level() {
+        let code = "const a = 1;\n\n\n\n\nconst b = 2;\n";
+        let result = BlankLineNormalizer::normalize(code, "test.ts");
+        assert_eq!(result, "const a = 1;\n\n\nconst b = 2;\n");
+    }
+
+    #[test]
+    fn test_preserves_blank_lines_in_template_literals() {
+        let code = "function foo() {\n    return `a\n\n\n\nb`;\n}\n";
+        let result = BlankLineNormalizer::normalize(code, "test.ts");
+        assert_eq!(result, code);
+    }
+
+    #[test]
+    fn test_preserves_blank_lines_in_block_comments() {
+        let code = "function foo() {\n    /*\n     * Step one\n\n\n\n     * Step two\n     */\n    return 1;\n}\n";
+        let result = BlankLineNormalizer::normalize(code, "test.ts");
+        assert_eq!(result, code);
+    }
+
+    #[test]
+    fn test_unparseable_code_is_untouched() {
+        let code = "const = ;\n\n\n\n";
+        let result = BlankLineNormalizer::normalize(code, "test.ts");
+        assert_eq!(result, code);
+    }
+}
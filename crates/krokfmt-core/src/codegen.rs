@@ -0,0 +1,595 @@
+use anyhow::Result;
+use std::collections::{HashMap, HashSet};
+use swc_common::{
+    comments::SingleThreadedComments, errors::SourceMapper, sync::Lrc, BytePos, FileName,
+    SourceMap, Span, Spanned,
+};
+use swc_ecma_ast::*;
+use swc_ecma_codegen::{text_writer::JsWriter, Config, Emitter};
+use swc_ecma_parser::{lexer::Lexer, Parser, StringInput, Syntax};
+use swc_ecma_visit::{VisitMut, VisitMutWith};
+
+use crate::organizer::directive_prologue_len;
+use crate::transformer::{ImportAnalyzer, ImportProfile, ProjectLayout};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DeclarationType {
+    Function,
+    Class,
+    Interface,
+    Type,
+    Const,
+    Enum,
+    Namespace,
+    /// `declare global` and `declare module 'foo'` blocks
+    Ambient,
+    Export,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ClassMemberGroup {
+    PublicStaticFields,
+    PrivateStaticFields,
+    PublicStaticMethods,
+    PrivateStaticMethods,
+    PublicInstanceFields,
+    PrivateInstanceFields,
+    Constructor,
+    PublicInstanceMethods,
+    PrivateInstanceMethods,
+}
+
+/// What decides the blank lines around a top-level item.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum ItemKind {
+    /// A directive like `'use client'` opening the module
+    Directive,
+    /// An import, with its group unless it imports an entity (`import A = B.C`)
+    Import(Option<u8>),
+    /// A re-export, with the group of the module it re-exports from
+    ReExport(u8),
+    /// A declaration the module-level separation rules (FR7.1) apply to
+    Declaration {
+        exported: bool,
+        declaration: DeclarationType,
+    },
+    /// Anything else, which neither gets nor changes a separation
+    Other,
+}
+
+/// The blank-line decisions made so far, from the top-level items before the
+/// current one.
+#[derive(Debug, Default)]
+struct Separation {
+    last_import_group: Option<u8>,
+    last_re_export_group: Option<u8>,
+    last_was_import: bool,
+    last_was_re_export: bool,
+    last_was_directive: bool,
+    /// Whether the imports and re-exports were already set off from the rest
+    left_header: bool,
+    last_declaration: Option<(bool, DeclarationType)>,
+}
+
+impl Separation {
+    /// Whether an item of `kind` is set off from the item before it by an
+    /// empty line.
+    fn blank_line_before(&mut self, kind: ItemKind) -> bool {
+        // After the directive prologue
+        let after_directives = self.last_was_directive && kind != ItemKind::Directive;
+        let blank_line = match kind {
+            ItemKind::Directive => false,
+            // Between import groups
+            ItemKind::Import(group) => {
+                let blank_line = matches!(
+                    (self.last_import_group, group),
+                    (Some(last), Some(group)) if last != group
+                );
+                if group.is_some() {
+                    self.last_import_group = group;
+                }
+                blank_line
+            }
+            // After the imports, and between re-export groups
+            ItemKind::ReExport(group) => {
+                let blank_line = self.last_was_import
+                    || self.last_re_export_group.is_some_and(|last| last != group);
+                self.last_re_export_group = Some(group);
+                blank_line
+            }
+            // After the imports and re-exports, and between declarations of
+            // another visibility or type
+            ItemKind::Declaration { .. } | ItemKind::Other => {
+                let mut blank_line =
+                    (self.last_was_import || self.last_was_re_export) && !self.left_header;
+                self.left_header |= blank_line;
+
+                if let ItemKind::Declaration {
+                    exported,
+                    declaration,
+                } = kind
+                {
+                    if let Some(last) = self.last_declaration {
+                        blank_line |= last != (exported, declaration);
+                    }
+                    self.last_declaration = Some((exported, declaration));
+                }
+                blank_line
+            }
+        };
+
+        self.last_was_import = matches!(kind, ItemKind::Import(_));
+        self.last_was_re_export = matches!(kind, ItemKind::ReExport(_));
+        self.last_was_directive = kind == ItemKind::Directive;
+        blank_line || after_directives
+    }
+}
+
+/// The code emitted for part of a module.
+struct Emitted {
+    code: String,
+    /// The line of `code` each source position was first emitted on
+    lines: HashMap<BytePos, usize>,
+}
+
+impl Emitted {
+    /// The first line of `code` any of `positions` was emitted on.
+    fn first_line(&self, positions: impl IntoIterator<Item = BytePos>) -> Option<usize> {
+        positions
+            .into_iter()
+            .filter_map(|pos| self.lines.get(&pos).copied())
+            .min()
+    }
+}
+
+/// Gives literals back the spelling they had in the source.
+///
+/// The emitter prints a literal's `raw` text when it has one and otherwise
+/// spells out its value, which turns `0xFF` into `255` and `'it\'s'` into
+/// `"it's"`. A literal rebuilt without its `raw` text gets the source spelling
+/// back, as long as the source still spells the same value.
+struct RawLiterals<'a> {
+    source_map: &'a SourceMap,
+}
+
+impl RawLiterals<'_> {
+    /// The literal written at `span` in the source, if there is one.
+    fn source_literal(&self, span: Span) -> Option<Lit> {
+        if span.is_dummy() {
+            return None;
+        }
+        let snippet = self.source_map.span_to_snippet(span).ok()?;
+
+        // A throwaway source map, so the snippet doesn't join the file's
+        let source_map = SourceMap::default();
+        let fm = source_map.new_source_file(Lrc::new(FileName::Anon), snippet);
+        let lexer = Lexer::new(
+            Syntax::Typescript(Default::default()),
+            EsVersion::latest(),
+            StringInput::from(&*fm),
+            None,
+        );
+
+        match *Parser::new_from(lexer).parse_expr().ok()? {
+            Expr::Lit(lit) => Some(lit),
+            _ => None,
+        }
+    }
+}
+
+impl VisitMut for RawLiterals<'_> {
+    fn visit_mut_str(&mut self, string: &mut Str) {
+        if string.raw.is_none() {
+            if let Some(Lit::Str(source)) = self.source_literal(string.span) {
+                if source.value == string.value {
+                    string.raw = source.raw;
+                }
+            }
+        }
+    }
+
+    fn visit_mut_number(&mut self, number: &mut Number) {
+        if number.raw.is_none() {
+            if let Some(Lit::Num(source)) = self.source_literal(number.span) {
+                if source.value == number.value {
+                    number.raw = source.raw;
+                }
+            }
+        }
+    }
+
+    fn visit_mut_big_int(&mut self, big_int: &mut BigInt) {
+        if big_int.raw.is_none() {
+            if let Some(Lit::BigInt(source)) = self.source_literal(big_int.span) {
+                if source.value == big_int.value {
+                    big_int.raw = source.raw;
+                }
+            }
+        }
+    }
+}
+
+/// Generates formatted TypeScript/JavaScript code from the AST.
+///
+/// This is a wrapper around SWC's code generator. SWC's AST doesn't model
+/// empty lines, so each top-level item is emitted on its own and the empty
+/// lines between them are decided from the items themselves:
+/// 1. Between import and re-export groups, and after the imports (FR1.5)
+/// 2. Between declarations of another visibility or type (FR7.1)
+/// 3. Between the member groups of top-level classes (FR7.3)
+pub struct CodeGenerator {
+    source_map: Lrc<SourceMap>,
+    comments: Option<SingleThreadedComments>,
+    import_profile: ImportProfile,
+    project_layout: ProjectLayout,
+    import_group_banners: bool,
+    file_header: Vec<String>,
+}
+
+impl CodeGenerator {
+    pub fn new(source_map: Lrc<SourceMap>) -> Self {
+        Self {
+            source_map,
+            comments: None,
+            import_profile: ImportProfile::default(),
+            project_layout: ProjectLayout::default(),
+            import_group_banners: false,
+            file_header: Vec::new(),
+        }
+    }
+
+    pub fn with_comments(source_map: Lrc<SourceMap>, comments: SingleThreadedComments) -> Self {
+        Self {
+            source_map,
+            comments: Some(comments),
+            import_profile: ImportProfile::default(),
+            project_layout: ProjectLayout::default(),
+            import_group_banners: false,
+            file_header: Vec::new(),
+        }
+    }
+
+    /// Separate import groups according to the given profile instead of the
+    /// default External/Absolute/Relative categories.
+    pub fn with_import_profile(mut self, import_profile: ImportProfile) -> Self {
+        self.import_profile = import_profile;
+        self
+    }
+
+    /// Group imports by the project's workspace packages and path aliases,
+    /// which their specifiers alone don't tell.
+    pub fn with_project_layout(mut self, project_layout: ProjectLayout) -> Self {
+        self.project_layout = project_layout;
+        self
+    }
+
+    /// Label each import group with a canonical banner comment like `// External`.
+    pub fn with_import_group_banners(mut self, enabled: bool) -> Self {
+        self.import_group_banners = enabled;
+        self
+    }
+
+    /// Print `lines`, the file's pragma comments, right below the shebang.
+    pub fn with_file_header(mut self, lines: Vec<String>) -> Self {
+        self.file_header = lines;
+        self
+    }
+
+    pub fn generate(&self, module: &Module) -> Result<String> {
+        let mut lines: Vec<String> = Vec::new();
+        let mut separation = Separation::default();
+        let mut in_imports = true;
+        let mut last_banner_group = None;
+
+        // The shebang and the comments heading the file stay on top
+        let start = Span::new(module.span.lo, module.span.lo);
+        let header = self.emit(Module {
+            span: start,
+            body: Vec::new(),
+            shebang: module.shebang.clone(),
+        })?;
+        lines.extend(header.code.lines().map(str::to_string));
+        lines.extend(self.file_header.iter().cloned());
+
+        let prologue_len = directive_prologue_len(&module.body);
+        for (i, item) in module.body.iter().enumerate() {
+            let kind = if i < prologue_len {
+                ItemKind::Directive
+            } else {
+                self.item_kind(item)
+            };
+            if separation.blank_line_before(kind) && !lines.is_empty() {
+                lines.push(String::new());
+            }
+
+            // Banners the author wrote were dropped before codegen, which
+            // keeps regenerating them idempotent
+            if self.import_group_banners && in_imports {
+                match kind {
+                    ItemKind::Import(Some(group)) if last_banner_group != Some(group) => {
+                        lines.push(format!("// {}", self.import_profile.group_name(group)));
+                        last_banner_group = Some(group);
+                    }
+                    ItemKind::Import(_) | ItemKind::Directive => {}
+                    _ => in_imports = false,
+                }
+            }
+
+            let emitted = self.emit(Module {
+                span: item.span(),
+                body: vec![item.clone()],
+                shebang: None,
+            })?;
+            lines.extend(class_member_lines(item, &emitted).map(str::to_string));
+        }
+
+        // As are the comments closing it
+        let end = Span::new(module.span.hi, module.span.hi);
+        let footer = self.emit(Module {
+            span: end,
+            body: Vec::new(),
+            shebang: None,
+        })?;
+        lines.extend(footer.code.lines().map(str::to_string));
+
+        Ok(lines.join("\n"))
+    }
+
+    /// Emit `module`, recording the line each source position lands on.
+    fn emit(&self, mut module: Module) -> Result<Emitted> {
+        module.visit_mut_with(&mut RawLiterals {
+            source_map: &self.source_map,
+        });
+
+        let mut buf = Vec::new();
+        let mut srcmap = Vec::new();
+
+        {
+            let writer = JsWriter::new(self.source_map.clone(), "\n", &mut buf, Some(&mut srcmap));
+
+            let mut config = Config::default();
+            config.minify = false;
+            // The default ES5 target downlevels literals: `1_000_000` loses its
+            // separators and `0b1010` becomes `10`. Targeting the latest version
+            // lets the emitter print every literal's raw text as it is.
+            config.target = EsVersion::latest();
+
+            let mut emitter = Emitter {
+                cfg: config,
+                cm: self.source_map.clone(),
+                comments: self
+                    .comments
+                    .as_ref()
+                    .map(|c| c as &dyn swc_common::comments::Comments),
+                wr: Box::new(writer),
+            };
+
+            emitter.emit_module(&module)?;
+        }
+
+        let mut lines = HashMap::new();
+        for (pos, line_col) in srcmap {
+            lines.entry(pos).or_insert(line_col.line as usize);
+        }
+
+        Ok(Emitted {
+            code: String::from_utf8(buf)?,
+            lines,
+        })
+    }
+
+    /// How the top-level `item` is separated from its neighbors.
+    fn item_kind(&self, item: &ModuleItem) -> ItemKind {
+        let group = |path: &str, is_side_effect: bool| {
+            self.import_profile
+                .group(path, is_side_effect, &self.project_layout)
+        };
+        let declaration = |decl: &Decl, exported: bool| match declaration_type(decl) {
+            Some(declaration) => ItemKind::Declaration {
+                exported,
+                declaration,
+            },
+            None => ItemKind::Other,
+        };
+
+        match item {
+            ModuleItem::ModuleDecl(ModuleDecl::Import(import)) => {
+                ItemKind::Import(Some(group(&import.src.value, import.specifiers.is_empty())))
+            }
+            ModuleItem::ModuleDecl(ModuleDecl::TsImportEquals(import)) if !import.is_export => {
+                ItemKind::Import(
+                    ImportAnalyzer::require_path(import).map(|path| group(path, false)),
+                )
+            }
+            ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(NamedExport {
+                src: Some(src), ..
+            })) => ItemKind::ReExport(group(&src.value, false)),
+            ModuleItem::ModuleDecl(ModuleDecl::ExportAll(export)) => {
+                ItemKind::ReExport(group(&export.src.value, false))
+            }
+            ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(_)) => ItemKind::Declaration {
+                exported: true,
+                declaration: DeclarationType::Export,
+            },
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) => {
+                declaration(&export.decl, true)
+            }
+            ModuleItem::Stmt(Stmt::Decl(decl)) => declaration(decl, false),
+            _ => ItemKind::Other,
+        }
+    }
+}
+
+/// The type of a declaration for module-level separation. Ambient (`declare`)
+/// declarations and global augmentations have none and are left as they are.
+fn declaration_type(decl: &Decl) -> Option<DeclarationType> {
+    match decl {
+        Decl::Class(class) if !class.declare => Some(DeclarationType::Class),
+        Decl::Fn(function) if !function.declare => Some(DeclarationType::Function),
+        Decl::Var(var) if !var.declare => Some(DeclarationType::Const),
+        Decl::TsInterface(interface) if !interface.declare => Some(DeclarationType::Interface),
+        Decl::TsTypeAlias(alias) if !alias.declare => Some(DeclarationType::Type),
+        Decl::TsEnum(e) if !e.declare => Some(DeclarationType::Enum),
+        Decl::TsModule(module)
+            if !module.declare && !module.global && matches!(module.id, TsModuleName::Ident(_)) =>
+        {
+            Some(DeclarationType::Namespace)
+        }
+        Decl::TsModule(module) if module.global || matches!(module.id, TsModuleName::Str(_)) => {
+            Some(DeclarationType::Ambient)
+        }
+        _ => None,
+    }
+}
+
+/// The lines of an emitted top-level `item`, with an empty line between the
+/// member groups of a class (FR7.3).
+///
+/// Members are found by the line their first decorator or keyword was emitted
+/// on, so nothing in their bodies, like a string starting with `static `, can
+/// pass for one.
+fn class_member_lines<'a>(
+    item: &ModuleItem,
+    emitted: &'a Emitted,
+) -> impl Iterator<Item = &'a str> {
+    let class = match item {
+        ModuleItem::Stmt(Stmt::Decl(Decl::Class(class))) => Some(&class.class),
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+            decl: Decl::Class(class),
+            ..
+        })) => Some(&class.class),
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultDecl(ExportDefaultDecl {
+            decl: DefaultDecl::Class(class),
+            ..
+        })) => Some(&class.class),
+        _ => None,
+    };
+
+    let mut blank_lines_before = HashSet::new();
+    let mut last_group = None;
+    for member in class.map(|class| class.body.as_slice()).unwrap_or_default() {
+        let Some(group) = class_member_group(member) else {
+            continue;
+        };
+        if last_group.is_some_and(|last| last != group) {
+            let positions = std::iter::once(member.span().lo)
+                .chain(member_decorators(member).iter().map(|d| d.span.lo));
+            // Never above the class itself
+            if let Some(line) = emitted.first_line(positions).filter(|&line| line > 0) {
+                blank_lines_before.insert(line);
+            }
+        }
+        last_group = Some(group);
+    }
+
+    emitted.code.lines().enumerate().flat_map(move |(i, line)| {
+        let blank_line = blank_lines_before.contains(&i).then_some("");
+        blank_line.into_iter().chain([line])
+    })
+}
+
+/// The group a class member is ordered and separated by.
+fn class_member_group(member: &ClassMember) -> Option<ClassMemberGroup> {
+    let private =
+        |accessibility: Option<Accessibility>| accessibility == Some(Accessibility::Private);
+    let (is_static, is_private, is_method) = match member {
+        ClassMember::Constructor(_) => return Some(ClassMemberGroup::Constructor),
+        ClassMember::Method(method) => (method.is_static, private(method.accessibility), true),
+        ClassMember::PrivateMethod(method) => (method.is_static, true, true),
+        ClassMember::ClassProp(prop) => (prop.is_static, private(prop.accessibility), false),
+        ClassMember::PrivateProp(prop) => (prop.is_static, true, false),
+        ClassMember::AutoAccessor(accessor) => (
+            accessor.is_static,
+            matches!(accessor.key, Key::Private(_)) || private(accessor.accessibility),
+            false,
+        ),
+        ClassMember::TsIndexSignature(signature) => (signature.is_static, false, false),
+        ClassMember::StaticBlock(_) => (true, false, false),
+        _ => return None,
+    };
+
+    Some(match (is_static, is_private, is_method) {
+        (true, false, false) => ClassMemberGroup::PublicStaticFields,
+        (true, true, false) => ClassMemberGroup::PrivateStaticFields,
+        (true, false, true) => ClassMemberGroup::PublicStaticMethods,
+        (true, true, true) => ClassMemberGroup::PrivateStaticMethods,
+        (false, false, false) => ClassMemberGroup::PublicInstanceFields,
+        (false, true, false) => ClassMemberGroup::PrivateInstanceFields,
+        (false, false, true) => ClassMemberGroup::PublicInstanceMethods,
+        (false, true, true) => ClassMemberGroup::PrivateInstanceMethods,
+    })
+}
+
+/// The decorators written above a class member.
+fn member_decorators(member: &ClassMember) -> &[Decorator] {
+    match member {
+        ClassMember::Method(method) => &method.function.decorators,
+        ClassMember::PrivateMethod(method) => &method.function.decorators,
+        ClassMember::ClassProp(prop) => &prop.decorators,
+        ClassMember::PrivateProp(prop) => &prop.decorators,
+        ClassMember::AutoAccessor(accessor) => &accessor.decorators,
+        _ => &[],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::TypeScriptParser;
+
+    /// Drops every literal's raw text, like a transform rebuilding it would.
+    struct ForgetRaw;
+
+    impl VisitMut for ForgetRaw {
+        fn visit_mut_str(&mut self, string: &mut Str) {
+            string.raw = None;
+        }
+
+        fn visit_mut_number(&mut self, number: &mut Number) {
+            number.raw = None;
+        }
+
+        fn visit_mut_big_int(&mut self, big_int: &mut BigInt) {
+            big_int.raw = None;
+        }
+    }
+
+    #[test]
+    fn test_literals_without_raw_text_keep_their_spelling() {
+        let source =
+            "const a = { mask: 0xFF, big: 1E10, count: 1_000n, quote: 'it\\'s \"quoted\"' };\n";
+        let parser = TypeScriptParser::new();
+        let mut module = parser.parse(source, "test.ts").unwrap();
+        module.visit_mut_with(&mut ForgetRaw);
+
+        let code = CodeGenerator::new(parser.source_map.clone())
+            .generate(&module)
+            .unwrap();
+
+        for raw in ["0xFF", "1E10", "1_000n", "'it\\'s \"quoted\"'"] {
+            assert!(code.contains(raw), "{raw} missing from:\n{code}");
+        }
+    }
+
+    #[test]
+    fn test_changed_literals_are_spelled_from_their_value() {
+        let parser = TypeScriptParser::new();
+        let mut module = parser.parse("const a = 0xFF;\n", "test.ts").unwrap();
+        module.visit_mut_with(&mut ForgetRaw);
+
+        // A transform changing the value makes the source spelling stale
+        struct Increment;
+        impl VisitMut for Increment {
+            fn visit_mut_number(&mut self, number: &mut Number) {
+                number.value += 1.0;
+            }
+        }
+        module.visit_mut_with(&mut Increment);
+
+        let code = CodeGenerator::new(parser.source_map.clone())
+            .generate(&module)
+            .unwrap();
+
+        assert!(code.contains("256"), "{code}");
+    }
+}
@@ -6,7 +6,8 @@ use swc_common::{
 use swc_ecma_ast::*;
 use swc_ecma_visit::{Visit, VisitWith};
 
-use crate::semantic_hash::SemanticHasher;
+use crate::logging::trace;
+use crate::semantic_hash::{LiteralKeys, SemanticHasher};
 
 /// Context for inline comments that appear within expressions or other constructs
 #[derive(Debug, Clone)]
@@ -63,6 +64,9 @@ pub enum CommentType {
     Leading,
     Trailing,
     Inline, // New type for inline comments
+    /// Comment owned by an enclosing object or array literal rather than any of
+    /// its elements, e.g. a note on its own line before the closing brace
+    Dangling,
 }
 
 /// Extracts comments from an AST and associates them with semantic hashes
@@ -81,6 +85,8 @@ pub struct CommentExtractor<'a> {
     context_depth: usize,
     /// Current variable declaration hash (when inside a VarDecl)
     current_var_decl_hash: Option<u64>,
+    /// Keys for the literals dangling comments are stored under
+    literal_keys: LiteralKeys,
 }
 
 impl<'a> CommentExtractor<'a> {
@@ -94,6 +100,7 @@ impl<'a> CommentExtractor<'a> {
             source_lines,
             context_depth: 0,
             current_var_decl_hash: None,
+            literal_keys: LiteralKeys::default(),
         }
     }
 
@@ -154,6 +161,71 @@ impl<'a> CommentExtractor<'a> {
         }
     }
 
+    /// Extract same-line comments that follow a trailing comma after the last
+    /// element of a literal (`b: 2, // note`), or the semicolon or comma
+    /// after a type member (`name: string; // note`).
+    ///
+    /// The parser keys these on the separator rather than the element, so
+    /// they'd otherwise be lost, or end up on whichever element sorts last.
+    fn extract_trailing_comma_comments(&mut self, span: swc_common::Span, semantic_hash: u64) {
+        let Some(rest) = self.source.get(span.hi.0 as usize..) else {
+            return;
+        };
+        let after_whitespace = rest.trim_start();
+        if !after_whitespace.starts_with([',', ';']) {
+            return;
+        }
+        let comma_hi = span.hi + BytePos((rest.len() - after_whitespace.len() + 1) as u32);
+
+        if let Some(trailing) = self.comments.get_trailing(comma_hi) {
+            let node_end_line = self.get_line_number(span.hi);
+            for (index, comment) in trailing.iter().enumerate() {
+                if self.get_line_number(comment.span.lo) == node_end_line {
+                    self.extracted
+                        .entry(semantic_hash)
+                        .or_default()
+                        .push(ExtractedComment {
+                            semantic_hash,
+                            comment_type: CommentType::Trailing,
+                            comment: comment.clone(),
+                            index,
+                            inline_context: None,
+                        });
+                }
+            }
+        }
+    }
+
+    /// Extract comments that sit on their own lines between a literal's last
+    /// element and its closing bracket.
+    ///
+    /// They belong to the literal itself, so they're keyed by the literal's key
+    /// and stay just before the closing bracket however the elements get sorted.
+    fn extract_dangling_comments(&mut self, span: swc_common::Span, literal_key: u64) {
+        let closing_bracket = span.hi - BytePos(1);
+        let closing_line = self.get_line_number(closing_bracket);
+
+        if let Some(leading) = self.comments.get_leading(closing_bracket) {
+            for (index, comment) in leading.iter().enumerate() {
+                // Comments sharing a line with code are inline and left to codegen
+                if self.get_line_number(comment.span.hi) == closing_line {
+                    continue;
+                }
+
+                self.extracted
+                    .entry(literal_key)
+                    .or_default()
+                    .push(ExtractedComment {
+                        semantic_hash: literal_key,
+                        comment_type: CommentType::Dangling,
+                        comment: comment.clone(),
+                        index,
+                        inline_context: None,
+                    });
+            }
+        }
+    }
+
     /// Check if there are comments between two positions that haven't been extracted
     #[allow(dead_code)]
     fn check_floating_comments(&mut self, _start: BytePos, _end: BytePos) {
@@ -258,8 +330,6 @@ impl<'a> CommentExtractor<'a> {
 
     /// Reassign trailing comments that are separated by line breaks
     fn reassign_trailing_comments(&mut self, module: &Module) {
-        // eprintln!("Starting comment reassignment check...");
-
         // Collect all module items with their positions and hashes
         let mut items_info: Vec<(BytePos, BytePos, u64)> = Vec::new();
 
@@ -302,8 +372,12 @@ impl<'a> CommentExtractor<'a> {
 
         // Apply reassignments
         for (from_hash, to_hash, mut comment) in reassignments {
-            // eprintln!("Reassigning comment '{}' from {:x} to {:x}",
-            //     comment.comment.text, from_hash, to_hash);
+            trace!(
+                comment = %comment.comment.text,
+                from = format_args!("{from_hash:x}"),
+                to = format_args!("{to_hash:x}"),
+                "reassigning trailing comment as leading"
+            );
 
             // Remove from current node
             if let Some(comments) = self.extracted.get_mut(&from_hash) {
@@ -415,7 +489,10 @@ impl<'a> Visit for CommentExtractor<'a> {
             }
 
             // Visit children
+            let hash = SemanticHasher::hash_module_item(item).map_or(0, |(hash, _)| hash);
+            self.literal_keys.enter(hash);
             item.visit_with(self);
+            self.literal_keys.exit();
         }
 
         // Check for comments at the very beginning of the file
@@ -474,7 +551,16 @@ impl<'a> Visit for CommentExtractor<'a> {
         class.visit_children_with(self);
     }
 
+    fn visit_class_member(&mut self, member: &ClassMember) {
+        let hash = SemanticHasher::hash_class_member(member, "").map_or(0, |(hash, _)| hash);
+        self.literal_keys.enter(hash);
+        member.visit_children_with(self);
+        self.literal_keys.exit();
+    }
+
     fn visit_object_lit(&mut self, obj: &ObjectLit) {
+        let literal_key = self.literal_keys.key(SemanticHasher::hash_object_lit(obj));
+
         // Extract comments for object properties
         for prop in &obj.props {
             if let PropOrSpread::Prop(prop) = prop {
@@ -483,9 +569,40 @@ impl<'a> Visit for CommentExtractor<'a> {
             }
         }
 
+        if let Some(PropOrSpread::Prop(last)) = obj.props.last() {
+            let hash = self.hash_prop(last);
+            self.extract_trailing_comma_comments(last.span(), hash);
+        }
+        self.extract_dangling_comments(obj.span, literal_key);
+
         obj.visit_children_with(self);
     }
 
+    fn visit_prop(&mut self, prop: &Prop) {
+        self.literal_keys.enter(self.hash_prop(prop));
+        prop.visit_children_with(self);
+        self.literal_keys.exit();
+    }
+
+    fn visit_ts_interface_body(&mut self, body: &TsInterfaceBody) {
+        self.extract_type_element_comments(&body.body);
+
+        body.visit_children_with(self);
+    }
+
+    fn visit_ts_type_lit(&mut self, type_lit: &TsTypeLit) {
+        self.extract_type_element_comments(&type_lit.members);
+
+        type_lit.visit_children_with(self);
+    }
+
+    fn visit_array_lit(&mut self, array: &ArrayLit) {
+        let literal_key = self.literal_keys.key(SemanticHasher::hash_array_lit(array));
+        self.extract_dangling_comments(array.span, literal_key);
+
+        array.visit_children_with(self);
+    }
+
     fn visit_jsx_element(&mut self, jsx: &JSXElement) {
         // Extract comments for JSX attributes
         for attr in &jsx.opening.attrs {
@@ -498,6 +615,12 @@ impl<'a> Visit for CommentExtractor<'a> {
         jsx.visit_children_with(self);
     }
 
+    fn visit_jsx_attr(&mut self, attr: &JSXAttr) {
+        self.literal_keys.enter(self.hash_jsx_attr(attr));
+        attr.visit_children_with(self);
+        self.literal_keys.exit();
+    }
+
     fn visit_var_decl(&mut self, var_decl: &VarDecl) {
         // Get the hash for this variable declaration
         if let Some((hash, _)) = SemanticHasher::hash_module_item(&ModuleItem::Stmt(Stmt::Decl(
@@ -630,6 +753,16 @@ impl<'a> CommentExtractor<'a> {
         }
     }
 
+    /// Extract comments for the members of an interface body or type literal,
+    /// which move with their members when those are sorted.
+    fn extract_type_element_comments(&mut self, members: &[TsTypeElement]) {
+        let hashes = SemanticHasher::hash_type_elements(members);
+        for (member, hash) in members.iter().zip(hashes) {
+            self.extract_node_comments(member.span(), hash);
+            self.extract_trailing_comma_comments(member.span(), hash);
+        }
+    }
+
     /// Helper to get the current class name (simplified - would need proper context tracking)
     fn get_current_class_name(&self) -> Option<String> {
         // In a real implementation, we'd track the current class context
@@ -1263,6 +1396,41 @@ const y = 100;
         assert_eq!(answer_comment.comment_type, CommentType::Trailing);
     }
 
+    #[test]
+    fn test_dangling_literal_comments() {
+        let source = r#"
+const config = {
+    zebra: 1,
+    apple: 2, // About apple
+    // More keys to come
+};
+const list = [
+    b,
+    a,
+    // Keep this last
+];
+"#;
+
+        let result = extract_comments(source);
+        let comments = result.all_comments_sorted();
+        let find = |text: &str| {
+            comments
+                .iter()
+                .find(|c| c.comment.text.contains(text))
+                .unwrap_or_else(|| panic!("{text} was not extracted"))
+        };
+
+        // The comment after the trailing comma belongs to the last property
+        assert_eq!(find("About apple").comment_type, CommentType::Trailing);
+
+        // Comments before the closing bracket belong to the literal itself
+        let object_comment = find("More keys to come");
+        assert_eq!(object_comment.comment_type, CommentType::Dangling);
+        let array_comment = find("Keep this last");
+        assert_eq!(array_comment.comment_type, CommentType::Dangling);
+        assert_ne!(object_comment.semantic_hash, array_comment.semantic_hash);
+    }
+
     #[test]
     fn test_comment_preservation_order() {
         let source = r#"
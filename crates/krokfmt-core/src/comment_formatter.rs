@@ -0,0 +1,778 @@
+use anyhow::Result;
+use swc_common::{
+    comments::{Comment, CommentKind, SingleThreadedComments},
+    sync::Lrc,
+    BytePos, SourceMap, Spanned,
+};
+use swc_ecma_ast::{JSXElement, JSXFragment, Module};
+use swc_ecma_visit::{Visit, VisitWith};
+
+use crate::{
+    blank_line_normalizer::BlankLineNormalizer,
+    codegen::CodeGenerator,
+    comment_classifier::{
+        find_file_header, find_ignored_items, find_import_group_banners, has_directive,
+        CommentClassification, IGNORE_FILE_DIRECTIVE,
+    },
+    comment_extractor::{CommentExtractionResult, CommentExtractor},
+    comment_reinserter::CommentReinserter,
+    logging::{debug, debug_span},
+    organizer::{ChangeLog, KrokOrganizer},
+    rules::RuleSet,
+    selective_comment_handler::SelectiveCommentHandler,
+    transformer::{
+        apply_import_fixes, hoist_type_specifiers, remove_unused_imports, ImportFixes,
+        ImportProfile, ProjectLayout,
+    },
+};
+
+/// A comment opting a file's re-export statements into alphabetical sorting.
+pub const SORT_EXPORTS_DIRECTIVE: &str = "krokfmt-sort-exports";
+
+/// A comment keeping a file's unused imports, for modules imported for what
+/// loading them does as much as for their bindings.
+pub const KEEP_UNUSED_IMPORTS_DIRECTIVE: &str = "krokfmt-keep-unused-imports";
+
+/// A module organized by [`CommentFormatter::organize`], with the comments
+/// taken out of it for [`CommentFormatter::generate`] to put back.
+///
+/// Comments are matched back to their nodes by semantic hash, so a node
+/// changed in between loses its comments, and new nodes have none.
+pub struct OrganizedModule {
+    pub module: Module,
+    /// What organizing regrouped, hoisted and sorted
+    pub change_log: ChangeLog,
+    pub(crate) comments: CommentExtractionResult,
+    /// Comments inside expressions, which stay in the AST through codegen
+    pub(crate) inline_comments: SingleThreadedComments,
+    /// The shebang's pragmas and the comments above them, kept on top
+    pub(crate) file_header: Vec<String>,
+    /// Re-parsing our own output later needs the same syntax the input used
+    pub(crate) syntax_filename: &'static str,
+}
+
+/// Main comment-aware formatter for krokfmt
+///
+/// This formatter uses selective comment preservation to maintain inline comments
+/// in the AST while extracting and reinserting other comments.
+pub struct CommentFormatter {
+    source_map: Lrc<SourceMap>,
+    comments: SingleThreadedComments,
+    import_profile: ImportProfile,
+    project_layout: ProjectLayout,
+    sort_hook_dependencies: bool,
+    react_first: bool,
+    group_type_imports: bool,
+    group_constants: bool,
+    sort_re_exports: bool,
+    import_fixes: ImportFixes,
+    remove_unused_imports: bool,
+    import_group_banners: bool,
+    rules: RuleSet,
+    reorganize: bool,
+}
+
+impl CommentFormatter {
+    pub fn new(source_map: Lrc<SourceMap>, comments: SingleThreadedComments) -> Self {
+        Self {
+            source_map,
+            comments,
+            import_profile: ImportProfile::default(),
+            project_layout: ProjectLayout::default(),
+            sort_hook_dependencies: false,
+            react_first: false,
+            group_type_imports: false,
+            group_constants: false,
+            sort_re_exports: false,
+            import_fixes: ImportFixes::default(),
+            remove_unused_imports: false,
+            import_group_banners: false,
+            rules: RuleSet::all(),
+            reorganize: true,
+        }
+    }
+
+    /// Order and group imports using the given profile.
+    pub fn with_import_profile(mut self, import_profile: ImportProfile) -> Self {
+        self.import_profile = import_profile;
+        self
+    }
+
+    /// Group imports of the monorepo's own packages between the external and
+    /// absolute ones, and imports through the project's path aliases with the
+    /// absolute ones.
+    pub fn with_project_layout(mut self, project_layout: ProjectLayout) -> Self {
+        self.project_layout = project_layout;
+        self
+    }
+
+    /// Also alphabetize identifier-only React hook dependency arrays.
+    pub fn with_sorted_hook_dependencies(mut self, enabled: bool) -> Self {
+        self.sort_hook_dependencies = enabled;
+        self
+    }
+
+    /// Keep `import React from 'react'` first, for codebases on the classic
+    /// JSX runtime whose convention puts it there.
+    pub fn with_react_first(mut self, enabled: bool) -> Self {
+        self.react_first = enabled;
+        self
+    }
+
+    /// Put type-only imports after the value imports of each group, making
+    /// imports of nothing but inline `type` specifiers type-only first.
+    pub fn with_grouped_type_imports(mut self, enabled: bool) -> Self {
+        self.group_type_imports = enabled;
+        self
+    }
+
+    /// Gather SCREAMING_CASE primitive constants into one block at the top of
+    /// each visibility group.
+    pub fn with_grouped_constants(mut self, enabled: bool) -> Self {
+        self.group_constants = enabled;
+        self
+    }
+
+    /// Sort re-export statements in every file, as if each carried the
+    /// `// krokfmt-sort-exports` directive.
+    pub fn with_sorted_re_exports(mut self, enabled: bool) -> Self {
+        self.sort_re_exports = enabled;
+        self
+    }
+
+    /// Apply the given opt-in import hygiene fixes.
+    pub fn with_import_fixes(mut self, import_fixes: ImportFixes) -> Self {
+        self.import_fixes = import_fixes;
+        self
+    }
+
+    /// Drop imported names the file never uses, except in files carrying
+    /// the `// krokfmt-keep-unused-imports` directive.
+    pub fn with_unused_imports_removed(mut self, enabled: bool) -> Self {
+        self.remove_unused_imports = enabled;
+        self
+    }
+
+    /// Label each import group with a canonical banner comment.
+    ///
+    /// Banners the author wrote (`// External`, `// --- Internal ---`) are always
+    /// dropped, because sorting would leave them above the wrong group.
+    pub fn with_import_group_banners(mut self, enabled: bool) -> Self {
+        self.import_group_banners = enabled;
+        self
+    }
+
+    /// Only apply the given rule groups, e.g. for a legacy directory that opted
+    /// out of some of them.
+    pub fn with_rules(mut self, rules: RuleSet) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// Skip organization entirely and only lay out the code as written.
+    ///
+    /// Nothing is reordered: not imports, declarations, members, nor keys.
+    pub fn with_reorganization(mut self, enabled: bool) -> Self {
+        self.reorganize = enabled;
+        self
+    }
+
+    /// Format a module with selective comment preservation
+    pub fn format(&self, module: Module, source: &str) -> Result<String> {
+        let organized = self.organize(module, source)?;
+        self.generate(organized, source)
+    }
+
+    /// Take the comments out of `module` and organize it.
+    ///
+    /// The first half of [`Self::format`]; [`Self::generate`] prints the result
+    /// and puts the comments back.
+    pub fn organize(&self, mut module: Module, source: &str) -> Result<OrganizedModule> {
+        let all_comments: Vec<_> = {
+            let (leading, trailing) = self.comments.borrow_all();
+            let mut comments = Vec::new();
+            for (_, vec) in leading.iter() {
+                comments.extend(vec.iter().cloned());
+            }
+            for (_, vec) in trailing.iter() {
+                comments.extend(vec.iter().cloned());
+            }
+            comments
+        };
+
+        // A file opting out with `// krokfmt-ignore-file` is only laid out
+        let reorganize = self.reorganize && !has_directive(&all_comments, IGNORE_FILE_DIRECTIVE);
+
+        // Pragmas heading the file stay there however the statement they
+        // lead moves, or whether it's still there after the import fixes
+        let file_header = find_file_header(&module, &self.comments);
+        let file_header_lines = module
+            .body
+            .first()
+            .map(|first| self.file_header_lines(&file_header, first.span_lo()))
+            .unwrap_or_default();
+
+        // Phase 0: Import fixes change how imports hash, so they go before any
+        // comment is extracted and keyed by that hash
+        if reorganize {
+            apply_import_fixes(&mut module, &self.import_fixes);
+            if self.group_type_imports {
+                hoist_type_specifiers(&mut module);
+            }
+            if self.remove_unused_imports
+                && !has_directive(&all_comments, KEEP_UNUSED_IMPORTS_DIRECTIVE)
+            {
+                remove_unused_imports(&mut module, &jsx_pragma_factories(&all_comments));
+            }
+        }
+
+        // Phase 1: Separate inline from non-inline comments
+        let (inline_only_comments, _non_inline_comments) =
+            SelectiveCommentHandler::extract_non_inline_comments(
+                &self.comments,
+                &module,
+                source,
+                &self.source_map,
+            );
+
+        // Phase 2: Extract ALL comments (we'll filter later)
+        let extractor = CommentExtractor::with_source(&self.comments, source.to_string());
+        let mut extracted_comments =
+            debug_span!("extract_comments").in_scope(|| extractor.extract(&module));
+
+        // Phase 2b: Get all inline comment positions to filter them out
+        let mut classifier =
+            crate::comment_classifier::CommentClassifier::new(&self.source_map, source);
+        let classifications = classifier.classify_module(&module, &all_comments);
+
+        let inline_positions: std::collections::HashSet<_> = classifications
+            .iter()
+            .filter(|(_, class)| **class == CommentClassification::Inline)
+            .map(|(pos, _)| *pos)
+            .collect();
+
+        // Import group banners would label the wrong group once imports are
+        // sorted, so they're dropped along with inline comments
+        let banner_positions = if reorganize {
+            find_import_group_banners(&module, &self.comments)
+        } else {
+            Default::default()
+        };
+        let is_dropped = |pos: &swc_common::BytePos| {
+            inline_positions.contains(pos)
+                || banner_positions.contains(pos)
+                || file_header.iter().any(|comment| comment.span.lo == *pos)
+        };
+
+        // Remove inline comments from extracted comments
+        for (_, comments) in extracted_comments.node_comments.iter_mut() {
+            comments.retain(|c| !is_dropped(&c.comment.span.lo));
+        }
+
+        extracted_comments
+            .standalone_comments
+            .retain(|c| !is_dropped(&c.comment.span.lo));
+
+        debug!(
+            inline = inline_positions.len(),
+            banners = banner_positions.len(),
+            header = file_header.len(),
+            attached = extracted_comments
+                .node_comments
+                .values()
+                .map(Vec::len)
+                .sum::<usize>(),
+            standalone = extracted_comments.standalone_comments.len(),
+            "classified comments"
+        );
+
+        // Re-parsing our own output later needs the same syntax the input used
+        let syntax_filename = if contains_jsx_nodes(&module) {
+            "organized.tsx"
+        } else {
+            "organized.ts"
+        };
+
+        // Phase 3: Organize the AST using the organizer
+        let organizer = KrokOrganizer::with_import_profile(self.import_profile)
+            .with_project_layout(self.project_layout.clone())
+            .with_sorted_hook_dependencies(self.sort_hook_dependencies)
+            .with_react_first(self.react_first)
+            .with_grouped_type_imports(self.group_type_imports)
+            .with_grouped_constants(self.group_constants)
+            .with_sorted_re_exports(
+                self.sort_re_exports || has_directive(&all_comments, SORT_EXPORTS_DIRECTIVE),
+            )
+            .with_ignored_items(find_ignored_items(&module, &self.comments))
+            .with_rules(self.rules);
+        let (organized_module, change_log) = if reorganize {
+            debug_span!("organize").in_scope(|| organizer.organize_with_log(module))?
+        } else {
+            (module, ChangeLog::default())
+        };
+
+        Ok(OrganizedModule {
+            module: organized_module,
+            change_log,
+            comments: extracted_comments,
+            inline_comments: inline_only_comments,
+            file_header: file_header_lines,
+            syntax_filename,
+        })
+    }
+
+    /// The lines of the `header` comments as written, with a blank line
+    /// wherever the source had blank lines between them or before the
+    /// statement at `first_item`.
+    fn file_header_lines(&self, header: &[Comment], first_item: BytePos) -> Vec<String> {
+        let line_of = |pos| self.source_map.lookup_char_pos(pos).line;
+        let mut lines: Vec<String> = Vec::new();
+        let mut last_line = None;
+
+        for comment in header {
+            let text = match comment.kind {
+                CommentKind::Line => format!("//{}", comment.text),
+                CommentKind::Block => format!("/*{}*/", comment.text),
+            };
+            let mut text_lines = text.lines().map(str::to_string);
+            let start = line_of(comment.span.lo);
+            match (last_line, lines.last_mut()) {
+                (Some(last), Some(previous)) if last == start => {
+                    previous.push(' ');
+                    previous.push_str(&text_lines.next().unwrap_or_default());
+                }
+                (Some(last), _) if start > last + 1 => lines.push(String::new()),
+                _ => {}
+            }
+            lines.extend(text_lines);
+            last_line = Some(line_of(comment.span.hi));
+        }
+
+        if last_line.is_some_and(|last| line_of(first_item) > last + 1) {
+            lines.push(String::new());
+        }
+        lines
+    }
+
+    /// Print a module from [`Self::organize`] with its comments back in place.
+    pub fn generate(&self, organized: OrganizedModule, source: &str) -> Result<String> {
+        let OrganizedModule {
+            module: organized_module,
+            comments: extracted_comments,
+            inline_comments: inline_only_comments,
+            file_header,
+            syntax_filename,
+            ..
+        } = organized;
+
+        // Phase 4: Generate code WITH inline comments (they're preserved)
+        let generator = CodeGenerator::with_comments(self.source_map.clone(), inline_only_comments)
+            .with_import_profile(self.import_profile)
+            .with_project_layout(self.project_layout.clone())
+            .with_import_group_banners(self.import_group_banners)
+            .with_file_header(file_header);
+        let code_with_inline_comments =
+            debug_span!("codegen").in_scope(|| generator.generate(&organized_module))?;
+
+        // Phase 5: Reinsert only non-inline comments at the correct positions
+        let mut reinserter =
+            CommentReinserter::new(extracted_comments).with_aligned_trailing_comments(source);
+        let code_with_comments = debug_span!("reinsert_comments")
+            .in_scope(|| reinserter.reinsert_comments(&code_with_inline_comments))?;
+
+        // Phase 6: Squash the blank-line runs left behind by moved statements
+        let final_code = debug_span!("normalize_blank_lines")
+            .in_scope(|| BlankLineNormalizer::normalize(&code_with_comments, syntax_filename));
+
+        Ok(final_code)
+    }
+}
+
+/// Whether the module holds actual JSX syntax.
+///
+/// Only parsed JSX nodes count. Element factories such as `React.createElement`
+/// or hyperscript's `h('div')` are ordinary calls, and treating files that use
+/// them as TSX would turn their generic arrows (`<T>(x: T) => x`) into JSX.
+fn contains_jsx_nodes(module: &Module) -> bool {
+    struct JsxFinder(bool);
+
+    impl Visit for JsxFinder {
+        fn visit_jsx_element(&mut self, _: &JSXElement) {
+            self.0 = true;
+        }
+
+        fn visit_jsx_fragment(&mut self, _: &JSXFragment) {
+            self.0 = true;
+        }
+    }
+
+    let mut finder = JsxFinder(false);
+    module.visit_with(&mut finder);
+    finder.0
+}
+
+/// The names `@jsx` and `@jsxFrag` pragmas (`/** @jsx h */`) have JSX compile
+/// to, by the binding they start with.
+pub(crate) fn jsx_pragma_factories(comments: &[Comment]) -> Vec<String> {
+    let mut factories = Vec::new();
+
+    for comment in comments {
+        let mut rest: &str = &comment.text;
+        while let Some(at) = rest.find("@jsx") {
+            rest = &rest[at + "@jsx".len()..];
+            let pragma = rest.strip_prefix("Frag").unwrap_or(rest);
+            // `@jsxImportSource` and `@jsxRuntime` name no binding
+            if !pragma.starts_with(char::is_whitespace) {
+                continue;
+            }
+            let name: String = pragma
+                .trim_start()
+                .chars()
+                .take_while(|c| c.is_alphanumeric() || *c == '_' || *c == '$')
+                .collect();
+            if !name.is_empty() {
+                factories.push(name);
+            }
+        }
+    }
+
+    factories
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::TypeScriptParser;
+
+    #[test]
+    fn test_jsx_pragma_factories() {
+        let parser = TypeScriptParser::new();
+        let source = "/** @jsx h */\n/** @jsxFrag Preact.Fragment */\n/** @jsxImportSource preact */\nconst a = 1;\n";
+        parser.parse(source, "test.tsx").unwrap();
+        let (leading, trailing) = parser.comments.borrow_all();
+        let comments: Vec<_> = leading
+            .values()
+            .chain(trailing.values())
+            .flatten()
+            .cloned()
+            .collect();
+
+        assert_eq!(jsx_pragma_factories(&comments), ["h", "Preact"]);
+    }
+
+    fn format_with_comments(source: &str) -> Result<String> {
+        let parser = TypeScriptParser::new();
+        let module = parser.parse(source, "test.ts")?;
+
+        let formatter = CommentFormatter::new(parser.source_map.clone(), parser.comments.clone());
+
+        formatter.format(module, source)
+    }
+
+    #[test]
+    fn test_inline_comments_preserved() {
+        let source = r#"
+const x = /* inline comment */ 42;
+let y = /* another inline */ "hello";
+"#;
+
+        let result = format_with_comments(source).unwrap();
+        // Inline comments should be preserved in their exact positions
+        assert!(result.contains("const x = /* inline comment */ 42"));
+        assert!(result.contains("let y = /* another inline */ \"hello\""));
+    }
+
+    #[test]
+    fn test_leading_trailing_comments() {
+        let source = r#"
+// Leading comment
+const x = 42; // Trailing comment
+"#;
+
+        let result = format_with_comments(source).unwrap();
+
+        // Non-inline comments should still be preserved
+        assert!(result.contains("// Leading comment"));
+        assert!(result.contains("// Trailing comment"));
+        assert!(result.contains("const x = 42"));
+    }
+
+    #[test]
+    fn test_mixed_comment_types() {
+        let source = r#"
+// This is a leading comment
+function foo(/* param comment */ x: number) {
+    return x * 2; // multiply by two
+}
+"#;
+
+        let result = format_with_comments(source).unwrap();
+        // All comment types should be preserved
+        assert!(result.contains("// This is a leading comment"));
+        assert!(result.contains("/* param comment */"));
+    }
+
+    #[test]
+    fn test_dangling_literal_comments_stay_before_closing_bracket() {
+        let source = r#"
+const config = {
+    zebra: 1,
+    apple: 2, // About apple
+    // More keys to come
+};
+const list = [
+    b,
+    a,
+    // Keep this last
+];
+"#;
+
+        let result = format_with_comments(source).unwrap();
+
+        // The last property's comment moves with it when it sorts first
+        assert!(result.contains("apple: 2, // About apple"), "{result}");
+
+        // The dangling comment stays after whatever sorts last
+        let zebra = result.find("zebra: 1").unwrap();
+        let object_comment = result.find("// More keys to come").unwrap();
+        let object_close = result.find("};").unwrap();
+        assert!(
+            zebra < object_comment && object_comment < object_close,
+            "{result}"
+        );
+
+        let list = result.find("const list").unwrap();
+        let array_comment = result.find("// Keep this last").unwrap();
+        let array_close = result.rfind(']').unwrap();
+        let elements = &result[list..array_comment];
+        assert!(elements.contains('a') && elements.contains('b'), "{result}");
+        assert!(array_comment < array_close, "{result}");
+    }
+
+    #[test]
+    fn test_dangling_comments_of_same_shaped_literals_stay_apart() {
+        let source = r#"
+const rows = [
+    {
+        name: 'first',
+        id: 1,
+        // End of the first row
+    },
+    {
+        name: 'second',
+        id: 2,
+        // End of the second row
+    },
+];
+const sizes = {
+    zebra: {
+        small: 1,
+        // More zebra sizes to come
+    },
+    apple: {
+        small: 2,
+        // More apple sizes to come
+    },
+};
+"#;
+
+        let result = format_with_comments(source).unwrap();
+        let position = |text: &str| {
+            result
+                .find(text)
+                .unwrap_or_else(|| panic!("{text} missing from:\n{result}"))
+        };
+
+        // Each comment stays in its own literal, though both literals of a
+        // pair hash alike and the second pair sorts
+        assert!(
+            position("id: 1") < position("// End of the first row"),
+            "{result}"
+        );
+        assert!(
+            position("// End of the first row") < position("id: 2"),
+            "{result}"
+        );
+        assert!(
+            position("id: 2") < position("// End of the second row"),
+            "{result}"
+        );
+        assert!(
+            position("small: 2") < position("// More apple sizes to come"),
+            "{result}"
+        );
+        assert!(
+            position("// More apple sizes to come") < position("small: 1"),
+            "{result}"
+        );
+        assert!(
+            position("small: 1") < position("// More zebra sizes to come"),
+            "{result}"
+        );
+    }
+
+    #[test]
+    fn test_type_member_comments_move_with_their_members() {
+        let source = r#"
+interface User {
+    // The display name
+    name: string;
+    age: number; // In years
+}
+"#;
+
+        let result = format_with_comments(source).unwrap();
+
+        let age = result.find("age: number; // In years").expect(&result);
+        let comment = result.find("// The display name").unwrap();
+        let name = result.find("name: string;").unwrap();
+        assert!(age < comment && comment < name, "{result}");
+    }
+
+    #[test]
+    fn test_element_factories_are_not_jsx() {
+        let source = r#"
+const identity = <T>(value: T): T => value;
+const view = React.createElement('div', { id: 'root' }, h('span', null, identity('hi')));
+"#;
+
+        let parser = TypeScriptParser::new();
+        let module = parser.parse(source, "widget.spec.ts").unwrap();
+        assert!(!contains_jsx_nodes(&module));
+
+        let result = format_with_comments(source).unwrap();
+        assert!(result.contains("<T>(value: T): T => value"), "{result}");
+        assert!(result.contains("React.createElement('div'"), "{result}");
+        assert!(result.contains("h('span', null"), "{result}");
+    }
+
+    #[test]
+    fn test_sort_exports_directive() {
+        let source = "export * from './zebra';\nexport * from './apple';\n";
+        let result = format_with_comments(source).unwrap();
+        assert!(
+            result.find("./zebra").unwrap() < result.find("./apple").unwrap(),
+            "{result}"
+        );
+
+        let source = format!("// {SORT_EXPORTS_DIRECTIVE}\n{source}");
+        let result = format_with_comments(&source).unwrap();
+        assert!(result.contains(SORT_EXPORTS_DIRECTIVE), "{result}");
+        assert!(
+            result.find("./apple").unwrap() < result.find("./zebra").unwrap(),
+            "{result}"
+        );
+    }
+
+    #[test]
+    fn test_ignore_directive_pins_the_next_statement() {
+        let source = r#"const zebra = 1;
+// krokfmt-ignore
+const handlers = { second: b, first: a };
+const apple = 2;
+const mango = 3;
+"#;
+        let result = format_with_comments(source).unwrap();
+
+        // Nothing moves across the ignored statement, nor inside it
+        assert!(
+            result.find("const zebra").unwrap() < result.find("const handlers").unwrap(),
+            "{result}"
+        );
+        assert!(
+            result.find("second").unwrap() < result.find("first").unwrap(),
+            "{result}"
+        );
+        assert!(
+            result.find("const apple").unwrap() < result.find("const mango").unwrap(),
+            "{result}"
+        );
+        assert!(
+            result.contains("// krokfmt-ignore\nconst handlers"),
+            "{result}"
+        );
+    }
+
+    #[test]
+    fn test_ignore_region_is_kept_as_a_unit() {
+        let source = r#"const zebra = 1;
+// krokfmt-ignore-start
+function second() {}
+function first() {}
+const table = { second, first };
+// krokfmt-ignore-end
+const mango = 3;
+const apple = 2;
+"#;
+        let result = format_with_comments(source).unwrap();
+
+        let positions: Vec<_> = [
+            "const zebra",
+            "// krokfmt-ignore-start",
+            "function second",
+            "function first",
+            "const table",
+            "// krokfmt-ignore-end",
+            // Stays put to keep closing the region
+            "const mango",
+            "const apple",
+        ]
+        .iter()
+        .map(|text| {
+            result
+                .find(text)
+                .unwrap_or_else(|| panic!("{text}: {result}"))
+        })
+        .collect();
+        assert!(positions.is_sorted(), "{result}");
+
+        let table = &result[result.find("const table").unwrap()..];
+        assert!(
+            table.find("second").unwrap() < table.find("first").unwrap(),
+            "{result}"
+        );
+    }
+
+    #[test]
+    fn test_ignore_file_directive_keeps_the_order() {
+        let source = "// krokfmt-ignore-file\nimport { z } from 'z';\nimport { a } from 'a';\nconst config = { b: 1, a: 2 };\n";
+        let result = format_with_comments(source).unwrap();
+
+        assert!(
+            result.find("'z'").unwrap() < result.find("'a'").unwrap(),
+            "{result}"
+        );
+        assert!(
+            result.find("b: 1").unwrap() < result.find("a: 2").unwrap(),
+            "{result}"
+        );
+    }
+
+    #[test]
+    fn test_import_group_banners_regenerated() {
+        let source = r#"// Internal
+import { helper } from './helper';
+// External
+import React from 'react';
+"#;
+
+        let parser = TypeScriptParser::new();
+        let module = parser.parse(source, "test.ts").unwrap();
+        let formatter = CommentFormatter::new(parser.source_map.clone(), parser.comments.clone());
+        let result = formatter.format(module, source).unwrap();
+
+        assert!(!result.contains("// Internal"), "{result}");
+        assert!(!result.contains("// External"), "{result}");
+
+        let parser = TypeScriptParser::new();
+        let module = parser.parse(source, "test.ts").unwrap();
+        let formatter = CommentFormatter::new(parser.source_map.clone(), parser.comments.clone())
+            .with_import_group_banners(true);
+        let result = formatter.format(module, source).unwrap();
+
+        assert!(!result.contains("// Internal"), "{result}");
+        let external = result.find("// External\nimport React").unwrap();
+        let relative = result.find("// Relative\nimport { helper }").unwrap();
+        assert!(external < relative, "{result}");
+    }
+}
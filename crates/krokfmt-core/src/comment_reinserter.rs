@@ -1,17 +1,21 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use swc_common::{
     comments::{Comment, CommentKind},
-    Spanned,
+    BytePos, Spanned,
 };
 use swc_ecma_ast::*;
 use swc_ecma_visit::{Visit, VisitWith};
 
+use crate::comment_classifier::is_preformatted_comment;
 use crate::comment_extractor::{
     CommentExtractionResult, CommentType, ExtractedComment, InlineCommentContext, InlinePosition,
     StandaloneComment,
 };
 use crate::parser::TypeScriptParser;
-use crate::semantic_hash::SemanticHasher;
+use crate::semantic_hash::{LiteralKeys, SemanticHasher};
+
+/// Indentation unit of the generated code (SWC's emitter indents by four spaces)
+const INDENT: &str = "    ";
 
 /// Represents a position in the source code where a comment should be inserted
 #[derive(Debug)]
@@ -37,6 +41,9 @@ pub struct CommentReinserter {
     node_positions: HashMap<u64, NodePosition>,
     /// Source lines for checking empty lines
     source_lines: Vec<String>,
+    /// Aligned trailing comments of the original source, to realign once
+    /// reinserted
+    comment_tables: Option<TrailingCommentTables>,
 }
 
 #[derive(Debug, Clone)]
@@ -55,9 +62,19 @@ impl CommentReinserter {
             extracted_comments,
             node_positions: HashMap::new(),
             source_lines: Vec::new(),
+            comment_tables: None,
         }
     }
 
+    /// Keep trailing comments aligned in columns where `source` aligned them.
+    ///
+    /// Trailing comments are otherwise appended one space after their code,
+    /// which turns a table of commented constants into a ragged edge.
+    pub fn with_aligned_trailing_comments(mut self, source: &str) -> Self {
+        self.comment_tables = Some(TrailingCommentTables::detect(source));
+        self
+    }
+
     /// Reinsert comments into the generated code
     pub fn reinsert_comments(&mut self, generated_code: &str) -> Result<String, anyhow::Error> {
         // Step 1: Parse the generated code to find node positions
@@ -67,7 +84,13 @@ impl CommentReinserter {
         let insertion_points = self.calculate_insertion_points()?;
 
         // Step 3: Insert comments into the code
-        Ok(self.insert_comments_into_code(generated_code, insertion_points))
+        let code = self.insert_comments_into_code(generated_code, insertion_points);
+
+        // Step 4: Line trailing comments back up into the tables they came from
+        Ok(match &self.comment_tables {
+            Some(tables) => tables.reflow(code),
+            None => code,
+        })
     }
 
     /// Analyze the generated code to find where each node is positioned
@@ -75,15 +98,9 @@ impl CommentReinserter {
         // Store source lines for empty line detection
         self.source_lines = code.lines().map(String::from).collect();
 
-        // Parse the generated code
-        let parser = TypeScriptParser::new();
-        // Detect if the code contains JSX by looking for < and > characters
-        let filename = if code.contains("<") && code.contains(">") {
-            "generated.tsx"
-        } else {
-            "generated.ts"
-        };
-        let module = parser.parse(code, filename)?;
+        // Parse the generated code. Guessing TSX from `<` and `>` would turn type
+        // assertions into JSX, so let a failed TS parse decide instead.
+        let module = TypeScriptParser::parse_disambiguated(code, "generated.ts")?.module;
 
         // Create a visitor to collect node positions
         let mut position_collector = PositionCollector::new(code);
@@ -145,6 +162,23 @@ impl CommentReinserter {
                             comment: CommentWithType::Regular(comment.clone()),
                             indentation: node_pos.indentation.clone(),
                         },
+                        CommentType::Dangling => {
+                            // Dangling comments sit one level inside the literal,
+                            // just before its closing bracket
+                            let closing_indentation: String = self
+                                .source_lines
+                                .get(node_pos.end_line)
+                                .map(|line| {
+                                    line.chars().take_while(|c| c.is_whitespace()).collect()
+                                })
+                                .unwrap_or_default();
+                            InsertionPoint {
+                                line: node_pos.end_line,
+                                column: node_pos.end_column,
+                                comment: CommentWithType::Regular(comment.clone()),
+                                indentation: format!("{closing_indentation}{INDENT}"),
+                            }
+                        }
                     };
                     insertion_points.push(point);
                 }
@@ -202,6 +236,8 @@ impl CommentReinserter {
             ));
         }
 
+        let insertion_points = Self::dedupe_by_span(insertion_points);
+
         // Separate inline comments from other comments
         let (inline_points, mut regular_points): (Vec<_>, Vec<_>) =
             insertion_points.into_iter().partition(|point| {
@@ -260,6 +296,61 @@ impl CommentReinserter {
         Ok(regular_points)
     }
 
+    /// Drop insertion points that would emit the same source comment twice.
+    ///
+    /// Trailing comment reassignment can leave one source comment attached to two
+    /// nodes (once as trailing, once as leading on the next node), which shows up as
+    /// identical adjacent comments in the output. Every comment has exactly one
+    /// span in the original source, so the span is a reliable identity: we keep a
+    /// single insertion per span, preferring the leading attachment since that's
+    /// where reassignment meant to put it.
+    fn dedupe_by_span(points: Vec<InsertionPoint>) -> Vec<InsertionPoint> {
+        let priority = |comment_type: CommentType| match comment_type {
+            CommentType::Leading => 0,
+            CommentType::Inline | CommentType::Dangling => 1,
+            CommentType::Trailing => 2,
+        };
+
+        let mut best: HashMap<(BytePos, BytePos), (usize, u8)> = HashMap::new();
+        for (idx, point) in points.iter().enumerate() {
+            if let CommentWithType::Regular(extracted) = &point.comment {
+                let key = (extracted.comment.span.lo, extracted.comment.span.hi);
+                let rank = priority(extracted.comment_type);
+                match best.get(&key) {
+                    Some(&(_, best_rank)) if best_rank <= rank => {}
+                    _ => {
+                        best.insert(key, (idx, rank));
+                    }
+                }
+            }
+        }
+
+        let mut deduped = Vec::with_capacity(points.len());
+        for (idx, mut point) in points.into_iter().enumerate() {
+            match &mut point.comment {
+                CommentWithType::Regular(extracted) => {
+                    let key = (extracted.comment.span.lo, extracted.comment.span.hi);
+                    if best.get(&key).map(|&(best_idx, _)| best_idx) == Some(idx) {
+                        deduped.push(point);
+                    }
+                }
+                CommentWithType::StandaloneGroup(group) => {
+                    // Standalone comments lose to node-attached ones, and groups can't
+                    // repeat a span among themselves either
+                    group.retain(|standalone| {
+                        let key = (standalone.comment.span.lo, standalone.comment.span.hi);
+                        best.insert(key, (usize::MAX, u8::MAX)).is_none()
+                    });
+                    if !group.is_empty() {
+                        deduped.push(point);
+                    }
+                }
+            }
+        }
+
+        deduped
+    }
+
     /// Insert comments into the code at the calculated positions
     fn insert_comments_into_code(
         &self,
@@ -291,6 +382,42 @@ impl CommentReinserter {
                                 lines[point.line].push_str(comment_text.trim());
                             }
                         }
+                        CommentType::Dangling if point.line < lines.len() => {
+                            let bracket = point.column.saturating_sub(1);
+                            let closing_line = &lines[point.line];
+                            let bracket_in_place = closing_line
+                                .get(bracket..)
+                                .is_some_and(|rest| rest.starts_with(['}', ']']));
+
+                            if !bracket_in_place {
+                                // An earlier dangling comment of this literal already
+                                // moved the bracket down; this one goes above that comment
+                                let trimmed = closing_line.trim_start();
+                                let at = if trimmed.starts_with("//") || trimmed.starts_with("/*") {
+                                    point.line
+                                } else {
+                                    point.line + 1
+                                };
+                                lines.insert(at, comment_text);
+                            } else if closing_line[..bracket].trim().is_empty() {
+                                // The closing bracket starts its own line
+                                lines.insert(point.line, comment_text);
+                            } else {
+                                // Single-line literal: break it open so the comment
+                                // can sit on its own line before the closing bracket
+                                let closing_indentation: String = closing_line
+                                    .chars()
+                                    .take_while(|c| c.is_whitespace())
+                                    .collect();
+                                let rest =
+                                    format!("{closing_indentation}{}", &closing_line[bracket..]);
+                                let before = closing_line[..bracket].trim_end().to_string();
+                                lines[point.line] = before;
+                                lines.insert(point.line + 1, rest);
+                                lines.insert(point.line + 1, comment_text);
+                            }
+                        }
+                        CommentType::Dangling => {}
                         CommentType::Inline => {
                             // Handle inline comments based on their context
                             if let Some(context) = &extracted.inline_context {
@@ -369,21 +496,19 @@ impl CommentReinserter {
                                         param_index: _,
                                         param_name,
                                         ..
-                                    } => {
+                                    } if point.line < lines.len() => {
                                         // For parameter comments, find the parameter in the function signature
-                                        if point.line < lines.len() {
-                                            let comment_text =
-                                                self.format_comment(&extracted.comment, "");
-                                            let line = &mut lines[point.line];
-
-                                            // Try to find the parameter name in the line
-                                            if let Some(param_pos) = line.find(param_name) {
-                                                // Insert the comment before the parameter
-                                                let insert_pos = param_pos;
-                                                let before = &line[..insert_pos];
-                                                let after = &line[insert_pos..];
-                                                *line = format!("{before}{comment_text} {after}");
-                                            }
+                                        let comment_text =
+                                            self.format_comment(&extracted.comment, "");
+                                        let line = &mut lines[point.line];
+
+                                        // Try to find the parameter name in the line
+                                        if let Some(param_pos) = line.find(param_name) {
+                                            // Insert the comment before the parameter
+                                            let insert_pos = param_pos;
+                                            let before = &line[..insert_pos];
+                                            let after = &line[insert_pos..];
+                                            *line = format!("{before}{comment_text} {after}");
                                         }
                                     }
                                     _ => {
@@ -446,6 +571,11 @@ impl CommentReinserter {
     fn format_comment(&self, comment: &Comment, indentation: &str) -> String {
         match comment.kind {
             CommentKind::Line => format!("{}//{}", indentation, comment.text),
+            // Diagrams and tables only line up as written, so they're never
+            // re-indented or trimmed
+            CommentKind::Block if is_preformatted_comment(comment) => {
+                format!("{}/*{}*/", indentation, comment.text)
+            }
             CommentKind::Block => {
                 // Handle multi-line block comments
                 let lines: Vec<&str> = comment.text.lines().collect();
@@ -502,11 +632,246 @@ impl CommentReinserter {
     }
 }
 
+/// Runs of trailing line comments that the source aligned into a column:
+///
+/// ```text
+/// const WIDTH = 80;    // characters
+/// const TAB_SIZE = 4;  // spaces
+/// ```
+///
+/// A run counts as a table when it spans at least two consecutive lines, its
+/// comments all start in the same column, and at least one of them is set off
+/// by more than a single space, which rules out alignment by coincidence.
+/// Only comments the parser found count, so a line of a template literal that
+/// happens to end in `// ...` is never part of a table or padded.
+#[derive(Debug, Default, Clone)]
+pub struct TrailingCommentTables {
+    /// Comment text (from `//` on) to the tables it's a row of
+    tables: HashMap<String, Vec<usize>>,
+}
+
+impl TrailingCommentTables {
+    /// Find the comment tables of `source`.
+    pub fn detect(source: &str) -> Self {
+        // Most files have no table, which is cheap to tell without parsing
+        if Self::scan(source, None).is_empty() {
+            return Self::default();
+        }
+        match line_comment_offsets(source) {
+            Some(comments) => Self {
+                tables: Self::scan(source, Some(&comments)),
+            },
+            None => Self::default(),
+        }
+    }
+
+    /// The tables of `source`, counting only the comments starting at one of
+    /// `comments`' byte offsets when given.
+    fn scan(source: &str, comments: Option<&HashSet<usize>>) -> HashMap<String, Vec<usize>> {
+        let mut tables: HashMap<String, Vec<usize>> = HashMap::new();
+        let mut table_count = 0;
+        // Column, spaces before the comment, and comment of each row so far
+        let mut run: Vec<(usize, usize, &str)> = Vec::new();
+
+        // A final `None` closes the last run
+        for line in lines_with_offsets(source)
+            .map(Some)
+            .chain(std::iter::once(None))
+        {
+            let row = line.and_then(|(offset, line)| {
+                let (code, comment) = trailing_comment(offset, line, comments)?;
+                let gap = line.len() - leading_len(line) - code.len() - comment.len();
+                Some((comment_column(line, comment), gap, comment))
+            });
+
+            let continues = matches!(
+                (row, run.last()),
+                (Some((column, _, _)), Some((previous, _, _))) if column == *previous
+            );
+            if !continues {
+                if run.len() >= 2 && run.iter().any(|(_, gap, _)| *gap > 1) {
+                    for (_, _, comment) in &run {
+                        let rows_of = tables.entry(comment.to_string()).or_default();
+                        if !rows_of.contains(&table_count) {
+                            rows_of.push(table_count);
+                        }
+                    }
+                    table_count += 1;
+                }
+                run.clear();
+            }
+            run.extend(row);
+        }
+
+        tables
+    }
+
+    /// Align the comments of `code` that belonged to the same table and still
+    /// sit on consecutive lines, one space past the longest line of code.
+    pub fn reflow(&self, code: String) -> String {
+        if self.tables.is_empty() {
+            return code;
+        }
+        let Some(comments) = line_comment_offsets(&code) else {
+            return code;
+        };
+
+        let mut lines: Vec<String> = code.lines().map(String::from).collect();
+        // The tables each line's comment could be a row of
+        let tables: Vec<&[usize]> = lines_with_offsets(&code)
+            .map(|(offset, line)| {
+                trailing_comment(offset, line, Some(&comments))
+                    .and_then(|(_, comment)| self.tables.get(comment))
+                    .map_or(&[][..], Vec::as_slice)
+            })
+            .collect();
+
+        let mut start = 0;
+        while start < lines.len() {
+            // Rows of one table have a table in common; a comment repeated in
+            // several tables, like `// px`, doesn't link them
+            let mut shared = tables[start].to_vec();
+            let mut end = start;
+            while end < lines.len() {
+                let still_shared: Vec<usize> = shared
+                    .iter()
+                    .copied()
+                    .filter(|table| tables[end].contains(table))
+                    .collect();
+                if still_shared.is_empty() {
+                    break;
+                }
+                shared = still_shared;
+                end += 1;
+            }
+
+            if end - start >= 2 {
+                let rows: Vec<(String, String)> = lines[start..end]
+                    .iter()
+                    .filter_map(|line| {
+                        let (code, comment) = split_trailing_comment(line)?;
+                        let indent = &line[..leading_len(line)];
+                        Some((format!("{indent}{code}"), comment.to_string()))
+                    })
+                    .collect();
+                let column = rows
+                    .iter()
+                    .map(|(code, _)| code.chars().count())
+                    .max()
+                    .unwrap_or(0)
+                    + 1;
+                for (line, (code, comment)) in lines[start..end].iter_mut().zip(rows) {
+                    let padding = column - code.chars().count();
+                    *line = format!("{code}{}{comment}", " ".repeat(padding));
+                }
+            }
+            start = end.max(start + 1);
+        }
+
+        let mut reflowed = lines.join("\n");
+        if code.ends_with('\n') {
+            reflowed.push('\n');
+        }
+        reflowed
+    }
+}
+
+/// The byte offsets where the line comments of `code` start, or `None` if it
+/// doesn't parse.
+fn line_comment_offsets(code: &str) -> Option<HashSet<usize>> {
+    let parsed = TypeScriptParser::parse_disambiguated(code, "comments.ts").ok()?;
+    let source_map = &parsed.parser.source_map;
+    let (leading, trailing) = parsed.parser.comments.borrow_all();
+
+    let offsets = leading
+        .values()
+        .chain(trailing.values())
+        .flatten()
+        .filter(|comment| comment.kind == CommentKind::Line)
+        .map(|comment| source_map.lookup_byte_offset(comment.span.lo).pos.0 as usize)
+        .collect();
+    Some(offsets)
+}
+
+/// The lines of `code` with the byte offset each starts at.
+fn lines_with_offsets(code: &str) -> impl Iterator<Item = (usize, &str)> {
+    code.split_inclusive('\n').scan(0, |offset, line| {
+        let start = *offset;
+        *offset += line.len();
+        Some((start, line.trim_end_matches(['\n', '\r'])))
+    })
+}
+
+/// [`split_trailing_comment`] for the line at `offset`, keeping only a comment
+/// starting at one of `comments`' offsets when given.
+fn trailing_comment<'a>(
+    offset: usize,
+    line: &'a str,
+    comments: Option<&HashSet<usize>>,
+) -> Option<(&'a str, &'a str)> {
+    let (code, comment) = split_trailing_comment(line)?;
+    let start = offset + line.len() - comment.len();
+    match comments {
+        Some(comments) if !comments.contains(&start) => None,
+        _ => Some((code, comment)),
+    }
+}
+
+/// Split a line into its code (without indentation) and a trailing `//`
+/// comment, if it has both. Quotes are tracked so `'http://x'` isn't mistaken
+/// for a comment.
+fn split_trailing_comment(line: &str) -> Option<(&str, &str)> {
+    let indent = leading_len(line);
+    let mut quote = None;
+    let mut escaped = false;
+
+    for (i, c) in line.char_indices().skip_while(|(i, _)| *i < indent) {
+        if let Some(open) = quote {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == open {
+                quote = None;
+            }
+            continue;
+        }
+
+        match c {
+            '\'' | '"' | '`' => quote = Some(c),
+            '/' if line[i..].starts_with("//") => {
+                let code = line[indent..i].trim_end();
+                // Needs whitespace before it, which `//` inside a regex
+                // literal like `/a\//` lacks
+                if code.is_empty() || code.len() == i - indent {
+                    return None;
+                }
+                return Some((code, &line[i..]));
+            }
+            '/' if line[i..].starts_with("/*") => return None,
+            _ => {}
+        }
+    }
+
+    None
+}
+
+fn leading_len(line: &str) -> usize {
+    line.len() - line.trim_start().len()
+}
+
+/// Column (in characters) where `comment`, a suffix of `line`, starts.
+fn comment_column(line: &str, comment: &str) -> usize {
+    line[..line.len() - comment.len()].chars().count()
+}
+
 /// Visitor to collect node positions in the generated code
 struct PositionCollector {
     source_lines: Vec<String>,
     positions: HashMap<u64, NodePosition>,
     current_class_name: Option<String>,
+    /// Keys for the literals dangling comments are stored under
+    literal_keys: LiteralKeys,
 }
 
 impl PositionCollector {
@@ -515,6 +880,7 @@ impl PositionCollector {
             source_lines: source.lines().map(String::from).collect(),
             positions: HashMap::new(),
             current_class_name: None,
+            literal_keys: LiteralKeys::default(),
         }
     }
 
@@ -542,6 +908,37 @@ impl PositionCollector {
         hasher.finish()
     }
 
+    /// Generate hash for JSX attribute (same as in CommentExtractor)
+    fn hash_jsx_attr(&self, attr: &JSXAttr) -> u64 {
+        use std::collections::hash_map::DefaultHasher;
+        use std::hash::{Hash, Hasher};
+
+        let mut hasher = DefaultHasher::new();
+        "jsx_attr".hash(&mut hasher);
+
+        match &attr.name {
+            JSXAttrName::Ident(ident) => {
+                ident.sym.hash(&mut hasher);
+            }
+            JSXAttrName::JSXNamespacedName(ns) => {
+                ns.ns.sym.hash(&mut hasher);
+                ns.name.sym.hash(&mut hasher);
+            }
+        }
+
+        hasher.finish()
+    }
+
+    /// Track where the members of an interface body or type literal ended up
+    fn collect_type_element_positions(&mut self, members: &[TsTypeElement]) {
+        let hashes = SemanticHasher::hash_type_elements(members);
+        for (member, hash) in members.iter().zip(hashes) {
+            if let Some(pos) = self.get_position_info(member.span()) {
+                self.positions.insert(hash, pos);
+            }
+        }
+    }
+
     fn get_position_info(&self, span: swc_common::Span) -> Option<NodePosition> {
         // Convert byte positions to line/column
         let mut byte_pos = 0;
@@ -594,7 +991,13 @@ impl Visit for PositionCollector {
                 }
             }
         }
-        module.visit_children_with(self);
+
+        for item in &module.body {
+            let hash = SemanticHasher::hash_module_item(item).map_or(0, |(hash, _)| hash);
+            self.literal_keys.enter(hash);
+            item.visit_with(self);
+            self.literal_keys.exit();
+        }
     }
 
     fn visit_class_decl(&mut self, class_decl: &ClassDecl) {
@@ -617,7 +1020,16 @@ impl Visit for PositionCollector {
         class.visit_children_with(self);
     }
 
+    fn visit_class_member(&mut self, member: &ClassMember) {
+        let hash = SemanticHasher::hash_class_member(member, "").map_or(0, |(hash, _)| hash);
+        self.literal_keys.enter(hash);
+        member.visit_children_with(self);
+        self.literal_keys.exit();
+    }
+
     fn visit_object_lit(&mut self, obj: &ObjectLit) {
+        let literal_key = self.literal_keys.key(SemanticHasher::hash_object_lit(obj));
+
         // Track object property positions
         for prop in &obj.props {
             if let PropOrSpread::Prop(prop) = prop {
@@ -627,9 +1039,45 @@ impl Visit for PositionCollector {
                 }
             }
         }
+
+        // Track the literal itself for comments dangling before its closing brace
+        if let Some(pos) = self.get_position_info(obj.span()) {
+            self.positions.insert(literal_key, pos);
+        }
         obj.visit_children_with(self);
     }
 
+    fn visit_prop(&mut self, prop: &Prop) {
+        self.literal_keys.enter(self.hash_prop(prop));
+        prop.visit_children_with(self);
+        self.literal_keys.exit();
+    }
+
+    fn visit_ts_interface_body(&mut self, body: &TsInterfaceBody) {
+        self.collect_type_element_positions(&body.body);
+        body.visit_children_with(self);
+    }
+
+    fn visit_ts_type_lit(&mut self, type_lit: &TsTypeLit) {
+        self.collect_type_element_positions(&type_lit.members);
+        type_lit.visit_children_with(self);
+    }
+
+    fn visit_array_lit(&mut self, array: &ArrayLit) {
+        // Track the literal for comments dangling before its closing bracket
+        let literal_key = self.literal_keys.key(SemanticHasher::hash_array_lit(array));
+        if let Some(pos) = self.get_position_info(array.span()) {
+            self.positions.insert(literal_key, pos);
+        }
+        array.visit_children_with(self);
+    }
+
+    fn visit_jsx_attr(&mut self, attr: &JSXAttr) {
+        self.literal_keys.enter(self.hash_jsx_attr(attr));
+        attr.visit_children_with(self);
+        self.literal_keys.exit();
+    }
+
     fn visit_arrow_expr(&mut self, arrow: &ArrowExpr) {
         // Track arrow expression position for inline parameter comments
         let hash = SemanticHasher::hash_node(arrow);
@@ -736,6 +1184,66 @@ const x = 42; // The answer
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn test_aligned_trailing_comments_stay_aligned() {
+        let source = r#"const WIDTH = 80;     // characters
+const TAB_SIZE = 4;   // spaces
+const URL = "http://example.com"; // unaligned
+const DEPTH = 3; // levels
+const NAME = 'x'; // name
+"#;
+        let tables = TrailingCommentTables::detect(source);
+        assert_eq!(tables.tables.len(), 2);
+
+        // Reordered and re-indented, the table is realigned to its new longest
+        // line; comments that were never aligned keep their single space
+        let generated = "const TAB_SIZE = 4; // spaces\nconst WIDTH = 80; // characters\nconst NAME = \"x\"; // name\n";
+        assert_eq!(
+            tables.reflow(generated.to_string()),
+            "const TAB_SIZE = 4; // spaces\nconst WIDTH = 80;   // characters\nconst NAME = \"x\"; // name\n"
+        );
+    }
+
+    #[test]
+    fn test_template_lines_are_not_table_rows() {
+        let source = "const WIDTH = 80;     // characters\nconst TAB_SIZE = 4;   // spaces\nconst CSS = `\na { margin: 0; } // characters\nb { padding: 0; } // spaces\n`;\n";
+        let tables = TrailingCommentTables::detect(source);
+
+        // The lines of the template end like the table's rows but are text,
+        // so they're left as written
+        let generated = "const WIDTH = 80; // characters\nconst TAB_SIZE = 4; // spaces\nconst CSS = `\na { margin: 0; } // characters\nb { padding: 0; } // spaces\n`;\n";
+        assert_eq!(
+            tables.reflow(generated.to_string()),
+            "const WIDTH = 80;   // characters\nconst TAB_SIZE = 4; // spaces\nconst CSS = `\na { margin: 0; } // characters\nb { padding: 0; } // spaces\n`;\n"
+        );
+    }
+
+    #[test]
+    fn test_repeated_comments_keep_their_own_tables() {
+        let source = "const WIDTH = 80;   // px\nconst HEIGHT = 6;   // rows\n\nconst GAP = 8;       // px\nconst MARGIN = 16;   // em\n";
+        let tables = TrailingCommentTables::detect(source);
+
+        // `// px` is a row of both tables, so it lines up with `// em` of the
+        // second without pulling in `// rows` of the first
+        let generated =
+            "const GAP = 8; // px\nconst MARGIN = 16; // em\nconst HEIGHT = 6; // rows\n";
+        assert_eq!(
+            tables.reflow(generated.to_string()),
+            "const GAP = 8;     // px\nconst MARGIN = 16; // em\nconst HEIGHT = 6; // rows\n"
+        );
+    }
+
+    #[test]
+    fn test_split_trailing_comment() {
+        assert_eq!(
+            split_trailing_comment("  const a = 1; // one"),
+            Some(("const a = 1;", "// one"))
+        );
+        assert_eq!(split_trailing_comment("const url = 'http://x';"), None);
+        assert_eq!(split_trailing_comment("// only a comment"), None);
+        assert_eq!(split_trailing_comment("const b = 2; /* block */"), None);
+    }
+
     #[test]
     fn test_reinsert_import_comments() {
         let source = r#"
@@ -1034,6 +1542,77 @@ const App = () => "Hello";"#;
         });
     }
 
+    #[test]
+    fn test_duplicate_span_comments_are_emitted_once() {
+        with_globals(|| {
+            // One source comment that reassignment attached twice: trailing on the
+            // previous node and leading on the next one
+            let comment = Comment {
+                kind: CommentKind::Line,
+                span: swc_common::Span::dummy_with_cmt(),
+                text: " Shared".into(),
+            };
+
+            let mut node_comments = HashMap::new();
+            node_comments.insert(
+                1,
+                vec![ExtractedComment {
+                    semantic_hash: 1,
+                    comment_type: CommentType::Trailing,
+                    comment: comment.clone(),
+                    index: 0,
+                    inline_context: None,
+                }],
+            );
+            node_comments.insert(
+                2,
+                vec![ExtractedComment {
+                    semantic_hash: 2,
+                    comment_type: CommentType::Leading,
+                    comment: comment.clone(),
+                    index: 0,
+                    inline_context: None,
+                }],
+            );
+
+            let mut reinserter = CommentReinserter::new(CommentExtractionResult {
+                node_comments,
+                standalone_comments: vec![StandaloneComment {
+                    comment,
+                    line: 0,
+                    context_depth: 0,
+                }],
+            });
+
+            for (hash, line) in [(1, 0), (2, 1)] {
+                reinserter.node_positions.insert(
+                    hash,
+                    NodePosition {
+                        start_line: line,
+                        start_column: 0,
+                        end_line: line,
+                        end_column: 10,
+                        indentation: String::new(),
+                    },
+                );
+            }
+
+            let insertion_points = reinserter.calculate_insertion_points().unwrap();
+
+            assert_eq!(insertion_points.len(), 1);
+            match &insertion_points[0].comment {
+                CommentWithType::Regular(extracted) => {
+                    assert_eq!(extracted.comment_type, CommentType::Leading);
+                }
+                CommentWithType::StandaloneGroup(_) => panic!("Expected the leading attachment"),
+            }
+
+            let code = reinserter
+                .insert_comments_into_code("const a = 1;\nconst b = 2;", insertion_points);
+            assert_eq!(code.matches("// Shared").count(), 1);
+        });
+    }
+
     #[test]
     fn test_insert_comments_into_code() {
         with_globals(|| {
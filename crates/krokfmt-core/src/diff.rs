@@ -0,0 +1,134 @@
+//! Structured line diffs between a source and its formatted form.
+//!
+//! Check mode only needs to know *whether* a file would change; editors and the
+//! playground want to show *what* would change without replacing the user's
+//! text, so the difference is reported as unified-diff style hunks.
+
+use serde::Serialize;
+use similar::{ChangeTag, TextDiff};
+
+/// Unchanged lines shown around each change, as in `diff -u`.
+pub const DEFAULT_CONTEXT_LINES: usize = 3;
+
+/// One line of a hunk.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", content = "text", rename_all = "lowercase")]
+pub enum DiffLine {
+    /// Unchanged, shown for context
+    Context(String),
+    /// Only in the original source
+    Removed(String),
+    /// Only in the formatted source
+    Added(String),
+}
+
+/// A contiguous region of changes with its surrounding context.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Hunk {
+    /// 1-based line in the original source the hunk starts at
+    pub old_start: usize,
+    /// Number of original lines the hunk covers
+    pub old_lines: usize,
+    /// 1-based line in the formatted source the hunk starts at
+    pub new_start: usize,
+    /// Number of formatted lines the hunk covers
+    pub new_lines: usize,
+    /// Lines without their line terminators
+    pub lines: Vec<DiffLine>,
+}
+
+/// Diff `original` against `formatted` line by line.
+///
+/// Changes closer than twice `context` lines apart share a hunk. An empty
+/// result means the sources are identical.
+pub fn diff_hunks(original: &str, formatted: &str, context: usize) -> Vec<Hunk> {
+    let diff = TextDiff::from_lines(original, formatted);
+
+    diff.grouped_ops(context)
+        .into_iter()
+        .filter_map(|group| {
+            let first = group.first()?;
+            let last = group.last()?;
+            let old_range = first.old_range().start..last.old_range().end;
+            let new_range = first.new_range().start..last.new_range().end;
+
+            let lines = group
+                .iter()
+                .flat_map(|op| diff.iter_changes(op))
+                .map(|change| {
+                    let text = change
+                        .value()
+                        .trim_end_matches('\n')
+                        .trim_end_matches('\r')
+                        .to_string();
+                    match change.tag() {
+                        ChangeTag::Equal => DiffLine::Context(text),
+                        ChangeTag::Delete => DiffLine::Removed(text),
+                        ChangeTag::Insert => DiffLine::Added(text),
+                    }
+                })
+                .collect();
+
+            Some(Hunk {
+                old_start: old_range.start + 1,
+                old_lines: old_range.len(),
+                new_start: new_range.start + 1,
+                new_lines: new_range.len(),
+                lines,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identical_sources_have_no_hunks() {
+        let source = "const a = 1;\nconst b = 2;\n";
+        assert_eq!(diff_hunks(source, source, DEFAULT_CONTEXT_LINES), vec![]);
+    }
+
+    #[test]
+    fn test_changes_are_grouped_with_context() {
+        let original = "import b from 'b';\nimport a from 'a';\n\nconst x = 1;\nconst y = 2;\nconst z = 3;\nconst w = 4;\nconst v = 5;\nconst u = 6;\nlet   last = 0;\n";
+        let formatted = "import a from 'a';\nimport b from 'b';\n\nconst x = 1;\nconst y = 2;\nconst z = 3;\nconst w = 4;\nconst v = 5;\nconst u = 6;\nlet last = 0;\n";
+
+        let hunks = diff_hunks(original, formatted, 1);
+        assert_eq!(hunks.len(), 2);
+
+        let first = &hunks[0];
+        assert_eq!(
+            (
+                first.old_start,
+                first.old_lines,
+                first.new_start,
+                first.new_lines
+            ),
+            (1, 3, 1, 3)
+        );
+        let count =
+            |is_kind: fn(&DiffLine) -> bool| first.lines.iter().filter(|l| is_kind(l)).count();
+        assert_eq!(count(|line| matches!(line, DiffLine::Removed(_))), 1);
+        assert_eq!(count(|line| matches!(line, DiffLine::Added(_))), 1);
+        assert_eq!(first.lines.last(), Some(&DiffLine::Context(String::new())));
+
+        assert_eq!((hunks[1].old_start, hunks[1].old_lines), (9, 2));
+        assert_eq!(
+            hunks[1].lines.last(),
+            Some(&DiffLine::Added("let last = 0;".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_hunks_serialize_with_tagged_lines() {
+        let hunks = diff_hunks("let  a = 1;\n", "let a = 1;\n", DEFAULT_CONTEXT_LINES);
+        let json = serde_json::to_value(&hunks).unwrap();
+
+        assert_eq!(json[0]["old_start"], 1);
+        assert_eq!(json[0]["lines"][0]["kind"], "removed");
+        assert_eq!(json[0]["lines"][1]["kind"], "added");
+        assert_eq!(json[0]["lines"][1]["text"], "let a = 1;");
+    }
+}
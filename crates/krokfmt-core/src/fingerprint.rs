@@ -0,0 +1,661 @@
+//! Semantic fingerprints for checking that formatting preserved behavior.
+//!
+//! A fingerprint is the debug rendering of a top-level item with everything
+//! formatting may legitimately change erased: spans, syntax contexts, literal
+//! raw text (quote style, numeric separators), parentheses, JSX whitespace, and
+//! the order of lists krokfmt is allowed to sort. Comparing the fingerprints of
+//! the input and the output then finds any construct whose meaning changed.
+//!
+//! Orders that are observable stay significant: object spreads and JSX spread
+//! attributes split their lists into segments that are only sorted internally,
+//! static and instance class fields with initializers each keep their relative
+//! order where one of those initializers reads the class or an instance or
+//! runs code, and numeric enums aren't sorted at all.
+//!
+//! An import of nothing but inline `type` specifiers counts as type-only. The
+//! import fixes a run enables, like merging imports of the same module or
+//! converting leading `const x = require('y')` declarations, and the removal
+//! of unused imports are applied to both sides before comparing; the ones it
+//! doesn't enable aren't, so an import rewritten without being asked to is a
+//! change.
+//!
+//! Top-level items are free to move, except that code running while the
+//! module loads must not end up reading a `const`, `let`, class or enum above
+//! its declaration, which the original didn't do.
+
+use anyhow::{Context, Result};
+use std::collections::{HashMap, HashSet};
+use swc_common::comments::Comment;
+use swc_common::{Span, Spanned, SyntaxContext, DUMMY_SP};
+use swc_ecma_ast::*;
+use swc_ecma_visit::{Visit, VisitMut, VisitMutWith, VisitWith};
+
+use crate::comment_classifier::has_directive;
+use crate::comment_formatter::{jsx_pragma_factories, KEEP_UNUSED_IMPORTS_DIRECTIVE};
+use crate::organizer::{has_independent_cases, is_order_sensitive};
+use crate::parser::TypeScriptParser;
+use crate::semantic_hash::SemanticHasher;
+use crate::transformer::{apply_import_fixes, hoist_inline_types, remove_unused_imports};
+use crate::FormatOptions;
+
+/// A top-level construct of the original source with no equivalent in the
+/// formatted output.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SemanticChange {
+    /// 1-based line of the construct in the original source
+    pub line: usize,
+    /// Short description of the construct, e.g. `function foo`
+    pub construct: String,
+}
+
+impl std::fmt::Display for SemanticChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}: formatting would change semantics of {}",
+            self.line, self.construct
+        )
+    }
+}
+
+/// Compare `original` and `formatted` and report every construct of `original`
+/// whose semantics didn't survive formatting.
+///
+/// Top-level items are matched as a multiset, since reordering them is what
+/// krokfmt does; an empty result means every item has an equivalent and the
+/// new order reads no binding before its declaration.
+///
+/// The import fixes and removals `options` enable change what imports say on
+/// purpose, so they're applied to both sides before comparing. Those it
+/// leaves off aren't, and an import they would have rewritten is a change.
+pub fn semantic_changes(
+    original: &str,
+    formatted: &str,
+    filename: &str,
+    options: &FormatOptions,
+) -> Result<Vec<SemanticChange>> {
+    let mut before = TypeScriptParser::parse_disambiguated(original, filename)
+        .context("Failed to parse the original source")?;
+    let mut after = TypeScriptParser::parse_disambiguated(formatted, filename)
+        .context("Failed to parse the formatted source")?;
+
+    let comments: Vec<Comment> = {
+        let (leading, trailing) = before.parser.comments.borrow_all();
+        leading
+            .values()
+            .chain(trailing.values())
+            .flatten()
+            .cloned()
+            .collect()
+    };
+    let remove_unused =
+        options.remove_unused_imports && !has_directive(&comments, KEEP_UNUSED_IMPORTS_DIRECTIVE);
+    let jsx_factories = jsx_pragma_factories(&comments);
+    for module in [&mut before.module, &mut after.module] {
+        apply_import_fixes(module, &options.import_fixes);
+        if remove_unused {
+            remove_unused_imports(module, &jsx_factories);
+        }
+    }
+
+    let mut remaining: HashMap<String, usize> = HashMap::new();
+    for item in &after.module.body {
+        *remaining.entry(fingerprint(item)).or_default() += 1;
+    }
+
+    let mut changes = Vec::new();
+    for item in &before.module.body {
+        match remaining.get_mut(&fingerprint(item)) {
+            Some(count) if *count > 0 => *count -= 1,
+            _ => changes.push(SemanticChange {
+                line: before
+                    .parser
+                    .source_map
+                    .lookup_char_pos(item.span().lo)
+                    .line,
+                construct: describe(item),
+            }),
+        }
+    }
+
+    // Dependency analysis decides where declarations may move; a mistake there
+    // leaves every item intact but throws a ReferenceError on load
+    let before_reads = reads_before_declaration(&before.module);
+    for (item, name) in reads_before_declaration(&after.module) {
+        if before_reads.contains(&(item.clone(), name.clone())) {
+            continue;
+        }
+        if let Some(original) = before
+            .module
+            .body
+            .iter()
+            .find(|original| fingerprint(original) == item)
+        {
+            changes.push(SemanticChange {
+                line: before
+                    .parser
+                    .source_map
+                    .lookup_char_pos(original.span().lo)
+                    .line,
+                construct: format!(
+                    "{} (it would read `{name}` before its declaration)",
+                    describe(original)
+                ),
+            });
+        }
+    }
+    changes.sort_by_key(|change| change.line);
+
+    Ok(changes)
+}
+
+/// Every top-level item, by fingerprint, whose code run at load time reads a
+/// binding declared further down, with that binding's name.
+///
+/// Only bindings that can't be read before their declaration count: `const`,
+/// `let` and classes throw, and enums are still `undefined`. Functions are
+/// hoisted whole, and function bodies only run when called.
+fn reads_before_declaration(module: &Module) -> HashSet<(String, String)> {
+    let mut declared_at = HashMap::new();
+    for (index, item) in module.body.iter().enumerate() {
+        for name in lexical_names(item) {
+            declared_at.entry(name).or_insert(index);
+        }
+    }
+
+    let mut reads = HashSet::new();
+    for (index, item) in module.body.iter().enumerate() {
+        let mut finder = LoadTimeReads::default();
+        item.visit_with(&mut finder);
+        for name in finder.names {
+            if declared_at.get(&name).is_some_and(|&at| at > index) {
+                reads.insert((fingerprint(item), name));
+            }
+        }
+    }
+    reads
+}
+
+/// The names a top-level item declares that are unusable before it runs.
+fn lexical_names(item: &ModuleItem) -> Vec<String> {
+    let decl = match item {
+        ModuleItem::Stmt(Stmt::Decl(decl))
+        | ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl { decl, .. })) => decl,
+        _ => return Vec::new(),
+    };
+
+    match decl {
+        Decl::Var(var) if var.kind != VarDeclKind::Var => {
+            let mut finder = BindingNames::default();
+            for declarator in &var.decls {
+                declarator.name.visit_with(&mut finder);
+            }
+            finder.0
+        }
+        Decl::Class(class) => vec![class.ident.sym.to_string()],
+        Decl::TsEnum(ts_enum) if !ts_enum.is_const => vec![ts_enum.id.sym.to_string()],
+        _ => Vec::new(),
+    }
+}
+
+/// Collects the names bound by a pattern.
+#[derive(Default)]
+struct BindingNames(Vec<String>);
+
+impl Visit for BindingNames {
+    fn visit_binding_ident(&mut self, binding: &BindingIdent) {
+        self.0.push(binding.id.sym.to_string());
+    }
+
+    // Default values aren't bindings
+    fn visit_expr(&mut self, _: &Expr) {}
+}
+
+/// Collects the identifiers code reads while the module loads, leaving out
+/// function bodies, instance fields and types.
+#[derive(Default)]
+struct LoadTimeReads {
+    names: HashSet<String>,
+}
+
+impl Visit for LoadTimeReads {
+    fn visit_ident(&mut self, ident: &Ident) {
+        self.names.insert(ident.sym.to_string());
+    }
+
+    fn visit_function(&mut self, _: &Function) {}
+
+    fn visit_arrow_expr(&mut self, _: &ArrowExpr) {}
+
+    fn visit_constructor(&mut self, _: &Constructor) {}
+
+    fn visit_class_prop(&mut self, prop: &ClassProp) {
+        prop.key.visit_with(self);
+        if prop.is_static {
+            prop.value.visit_with(self);
+        }
+    }
+
+    fn visit_private_prop(&mut self, prop: &PrivateProp) {
+        if prop.is_static {
+            prop.value.visit_with(self);
+        }
+    }
+
+    fn visit_ts_type(&mut self, _: &TsType) {}
+
+    fn visit_ts_interface_decl(&mut self, _: &TsInterfaceDecl) {}
+
+    fn visit_ts_type_alias_decl(&mut self, _: &TsTypeAliasDecl) {}
+}
+
+/// Fingerprint a single top-level item.
+pub fn fingerprint(item: &ModuleItem) -> String {
+    let mut item = item.clone();
+    item.visit_mut_with(&mut Normalizer::default());
+    format!("{item:?}")
+}
+
+fn describe(item: &ModuleItem) -> String {
+    match item {
+        ModuleItem::ModuleDecl(ModuleDecl::Import(import)) => {
+            format!("import from '{}'", import.src.value)
+        }
+        ModuleItem::Stmt(Stmt::Decl(Decl::Fn(_)))
+        | ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+            decl: Decl::Fn(_), ..
+        })) => {
+            let (_, name) = SemanticHasher::hash_module_item(item).unwrap_or_default();
+            format!("function {name}")
+        }
+        ModuleItem::Stmt(Stmt::Decl(Decl::Class(_)))
+        | ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+            decl: Decl::Class(_),
+            ..
+        })) => {
+            let (_, name) = SemanticHasher::hash_module_item(item).unwrap_or_default();
+            format!("class {name}")
+        }
+        ModuleItem::Stmt(Stmt::Decl(_)) | ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(_)) => {
+            match SemanticHasher::hash_module_item(item) {
+                Some((_, name)) => format!("`{name}`"),
+                None => "declaration".to_string(),
+            }
+        }
+        ModuleItem::ModuleDecl(_) => "export".to_string(),
+        ModuleItem::Stmt(_) => "statement".to_string(),
+    }
+}
+
+/// Erases everything formatting may change without changing behavior.
+#[derive(Default)]
+struct Normalizer {
+    /// The name of the class about to be visited, which its fields can read
+    class_name: Option<Ident>,
+}
+
+impl Normalizer {
+    /// Sort each run of items between barriers by its fingerprint, leaving the
+    /// barriers (and so which side of them every item is on) in place.
+    fn sort_segments<T: std::fmt::Debug>(items: &mut [T], is_barrier: impl Fn(&T) -> bool) {
+        for segment in items.split_mut(is_barrier) {
+            segment.sort_by_cached_key(|item| format!("{item:?}"));
+        }
+    }
+
+    /// Sort the members of an interface or type literal. The order of call
+    /// and construct signatures picks the overload, so they keep theirs.
+    fn sort_type_elements(members: &mut Vec<TsTypeElement>) {
+        let (mut signatures, mut rest): (Vec<_>, Vec<_>) = members.drain(..).partition(|member| {
+            matches!(
+                member,
+                TsTypeElement::TsCallSignatureDecl(_) | TsTypeElement::TsConstructSignatureDecl(_)
+            )
+        });
+        rest.sort_by_cached_key(|member| format!("{member:?}"));
+        signatures.extend(rest);
+        *members = signatures;
+    }
+}
+
+impl VisitMut for Normalizer {
+    fn visit_mut_span(&mut self, span: &mut Span) {
+        *span = DUMMY_SP;
+    }
+
+    fn visit_mut_syntax_context(&mut self, ctxt: &mut SyntaxContext) {
+        *ctxt = SyntaxContext::empty();
+    }
+
+    fn visit_mut_str(&mut self, s: &mut Str) {
+        s.raw = None;
+        s.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_number(&mut self, n: &mut Number) {
+        n.raw = None;
+        n.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_big_int(&mut self, b: &mut BigInt) {
+        b.raw = None;
+        b.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_jsx_text(&mut self, text: &mut JSXText) {
+        // JSX collapses runs of whitespace, so rewrapped text means the same
+        let collapsed = text.value.split_whitespace().collect::<Vec<_>>().join(" ");
+        text.value = collapsed.as_str().into();
+        text.raw = collapsed.as_str().into();
+        text.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_expr(&mut self, expr: &mut Expr) {
+        while let Expr::Paren(paren) = expr {
+            let inner = *paren.expr.clone();
+            *expr = inner;
+        }
+        expr.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_object_lit(&mut self, obj: &mut ObjectLit) {
+        obj.visit_mut_children_with(self);
+        // Later keys override earlier ones, so spreads and computed keys (which
+        // may collide with anything) pin what comes before and after them
+        Self::sort_segments(&mut obj.props, |prop| match prop {
+            PropOrSpread::Spread(_) => true,
+            PropOrSpread::Prop(prop) => matches!(
+                &**prop,
+                Prop::KeyValue(KeyValueProp {
+                    key: PropName::Computed(_),
+                    ..
+                })
+            ),
+        });
+    }
+
+    fn visit_mut_object_pat(&mut self, pat: &mut ObjectPat) {
+        pat.visit_mut_children_with(self);
+        Self::sort_segments(&mut pat.props, |prop| {
+            matches!(prop, ObjectPatProp::Rest(_))
+        });
+    }
+
+    fn visit_mut_class_decl(&mut self, class_decl: &mut ClassDecl) {
+        self.class_name = Some(class_decl.ident.clone());
+        class_decl.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_class_expr(&mut self, class_expr: &mut ClassExpr) {
+        self.class_name = class_expr.ident.clone();
+        class_expr.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_class(&mut self, class: &mut Class) {
+        let class_name = self.class_name.take();
+        class.visit_mut_children_with(self);
+
+        // Initializers run in declaration order, which only shows where one of
+        // them reads the class or an instance or runs code. There, the static
+        // or instance members with an initializer keep their relative order;
+        // everything else is free. Static ones run when the class is defined
+        // and instance ones on construction, so the two never interleave.
+        let initialized_static = |member: &ClassMember| match member {
+            ClassMember::ClassProp(prop) => prop.value.as_ref().map(|_| prop.is_static),
+            ClassMember::PrivateProp(prop) => prop.value.as_ref().map(|_| prop.is_static),
+            ClassMember::StaticBlock(_) => Some(true),
+            _ => None,
+        };
+        let order_sensitive = |is_static: bool| {
+            class.body.iter().any(|member| {
+                initialized_static(member) == Some(is_static)
+                    && is_order_sensitive(member, class_name.as_ref())
+            })
+        };
+        let keeps_order = [order_sensitive(false), order_sensitive(true)];
+
+        let (mut body, mut rest): (Vec<_>, Vec<_>) = class.body.drain(..).partition(|member| {
+            initialized_static(member).is_some_and(|is_static| keeps_order[is_static as usize])
+        });
+        // Static members first, each side in its written order
+        body.sort_by_key(|member| initialized_static(member) != Some(true));
+        rest.sort_by_cached_key(|member| format!("{member:?}"));
+        body.extend(rest);
+        class.body = body;
+    }
+
+    fn visit_mut_jsx_opening_element(&mut self, element: &mut JSXOpeningElement) {
+        element.visit_mut_children_with(self);
+        Self::sort_segments(&mut element.attrs, |attr| {
+            matches!(attr, JSXAttrOrSpread::SpreadElement(_))
+        });
+    }
+
+    fn visit_mut_import_decl(&mut self, import: &mut ImportDecl) {
+        import.visit_mut_children_with(self);
+        // `import { type A }` and `import type { A }` import the same type
+        hoist_inline_types(import);
+        import
+            .specifiers
+            .sort_by_cached_key(|spec| format!("{spec:?}"));
+    }
+
+    fn visit_mut_named_export(&mut self, export: &mut NamedExport) {
+        export.visit_mut_children_with(self);
+        export
+            .specifiers
+            .sort_by_cached_key(|spec| format!("{spec:?}"));
+    }
+
+    fn visit_mut_ts_union_type(&mut self, union: &mut TsUnionType) {
+        union.visit_mut_children_with(self);
+        union.types.sort_by_cached_key(|ty| format!("{ty:?}"));
+    }
+
+    fn visit_mut_ts_intersection_type(&mut self, intersection: &mut TsIntersectionType) {
+        intersection.visit_mut_children_with(self);
+        intersection
+            .types
+            .sort_by_cached_key(|ty| format!("{ty:?}"));
+    }
+
+    fn visit_mut_switch_stmt(&mut self, switch: &mut SwitchStmt) {
+        switch.visit_mut_children_with(self);
+        // Cases that can't fall through into each other may go in any order,
+        // save a `default` ending the switch
+        if has_independent_cases(&switch.cases) {
+            let tested = switch.cases.len()
+                - usize::from(switch.cases[switch.cases.len() - 1].test.is_none());
+            switch.cases[..tested].sort_by_cached_key(|case| format!("{case:?}"));
+        }
+    }
+
+    fn visit_mut_ts_interface_body(&mut self, body: &mut TsInterfaceBody) {
+        body.visit_mut_children_with(self);
+        Self::sort_type_elements(&mut body.body);
+    }
+
+    fn visit_mut_ts_type_lit(&mut self, type_lit: &mut TsTypeLit) {
+        type_lit.visit_mut_children_with(self);
+        Self::sort_type_elements(&mut type_lit.members);
+    }
+
+    fn visit_mut_ts_enum_decl(&mut self, ts_enum: &mut TsEnumDecl) {
+        ts_enum.visit_mut_children_with(self);
+
+        // Only string enums are order-free; numeric members count up by position
+        let all_strings = ts_enum
+            .members
+            .iter()
+            .all(|member| matches!(member.init.as_deref(), Some(Expr::Lit(Lit::Str(_)))));
+        if all_strings {
+            ts_enum
+                .members
+                .sort_by_cached_key(|member| format!("{member:?}"));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transformer::ImportFixes;
+
+    fn changes(original: &str, formatted: &str) -> Vec<SemanticChange> {
+        changes_with(original, formatted, &FormatOptions::default())
+    }
+
+    fn changes_with(
+        original: &str,
+        formatted: &str,
+        options: &FormatOptions,
+    ) -> Vec<SemanticChange> {
+        semantic_changes(original, formatted, "test.ts", options).unwrap()
+    }
+
+    #[test]
+    fn test_reordering_and_layout_are_not_changes() {
+        let original = r#"
+import { b, a } from './mod';
+const zebra = { y: 'y', x: 0x10 };
+export function apple(value: 'b' | 'a') { return (value); }
+class Model { save() {} constructor() {} }
+interface Props { title: string; (): void; id: number }
+"#;
+        let formatted = r#"
+import { a, b } from "./mod";
+
+interface Props {
+  (): void;
+  id: number;
+  title: string;
+}
+
+export function apple(value: "a" | "b") {
+  return value;
+}
+
+class Model {
+  constructor() {}
+  save() {}
+}
+const zebra = { x: 16, y: "y" };
+"#;
+
+        assert_eq!(changes(original, formatted), vec![]);
+
+        // Merged imports of one module bind the same names
+        let merging = FormatOptions {
+            import_fixes: ImportFixes {
+                merge_duplicates: true,
+                ..ImportFixes::default()
+            },
+            ..FormatOptions::default()
+        };
+        assert_eq!(
+            changes_with(
+                "import { b } from './mod';\nimport { a } from './mod';\n",
+                "import { a, b } from './mod';\n",
+                &merging
+            ),
+            vec![]
+        );
+
+        // Static and instance fields initialize at different times
+        assert_eq!(
+            changes(
+                "class Config { total = 0; static version = 1; }\n",
+                "class Config { static version = 1; total = 0; }\n"
+            ),
+            vec![]
+        );
+
+        // Nothing can tell literal initializers apart by when they ran
+        assert_eq!(
+            changes(
+                "class Config { zoo = 'zoo'; bar = 'bar'; }\n",
+                "class Config { bar = 'bar'; zoo = 'zoo'; }\n"
+            ),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_import_transforms_count_only_when_enabled() {
+        let original = "import { used, unused } from './mod';\nexport const value = used;\n";
+        let formatted = "import { used } from './mod';\nexport const value = used;\n";
+
+        assert_eq!(
+            changes(original, formatted),
+            vec![SemanticChange {
+                line: 1,
+                construct: "import from './mod'".to_string(),
+            }]
+        );
+
+        let removing = FormatOptions {
+            remove_unused_imports: true,
+            ..FormatOptions::default()
+        };
+        assert_eq!(changes_with(original, formatted, &removing), vec![]);
+
+        // Unless the file asks to keep them
+        let kept = format!("// krokfmt-keep-unused-imports\n{original}");
+        assert_eq!(changes_with(&kept, formatted, &removing).len(), 1);
+    }
+
+    #[test]
+    fn test_reads_before_declaration_are_reported() {
+        let original = r#"
+const defaults = { debug: false };
+export const config = { ...defaults };
+export function load() { return later; }
+const later = 1;
+"#;
+        let formatted = r#"
+export const config = { ...defaults };
+export function load() { return later; }
+const defaults = { debug: false };
+const later = 1;
+"#;
+
+        let reported: Vec<_> = changes(original, formatted)
+            .into_iter()
+            .map(|change| (change.line, change.construct))
+            .collect();
+        assert_eq!(
+            reported,
+            vec![(
+                3,
+                "`config` (it would read `defaults` before its declaration)".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_behavior_changes_are_reported() {
+        let original = r#"
+const merged = { ...defaults, debug: true };
+class Counter { count = 0; double = this.count * 2; }
+enum Level { Low, High }
+"#;
+        let formatted = r#"
+const merged = { debug: true, ...defaults };
+class Counter { double = this.count * 2; count = 0; }
+enum Level { High, Low }
+"#;
+
+        let reported: Vec<_> = changes(original, formatted)
+            .into_iter()
+            .map(|change| (change.line, change.construct))
+            .collect();
+        assert_eq!(
+            reported,
+            vec![
+                (2, "`merged`".to_string()),
+                (3, "class Counter".to_string()),
+                (4, "`Level`".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,211 @@
+//! Guarantees a formatter has to keep, checked on its actual output.
+//!
+//! Formatting the output again must not change it (idempotency), and every
+//! comment of the input must still be in the output. Violations of either are
+//! krokfmt bugs rather than formatting needs, which a strict CI check reports
+//! instead of letting them land in a commit.
+
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use swc_common::comments::Comment;
+
+use crate::comment_classifier::is_import_group_banner;
+use crate::parser::{ParsedSource, TypeScriptParser};
+
+/// A broken formatting guarantee.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    /// Formatting the output again changes it, first at `line` (1-based) of
+    /// the output
+    NotIdempotent { line: usize },
+    /// A comment at `line` of the original has no counterpart in the output
+    CommentLost { line: usize, text: String },
+    /// A comment at `line` of the output has no counterpart in the original
+    CommentAdded { line: usize, text: String },
+}
+
+impl std::fmt::Display for Violation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Violation::NotIdempotent { line } => write!(
+                f,
+                "line {line}: formatting the output again would change it"
+            ),
+            Violation::CommentLost { line, text } => {
+                write!(f, "line {line}: formatting would drop comment `{text}`")
+            }
+            Violation::CommentAdded { line, text } => write!(
+                f,
+                "line {line} of the output: formatting would add comment `{text}`"
+            ),
+        }
+    }
+}
+
+/// Compare the output of formatting with the result of formatting it again.
+pub fn idempotency_violation(formatted: &str, reformatted: &str) -> Option<Violation> {
+    if formatted == reformatted {
+        return None;
+    }
+
+    let line = formatted
+        .lines()
+        .zip(reformatted.lines())
+        .position(|(before, after)| before != after)
+        .unwrap_or_else(|| formatted.lines().count().min(reformatted.lines().count()));
+    Some(Violation::NotIdempotent { line: line + 1 })
+}
+
+/// Compare the comments of `original` and `formatted` and report every one
+/// that only one of them has.
+///
+/// Comments are matched as a multiset, since moving them along with their
+/// code is what krokfmt does. Text is compared with what formatting may
+/// legitimately change erased: surrounding whitespace and the `*` gutter of
+/// block comments. Import group banners are left out on both sides, because
+/// krokfmt drops them on purpose and may emit canonical ones instead.
+pub fn comment_changes(original: &str, formatted: &str, filename: &str) -> Result<Vec<Violation>> {
+    let before = TypeScriptParser::parse_disambiguated(original, filename)
+        .context("Failed to parse the original source")?;
+    let after = TypeScriptParser::parse_disambiguated(formatted, filename)
+        .context("Failed to parse the formatted source")?;
+
+    let mut remaining: HashMap<String, Vec<(usize, String)>> = HashMap::new();
+    for (line, comment) in comments(&after) {
+        remaining
+            .entry(normalize(&comment.text))
+            .or_default()
+            .push((line, comment.text.trim().to_string()));
+    }
+
+    let mut changes = Vec::new();
+    for (line, comment) in comments(&before) {
+        match remaining.get_mut(&normalize(&comment.text)) {
+            Some(matches) if !matches.is_empty() => {
+                matches.pop();
+            }
+            _ => changes.push(Violation::CommentLost {
+                line,
+                text: comment.text.trim().to_string(),
+            }),
+        }
+    }
+    changes.extend(
+        remaining
+            .into_values()
+            .flatten()
+            .map(|(line, text)| Violation::CommentAdded { line, text }),
+    );
+    changes.sort_by_key(|change| match change {
+        Violation::CommentLost { line, .. } => (0, *line),
+        Violation::CommentAdded { line, .. } => (1, *line),
+        Violation::NotIdempotent { line } => (2, *line),
+    });
+
+    Ok(changes)
+}
+
+/// Every comment of `parsed` other than import group banners, with its
+/// 1-based line, in source order.
+fn comments(parsed: &ParsedSource) -> Vec<(usize, Comment)> {
+    let (leading, trailing) = parsed.parser.comments.borrow_all();
+    let mut comments: Vec<Comment> = leading
+        .values()
+        .chain(trailing.values())
+        .flatten()
+        .filter(|comment| !is_import_group_banner(comment))
+        .cloned()
+        .collect();
+    comments.sort_by_key(|comment| comment.span.lo);
+
+    comments
+        .into_iter()
+        .map(|comment| {
+            let line = parsed
+                .parser
+                .source_map
+                .lookup_char_pos(comment.span.lo)
+                .line;
+            (line, comment)
+        })
+        .collect()
+}
+
+fn normalize(text: &str) -> String {
+    text.lines()
+        .map(|line| {
+            let line = line.trim();
+            line.strip_prefix('*').unwrap_or(line).trim()
+        })
+        .filter(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_idempotency_violation_points_at_first_difference() {
+        assert_eq!(idempotency_violation("a\nb\n", "a\nb\n"), None);
+        assert_eq!(
+            idempotency_violation("a\nb\nc\n", "a\nB\nc\n"),
+            Some(Violation::NotIdempotent { line: 2 })
+        );
+        assert_eq!(
+            idempotency_violation("a\n", "a\nb\n"),
+            Some(Violation::NotIdempotent { line: 2 })
+        );
+    }
+
+    #[test]
+    fn test_moved_and_reindented_comments_are_retained() {
+        let original = r#"// External
+import b from 'b';
+import a from 'a';
+
+/**
+   * Docs for y
+   */
+const y = 2; // trailing
+// About x
+const x = 1;
+"#;
+        let formatted = r#"import a from 'a';
+import b from 'b';
+
+// About x
+const x = 1;
+/**
+ * Docs for y
+ */
+const y = 2; // trailing
+"#;
+
+        assert_eq!(
+            comment_changes(original, formatted, "test.ts").unwrap(),
+            vec![]
+        );
+    }
+
+    #[test]
+    fn test_dropped_and_added_comments_are_reported() {
+        let original = "// About a\nconst a = 1;\nconst b = 2; // keep\n";
+        let formatted = "const a = 1;\n/* new */\nconst b = 2; // keep\n";
+
+        assert_eq!(
+            comment_changes(original, formatted, "test.ts").unwrap(),
+            vec![
+                Violation::CommentLost {
+                    line: 1,
+                    text: "About a".to_string()
+                },
+                Violation::CommentAdded {
+                    line: 2,
+                    text: "new".to_string()
+                },
+            ]
+        );
+    }
+}
@@ -0,0 +1,245 @@
+//! krokfmt's formatting pipeline: parse, organize, and format TypeScript.
+//!
+//! Everything here works on strings in memory. Finding files, reading
+//! `krokfmt.toml` and talking to the terminal belong to the `krokfmt` CLI, so
+//! this crate can be embedded anywhere, WASM included.
+
+pub mod biome_formatter;
+pub mod blank_line_normalizer;
+pub mod codegen;
+pub mod comment_classifier;
+pub mod comment_extractor;
+pub mod comment_formatter;
+pub mod comment_reinserter;
+pub mod diff;
+pub mod fingerprint;
+pub mod invariants;
+pub mod line_ending;
+mod logging;
+pub mod node_selection;
+pub mod organizer;
+pub mod package_json_formatter;
+pub mod parser;
+pub mod pipeline;
+pub mod rules;
+pub mod segmented;
+pub mod selective_comment_handler;
+pub mod semantic_hash;
+pub mod transformer;
+pub mod versioning;
+
+pub use line_ending::LineEnding;
+pub use semantic_hash::{hash_source, SemanticHasher, SEMANTIC_HASH_VERSION};
+
+use anyhow::Result;
+use std::borrow::Cow;
+
+use crate::logging::debug_span;
+use crate::parser::JsxDetection;
+use crate::rules::RuleSet;
+use crate::transformer::{ImportFixes, ImportProfile, ProjectLayout};
+
+/// What [`format_typescript_with_options`] applies.
+///
+/// krokfmt stays opinionated: rule groups can be switched off and the output
+/// fitted to where it goes, but there's nothing to pick between styles. The
+/// opt-in behaviors behind the CLI's flags are all off by default.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormatOptions {
+    /// Rule groups in effect; all of them by default
+    pub rules: RuleSet,
+    /// Line breaks of the output; LF by default
+    pub line_ending: LineEnding,
+    /// Whether non-empty output ends with a line break; it does by default
+    pub final_newline: bool,
+    /// Whether TypeScript is parsed with JSX; decided from the filename by
+    /// default
+    pub jsx: JsxDetection,
+    /// How imports are ordered and grouped
+    pub import_profile: ImportProfile,
+    /// The project's own packages and path aliases, for grouping imports
+    pub project_layout: ProjectLayout,
+    /// Also alphabetize identifier-only React hook dependency arrays
+    pub sort_hook_dependencies: bool,
+    /// Keep `import React from 'react'` first
+    pub react_first: bool,
+    /// Put type-only imports after the value imports of each group
+    pub group_type_imports: bool,
+    /// Gather SCREAMING_CASE primitive constants at the top of each
+    /// visibility group
+    pub group_constants: bool,
+    /// Sort re-export statements in every file
+    pub sort_re_exports: bool,
+    /// The opt-in import hygiene fixes to apply
+    pub import_fixes: ImportFixes,
+    /// Drop imported names the file never uses
+    pub remove_unused_imports: bool,
+    /// Label each import group with a banner comment
+    pub import_group_banners: bool,
+    /// Indent like the source already does, instead of with two spaces
+    pub detect_indentation: bool,
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            rules: RuleSet::default(),
+            line_ending: LineEnding::default(),
+            final_newline: true,
+            jsx: JsxDetection::default(),
+            import_profile: ImportProfile::default(),
+            project_layout: ProjectLayout::default(),
+            sort_hook_dependencies: false,
+            react_first: false,
+            group_type_imports: false,
+            group_constants: false,
+            sort_re_exports: false,
+            import_fixes: ImportFixes::default(),
+            remove_unused_imports: false,
+            import_group_banners: false,
+            detect_indentation: false,
+        }
+    }
+}
+
+/// Format TypeScript/TSX code with krokfmt's opinionated rules.
+///
+/// This is the main entry point for programmatic use of krokfmt.
+/// It applies the full formatting pipeline: parsing, organizing, and final formatting.
+pub fn format_typescript(source: &str, filename: &str) -> Result<String> {
+    format_typescript_with_options(source, filename, &FormatOptions::default())
+}
+
+/// Like [`format_typescript`], with some rule groups switched off.
+///
+/// This is what a project's `krokfmt.toml` amounts to once the CLI has worked
+/// out which rules apply to a file.
+pub fn format_typescript_with_options(
+    source: &str,
+    filename: &str,
+    options: &FormatOptions,
+) -> Result<String> {
+    format(source, filename, true, options).map(Cow::into_owned)
+}
+
+/// Like [`format_typescript`], but hands `source` back borrowed when it's
+/// already formatted.
+///
+/// The whole pipeline still runs, so this is no faster than
+/// [`format_typescript`]: the output is compared with `source` once it's
+/// done. What it saves is the caller's side of that. A `Cow::Borrowed` result
+/// already says nothing changed, and the formatted copy is dropped here
+/// rather than kept around next to the file.
+pub fn format_typescript_cow<'a>(source: &'a str, filename: &str) -> Result<Cow<'a, str>> {
+    format(source, filename, true, &FormatOptions::default())
+}
+
+/// Format generated TypeScript/TSX code without reorganizing it.
+///
+/// Meant for template-based code generators that already emit declarations in
+/// an intentional order but want consistent whitespace. Only layout is applied:
+/// indentation, spacing, and blank lines between import groups. It is
+/// guaranteed that nothing is reordered: imports, declarations, class members,
+/// object keys and every other list keep the order they were generated in.
+pub fn format_generated(source: &str, filename: &str) -> Result<String> {
+    format(source, filename, false, &FormatOptions::default()).map(Cow::into_owned)
+}
+
+/// Format only the top-level statements touching lines `start_line` through
+/// `end_line` (1-based, inclusive), leaving the rest of `source` byte for byte
+/// as it was.
+///
+/// For format-selection in editors. The whole file is still formatted to work
+/// out what the statements in range become, but they stay where they were:
+/// nothing moves in or out of the range. Imports count as one block, so a
+/// range touching any import formats all of them.
+pub fn format_typescript_range(
+    source: &str,
+    filename: &str,
+    start_line: usize,
+    end_line: usize,
+) -> Result<String> {
+    let formatted = format_typescript(source, filename)?;
+    node_selection::splice_lines(source, &formatted, filename, start_line..=end_line)
+}
+
+/// Report what [`format_typescript`] would change, as line hunks.
+///
+/// Lets an editor or the playground show the changes alongside the user's
+/// text instead of replacing it. An empty result means the source is already
+/// formatted.
+pub fn check_typescript(source: &str, filename: &str) -> Result<Vec<diff::Hunk>> {
+    match format_typescript_cow(source, filename)? {
+        // No need to diff a file against itself
+        Cow::Borrowed(_) => Ok(Vec::new()),
+        Cow::Owned(formatted) => Ok(diff::diff_hunks(
+            source,
+            &formatted,
+            diff::DEFAULT_CONTEXT_LINES,
+        )),
+    }
+}
+
+/// Format `source` twice and report where the second pass changed the first
+/// pass's output.
+///
+/// `None` means formatting is idempotent for this source. Anything else is a
+/// krokfmt bug worth reporting along with the source.
+pub fn verify_idempotent(source: &str, filename: &str) -> Result<Option<invariants::Violation>> {
+    verify_idempotent_with_options(source, filename, &FormatOptions::default())
+}
+
+/// Like [`verify_idempotent`], formatting with some rule groups switched off.
+///
+/// Which rules run changes what the first pass produces, so a project's rules
+/// have to be verified as they're configured.
+pub fn verify_idempotent_with_options(
+    source: &str,
+    filename: &str,
+    options: &FormatOptions,
+) -> Result<Option<invariants::Violation>> {
+    let formatted = format_typescript_with_options(source, filename, options)?;
+    let reformatted = format_typescript_with_options(&formatted, filename, options)?;
+    Ok(invariants::idempotency_violation(&formatted, &reformatted))
+}
+
+fn format<'a>(
+    source: &'a str,
+    filename: &str,
+    reorganize: bool,
+    options: &FormatOptions,
+) -> Result<Cow<'a, str>> {
+    let _span = debug_span!("format", file = filename).entered();
+
+    let line_break = options.line_ending.line_break(source);
+    let lf_source = line_ending::to_lf(source);
+
+    // Files named .ts may still hold JSX (the playground always says .ts), but
+    // unless the options say otherwise, only a failed TS parse is allowed to
+    // switch to TSX
+    let parsed = pipeline::parse_with_jsx(&lf_source, filename, options.jsx)?;
+    let organized = pipeline::organize_with(parsed, options, reorganize)?;
+    let generated = pipeline::reinsert_comments(organized)?;
+    let formatted = pipeline::biome_format(generated)?;
+    let formatted = end_lines(formatted, line_break, options.final_newline);
+
+    // Only decided now: nothing short of formatting tells whether Biome would
+    // lay the source out differently
+    Ok(if formatted == source {
+        Cow::Borrowed(source)
+    } else {
+        Cow::Owned(formatted)
+    })
+}
+
+/// Give `formatted`, which breaks lines with LF, the `line_break` and final
+/// line break asked for.
+fn end_lines(mut formatted: String, line_break: &str, final_newline: bool) -> String {
+    if !final_newline {
+        formatted.truncate(formatted.trim_end_matches('\n').len());
+    } else if !formatted.is_empty() && !formatted.ends_with('\n') {
+        formatted.push('\n');
+    }
+
+    line_ending::with_line_break(formatted, line_break)
+}
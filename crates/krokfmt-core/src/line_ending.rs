@@ -0,0 +1,109 @@
+//! The line breaks of sources and of the code formatted from them.
+//!
+//! Lines are broken with LF all the way from parsing to Biome, which prints
+//! LF whatever it's given. Sources are read as LF, and the output's line
+//! breaks are only picked once it's finished. That doesn't change what the
+//! code means: a CRLF inside a template literal reads as LF too.
+
+use std::borrow::Cow;
+
+/// The line breaks formatted code uses.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    Crlf,
+    /// Whichever most of the source's lines end with
+    Auto,
+}
+
+impl LineEnding {
+    /// The line ending most lines of `source` end with: CRLF when more of
+    /// them end with it than with a bare LF, LF otherwise.
+    pub fn detect(source: &str) -> Self {
+        let crlf = source.matches("\r\n").count();
+        let lf = source.matches('\n').count() - crlf;
+        if crlf > lf {
+            LineEnding::Crlf
+        } else {
+            LineEnding::Lf
+        }
+    }
+
+    /// The line break of the output formatted from `source`.
+    pub fn line_break(self, source: &str) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::Crlf => "\r\n",
+            LineEnding::Auto => Self::detect(source).line_break(source),
+        }
+    }
+}
+
+impl std::str::FromStr for LineEnding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "lf" => Ok(LineEnding::Lf),
+            "crlf" => Ok(LineEnding::Crlf),
+            "auto" => Ok(LineEnding::Auto),
+            other => Err(format!(
+                "unknown line ending '{other}' (expected 'lf', 'crlf' or 'auto')"
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for LineEnding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LineEnding::Lf => write!(f, "lf"),
+            LineEnding::Crlf => write!(f, "crlf"),
+            LineEnding::Auto => write!(f, "auto"),
+        }
+    }
+}
+
+/// `source` with every line break, CRLF or a lone CR, read as LF.
+pub fn to_lf(source: &str) -> Cow<'_, str> {
+    if source.contains('\r') {
+        Cow::Owned(source.replace("\r\n", "\n").replace('\r', "\n"))
+    } else {
+        Cow::Borrowed(source)
+    }
+}
+
+/// `text`, whose lines are broken with LF, with `line_break` instead.
+pub fn with_line_break(text: String, line_break: &str) -> String {
+    if line_break == "\n" {
+        text
+    } else {
+        text.replace('\n', line_break)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_dominant_line_ending() {
+        assert_eq!(LineEnding::detect("a\r\nb\r\nc\n"), LineEnding::Crlf);
+        assert_eq!(LineEnding::detect("a\r\nb\nc\n"), LineEnding::Lf);
+        assert_eq!(LineEnding::detect("a"), LineEnding::Lf);
+        assert_eq!(LineEnding::Auto.line_break("a\r\nb\r\n"), "\r\n");
+        assert_eq!(LineEnding::Lf.line_break("a\r\nb\r\n"), "\n");
+    }
+
+    #[test]
+    fn test_line_break_round_trip() {
+        let source = "a\r\nb\rc\n";
+        assert_eq!(to_lf(source), "a\nb\nc\n");
+        assert!(matches!(to_lf("a\nb\n"), Cow::Borrowed(_)));
+        assert_eq!(
+            with_line_break(to_lf(source).into_owned(), "\r\n"),
+            "a\r\nb\r\nc\r\n"
+        );
+    }
+}
@@ -0,0 +1,43 @@
+// The pipeline reports what it's doing through `tracing` spans and events so
+// every frontend (CLI, web server, LSP) can surface the same internals in
+// whatever way suits it. `tracing` is behind the default `tracing` feature:
+// the WASM playground turns it off and the macros below compile to nothing.
+
+#[cfg(feature = "tracing")]
+pub(crate) use tracing::{debug, debug_span, trace, warn};
+
+#[cfg(not(feature = "tracing"))]
+pub(crate) use noop::{debug, debug_span, trace, warn};
+
+#[cfg(not(feature = "tracing"))]
+mod noop {
+    macro_rules! event {
+        ($($arg:tt)*) => {};
+    }
+
+    macro_rules! span {
+        ($($arg:tt)*) => {
+            $crate::logging::NoopSpan
+        };
+    }
+
+    pub(crate) use event as debug;
+    pub(crate) use event as trace;
+    pub(crate) use event as warn;
+    pub(crate) use span as debug_span;
+}
+
+/// Stand-in for `tracing::Span` when the feature is disabled.
+#[cfg(not(feature = "tracing"))]
+pub(crate) struct NoopSpan;
+
+#[cfg(not(feature = "tracing"))]
+impl NoopSpan {
+    pub(crate) fn entered(self) -> Self {
+        self
+    }
+
+    pub(crate) fn in_scope<T>(&self, f: impl FnOnce() -> T) -> T {
+        f()
+    }
+}
@@ -0,0 +1,446 @@
+use anyhow::{Context, Result};
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::{Range, RangeInclusive};
+use std::str::FromStr;
+use swc_common::comments::Comments;
+use swc_common::{BytePos, Spanned};
+use swc_ecma_ast::*;
+
+use crate::fingerprint::fingerprint;
+use crate::parser::{ParsedSource, TypeScriptParser};
+
+/// A kind of top-level construct that formatting can be restricted to.
+///
+/// Restricting lets a codebase adopt krokfmt one construct at a time, and lets
+/// reviewers see exactly what it does to, say, imports without wading through
+/// everything else it would touch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum NodeKind {
+    /// Import declarations, including `import x = require('y')`, as one block
+    Imports,
+    /// Export lists and re-exports (`export { a }`, `export * from`, `export =`)
+    Exports,
+    Classes,
+    Functions,
+    /// Interfaces and type aliases
+    Types,
+    Enums,
+}
+
+impl NodeKind {
+    pub const ALL: [NodeKind; 6] = [
+        NodeKind::Imports,
+        NodeKind::Exports,
+        NodeKind::Classes,
+        NodeKind::Functions,
+        NodeKind::Types,
+        NodeKind::Enums,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            NodeKind::Imports => "imports",
+            NodeKind::Exports => "exports",
+            NodeKind::Classes => "classes",
+            NodeKind::Functions => "functions",
+            NodeKind::Types => "types",
+            NodeKind::Enums => "enums",
+        }
+    }
+
+    fn bit(&self) -> u16 {
+        1 << (*self as u16)
+    }
+}
+
+impl FromStr for NodeKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        NodeKind::ALL
+            .into_iter()
+            .find(|kind| kind.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<_> = NodeKind::ALL.iter().map(NodeKind::name).collect();
+                format!(
+                    "unknown node kind '{s}' (expected one of: {})",
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+impl fmt::Display for NodeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The node kinds formatting is restricted to, parsed from a comma-separated
+/// list like `imports,classes`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct NodeSelection {
+    selected: u16,
+}
+
+impl NodeSelection {
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    pub fn with(mut self, kind: NodeKind) -> Self {
+        self.selected |= kind.bit();
+        self
+    }
+
+    pub fn contains(&self, kind: NodeKind) -> bool {
+        self.selected & kind.bit() != 0
+    }
+}
+
+impl FromStr for NodeSelection {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+            .try_fold(Self::none(), |selection, name| {
+                Ok(selection.with(name.parse()?))
+            })
+    }
+}
+
+/// Take the selected kinds of construct from `formatted` and everything else,
+/// byte for byte, from `original`.
+///
+/// Selected constructs are swapped in where they were in `original`; nothing is
+/// moved between them. Imports are the exception and are treated as a single
+/// block, since sorting them is the point: the formatted block replaces the
+/// first original import and the others are removed.
+pub fn splice_selected(
+    original: &str,
+    formatted: &str,
+    filename: &str,
+    selection: NodeSelection,
+) -> Result<String> {
+    splice(original, formatted, filename, declaration_key, |item, _| {
+        classify(item).is_some_and(|(kind, _)| selection.contains(kind))
+    })
+}
+
+/// Take the top-level statements touching the 1-based, inclusive `lines` of
+/// `original` from `formatted`, and everything else byte for byte from
+/// `original`.
+///
+/// Like [`splice_selected`], statements stay where they were in `original`
+/// and imports count as one block. Statements are matched across formatting
+/// by fingerprint, which sorting doesn't change.
+pub fn splice_lines(
+    original: &str,
+    formatted: &str,
+    filename: &str,
+    lines: RangeInclusive<usize>,
+) -> Result<String> {
+    let line_of = |offset: usize| original[..offset].matches('\n').count() + 1;
+    splice(original, formatted, filename, statement_key, |_, region| {
+        line_of(region.start) <= *lines.end() && *lines.start() <= line_of(region.end)
+    })
+}
+
+/// How a top-level item is matched across formatting.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum ItemKey {
+    /// Every import, as one block
+    Imports,
+    Item(String),
+}
+
+/// Replace the items of `original` that `is_selected` picks, given the byte
+/// region they take up, with their counterparts in `formatted`.
+fn splice(
+    original: &str,
+    formatted: &str,
+    filename: &str,
+    key: fn(&ModuleItem) -> Option<ItemKey>,
+    is_selected: impl Fn(&ModuleItem, Range<usize>) -> bool,
+) -> Result<String> {
+    let before = TypeScriptParser::parse_disambiguated(original, filename)
+        .context("Failed to parse the original source")?;
+    let after = TypeScriptParser::parse_disambiguated(formatted, filename)
+        .context("Failed to parse the formatted source")?;
+
+    let before_items = top_level_items(&before, original, key);
+    let mut after_items: HashMap<ItemKey, Vec<Range<usize>>> = HashMap::new();
+    let mut after_imports: Option<Range<usize>> = None;
+    for item in top_level_items(&after, formatted, key) {
+        if item.key == ItemKey::Imports {
+            let block = after_imports.get_or_insert(item.region.clone());
+            block.end = item.region.end;
+        } else {
+            after_items.entry(item.key).or_default().push(item.region);
+        }
+    }
+    // Matches are taken front to back, so duplicates pair up in order
+    for regions in after_items.values_mut() {
+        regions.reverse();
+    }
+
+    // Imports are one block, so touching one selects them all
+    let imports_selected = before_items
+        .iter()
+        .any(|item| item.key == ItemKey::Imports && is_selected(item.item, item.region.clone()));
+
+    let mut replacements: Vec<(Range<usize>, &str)> = Vec::new();
+    let mut imports_placed = false;
+    for item in before_items {
+        if item.key == ItemKey::Imports {
+            if !imports_selected {
+                continue;
+            }
+            if !imports_placed {
+                imports_placed = true;
+                let block = after_imports.clone().unwrap_or_default();
+                replacements.push((item.region, &formatted[block]));
+            } else {
+                replacements.push((with_line_break(original, item.region), ""));
+            }
+            continue;
+        }
+
+        // Unselected items still take their match, so duplicates keep pairing
+        // up in order
+        let Some(region) = after_items.get_mut(&item.key).and_then(Vec::pop) else {
+            continue;
+        };
+        if is_selected(item.item, item.region.clone()) {
+            replacements.push((item.region, &formatted[region]));
+        }
+    }
+
+    let mut result = String::with_capacity(formatted.len().max(original.len()));
+    let mut cursor = 0;
+    for (region, text) in replacements {
+        result.push_str(&original[cursor..region.start]);
+        result.push_str(text);
+        cursor = region.end;
+    }
+    result.push_str(&original[cursor..]);
+
+    Ok(result)
+}
+
+struct TopLevelItem<'a> {
+    item: &'a ModuleItem,
+    /// Identifies the same construct on both sides of formatting
+    key: ItemKey,
+    /// Byte range of the item and its leading comments, from the start of the line
+    region: Range<usize>,
+}
+
+fn top_level_items<'a>(
+    parsed: &'a ParsedSource,
+    source: &str,
+    key: fn(&ModuleItem) -> Option<ItemKey>,
+) -> Vec<TopLevelItem<'a>> {
+    let offset = |pos: BytePos| parsed.parser.source_map.lookup_byte_offset(pos).pos.0 as usize;
+
+    parsed
+        .module
+        .body
+        .iter()
+        .filter_map(|item| {
+            let key = key(item)?;
+            let span = item.span();
+
+            let leading = parsed.parser.comments.get_leading(span.lo);
+            let start = leading
+                .iter()
+                .flatten()
+                .map(|comment| comment.span.lo)
+                .min()
+                .unwrap_or(span.lo);
+            let start = offset(start);
+            let line_start = source[..start].rfind('\n').map_or(0, |i| i + 1);
+            let start = if source[line_start..start].trim().is_empty() {
+                line_start
+            } else {
+                start
+            };
+
+            Some(TopLevelItem {
+                item,
+                key,
+                region: start..offset(span.hi),
+            })
+        })
+        .collect()
+}
+
+/// Declarations keyed by kind and name, for [`splice_selected`].
+fn declaration_key(item: &ModuleItem) -> Option<ItemKey> {
+    classify(item).map(|(kind, key)| match kind {
+        NodeKind::Imports => ItemKey::Imports,
+        kind => ItemKey::Item(format!("{kind}:{key}")),
+    })
+}
+
+/// Every statement keyed by fingerprint, for [`splice_lines`].
+fn statement_key(item: &ModuleItem) -> Option<ItemKey> {
+    Some(match item {
+        ModuleItem::ModuleDecl(ModuleDecl::Import(_) | ModuleDecl::TsImportEquals(_)) => {
+            ItemKey::Imports
+        }
+        item => ItemKey::Item(fingerprint(item)),
+    })
+}
+
+/// The kind of a top-level item and the key matching it across formatting.
+///
+/// Declarations are keyed by name. Export lists have no name of their own, so
+/// they're keyed by fingerprint, which already ignores specifier order.
+fn classify(item: &ModuleItem) -> Option<(NodeKind, String)> {
+    let decl = match item {
+        ModuleItem::ModuleDecl(ModuleDecl::Import(_) | ModuleDecl::TsImportEquals(_)) => {
+            return Some((NodeKind::Imports, String::new()));
+        }
+        ModuleItem::ModuleDecl(
+            ModuleDecl::ExportNamed(_)
+            | ModuleDecl::ExportAll(_)
+            | ModuleDecl::TsExportAssignment(_),
+        ) => return Some((NodeKind::Exports, fingerprint(item))),
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultDecl(export)) => {
+            return match &export.decl {
+                DefaultDecl::Class(_) => Some((NodeKind::Classes, "default".to_string())),
+                DefaultDecl::Fn(_) => Some((NodeKind::Functions, "default".to_string())),
+                DefaultDecl::TsInterfaceDecl(_) => Some((NodeKind::Types, "default".to_string())),
+            };
+        }
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) => &export.decl,
+        ModuleItem::Stmt(Stmt::Decl(decl)) => decl,
+        _ => return None,
+    };
+
+    match decl {
+        Decl::Class(class) => Some((NodeKind::Classes, class.ident.sym.to_string())),
+        Decl::Fn(function) => Some((NodeKind::Functions, function.ident.sym.to_string())),
+        Decl::TsInterface(interface) => Some((NodeKind::Types, interface.id.sym.to_string())),
+        Decl::TsTypeAlias(alias) => Some((NodeKind::Types, alias.id.sym.to_string())),
+        Decl::TsEnum(ts_enum) => Some((NodeKind::Enums, ts_enum.id.sym.to_string())),
+        _ => None,
+    }
+}
+
+/// Extend `region` over the rest of its line when nothing else is on it, so a
+/// removed item doesn't leave an empty line behind.
+fn with_line_break(source: &str, region: Range<usize>) -> Range<usize> {
+    let rest = &source[region.end..];
+    match rest.find('\n') {
+        Some(newline) if rest[..newline].trim().is_empty() => {
+            region.start..region.end + newline + 1
+        }
+        _ => region,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn selection(kinds: &str) -> NodeSelection {
+        kinds.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_node_selection() {
+        let parsed = selection("imports, classes");
+        assert!(parsed.contains(NodeKind::Imports));
+        assert!(parsed.contains(NodeKind::Classes));
+        assert!(!parsed.contains(NodeKind::Functions));
+
+        assert!("imports,statements".parse::<NodeSelection>().is_err());
+    }
+
+    #[test]
+    fn test_only_selected_nodes_change() {
+        let original = r#"import { z } from './z';
+function   untouched( a,b ) { return {b, a} }
+import { a } from 'a';
+
+// Widget does things
+class Widget { render() {} constructor() {} }
+"#;
+        let formatted = r#"import { a } from "a";
+
+import { z } from "./z";
+
+// Widget does things
+class Widget {
+  constructor() {}
+  render() {}
+}
+
+function untouched(a, b) {
+  return { a, b };
+}
+"#;
+
+        assert_eq!(
+            splice_selected(original, formatted, "test.ts", selection("imports")).unwrap(),
+            r#"import { a } from "a";
+
+import { z } from "./z";
+function   untouched( a,b ) { return {b, a} }
+
+// Widget does things
+class Widget { render() {} constructor() {} }
+"#
+        );
+
+        assert_eq!(
+            splice_selected(original, formatted, "test.ts", selection("classes")).unwrap(),
+            r#"import { z } from './z';
+function   untouched( a,b ) { return {b, a} }
+import { a } from 'a';
+
+// Widget does things
+class Widget {
+  constructor() {}
+  render() {}
+}
+"#
+        );
+    }
+    #[test]
+    fn test_only_statements_in_range_change() {
+        let original = r#"const  b = { z: 1, y: 2 };
+const a   = 1;
+function   f( x ) { return x }
+"#;
+        let formatted = r#"const a = 1;
+const b = { y: 2, z: 1 };
+
+function f(x) {
+  return x;
+}
+"#;
+
+        assert_eq!(
+            splice_lines(original, formatted, "test.ts", 1..=1).unwrap(),
+            r#"const b = { y: 2, z: 1 };
+const a   = 1;
+function   f( x ) { return x }
+"#
+        );
+        assert_eq!(
+            splice_lines(original, formatted, "test.ts", 2..=3).unwrap(),
+            r#"const  b = { z: 1, y: 2 };
+const a = 1;
+function f(x) {
+  return x;
+}
+"#
+        );
+    }
+}
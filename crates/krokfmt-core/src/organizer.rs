@@ -0,0 +1,4840 @@
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use swc_common::Spanned;
+use swc_ecma_ast::*;
+use swc_ecma_visit::{Visit, VisitMut, VisitMutWith, VisitWith};
+
+use crate::comment_classifier::IgnoredItems;
+use crate::rules::{RuleGroup, RuleSet};
+use crate::transformer::{
+    pin_react_first, sort_imports_with_profile, sort_re_exports, type_imports_last, ImportAnalyzer,
+    ImportCategory, ImportProfile, ProjectLayout, ReExportAnalyzer,
+};
+
+/// The main organizer that orchestrates the code organization process.
+///
+/// This organizer takes an opinionated approach to code structure:
+/// 1. Imports are sorted and grouped by category
+/// 2. Exported members are prioritized over internal ones
+/// 3. Dependencies between declarations are preserved
+/// 4. Various AST elements (objects, JSX props, etc.) are alphabetically sorted
+#[derive(Default)]
+pub struct KrokOrganizer {
+    import_profile: ImportProfile,
+    project_layout: ProjectLayout,
+    sort_hook_dependencies: bool,
+    react_first: bool,
+    group_type_imports: bool,
+    group_constants: bool,
+    sort_re_exports: bool,
+    rules: RuleSet,
+    /// The statements ignore directives keep as written
+    ignored_items: IgnoredItems,
+}
+
+/// What [`KrokOrganizer::organize_with_log`] decided for a module, for
+/// `--explain` to report.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct ChangeLog {
+    /// Imports that had to move to land in their group and sorted position
+    pub regrouped_imports: usize,
+    /// Declarations moved above one written earlier that depends on them
+    pub hoisted_declarations: Vec<String>,
+    /// String enums whose members were out of order
+    pub sorted_enums: Vec<String>,
+    /// What the author should hear about, as warnings
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl ChangeLog {
+    /// Whether nothing was regrouped, hoisted or sorted.
+    pub fn is_empty(&self) -> bool {
+        self.regrouped_imports == 0
+            && self.hoisted_declarations.is_empty()
+            && self.sorted_enums.is_empty()
+    }
+}
+
+/// Something organizing couldn't do as its rules say.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Diagnostic {
+    /// Declarations depending on each other in a cycle, each on the next and
+    /// the last on the first, which no order can put all after what they use
+    DependencyCycle { declarations: Vec<String> },
+}
+
+impl std::fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Diagnostic::DependencyCycle { declarations } => write!(
+                f,
+                "{} depend on each other in a cycle, so they can't all be ordered after what they use",
+                declarations
+                    .iter()
+                    .chain(declarations.first())
+                    .map(String::as_str)
+                    .collect::<Vec<_>>()
+                    .join(" -> ")
+            ),
+        }
+    }
+}
+
+/// Analyzes exports in a module to determine which members are exported.
+///
+/// This is crucial for FR2 (member visibility ordering) - we need to know which
+/// declarations are public API vs internal implementation details. The analyzer
+/// tracks all forms of exports: direct exports, named exports, default exports
+/// and `export =`.
+///
+/// Members a namespace exports are recorded qualified by the namespaces they're
+/// in (`Api.defaultOptions`), so they never pass for module-level exports.
+#[derive(Default)]
+pub struct ExportAnalyzer {
+    exported_names: HashSet<String>,
+    /// The namespaces enclosing what's being visited, outermost first
+    namespace: Vec<String>,
+}
+
+impl ExportAnalyzer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn analyze(&mut self, module: &Module) -> ExportInfo {
+        self.exported_names.clear();
+        self.namespace.clear();
+        module.visit_with(self);
+
+        ExportInfo {
+            exported_names: self.exported_names.clone(),
+        }
+    }
+
+    fn export(&mut self, name: String) {
+        let name = if self.namespace.is_empty() {
+            name
+        } else {
+            format!("{}.{name}", self.namespace.join("."))
+        };
+        self.exported_names.insert(name);
+    }
+
+    /// Export the bindings `expr` hands out as the module's value: an
+    /// identifier, or the identifiers an object literal is built from, as in
+    /// `export = { parse, format }`.
+    fn export_value(&mut self, expr: &Expr) {
+        match expr {
+            Expr::Ident(ident) => self.export(ident.sym.to_string()),
+            Expr::Paren(paren) => self.export_value(&paren.expr),
+            Expr::Object(object) => {
+                for prop in &object.props {
+                    match prop {
+                        PropOrSpread::Prop(prop) => match &**prop {
+                            Prop::Shorthand(ident) => self.export(ident.sym.to_string()),
+                            Prop::KeyValue(key_value) => {
+                                if let Expr::Ident(ident) = &*key_value.value {
+                                    self.export(ident.sym.to_string());
+                                }
+                            }
+                            _ => {}
+                        },
+                        PropOrSpread::Spread(spread) => self.export_value(&spread.expr),
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+impl Visit for ExportAnalyzer {
+    fn visit_module_decl(&mut self, decl: &ModuleDecl) {
+        // We need to handle all export forms to correctly identify public API.
+        // This includes export declarations, named exports, and default exports.
+        match decl {
+            ModuleDecl::ExportDecl(export_decl) => match &export_decl.decl {
+                Decl::Fn(fn_decl) => {
+                    self.export(fn_decl.ident.sym.to_string());
+                }
+                Decl::Class(class_decl) => {
+                    self.export(class_decl.ident.sym.to_string());
+                }
+                Decl::Var(var_decl) => {
+                    for decl in &var_decl.decls {
+                        if let Pat::Ident(ident) = &decl.name {
+                            self.export(ident.id.sym.to_string());
+                        }
+                    }
+                }
+                Decl::TsInterface(interface) => {
+                    self.export(interface.id.sym.to_string());
+                }
+                Decl::TsTypeAlias(type_alias) => {
+                    self.export(type_alias.id.sym.to_string());
+                }
+                Decl::TsEnum(ts_enum) => {
+                    self.export(ts_enum.id.sym.to_string());
+                }
+                Decl::TsModule(ts_module) => {
+                    if let TsModuleName::Ident(ident) = &ts_module.id {
+                        self.export(ident.sym.to_string());
+                    }
+                }
+                _ => {}
+            },
+            ModuleDecl::ExportNamed(named_export) if named_export.src.is_none() => {
+                for spec in &named_export.specifiers {
+                    match spec {
+                        // `export { x as default }` exports `x` all the same
+                        ExportSpecifier::Named(named_spec) => {
+                            let name = match &named_spec.orig {
+                                ModuleExportName::Ident(ident) => ident.sym.to_string(),
+                                ModuleExportName::Str(_) => continue,
+                            };
+                            self.export(name);
+                        }
+                        ExportSpecifier::Default(_) => {}
+                        ExportSpecifier::Namespace(_) => {}
+                    }
+                }
+            }
+            ModuleDecl::ExportDefaultDecl(_) => {
+                // Default exports don't add to exported names
+            }
+            ModuleDecl::ExportDefaultExpr(export) => {
+                // For `export default someVar`, we track someVar as exported.
+                // This helps preserve the declaration order when someVar is defined elsewhere.
+                self.export_value(&export.expr);
+            }
+            ModuleDecl::TsExportAssignment(export) => {
+                // `export = someVar` is the CommonJS-interop equivalent of `export default`
+                self.export_value(&export.expr);
+            }
+            _ => {}
+        }
+
+        decl.visit_children_with(self);
+    }
+
+    fn visit_ts_module_decl(&mut self, ts_module: &TsModuleDecl) {
+        let name = match &ts_module.id {
+            TsModuleName::Ident(ident) => ident.sym.to_string(),
+            TsModuleName::Str(s) => s.value.to_string(),
+        };
+        self.namespace.push(name);
+        ts_module.body.visit_with(self);
+        self.namespace.pop();
+    }
+
+    // `namespace A.B { }` nests `B` in `A`
+    fn visit_ts_namespace_decl(&mut self, ts_namespace: &TsNamespaceDecl) {
+        self.namespace.push(ts_namespace.id.sym.to_string());
+        ts_namespace.body.visit_with(self);
+        self.namespace.pop();
+    }
+}
+
+/// Holds information about exported members in a module
+pub struct ExportInfo {
+    exported_names: HashSet<String>,
+}
+
+impl ExportInfo {
+    pub fn is_exported(&self, name: &str) -> bool {
+        self.exported_names.contains(name)
+    }
+
+    /// Whether the namespace `namespace`, like `Api` or `Api.V1`, exports
+    /// its member `name`.
+    pub fn is_exported_from(&self, namespace: &str, name: &str) -> bool {
+        self.exported_names.contains(&format!("{namespace}.{name}"))
+    }
+}
+
+/// Represents the type of a declaration for dependency analysis
+#[derive(Debug, Clone, PartialEq)]
+enum DeclType {
+    /// Function declarations - hoisted, can be called before declaration
+    FunctionDecl,
+    /// Class declarations - hoisted in type positions, runtime instantiation requires declaration
+    ClassDecl,
+    /// Interface declarations - type-only, can be forward referenced
+    Interface,
+    /// Type alias - type-only, can be forward referenced
+    TypeAlias,
+    /// Enum - can be forward referenced in type positions
+    Enum,
+    /// Variable with const/let/var - runtime value, must be declared before use
+    Variable,
+    /// Unknown declaration type
+    Unknown,
+}
+
+/// Context in which a dependency is used
+#[derive(Debug, Clone, PartialEq, Default)]
+enum DependencyContext {
+    /// Used in a type annotation or type-level construct
+    TypeLevel,
+    /// Used in a runtime expression or value
+    #[default]
+    RuntimeValue,
+}
+
+/// Analyzes dependencies between declarations in a module.
+///
+/// This is critical for FR2.3 - we must preserve dependency order even when
+/// reordering exports. The analyzer builds a dependency graph by tracking
+/// identifier references within each declaration. This ensures that if A uses B,
+/// B will always appear before A in the formatted output.
+///
+/// The analyzer now distinguishes between type-level and runtime dependencies,
+/// allowing TypeScript's forward reference rules to be properly handled.
+#[derive(Default)]
+pub struct DependencyAnalyzer {
+    /// Maps declaration names to their runtime dependencies
+    dependencies: HashMap<String, HashSet<String>>,
+    /// Current declaration being analyzed
+    current_decl: Option<String>,
+    /// Maps declaration names to their types
+    decl_types: HashMap<String, DeclType>,
+    /// Current context (type-level or runtime)
+    current_context: DependencyContext,
+    /// Whether we're inside a type annotation
+    in_type_annotation: bool,
+    /// How many functions deep in the current declaration we are, counting
+    /// only those whose bodies run later rather than while the module is
+    /// evaluated
+    deferred_depth: usize,
+    /// Whether the function about to be visited is called right away, like
+    /// an IIFE
+    invoked_now: bool,
+}
+
+impl DependencyAnalyzer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn analyze(&mut self, module: &Module) -> DependencyGraph {
+        self.dependencies.clear();
+        self.decl_types.clear();
+
+        // Two-pass analysis is necessary because forward references are allowed
+        // in JavaScript. First we catalog all declarations, then we can accurately
+        // identify which identifier references are dependencies.
+
+        // First pass: collect all declaration names and their types
+        for item in &module.body {
+            self.collect_declaration_info(item);
+        }
+
+        // Second pass: analyze dependencies
+        for item in &module.body {
+            if let Some(name) = Self::get_declaration_name(item) {
+                self.current_decl = Some(name.clone());
+                self.dependencies.insert(name, HashSet::new());
+                self.current_context = DependencyContext::RuntimeValue;
+                self.in_type_annotation = false;
+                self.deferred_depth = 0;
+                item.visit_with(self);
+                self.current_decl = None;
+            }
+        }
+
+        DependencyGraph {
+            dependencies: self.dependencies.clone(),
+            functions: self
+                .decl_types
+                .iter()
+                .filter(|(_, decl_type)| **decl_type == DeclType::FunctionDecl)
+                .map(|(name, _)| name.clone())
+                .collect(),
+        }
+    }
+
+    fn collect_declaration_info(&mut self, item: &ModuleItem) {
+        match item {
+            ModuleItem::Stmt(stmt) => self.collect_stmt_info(stmt),
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export_decl)) => {
+                self.collect_decl_info(&export_decl.decl);
+            }
+            _ => {}
+        }
+    }
+
+    fn collect_stmt_info(&mut self, stmt: &Stmt) {
+        if let Stmt::Decl(decl) = stmt {
+            self.collect_decl_info(decl);
+        }
+    }
+
+    fn collect_decl_info(&mut self, decl: &Decl) {
+        match decl {
+            Decl::Fn(fn_decl) => {
+                let name = fn_decl.ident.sym.to_string();
+                self.decl_types.insert(name, DeclType::FunctionDecl);
+            }
+            Decl::Class(class_decl) => {
+                let name = class_decl.ident.sym.to_string();
+                self.decl_types.insert(name, DeclType::ClassDecl);
+            }
+            Decl::Var(var_decl) => {
+                for decl in &var_decl.decls {
+                    self.collect_pat_info(&decl.name, DeclType::Variable);
+                }
+            }
+            Decl::TsInterface(interface) => {
+                let name = interface.id.sym.to_string();
+                self.decl_types.insert(name, DeclType::Interface);
+            }
+            Decl::TsTypeAlias(type_alias) => {
+                let name = type_alias.id.sym.to_string();
+                self.decl_types.insert(name, DeclType::TypeAlias);
+            }
+            Decl::TsEnum(ts_enum) => {
+                let name = ts_enum.id.sym.to_string();
+                self.decl_types.insert(name, DeclType::Enum);
+            }
+            Decl::TsModule(ts_module) => match &ts_module.id {
+                TsModuleName::Ident(ident) => {
+                    let name = ident.sym.to_string();
+                    self.decl_types.insert(name, DeclType::Unknown);
+                }
+                TsModuleName::Str(s) => {
+                    let name = s.value.to_string();
+                    self.decl_types.insert(name, DeclType::Unknown);
+                }
+            },
+            _ => {}
+        }
+    }
+
+    fn collect_pat_info(&mut self, pat: &Pat, decl_type: DeclType) {
+        match pat {
+            Pat::Ident(ident) => {
+                let name = ident.id.sym.to_string();
+                self.decl_types.insert(name, decl_type.clone());
+            }
+            Pat::Object(obj_pat) => {
+                for prop in &obj_pat.props {
+                    match prop {
+                        ObjectPatProp::KeyValue(kv) => {
+                            self.collect_pat_info(&kv.value, decl_type.clone())
+                        }
+                        ObjectPatProp::Assign(assign) => {
+                            let name = assign.key.sym.to_string();
+                            self.decl_types.insert(name, decl_type.clone());
+                        }
+                        ObjectPatProp::Rest(rest) => {
+                            self.collect_pat_info(&rest.arg, decl_type.clone())
+                        }
+                    }
+                }
+            }
+            Pat::Array(array_pat) => {
+                for p in array_pat.elems.iter().flatten() {
+                    self.collect_pat_info(p, decl_type.clone());
+                }
+            }
+            Pat::Rest(rest) => self.collect_pat_info(&rest.arg, decl_type.clone()),
+            _ => {}
+        }
+    }
+
+    fn get_declaration_name(item: &ModuleItem) -> Option<String> {
+        match item {
+            ModuleItem::Stmt(stmt) => Self::get_stmt_declaration_name(stmt),
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export_decl)) => {
+                Self::get_decl_name(&export_decl.decl)
+            }
+            _ => None,
+        }
+    }
+
+    fn get_stmt_declaration_name(stmt: &Stmt) -> Option<String> {
+        match stmt {
+            Stmt::Decl(decl) => Self::get_decl_name(decl),
+            _ => None,
+        }
+    }
+
+    fn get_decl_name(decl: &Decl) -> Option<String> {
+        match decl {
+            Decl::Fn(fn_decl) => Some(fn_decl.ident.sym.to_string()),
+            Decl::Class(class_decl) => Some(class_decl.ident.sym.to_string()),
+            Decl::Var(var_decl) => {
+                // For simplicity, return the first variable name
+                var_decl
+                    .decls
+                    .first()
+                    .and_then(|decl| Self::get_pat_name(&decl.name))
+            }
+            Decl::TsInterface(interface) => Some(interface.id.sym.to_string()),
+            Decl::TsTypeAlias(type_alias) => Some(type_alias.id.sym.to_string()),
+            Decl::TsEnum(ts_enum) => Some(ts_enum.id.sym.to_string()),
+            Decl::TsModule(ts_module) => match &ts_module.id {
+                TsModuleName::Ident(ident) => Some(ident.sym.to_string()),
+                TsModuleName::Str(s) => Some(s.value.to_string()),
+            },
+            _ => None,
+        }
+    }
+
+    fn get_pat_name(pat: &Pat) -> Option<String> {
+        match pat {
+            Pat::Ident(ident) => Some(ident.id.sym.to_string()),
+            Pat::Object(obj_pat) => {
+                // For object destructuring, return the first property name
+                obj_pat.props.first().and_then(|prop| match prop {
+                    ObjectPatProp::KeyValue(kv) => Self::get_pat_name(&kv.value),
+                    ObjectPatProp::Assign(assign) => Some(assign.key.sym.to_string()),
+                    ObjectPatProp::Rest(_) => None,
+                })
+            }
+            Pat::Array(array_pat) => {
+                // For array destructuring, return the first element name
+                array_pat
+                    .elems
+                    .iter()
+                    .find_map(|elem| elem.as_ref().and_then(Self::get_pat_name))
+            }
+            _ => None,
+        }
+    }
+}
+
+impl Visit for DependencyAnalyzer {
+    fn visit_ident(&mut self, ident: &Ident) {
+        if let Some(current) = &self.current_decl {
+            let name = ident.sym.to_string();
+
+            // Check if this is a known declaration and not a self-reference
+            if let Some(decl_type) = self.decl_types.get(&name).cloned() {
+                if &name != current {
+                    // Determine if we need to track this dependency
+                    let should_track = match (&self.current_context, &decl_type) {
+                        // Type-level dependencies on type-only constructs don't need ordering
+                        (DependencyContext::TypeLevel, DeclType::Interface) => false,
+                        (DependencyContext::TypeLevel, DeclType::TypeAlias) => false,
+                        (DependencyContext::TypeLevel, DeclType::Enum) => false,
+
+                        // Function declarations are hoisted, so calls don't need ordering
+                        (DependencyContext::RuntimeValue, DeclType::FunctionDecl) => false,
+
+                        // Class declarations in type positions don't need ordering
+                        (DependencyContext::TypeLevel, DeclType::ClassDecl) => false,
+
+                        // All other cases require dependency tracking
+                        _ => true,
+                    };
+
+                    if should_track {
+                        self.dependencies.get_mut(current).unwrap().insert(name);
+                    }
+                }
+            }
+        }
+        ident.visit_children_with(self);
+    }
+
+    // Track when we enter type annotations
+    fn visit_ts_type_ann(&mut self, type_ann: &TsTypeAnn) {
+        let prev_context = self.current_context.clone();
+        self.current_context = DependencyContext::TypeLevel;
+        self.in_type_annotation = true;
+        type_ann.visit_children_with(self);
+        self.current_context = prev_context;
+        self.in_type_annotation = false;
+    }
+
+    // Track type references
+    fn visit_ts_type_ref(&mut self, type_ref: &TsTypeRef) {
+        let prev_context = self.current_context.clone();
+        self.current_context = DependencyContext::TypeLevel;
+        type_ref.visit_children_with(self);
+        self.current_context = prev_context;
+    }
+
+    // Interface extends clauses are type-level
+    fn visit_ts_interface_decl(&mut self, interface: &TsInterfaceDecl) {
+        let prev_context = self.current_context.clone();
+
+        // Process extends clause in type context
+        if !interface.extends.is_empty() {
+            self.current_context = DependencyContext::TypeLevel;
+            for extend in &interface.extends {
+                extend.visit_with(self);
+            }
+        }
+
+        // Process body in type context
+        self.current_context = DependencyContext::TypeLevel;
+        interface.body.visit_children_with(self);
+
+        self.current_context = prev_context;
+    }
+
+    // Type alias RHS is type-level
+    fn visit_ts_type_alias_decl(&mut self, type_alias: &TsTypeAliasDecl) {
+        let prev_context = self.current_context.clone();
+        self.current_context = DependencyContext::TypeLevel;
+        type_alias.type_ann.visit_with(self);
+        self.current_context = prev_context;
+    }
+
+    // Class extends/implements are type-level
+    fn visit_class(&mut self, class: &Class) {
+        let prev_context = self.current_context.clone();
+
+        // Super class can be type-level or runtime depending on usage
+        if let Some(super_class) = &class.super_class {
+            // For now, treat extends as runtime since it affects prototype chain
+            super_class.visit_with(self);
+        }
+
+        // Implements clauses are type-level
+        if !class.implements.is_empty() {
+            self.current_context = DependencyContext::TypeLevel;
+            for implement in &class.implements {
+                implement.visit_with(self);
+            }
+            self.current_context = prev_context.clone();
+        }
+
+        // Visit class body
+        class.body.visit_children_with(self);
+
+        self.current_context = prev_context;
+    }
+
+    // Function declarations are hoisted, but the values they read aren't: a
+    // call made while the module is evaluated needs them declared by then, so
+    // it depends on the function it calls and, through it, on what it reads
+    fn visit_call_expr(&mut self, call: &CallExpr) {
+        if let Callee::Expr(callee) = &call.callee {
+            let mut callee = &**callee;
+            while let Expr::Paren(paren) = callee {
+                callee = &paren.expr;
+            }
+            match callee {
+                Expr::Ident(ident) if self.deferred_depth == 0 => {
+                    let name = ident.sym.to_string();
+                    let is_function = self.decl_types.get(&name) == Some(&DeclType::FunctionDecl);
+                    if let Some(current) = &self.current_decl {
+                        if is_function && &name != current {
+                            self.dependencies.get_mut(current).unwrap().insert(name);
+                        }
+                    }
+                }
+                Expr::Arrow(_) | Expr::Fn(_) => self.invoked_now = true,
+                _ => {}
+            }
+        }
+        call.visit_children_with(self);
+    }
+
+    // The body of the function declaration being analyzed runs whenever it's
+    // called, which can be while the module is evaluated
+    fn visit_fn_decl(&mut self, fn_decl: &FnDecl) {
+        if self.deferred_depth == 0
+            && self.current_decl.as_deref() == Some(fn_decl.ident.sym.as_str())
+        {
+            fn_decl.function.visit_children_with(self);
+        } else {
+            fn_decl.visit_children_with(self);
+        }
+    }
+
+    fn visit_function(&mut self, function: &Function) {
+        let deferred = !std::mem::take(&mut self.invoked_now);
+        self.deferred_depth += usize::from(deferred);
+        function.visit_children_with(self);
+        self.deferred_depth -= usize::from(deferred);
+    }
+
+    fn visit_arrow_expr(&mut self, arrow: &ArrowExpr) {
+        let deferred = !std::mem::take(&mut self.invoked_now);
+        self.deferred_depth += usize::from(deferred);
+        arrow.visit_children_with(self);
+        self.deferred_depth -= usize::from(deferred);
+    }
+
+    fn visit_member_expr(&mut self, expr: &MemberExpr) {
+        // Member expressions are always runtime value access
+        if let Some(ident) = expr.obj.as_ident() {
+            if let Some(current) = &self.current_decl {
+                let name = ident.sym.to_string();
+                if self.decl_types.contains_key(&name) && &name != current {
+                    // Member access always requires runtime value
+                    self.dependencies.get_mut(current).unwrap().insert(name);
+                }
+            }
+        }
+
+        expr.obj.visit_children_with(self);
+
+        if let MemberProp::Ident(_) = &expr.prop {
+            // Don't need to track the property name as a dependency
+        }
+    }
+}
+
+/// Represents the dependency graph of a module
+pub struct DependencyGraph {
+    pub dependencies: HashMap<String, HashSet<String>>,
+    /// The function declarations, which are hoisted
+    functions: HashSet<String>,
+}
+
+impl DependencyGraph {
+    /// Returns true if `from` depends on `to`
+    pub fn depends_on(&self, from: &str, to: &str) -> bool {
+        self.dependencies
+            .get(from)
+            .map(|deps| deps.contains(to))
+            .unwrap_or(false)
+    }
+
+    /// The cycles among the declarations named `items`, each as the names
+    /// along it: `["a", "b"]` when `a` depends on `b` and `b` on `a`.
+    ///
+    /// Each declaration is reported in one cycle at most. Cycles of function
+    /// declarations alone are left out, since hoisting makes any order of
+    /// mutually recursive functions work.
+    pub fn cycles(&self, items: &[String]) -> Vec<Vec<String>> {
+        let items: HashSet<&str> = items.iter().map(String::as_str).collect();
+        let mut starts: Vec<&str> = items.iter().copied().collect();
+        starts.sort_unstable();
+
+        let mut reported: HashSet<String> = HashSet::new();
+        let mut cycles = Vec::new();
+        for start in starts {
+            if reported.contains(start) {
+                continue;
+            }
+            let Some(cycle) = self.shortest_cycle(start, &items) else {
+                continue;
+            };
+            if cycle.iter().all(|name| self.functions.contains(name))
+                || cycle.iter().any(|name| reported.contains(name))
+            {
+                continue;
+            }
+            reported.extend(cycle.iter().cloned());
+            cycles.push(cycle);
+        }
+        cycles
+    }
+
+    /// The shortest path of dependencies among `items` from `start` back to
+    /// itself, breadth first.
+    fn shortest_cycle(&self, start: &str, items: &HashSet<&str>) -> Option<Vec<String>> {
+        let mut came_from: HashMap<&str, &str> = HashMap::new();
+        let mut queue = std::collections::VecDeque::from([start]);
+
+        while let Some(node) = queue.pop_front() {
+            let mut deps: Vec<&str> = self
+                .dependencies
+                .get(node)
+                .into_iter()
+                .flatten()
+                .map(String::as_str)
+                .filter(|dep| items.contains(*dep))
+                .collect();
+            deps.sort_unstable();
+
+            for dep in deps {
+                if dep == start {
+                    let mut path = vec![node.to_string()];
+                    let mut at = node;
+                    while at != start {
+                        at = came_from[at];
+                        path.push(at.to_string());
+                    }
+                    path.reverse();
+                    return Some(path);
+                }
+                if !came_from.contains_key(dep) {
+                    came_from.insert(dep, node);
+                    queue.push_back(dep);
+                }
+            }
+        }
+        None
+    }
+
+    /// Performs a topological sort of the given items based on dependencies.
+    /// Returns None if there's a circular dependency.
+    ///
+    /// We use depth-first search with cycle detection. The 'visiting' set tracks
+    /// the current path to detect cycles, while 'visited' prevents redundant work.
+    /// This ensures declarations appear after all their dependencies.
+    pub fn topological_sort(&self, items: Vec<String>) -> Option<Vec<String>> {
+        let mut result = Vec::new();
+        let mut visited = HashSet::new();
+        let mut visiting = HashSet::new();
+
+        for item in &items {
+            if !visited.contains(item)
+                && !self.visit_node(item, &items, &mut visited, &mut visiting, &mut result)
+            {
+                return None; // Circular dependency detected
+            }
+        }
+
+        result.reverse();
+        Some(result)
+    }
+
+    fn visit_node(
+        &self,
+        node: &str,
+        items: &[String],
+        visited: &mut HashSet<String>,
+        visiting: &mut HashSet<String>,
+        result: &mut Vec<String>,
+    ) -> bool {
+        if visiting.contains(node) {
+            return false; // Circular dependency
+        }
+
+        if visited.contains(node) {
+            return true;
+        }
+
+        visiting.insert(node.to_string());
+
+        if let Some(deps) = self.dependencies.get(node) {
+            for dep in deps {
+                if items.contains(dep) && !self.visit_node(dep, items, visited, visiting, result) {
+                    return false;
+                }
+            }
+        }
+
+        visiting.remove(node);
+        visited.insert(node.to_string());
+        result.push(node.to_string());
+
+        true
+    }
+}
+
+impl KrokOrganizer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create an organizer that orders imports using the given profile.
+    pub fn with_import_profile(import_profile: ImportProfile) -> Self {
+        Self {
+            import_profile,
+            ..Self::default()
+        }
+    }
+
+    /// Categorize imports by the project's workspace packages and path
+    /// aliases.
+    pub fn with_project_layout(mut self, project_layout: ProjectLayout) -> Self {
+        self.project_layout = project_layout;
+        self
+    }
+
+    /// Also alphabetize React hook dependency arrays (see FR3.7).
+    pub fn with_sorted_hook_dependencies(mut self, enabled: bool) -> Self {
+        self.sort_hook_dependencies = enabled;
+        self
+    }
+
+    /// Keep the React default import first, ahead of the sorted order.
+    pub fn with_react_first(mut self, enabled: bool) -> Self {
+        self.react_first = enabled;
+        self
+    }
+
+    /// Put type-only imports after the value imports of their group.
+    pub fn with_grouped_type_imports(mut self, enabled: bool) -> Self {
+        self.group_type_imports = enabled;
+        self
+    }
+
+    /// Cluster SCREAMING_CASE primitive constants into a block ahead of the
+    /// other declarations of each visibility group.
+    pub fn with_grouped_constants(mut self, enabled: bool) -> Self {
+        self.group_constants = enabled;
+        self
+    }
+
+    /// Alphabetize re-export statements within their category instead of
+    /// keeping the author's order.
+    pub fn with_sorted_re_exports(mut self, enabled: bool) -> Self {
+        self.sort_re_exports = enabled;
+        self
+    }
+
+    /// Only apply the given rule groups.
+    pub fn with_rules(mut self, rules: RuleSet) -> Self {
+        self.rules = rules;
+        self
+    }
+
+    /// Leave these top-level statements exactly as written: nothing inside
+    /// them is sorted, and nothing moves across them.
+    pub fn with_ignored_items(mut self, ignored_items: IgnoredItems) -> Self {
+        self.ignored_items = ignored_items;
+        self
+    }
+
+    pub fn organize(&self, module: Module) -> Result<Module> {
+        self.organize_with_log(module).map(|(module, _)| module)
+    }
+
+    /// [`Self::organize`], also logging what was regrouped, hoisted and sorted.
+    pub fn organize_with_log(&self, mut module: Module) -> Result<(Module, ChangeLog)> {
+        // The organizing pipeline follows a specific order to ensure correctness:
+        // 1. Analyze the existing structure (imports, exports, dependencies)
+        // 2. Reorganize based on our opinionated rules
+        // 3. Apply fine-grained organizing (sorting object keys, etc.)
+
+        // Step 1: Extract and categorize imports and re-exports
+        let import_infos = ImportAnalyzer::new()
+            .with_project_layout(self.project_layout.clone())
+            .analyze(&module);
+        let mut sorted_imports = sort_imports_with_profile(import_infos, self.import_profile);
+        if self.group_type_imports {
+            sorted_imports =
+                type_imports_last(sorted_imports, self.import_profile, &self.project_layout);
+        }
+        if self.react_first {
+            sorted_imports = pin_react_first(sorted_imports);
+        }
+
+        // Barrel files often curate their re-export order (the public API read
+        // top to bottom), so it's kept unless the file opts into sorting
+        let mut sorted_re_exports = ReExportAnalyzer::new()
+            .with_project_layout(self.project_layout.clone())
+            .analyze(&module);
+        if self.sort_re_exports {
+            sorted_re_exports =
+                sort_re_exports(sorted_re_exports, self.import_profile, &self.project_layout);
+        }
+
+        // Step 2: Analyze exports and dependencies
+        let mut export_analyzer = ExportAnalyzer::new();
+        let export_info = export_analyzer.analyze(&module);
+
+        let mut dependency_analyzer = DependencyAnalyzer::new();
+        let dependency_graph = dependency_analyzer.analyze(&module);
+
+        // Step 3: Separate the directive prologue, imports, re-exports, ambient
+        // blocks, and other items
+        let prologue_len = directive_prologue_len(&module.body);
+        let mut body = module.body.into_iter();
+        let directives: Vec<_> = body.by_ref().take(prologue_len).collect();
+        let mut imports = Vec::new();
+        let mut re_exports = Vec::new();
+        let mut ambient_blocks = Vec::new();
+        let mut export_assignments = Vec::new();
+        let mut other_items = Vec::new();
+
+        for item in body {
+            match &item {
+                ModuleItem::ModuleDecl(ModuleDecl::Import(_)) => {
+                    imports.push(item);
+                }
+                ModuleItem::ModuleDecl(ModuleDecl::TsImportEquals(import))
+                    if ImportAnalyzer::require_path(import).is_some() =>
+                {
+                    imports.push(item);
+                }
+                ModuleItem::ModuleDecl(ModuleDecl::TsExportAssignment(_)) => {
+                    // `export = foo` replaces the whole module's exports, so it reads
+                    // best as the very last statement, after everything it refers to.
+                    export_assignments.push(item);
+                }
+                ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(export)) if export.src.is_some() => {
+                    re_exports.push(item);
+                }
+                ModuleItem::ModuleDecl(ModuleDecl::ExportAll(_)) => {
+                    re_exports.push(item);
+                }
+                _ if is_ambient_block(&item) && !self.is_pinned(&item) => {
+                    ambient_blocks.push(item);
+                }
+                _ => {
+                    // All other items (including export statements) go through visibility organization
+                    other_items.push(item);
+                }
+            }
+        }
+
+        // Step 4: Organize by visibility with alphabetization.
+        //
+        // Top-level statements that mutate module state at load time (think
+        // `registry.push(handler)` between declarations) make the module order-dependent
+        // in ways our identifier-based dependency graph cannot see. Rather than guess,
+        // we treat them as barriers: each run of declarations between barriers is
+        // organized on its own, and the barriers stay exactly where the author put them.
+        // Statements pinned by ignore directives are barriers too.
+        let organized_items = if self.rules.is_enabled(RuleGroup::DeclarationOrder) {
+            let mut organized_items = Vec::new();
+            let mut segment = Vec::new();
+
+            for item in other_items {
+                if Self::is_mutation_barrier(&item) || self.is_pinned(&item) {
+                    organized_items.extend(self.organize_by_visibility(
+                        std::mem::take(&mut segment),
+                        &export_info,
+                        &dependency_graph,
+                    )?);
+                    organized_items.push(item);
+                } else {
+                    segment.push(item);
+                }
+            }
+
+            organized_items.extend(self.organize_by_visibility(
+                segment,
+                &export_info,
+                &dependency_graph,
+            )?);
+            organized_items
+        } else {
+            other_items
+        };
+
+        let mut change_log = ChangeLog {
+            regrouped_imports: moved_items(
+                sorted_imports
+                    .iter()
+                    .map(|import| import.import_decl.span_lo()),
+            ),
+            hoisted_declarations: Self::hoisted_declarations(&organized_items, &dependency_graph),
+            sorted_enums: Vec::new(),
+            diagnostics: Vec::new(),
+        };
+        if self.rules.is_enabled(RuleGroup::DeclarationOrder) {
+            let names: Vec<_> = organized_items
+                .iter()
+                .filter(|item| !self.is_pinned(item))
+                .filter_map(Self::get_item_name)
+                .collect();
+            change_log.diagnostics = dependency_graph
+                .cycles(&names)
+                .into_iter()
+                .map(|declarations| Diagnostic::DependencyCycle { declarations })
+                .collect();
+        }
+
+        // Step 5: Reconstruct module with organized imports and prioritized declarations
+        let mut new_body = directives;
+
+        // Add imports grouped by category with empty lines between groups
+        let mut last_category: Option<ImportCategory> = None;
+
+        for import_info in sorted_imports {
+            // Category transitions are tracked here but empty lines are inserted
+            // during code generation. This separation of concerns keeps the AST
+            // clean while still achieving the desired visual grouping.
+            if let Some(last_cat) = &last_category {
+                if std::mem::discriminant(last_cat) != std::mem::discriminant(&import_info.category)
+                {
+                    // We'll handle empty lines in the codegen phase
+                }
+            }
+
+            new_body.push(ModuleItem::from(import_info.import_decl));
+            last_category = Some(import_info.category);
+        }
+
+        // Add re-exports grouped by category (similar to imports)
+        let mut last_re_export_category: Option<ImportCategory> = None;
+        for re_export_info in sorted_re_exports {
+            if let Some(last_cat) = &last_re_export_category {
+                if std::mem::discriminant(last_cat)
+                    != std::mem::discriminant(&re_export_info.category)
+                {
+                    // We'll handle empty lines in the codegen phase
+                }
+            }
+            new_body.push(ModuleItem::from(re_export_info.export_decl));
+            last_re_export_category = Some(re_export_info.category);
+        }
+
+        // Add ambient blocks, in the order they were written
+        new_body.extend(ambient_blocks);
+
+        // Add organized items
+        new_body.extend(organized_items);
+        new_body.extend(export_assignments);
+
+        module.body = new_body;
+
+        // Apply other transformations
+        let mut organizer = OrganizerVisitor::new(self.sort_hook_dependencies, self.rules);
+        for item in &mut module.body {
+            if !self.is_ignored(item) && !is_ambient_block(item) {
+                item.visit_mut_with(&mut organizer);
+            }
+        }
+        change_log.sorted_enums = organizer.sorted_enums;
+
+        Ok((module, change_log))
+    }
+
+    /// The declarations of `items` placed above a declaration that depends on
+    /// them, which the author had written first.
+    fn hoisted_declarations(
+        items: &[ModuleItem],
+        dependency_graph: &DependencyGraph,
+    ) -> Vec<String> {
+        let named: Vec<_> = items
+            .iter()
+            .filter_map(|item| Some((Self::get_item_name(item)?, item.span_lo())))
+            .collect();
+
+        named
+            .iter()
+            .enumerate()
+            .filter(|(i, (name, written_at))| {
+                named[i + 1..]
+                    .iter()
+                    .any(|(dependent, dependent_written_at)| {
+                        dependent_written_at < written_at
+                            && dependency_graph.depends_on(dependent, name)
+                    })
+            })
+            .map(|(_, (name, _))| name.clone())
+            .collect()
+    }
+
+    /// Organize declarations by visibility level with alphabetization and locality.
+    ///
+    /// This implements FR2.4 and FR2.5: visibility-based grouping with alphabetization
+    /// and dependency-export locality.
+    /// - Exported items appear first (public API) with their dependencies
+    /// - Dependencies are grouped with the exports that use them
+    /// - Non-exported items appear last (internal implementation)
+    /// - Within each group, items are sorted appropriately
+    fn organize_by_visibility(
+        &self,
+        items: Vec<ModuleItem>,
+        export_info: &ExportInfo,
+        dependency_graph: &DependencyGraph,
+    ) -> Result<Vec<ModuleItem>> {
+        // Create ordered lists and a map for lookup. TypeScript merges
+        // declarations sharing a name (an interface declared twice, a function
+        // and its namespace, overload signatures), so a name can have several
+        // parts; they move together and keep their written order.
+        let mut ordered_items = Vec::new();
+        let mut name_to_items: HashMap<String, Vec<ModuleItem>> = HashMap::new();
+        let mut other_items = Vec::new();
+        let mut export_statements = Vec::new();
+
+        // Maintain original order while building the map
+        for item in items {
+            if let Some(name) = Self::get_item_name(&item) {
+                let parts = name_to_items.entry(name.clone()).or_default();
+                if parts.is_empty() {
+                    ordered_items.push(name);
+                }
+                parts.push(item);
+            } else {
+                // Check if this is an export statement
+                if let ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(export)) = &item {
+                    if export.src.is_none() {
+                        export_statements.push(item);
+                        continue;
+                    }
+                }
+                other_items.push(item);
+            }
+        }
+
+        // Separate exported and non-exported names
+        let mut exported_names = Vec::new();
+        let mut non_exported_names = Vec::new();
+
+        for name in &ordered_items {
+            if export_info.is_exported(name) {
+                exported_names.push(name.clone());
+            } else {
+                non_exported_names.push(name.clone());
+            }
+        }
+
+        // Sort exported names alphabetically (case-insensitive)
+        self.sort_names(&mut exported_names, &name_to_items);
+        self.sort_names(&mut non_exported_names, &name_to_items);
+
+        let mut result = Vec::new();
+        let mut added = HashSet::new();
+
+        // Create export groups based on shared dependencies
+        let mut export_groups: Vec<Vec<String>> = Vec::new();
+        let mut processed_exports = HashSet::new();
+
+        for export_name in &exported_names {
+            if processed_exports.contains(export_name) {
+                continue;
+            }
+
+            // Start a new group with this export
+            let mut group = vec![export_name.clone()];
+            processed_exports.insert(export_name.clone());
+
+            // Find other exports that share dependencies with this one
+            let mut export_deps = HashSet::new();
+            Self::collect_all_deps(export_name, dependency_graph, &mut export_deps);
+
+            for other_export in &exported_names {
+                if processed_exports.contains(other_export) {
+                    continue;
+                }
+
+                let mut other_deps = HashSet::new();
+                Self::collect_all_deps(other_export, dependency_graph, &mut other_deps);
+
+                // If they share any dependencies, add to the group
+                if export_deps.intersection(&other_deps).count() > 0 {
+                    group.push(other_export.clone());
+                    processed_exports.insert(other_export.clone());
+                    // Add the new export's dependencies to the group's dependency set
+                    export_deps.extend(other_deps);
+                }
+            }
+
+            export_groups.push(group);
+        }
+
+        // Process each export group with its dependencies
+        for group in export_groups {
+            // Collect all dependencies for this group
+            let mut group_deps = HashSet::new();
+            for export_name in &group {
+                Self::collect_non_exported_deps(
+                    export_name,
+                    dependency_graph,
+                    &non_exported_names,
+                    &mut group_deps,
+                );
+            }
+
+            // Add dependencies first (alphabetically sorted for same-level dependencies)
+            let mut deps_to_add: Vec<_> = group_deps.into_iter().collect();
+            deps_to_add.sort_by_key(|dep| dep.to_lowercase());
+
+            for dep in deps_to_add {
+                if !added.contains(&dep) {
+                    Self::add_item_with_dependencies(
+                        &dep,
+                        &mut name_to_items,
+                        dependency_graph,
+                        &mut result,
+                        &mut added,
+                    );
+                }
+            }
+
+            // Then add the exports in the group (alphabetically sorted within group)
+            let mut sorted_group = group.clone();
+            self.sort_names(&mut sorted_group, &name_to_items);
+
+            for export_name in sorted_group {
+                if !added.contains(&export_name) {
+                    if let Some(parts) = name_to_items.remove(&export_name) {
+                        result.extend(parts);
+                        added.insert(export_name);
+                    }
+                }
+            }
+
+            // Add any export statements that export members from this group
+            // Only add if ALL exported members have been added already
+            let group_set: HashSet<_> = group.iter().cloned().collect();
+            let mut i = 0;
+            while i < export_statements.len() {
+                let should_add = if let ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(export)) =
+                    &export_statements[i]
+                {
+                    // Check if ALL specifiers are either in this group or already added
+                    export.specifiers.iter().all(|spec| {
+                        if let ExportSpecifier::Named(named) = spec {
+                            if let ModuleExportName::Ident(ident) = &named.orig {
+                                let name = ident.sym.to_string();
+                                // Should add if the name is in current group or already added
+                                group_set.contains(&name) || added.contains(&name)
+                            } else {
+                                true // Non-identifier exports don't block
+                            }
+                        } else {
+                            true // Non-named exports don't block
+                        }
+                    })
+                } else {
+                    false
+                };
+
+                if should_add {
+                    result.push(export_statements.remove(i));
+                } else {
+                    i += 1;
+                }
+            }
+        }
+
+        // Add non-exported items that weren't dependencies
+        for name in &non_exported_names {
+            if !added.contains(name) {
+                Self::add_item_with_dependencies(
+                    name,
+                    &mut name_to_items,
+                    dependency_graph,
+                    &mut result,
+                    &mut added,
+                );
+            }
+        }
+
+        // Add any remaining export statements
+        result.extend(export_statements);
+
+        // Add remaining items (like expression statements)
+        result.extend(other_items);
+
+        Ok(result)
+    }
+
+    /// Sort declaration names alphabetically (case-insensitive), with the
+    /// constant block first when constant grouping is enabled.
+    fn sort_names(&self, names: &mut [String], name_to_items: &HashMap<String, Vec<ModuleItem>>) {
+        names.sort_by_cached_key(|name| {
+            let is_constant = self.group_constants
+                && name_to_items
+                    .get(name)
+                    .is_some_and(|parts| parts.iter().all(Self::is_screaming_case_constant));
+            (!is_constant, name.to_lowercase())
+        });
+    }
+
+    /// Check for a `const MAX_RETRIES = 3`-style declaration: a single
+    /// SCREAMING_CASE binding initialized with a primitive literal.
+    ///
+    /// Primitive literals can't depend on anything, so pulling these ahead of the
+    /// rest of their group never breaks declaration order.
+    fn is_screaming_case_constant(item: &ModuleItem) -> bool {
+        let var = match item {
+            ModuleItem::Stmt(Stmt::Decl(Decl::Var(var))) => var,
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                decl: Decl::Var(var),
+                ..
+            })) => var,
+            _ => return false,
+        };
+
+        let [declarator] = var.decls.as_slice() else {
+            return false;
+        };
+        let (Pat::Ident(binding), Some(init)) = (&declarator.name, &declarator.init) else {
+            return false;
+        };
+
+        let name = binding.id.sym.as_str();
+        let is_screaming_case = name.chars().any(|c| c.is_ascii_uppercase())
+            && name
+                .chars()
+                .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit() || c == '_' || c == '$');
+
+        var.kind == VarDeclKind::Const && is_screaming_case && Self::is_primitive_literal(init)
+    }
+
+    fn is_primitive_literal(expr: &Expr) -> bool {
+        match expr {
+            Expr::Lit(Lit::Str(_) | Lit::Num(_) | Lit::Bool(_) | Lit::Null(_) | Lit::BigInt(_)) => {
+                true
+            }
+            Expr::Tpl(tpl) => tpl.exprs.is_empty(),
+            Expr::Unary(unary) => {
+                unary.op == UnaryOp::Minus
+                    && matches!(&*unary.arg, Expr::Lit(Lit::Num(_) | Lit::BigInt(_)))
+            }
+            Expr::TsConstAssertion(assertion) => Self::is_primitive_literal(&assertion.expr),
+            Expr::Paren(paren) => Self::is_primitive_literal(&paren.expr),
+            _ => false,
+        }
+    }
+
+    // Helper method to add an item with its dependencies
+    fn add_item_with_dependencies(
+        name: &str,
+        name_to_items: &mut HashMap<String, Vec<ModuleItem>>,
+        dependency_graph: &DependencyGraph,
+        result: &mut Vec<ModuleItem>,
+        added: &mut HashSet<String>,
+    ) {
+        Self::add_item_with_dependencies_recursive(
+            name,
+            name_to_items,
+            dependency_graph,
+            result,
+            added,
+            &mut HashSet::new(),
+        );
+    }
+
+    fn add_item_with_dependencies_recursive(
+        name: &str,
+        name_to_items: &mut HashMap<String, Vec<ModuleItem>>,
+        dependency_graph: &DependencyGraph,
+        result: &mut Vec<ModuleItem>,
+        added: &mut HashSet<String>,
+        visiting: &mut HashSet<String>,
+    ) {
+        if added.contains(name) || !name_to_items.contains_key(name) || visiting.contains(name) {
+            return;
+        }
+
+        visiting.insert(name.to_string());
+
+        // First add dependencies
+        if let Some(deps) = dependency_graph.dependencies.get(name) {
+            let mut sorted_deps: Vec<_> = deps.iter().cloned().collect();
+            sorted_deps.sort_by_key(|dep| dep.to_lowercase());
+
+            for dep in sorted_deps {
+                if !added.contains(&dep) {
+                    Self::add_item_with_dependencies_recursive(
+                        &dep,
+                        name_to_items,
+                        dependency_graph,
+                        result,
+                        added,
+                        visiting,
+                    );
+                }
+            }
+        }
+
+        visiting.remove(name);
+
+        // Then add the item itself
+        if let Some(parts) = name_to_items.remove(name) {
+            result.extend(parts);
+            added.insert(name.to_string());
+        }
+    }
+
+    /// Collect all dependencies (both exported and non-exported) of a given item.
+    fn collect_all_deps(
+        item_name: &str,
+        dependency_graph: &DependencyGraph,
+        all_deps: &mut HashSet<String>,
+    ) {
+        Self::collect_all_deps_recursive(
+            item_name,
+            dependency_graph,
+            all_deps,
+            &mut HashSet::new(),
+        );
+    }
+
+    fn collect_all_deps_recursive(
+        item_name: &str,
+        dependency_graph: &DependencyGraph,
+        all_deps: &mut HashSet<String>,
+        visiting: &mut HashSet<String>,
+    ) {
+        if visiting.contains(item_name) {
+            // Cycle detected, stop recursion
+            return;
+        }
+
+        visiting.insert(item_name.to_string());
+
+        if let Some(deps) = dependency_graph.dependencies.get(item_name) {
+            for dep in deps {
+                if !all_deps.contains(dep) {
+                    all_deps.insert(dep.clone());
+                    // Recursively collect dependencies of this dependency
+                    Self::collect_all_deps_recursive(dep, dependency_graph, all_deps, visiting);
+                }
+            }
+        }
+
+        visiting.remove(item_name);
+    }
+
+    /// Collect all non-exported dependencies of a given item.
+    fn collect_non_exported_deps(
+        item_name: &str,
+        dependency_graph: &DependencyGraph,
+        non_exported_names: &[String],
+        hoisted_deps: &mut HashSet<String>,
+    ) {
+        Self::collect_non_exported_deps_recursive(
+            item_name,
+            dependency_graph,
+            non_exported_names,
+            hoisted_deps,
+            &mut HashSet::new(),
+        );
+    }
+
+    fn collect_non_exported_deps_recursive(
+        item_name: &str,
+        dependency_graph: &DependencyGraph,
+        non_exported_names: &[String],
+        hoisted_deps: &mut HashSet<String>,
+        visiting: &mut HashSet<String>,
+    ) {
+        if visiting.contains(item_name) {
+            // Cycle detected, stop recursion
+            return;
+        }
+
+        visiting.insert(item_name.to_string());
+
+        if let Some(deps) = dependency_graph.dependencies.get(item_name) {
+            for dep in deps {
+                if non_exported_names.contains(dep) && !hoisted_deps.contains(dep) {
+                    hoisted_deps.insert(dep.clone());
+                    // Recursively collect dependencies of this dependency
+                    Self::collect_non_exported_deps_recursive(
+                        dep,
+                        dependency_graph,
+                        non_exported_names,
+                        hoisted_deps,
+                        visiting,
+                    );
+                }
+            }
+        }
+
+        visiting.remove(item_name);
+    }
+
+    fn is_ignored(&self, item: &ModuleItem) -> bool {
+        self.ignored_items.items.contains(&item.span_lo())
+    }
+
+    /// Whether ignore directives keep `item` where it is.
+    fn is_pinned(&self, item: &ModuleItem) -> bool {
+        self.is_ignored(item) || self.ignored_items.region_ends.contains(&item.span_lo())
+    }
+
+    /// Returns true if the item is a top-level expression statement with side effects.
+    ///
+    /// Pure expression statements (directives like `'use strict'`, bare identifiers)
+    /// are harmless to move, so only statements that call, construct, assign, or
+    /// otherwise observably touch state count as barriers.
+    ///
+    /// Anything using the comma operator is a barrier too, declarations included.
+    /// `a = 1, b = 2;` and `const ready = (init(), true)` do several things in
+    /// one statement, while naming at most one of them, so the dependency graph
+    /// can't be trusted to place them.
+    fn is_mutation_barrier(item: &ModuleItem) -> bool {
+        match item {
+            ModuleItem::Stmt(Stmt::Expr(expr_stmt)) => {
+                let mut finder = SideEffectFinder::default();
+                expr_stmt.expr.visit_with(&mut finder);
+                finder.found || Self::uses_comma_operator(&expr_stmt.expr)
+            }
+            ModuleItem::Stmt(Stmt::Decl(Decl::Var(var_decl)))
+            | ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+                decl: Decl::Var(var_decl),
+                ..
+            })) => var_decl
+                .decls
+                .iter()
+                .filter_map(|declarator| declarator.init.as_deref())
+                .any(Self::uses_comma_operator),
+            _ => false,
+        }
+    }
+
+    /// Whether `expr` evaluates a sequence expression, not counting ones inside
+    /// functions and classes it merely defines.
+    fn uses_comma_operator(expr: &Expr) -> bool {
+        let mut finder = SequenceFinder::default();
+        expr.visit_with(&mut finder);
+        finder.found
+    }
+
+    fn get_item_name(item: &ModuleItem) -> Option<String> {
+        match item {
+            ModuleItem::Stmt(Stmt::Decl(decl)) => DependencyAnalyzer::get_decl_name(decl),
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export_decl)) => {
+                DependencyAnalyzer::get_decl_name(&export_decl.decl)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// How many statements open `body` with a directive prologue, like
+/// `'use client';` or `'use strict';`.
+///
+/// Directives only count as such before any other statement, so they're
+/// kept first, above the imports.
+pub(crate) fn directive_prologue_len(body: &[ModuleItem]) -> usize {
+    body.iter()
+        .take_while(|item| {
+            matches!(item, ModuleItem::Stmt(Stmt::Expr(ExprStmt { expr, .. }))
+                if matches!(&**expr, Expr::Lit(Lit::Str(_))))
+        })
+        .count()
+}
+
+/// Whether `item` is a `declare global { ... }` or `declare module 'foo' { ... }`
+/// block, augmenting the types of the program or of another module.
+///
+/// These describe other code rather than this module's own, so they sit in a
+/// section of their own between the imports and the code, as written.
+pub(crate) fn is_ambient_block(item: &ModuleItem) -> bool {
+    matches!(
+        item,
+        ModuleItem::Stmt(Stmt::Decl(Decl::TsModule(module)))
+            if module.global || matches!(module.id, TsModuleName::Str(_))
+    )
+}
+
+/// How many items, by where they were written, moved to get to the order
+/// given: those outside the longest run still in written order. Putting the
+/// last import first moves one import, not all of them.
+fn moved_items(written_at: impl Iterator<Item = swc_common::BytePos>) -> usize {
+    let written_at: Vec<_> = written_at.collect();
+    // The longest such run ending at each item
+    let mut longest: Vec<usize> = Vec::with_capacity(written_at.len());
+    for (i, at) in written_at.iter().enumerate() {
+        let before = written_at[..i]
+            .iter()
+            .zip(&longest)
+            .filter(|(earlier, _)| *earlier < at)
+            .map(|(_, len)| *len)
+            .max()
+            .unwrap_or(0);
+        longest.push(before + 1);
+    }
+    written_at.len() - longest.into_iter().max().unwrap_or(0)
+}
+
+/// Detects expressions that can observably mutate state when evaluated.
+///
+/// Function and arrow bodies are skipped on purpose: defining a callback has no
+/// effect until something invokes it, and that invocation is itself a call we catch.
+#[derive(Default)]
+struct SideEffectFinder {
+    found: bool,
+}
+
+impl Visit for SideEffectFinder {
+    fn visit_call_expr(&mut self, _: &CallExpr) {
+        self.found = true;
+    }
+
+    fn visit_new_expr(&mut self, _: &NewExpr) {
+        self.found = true;
+    }
+
+    fn visit_assign_expr(&mut self, _: &AssignExpr) {
+        self.found = true;
+    }
+
+    fn visit_update_expr(&mut self, _: &UpdateExpr) {
+        self.found = true;
+    }
+
+    fn visit_await_expr(&mut self, _: &AwaitExpr) {
+        self.found = true;
+    }
+
+    fn visit_yield_expr(&mut self, _: &YieldExpr) {
+        self.found = true;
+    }
+
+    fn visit_tagged_tpl(&mut self, _: &TaggedTpl) {
+        self.found = true;
+    }
+
+    fn visit_unary_expr(&mut self, expr: &UnaryExpr) {
+        if expr.op == UnaryOp::Delete {
+            self.found = true;
+        } else {
+            expr.visit_children_with(self);
+        }
+    }
+
+    fn visit_function(&mut self, _: &Function) {}
+
+    fn visit_arrow_expr(&mut self, _: &ArrowExpr) {}
+
+    fn visit_class(&mut self, class: &Class) {
+        visit_class_evaluation(class, self);
+    }
+}
+
+/// Visits the parts of `class` that run when the class itself is evaluated:
+/// decorators, the `extends` clause, computed keys, static field initializers,
+/// and static blocks. Method bodies and instance fields wait for a call or a
+/// `new`, so they're skipped like any other function body.
+fn visit_class_evaluation<V: Visit>(class: &Class, visitor: &mut V) {
+    class.decorators.visit_with(visitor);
+    class.super_class.visit_with(visitor);
+    for member in &class.body {
+        match member {
+            ClassMember::Method(method) => {
+                method.function.decorators.visit_with(visitor);
+                method.key.visit_with(visitor);
+            }
+            ClassMember::PrivateMethod(method) => {
+                method.function.decorators.visit_with(visitor);
+            }
+            ClassMember::ClassProp(prop) => {
+                prop.decorators.visit_with(visitor);
+                prop.key.visit_with(visitor);
+                if prop.is_static {
+                    prop.value.visit_with(visitor);
+                }
+            }
+            ClassMember::PrivateProp(prop) => {
+                prop.decorators.visit_with(visitor);
+                if prop.is_static {
+                    prop.value.visit_with(visitor);
+                }
+            }
+            ClassMember::AutoAccessor(accessor) => {
+                accessor.decorators.visit_with(visitor);
+                accessor.key.visit_with(visitor);
+                if accessor.is_static {
+                    accessor.value.visit_with(visitor);
+                }
+            }
+            ClassMember::StaticBlock(block) => block.body.visit_with(visitor),
+            ClassMember::Constructor(_)
+            | ClassMember::TsIndexSignature(_)
+            | ClassMember::Empty(_) => {}
+        }
+    }
+}
+
+/// Whether a class field's initializer, or a static block, has to run where
+/// it was written: it reads the class or an instance (`this`, `super`, a
+/// private name, the class by `class_name`) or runs code. Literals and other
+/// self-contained values come out the same whenever they're evaluated.
+pub(crate) fn is_order_sensitive(member: &ClassMember, class_name: Option<&Ident>) -> bool {
+    let value = match member {
+        ClassMember::ClassProp(prop) => &prop.value,
+        ClassMember::PrivateProp(prop) => &prop.value,
+        ClassMember::StaticBlock(_) => return true,
+        _ => return false,
+    };
+    let Some(value) = value else {
+        return false;
+    };
+
+    let mut effects = SideEffectFinder::default();
+    value.visit_with(&mut effects);
+    let mut state = ClassStateFinder {
+        class_name,
+        found: false,
+    };
+    value.visit_with(&mut state);
+    effects.found || state.found
+}
+
+/// Detects expressions reading the class being defined or its instance.
+///
+/// Like [`SideEffectFinder`], it skips function and arrow bodies, which read
+/// nothing until they're called.
+struct ClassStateFinder<'a> {
+    class_name: Option<&'a Ident>,
+    found: bool,
+}
+
+impl Visit for ClassStateFinder<'_> {
+    fn visit_this_expr(&mut self, _: &ThisExpr) {
+        self.found = true;
+    }
+
+    fn visit_super(&mut self, _: &Super) {
+        self.found = true;
+    }
+
+    fn visit_private_name(&mut self, _: &PrivateName) {
+        self.found = true;
+    }
+
+    fn visit_ident(&mut self, ident: &Ident) {
+        if self.class_name.is_some_and(|name| name.sym == ident.sym) {
+            self.found = true;
+        }
+    }
+
+    fn visit_function(&mut self, _: &Function) {}
+
+    fn visit_arrow_expr(&mut self, _: &ArrowExpr) {}
+
+    fn visit_class(&mut self, class: &Class) {
+        visit_class_evaluation(class, self);
+    }
+}
+
+/// Detects comma-operator sequences evaluated by an expression.
+#[derive(Default)]
+struct SequenceFinder {
+    found: bool,
+}
+
+impl Visit for SequenceFinder {
+    fn visit_seq_expr(&mut self, _: &SeqExpr) {
+        self.found = true;
+    }
+
+    fn visit_function(&mut self, _: &Function) {}
+
+    fn visit_arrow_expr(&mut self, _: &ArrowExpr) {}
+
+    fn visit_class(&mut self, class: &Class) {
+        visit_class_evaluation(class, self);
+    }
+}
+
+/// A framework whose classes have a conventional lifecycle method order, which
+/// alphabetizing would scramble.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LifecycleProfile {
+    /// Class components: `extends Component` or `React.PureComponent` and the like
+    React,
+    /// Components and services implementing a lifecycle hook interface such as `OnInit`
+    Angular,
+}
+
+/// Where a lifecycle method goes among a class's members.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LifecyclePosition {
+    /// After the constructor, at this index of the framework's call order
+    Hook(usize),
+    /// After every other member
+    Last,
+}
+
+impl LifecycleProfile {
+    /// React's instance lifecycle methods, in the order React calls them.
+    const REACT_METHODS: &'static [&'static str] = &[
+        "getChildContext",
+        "componentWillMount",
+        "UNSAFE_componentWillMount",
+        "componentDidMount",
+        "componentWillReceiveProps",
+        "UNSAFE_componentWillReceiveProps",
+        "shouldComponentUpdate",
+        "componentWillUpdate",
+        "UNSAFE_componentWillUpdate",
+        "getSnapshotBeforeUpdate",
+        "componentDidUpdate",
+        "componentDidCatch",
+        "componentWillUnmount",
+    ];
+
+    /// Angular's lifecycle hooks, in the order Angular calls them.
+    const ANGULAR_METHODS: &'static [&'static str] = &[
+        "ngOnChanges",
+        "ngOnInit",
+        "ngDoCheck",
+        "ngAfterContentInit",
+        "ngAfterContentChecked",
+        "ngAfterViewInit",
+        "ngAfterViewChecked",
+        "ngOnDestroy",
+    ];
+
+    const ANGULAR_INTERFACES: &'static [&'static str] = &[
+        "OnChanges",
+        "OnInit",
+        "DoCheck",
+        "AfterContentInit",
+        "AfterContentChecked",
+        "AfterViewInit",
+        "AfterViewChecked",
+        "OnDestroy",
+    ];
+
+    /// Recognize a framework class by what it extends or implements.
+    fn detect(class: &Class) -> Option<Self> {
+        let is_react_base = |name: &str| matches!(name, "Component" | "PureComponent");
+        let extends_react = match class.super_class.as_deref() {
+            Some(Expr::Ident(ident)) => is_react_base(&ident.sym),
+            Some(Expr::Member(member)) => member
+                .prop
+                .as_ident()
+                .is_some_and(|prop| is_react_base(&prop.sym)),
+            _ => false,
+        };
+        if extends_react {
+            return Some(LifecycleProfile::React);
+        }
+
+        let implements_hook = class.implements.iter().any(|implemented| {
+            let name = match &*implemented.expr {
+                Expr::Ident(ident) => Some(&ident.sym),
+                Expr::Member(member) => member.prop.as_ident().map(|prop| &prop.sym),
+                _ => None,
+            };
+            name.is_some_and(|name| Self::ANGULAR_INTERFACES.contains(&name.as_str()))
+        });
+        implements_hook.then_some(LifecycleProfile::Angular)
+    }
+
+    fn position(&self, method: &str) -> Option<LifecyclePosition> {
+        let methods = match self {
+            LifecycleProfile::React if method == "render" => {
+                return Some(LifecyclePosition::Last);
+            }
+            LifecycleProfile::React => Self::REACT_METHODS,
+            LifecycleProfile::Angular => Self::ANGULAR_METHODS,
+        };
+        methods
+            .iter()
+            .position(|name| *name == method)
+            .map(LifecyclePosition::Hook)
+    }
+}
+
+/// Whether the cases of a switch can go in any order without changing what
+/// it does.
+///
+/// Every case has to test a different string literal and leave the switch at
+/// the end of its body (`break`, `return`, `throw` or `continue`), so none
+/// falls through into the one after it. A `default` is allowed, as the last
+/// case only.
+pub(crate) fn has_independent_cases(cases: &[SwitchCase]) -> bool {
+    let tested = match cases.last() {
+        Some(case) if case.test.is_none() => &cases[..cases.len() - 1],
+        _ => cases,
+    };
+    let mut seen = HashSet::new();
+    tested.len() > 1
+        && tested.iter().all(|case| {
+            switch_case_key(case).is_some_and(|key| seen.insert(key)) && leaves_switch(&case.cons)
+        })
+}
+
+/// The string a case tests, if it tests a string literal.
+fn switch_case_key(case: &SwitchCase) -> Option<String> {
+    match case.test.as_deref()? {
+        Expr::Lit(Lit::Str(s)) => Some(s.value.to_string()),
+        _ => None,
+    }
+}
+
+/// Whether a case body always ends by leaving the switch. Only its last
+/// statement is looked at, so an `if` that returns on both branches doesn't
+/// count; keeping such a switch as written is always safe.
+fn leaves_switch(stmts: &[Stmt]) -> bool {
+    match stmts.last() {
+        Some(Stmt::Break(_) | Stmt::Return(_) | Stmt::Throw(_) | Stmt::Continue(_)) => true,
+        Some(Stmt::Block(block)) => leaves_switch(&block.stmts),
+        _ => false,
+    }
+}
+
+/// Visitor that applies fine-grained organizing rules to AST nodes.
+///
+/// This handles the detailed organizing work: sorting object properties,
+/// organizing class members, ordering JSX attributes, etc. Each sorting
+/// operation follows specific rules designed for maximum readability.
+struct OrganizerVisitor {
+    sort_hook_dependencies: bool,
+    rules: RuleSet,
+    /// Names of the enums whose members moved
+    sorted_enums: Vec<String>,
+    /// The name of the class about to be visited, which its fields can read
+    class_name: Option<Ident>,
+}
+
+/// React hooks that take a dependency array, with the argument index it sits at.
+///
+/// Custom hooks are deliberately absent: we can't know whether a custom hook's
+/// array argument is a dependency list or ordered data.
+const HOOKS_WITH_DEPENDENCIES: &[(&str, usize)] = &[
+    ("useCallback", 1),
+    ("useEffect", 1),
+    ("useImperativeHandle", 2),
+    ("useInsertionEffect", 1),
+    ("useLayoutEffect", 1),
+    ("useMemo", 1),
+];
+
+impl OrganizerVisitor {
+    fn new(sort_hook_dependencies: bool, rules: RuleSet) -> Self {
+        Self {
+            sort_hook_dependencies,
+            rules,
+            sorted_enums: Vec::new(),
+            class_name: None,
+        }
+    }
+
+    /// Find the dependency array of a React hook call, if it has one.
+    ///
+    /// Matches both `useEffect(...)` and `React.useEffect(...)`.
+    fn hook_dependency_array<'a>(&self, call: &'a mut CallExpr) -> Option<&'a mut ArrayLit> {
+        let Callee::Expr(callee) = &call.callee else {
+            return None;
+        };
+        let hook_name = match &**callee {
+            Expr::Ident(ident) => ident.sym.as_str(),
+            Expr::Member(member) => match (&*member.obj, &member.prop) {
+                (Expr::Ident(obj), MemberProp::Ident(prop)) if obj.sym == "React" => {
+                    prop.sym.as_str()
+                }
+                _ => return None,
+            },
+            _ => return None,
+        };
+
+        let &(_, deps_index) = HOOKS_WITH_DEPENDENCIES
+            .iter()
+            .find(|(name, _)| *name == hook_name)?;
+
+        match call.args.get_mut(deps_index) {
+            Some(ExprOrSpread { spread: None, expr }) => match &mut **expr {
+                Expr::Array(array) => Some(array),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Alphabetize a dependency array if it contains only plain identifiers.
+    ///
+    /// React compares dependencies by identity, position by position, so any
+    /// permutation of the same identifiers is equivalent. Anything richer (member
+    /// accesses, calls, spreads, holes) is left alone because reordering it could
+    /// reorder evaluation. Duplicates are kept: removing one would change the
+    /// array's length, which React warns about between renders.
+    fn sort_hook_dependencies(&self, array: &mut ArrayLit) {
+        let mut names = Vec::with_capacity(array.elems.len());
+        for elem in &array.elems {
+            match elem {
+                Some(ExprOrSpread { spread: None, expr }) => match &**expr {
+                    Expr::Ident(ident) => names.push(ident.sym.to_lowercase()),
+                    _ => return,
+                },
+                _ => return,
+            }
+        }
+
+        let mut keyed: Vec<_> = names.into_iter().zip(array.elems.drain(..)).collect();
+        keyed.sort_by(|(a, _), (b, _)| a.cmp(b));
+        array.elems = keyed.into_iter().map(|(_, elem)| elem).collect();
+    }
+
+    fn sort_object_props(&self, props: &mut [PropOrSpread]) {
+        // Later keys override what a spread brings in and spreads override the
+        // keys before them (`{ ...defaults, timeout: 5 }`), so spreads stay
+        // where they are and only the properties between them are sorted.
+        for segment in props.split_mut(|prop| matches!(prop, PropOrSpread::Spread(_))) {
+            segment.sort_by(|a, b| {
+                let key_a = self.get_prop_key(a);
+                let key_b = self.get_prop_key(b);
+                key_a.to_lowercase().cmp(&key_b.to_lowercase())
+            });
+        }
+    }
+
+    fn get_prop_key(&self, prop: &PropOrSpread) -> String {
+        match prop {
+            PropOrSpread::Prop(prop) => match &**prop {
+                Prop::Shorthand(ident) => ident.sym.to_string(),
+                Prop::KeyValue(kv) => match &kv.key {
+                    PropName::Ident(ident) => ident.sym.to_string(),
+                    PropName::Str(s) => s.value.to_string(),
+                    PropName::Num(n) => n.value.to_string(),
+                    _ => String::new(),
+                },
+                _ => String::new(),
+            },
+            PropOrSpread::Spread(_) => String::from("..."),
+        }
+    }
+
+    fn sort_object_pattern_props(&self, props: &mut [ObjectPatProp]) {
+        props.sort_by(|a, b| {
+            let key_a = self.get_object_pat_prop_key(a);
+            let key_b = self.get_object_pat_prop_key(b);
+            key_a.to_lowercase().cmp(&key_b.to_lowercase())
+        });
+    }
+
+    fn get_object_pat_prop_key(&self, prop: &ObjectPatProp) -> String {
+        match prop {
+            ObjectPatProp::KeyValue(kv) => match &kv.key {
+                PropName::Ident(ident) => ident.sym.to_string(),
+                PropName::Str(s) => s.value.to_string(),
+                PropName::Num(n) => n.value.to_string(),
+                _ => String::new(),
+            },
+            ObjectPatProp::Assign(assign) => assign.key.sym.to_string(),
+            ObjectPatProp::Rest(_) => String::from("..."), // Sort rest to the end
+        }
+    }
+
+    fn sort_class_members(
+        &self,
+        members: &mut [ClassMember],
+        profile: Option<LifecycleProfile>,
+        class_name: Option<&Ident>,
+    ) {
+        // Class member ordering follows a visibility-based hierarchy for clarity:
+        // 1. Public static fields (alphabetically) - public class-level state
+        // 2. Private static fields (alphabetically) - private class-level state
+        // 3. Public static methods (alphabetically) - public class-level behavior
+        // 4. Private static methods (alphabetically) - private class-level behavior
+        // 5. Public instance fields (alphabetically) - public instance state
+        // 6. Private instance fields (alphabetically) - private instance state
+        // 7. Constructor - initialization logic (always singular)
+        // 8. Public instance methods (alphabetically) - public instance behavior
+        // 9. Private instance methods (alphabetically) - private instance behavior
+        //
+        // This organization clearly separates public API from private implementation
+        // while maintaining logical grouping of related members. Private members use
+        // the # syntax for true runtime privacy.
+        //
+        // Framework classes are read in lifecycle order, so their lifecycle methods
+        // follow the constructor in the order the framework calls them, and a React
+        // component's `render` comes last of all.
+        members.sort_by(|a, b| {
+            use std::cmp::Ordering;
+
+            // First, categorize members
+            let (cat_a, rank_a, key_a) = self.categorize_class_member(a, profile);
+            let (cat_b, rank_b, key_b) = self.categorize_class_member(b, profile);
+
+            // Compare categories first, then lifecycle position
+            match (cat_a, rank_a).cmp(&(cat_b, rank_b)) {
+                Ordering::Equal => {
+                    // Within the same category, sort alphabetically by key. Keys
+                    // differing only in case are told apart so nothing lands
+                    // between a getter and its setter, which go getter first.
+                    key_a
+                        .to_lowercase()
+                        .cmp(&key_b.to_lowercase())
+                        .then_with(|| key_a.cmp(&key_b))
+                        .then_with(|| Self::accessor_rank(a).cmp(&Self::accessor_rank(b)))
+                }
+                other => other,
+            }
+        });
+
+        // Decorators run in the order their members are declared, which
+        // Angular and NestJS classes can depend on (the order `@Input()`s are
+        // registered in, say). So the decorated members of a group keep their
+        // written order, filling the places sorting gave them.
+        let mut start = 0;
+        while start < members.len() {
+            let (category, rank, _) = self.categorize_class_member(&members[start], profile);
+            let end = start
+                + members[start..]
+                    .iter()
+                    .take_while(|member| {
+                        let (c, r, _) = self.categorize_class_member(member, profile);
+                        (c, r) == (category, rank)
+                    })
+                    .count();
+
+            let slots: Vec<usize> = (start..end)
+                .filter(|&i| Self::is_decorated(&members[i]))
+                .collect();
+            Self::keep_written_order(members, slots);
+
+            start = end;
+        }
+
+        // Field initializers run in the order they're declared. Most can't
+        // tell, but one that reads the class or an instance, or runs code,
+        // sees exactly the fields initialized before it. So where a class's
+        // static or instance fields have such an initializer (or a static
+        // block), all the initialized ones there keep their written order,
+        // public and private alike, and decorated fields keep theirs along
+        // with them. Fields set to literals and the like still sort.
+        for is_static in [true, false] {
+            let fields: Vec<usize> = (0..members.len())
+                .filter(|&i| Self::field_staticness(&members[i]) == Some(is_static))
+                .collect();
+            let order_sensitive = fields
+                .iter()
+                .any(|&i| is_order_sensitive(&members[i], class_name));
+
+            let slots: Vec<usize> = fields
+                .into_iter()
+                .filter(|&i| {
+                    (order_sensitive && Self::is_initialized(&members[i]))
+                        || Self::is_decorated(&members[i])
+                })
+                .collect();
+            Self::keep_written_order(members, slots);
+        }
+    }
+
+    /// Puts the members at `slots` back in the order they were written,
+    /// filling the places sorting gave them.
+    fn keep_written_order(members: &mut [ClassMember], slots: Vec<usize>) {
+        let mut kept: Vec<ClassMember> = slots.iter().map(|&i| members[i].clone()).collect();
+        kept.sort_by_key(|member| member.span().lo);
+        for (slot, member) in slots.into_iter().zip(kept) {
+            members[slot] = member;
+        }
+    }
+
+    /// Whether `member` is a static or an instance field, if it's a field at
+    /// all. Static blocks count as static fields.
+    fn field_staticness(member: &ClassMember) -> Option<bool> {
+        match member {
+            ClassMember::ClassProp(prop) => Some(prop.is_static),
+            ClassMember::PrivateProp(prop) => Some(prop.is_static),
+            ClassMember::StaticBlock(_) => Some(true),
+            _ => None,
+        }
+    }
+
+    /// Whether `member` runs code as the class or an instance is set up.
+    fn is_initialized(member: &ClassMember) -> bool {
+        match member {
+            ClassMember::ClassProp(prop) => prop.value.is_some(),
+            ClassMember::PrivateProp(prop) => prop.value.is_some(),
+            ClassMember::StaticBlock(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Whether decorators run for `member`, its own or those of its
+    /// parameters (`@Body() body`).
+    fn is_decorated(member: &ClassMember) -> bool {
+        let function = match member {
+            ClassMember::ClassProp(prop) => return !prop.decorators.is_empty(),
+            ClassMember::PrivateProp(prop) => return !prop.decorators.is_empty(),
+            ClassMember::AutoAccessor(accessor) => return !accessor.decorators.is_empty(),
+            ClassMember::Method(method) => &method.function,
+            ClassMember::PrivateMethod(method) => &method.function,
+            _ => return false,
+        };
+        !function.decorators.is_empty()
+            || function
+                .params
+                .iter()
+                .any(|param| !param.decorators.is_empty())
+    }
+
+    /// Where a member goes among the members sharing its name: a getter
+    /// before its setter.
+    fn accessor_rank(member: &ClassMember) -> u8 {
+        let kind = match member {
+            ClassMember::Method(method) => method.kind,
+            ClassMember::PrivateMethod(method) => method.kind,
+            _ => return 0,
+        };
+        match kind {
+            MethodKind::Setter => 1,
+            MethodKind::Method | MethodKind::Getter => 0,
+        }
+    }
+
+    fn categorize_class_member(
+        &self,
+        member: &ClassMember,
+        profile: Option<LifecycleProfile>,
+    ) -> (u8, usize, String) {
+        match member {
+            ClassMember::ClassProp(prop) => {
+                let (is_private, key) = self.get_prop_key_and_visibility(&prop.key);
+
+                match (prop.is_static, is_private) {
+                    (true, false) => (0, 0, key),  // Public static fields
+                    (true, true) => (1, 0, key),   // Private static fields
+                    (false, false) => (4, 0, key), // Public instance fields
+                    (false, true) => (5, 0, key),  // Private instance fields
+                }
+            }
+            ClassMember::Constructor(_) => {
+                (6, 0, "constructor".to_string()) // Constructor is 7th
+            }
+            ClassMember::Method(method) => {
+                let (is_private, key) = self.get_prop_key_and_visibility(&method.key);
+
+                match (method.is_static, is_private) {
+                    (true, false) => (2, 0, key), // Public static methods
+                    (true, true) => (3, 0, key),  // Private static methods
+                    (false, false) => {
+                        let lifecycle = profile
+                            .filter(|_| method.kind == MethodKind::Method)
+                            .and_then(|profile| profile.position(&key));
+                        match lifecycle {
+                            Some(LifecyclePosition::Last) => (9, 0, key),
+                            // Right after the constructor, in call order
+                            Some(LifecyclePosition::Hook(rank)) => (6, rank + 1, key),
+                            None => (7, 0, key), // Public instance methods
+                        }
+                    }
+                    (false, true) => (8, 0, key), // Private instance methods
+                }
+            }
+            ClassMember::PrivateProp(prop) => {
+                let key = prop.key.name.to_string();
+                if prop.is_static {
+                    (1, 0, key) // Private static fields
+                } else {
+                    (5, 0, key) // Private instance fields
+                }
+            }
+            ClassMember::PrivateMethod(method) => {
+                let key = method.key.name.to_string();
+                if method.is_static {
+                    (3, 0, key) // Private static methods
+                } else {
+                    (8, 0, key) // Private instance methods
+                }
+            }
+            // Static blocks set up the class alongside its static fields
+            ClassMember::StaticBlock(_) => (0, 0, String::new()),
+            _ => (99, 0, String::new()), // Other members at the end
+        }
+    }
+
+    fn get_prop_key_and_visibility(&self, prop_name: &PropName) -> (bool, String) {
+        match prop_name {
+            PropName::Ident(ident) => (false, ident.sym.to_string()),
+            PropName::Str(s) => (false, s.value.to_string()),
+            PropName::Num(n) => (false, n.value.to_string()),
+            PropName::BigInt(b) => (false, b.value.to_string()),
+            PropName::Computed(_) => (false, String::new()),
+        }
+    }
+
+    fn sort_union_types(&self, types: &mut [Box<TsType>]) {
+        types.sort_by(|a, b| {
+            let key_a = self.get_type_sort_key(a);
+            let key_b = self.get_type_sort_key(b);
+            key_a.to_lowercase().cmp(&key_b.to_lowercase())
+        });
+    }
+
+    fn sort_intersection_types(&self, types: &mut [Box<TsType>]) {
+        types.sort_by(|a, b| {
+            let key_a = self.get_type_sort_key(a);
+            let key_b = self.get_type_sort_key(b);
+            key_a.to_lowercase().cmp(&key_b.to_lowercase())
+        });
+    }
+
+    fn sort_switch_cases(&self, cases: &mut [SwitchCase]) {
+        // A trailing `default` stays last, where it already was
+        let sortable = match cases.last() {
+            Some(case) if case.test.is_none() => cases.len() - 1,
+            _ => cases.len(),
+        };
+        cases[..sortable].sort_by(|a, b| {
+            let key_a = switch_case_key(a).unwrap_or_default();
+            let key_b = switch_case_key(b).unwrap_or_default();
+            key_a.to_lowercase().cmp(&key_b.to_lowercase())
+        });
+    }
+
+    fn sort_type_elements(&self, members: &mut [TsTypeElement]) {
+        // Call, construct and index signatures describe the type as a whole, and
+        // the order of call signatures picks the overload, so they stay first
+        // as written. The sort is stable, which keeps overloads of a method
+        // and a getter next to its setter in order too.
+        members.sort_by(|a, b| {
+            let (pinned_a, key_a) = self.get_type_element_key(a);
+            let (pinned_b, key_b) = self.get_type_element_key(b);
+            pinned_b
+                .cmp(&pinned_a)
+                .then_with(|| key_a.to_lowercase().cmp(&key_b.to_lowercase()))
+        });
+    }
+
+    fn get_type_element_key(&self, member: &TsTypeElement) -> (bool, String) {
+        let (key, computed) = match member {
+            TsTypeElement::TsCallSignatureDecl(_)
+            | TsTypeElement::TsConstructSignatureDecl(_)
+            | TsTypeElement::TsIndexSignature(_) => return (true, String::new()),
+            TsTypeElement::TsPropertySignature(prop) => (&prop.key, prop.computed),
+            TsTypeElement::TsGetterSignature(getter) => (&getter.key, getter.computed),
+            TsTypeElement::TsSetterSignature(setter) => (&setter.key, setter.computed),
+            TsTypeElement::TsMethodSignature(method) => (&method.key, method.computed),
+        };
+        if computed {
+            return (false, String::new());
+        }
+        let key = match &**key {
+            Expr::Ident(ident) => ident.sym.to_string(),
+            Expr::Lit(Lit::Str(s)) => s.value.to_string(),
+            Expr::Lit(Lit::Num(n)) => n.value.to_string(),
+            _ => String::new(),
+        };
+        (false, key)
+    }
+
+    fn get_type_sort_key(&self, ts_type: &TsType) -> String {
+        match ts_type {
+            TsType::TsTypeRef(type_ref) => {
+                match &type_ref.type_name {
+                    TsEntityName::Ident(ident) => ident.sym.to_string(),
+                    TsEntityName::TsQualifiedName(_) => String::from("~qualified"), // Sort qualified names later
+                }
+            }
+            TsType::TsLitType(lit) => {
+                match &lit.lit {
+                    TsLit::Str(s) => s.value.to_string(),
+                    TsLit::Number(n) => n.value.to_string(),
+                    TsLit::Bool(b) => b.value.to_string(),
+                    TsLit::BigInt(b) => b.value.to_string(),
+                    TsLit::Tpl(_) => String::from("~template"), // Sort template literals later
+                }
+            }
+            TsType::TsKeywordType(keyword) => {
+                format!("~keyword:{:?}", keyword.kind) // Sort keywords by their kind
+            }
+            _ => String::from("~other"), // Sort other types to the end
+        }
+    }
+
+    fn is_string_enum(&self, members: &[TsEnumMember]) -> bool {
+        // String enum detection is conservative to avoid breaking code.
+        // We only sort enums where ALL members have explicit string values.
+        // Numeric enums often encode meaningful order (priority levels, bit flags)
+        // so we preserve their original sequence.
+        let mut has_string_init = false;
+        let mut has_numeric_init = false;
+        let mut has_no_init = false;
+
+        for member in members {
+            match &member.init {
+                Some(init) => match &**init {
+                    Expr::Lit(Lit::Str(_)) => has_string_init = true,
+                    Expr::Lit(Lit::Num(_)) => has_numeric_init = true,
+                    _ => return false, // Complex expression, don't sort
+                },
+                None => has_no_init = true,
+            }
+        }
+
+        // Don't sort if we have both string and numeric initializers
+        if has_string_init && has_numeric_init {
+            return false;
+        }
+
+        // Don't sort if we have numeric initializers or implicit numeric values
+        if has_numeric_init || has_no_init {
+            return false;
+        }
+
+        // Only sort if all members have string initializers
+        has_string_init
+    }
+
+    fn sort_enum_members(&self, members: &mut [TsEnumMember]) {
+        members.sort_by(|a, b| {
+            let key_a =
+                a.id.as_ident()
+                    .map(|ident| ident.sym.to_string())
+                    .unwrap_or_default();
+            let key_b =
+                b.id.as_ident()
+                    .map(|ident| ident.sym.to_string())
+                    .unwrap_or_default();
+            key_a.to_lowercase().cmp(&key_b.to_lowercase())
+        });
+    }
+
+    fn sort_jsx_attributes(&self, attrs: &mut [JSXAttrOrSpread]) {
+        attrs.sort_by(|a, b| {
+            let (cat_a, key_a) = self.categorize_jsx_attr(a);
+            let (cat_b, key_b) = self.categorize_jsx_attr(b);
+
+            match cat_a.cmp(&cat_b) {
+                std::cmp::Ordering::Equal => key_a.to_lowercase().cmp(&key_b.to_lowercase()),
+                other => other,
+            }
+        });
+    }
+
+    fn normalize_jsx_boolean_attributes(&self, attrs: &mut [JSXAttrOrSpread]) {
+        // `prop={true}` and bare `prop` are equivalent in JSX, so we collapse to the
+        // shorthand. `prop={false}` stays explicit because omitting it would change
+        // meaning, and anything that isn't a literal `true` (variables, conditions)
+        // is left alone since we can't prove its value.
+        for attr in attrs.iter_mut() {
+            if let JSXAttrOrSpread::JSXAttr(jsx_attr) = attr {
+                let is_literal_true = matches!(
+                    &jsx_attr.value,
+                    Some(JSXAttrValue::JSXExprContainer(JSXExprContainer {
+                        expr: JSXExpr::Expr(expr),
+                        ..
+                    })) if matches!(&**expr, Expr::Lit(Lit::Bool(Bool { value: true, .. })))
+                );
+
+                if is_literal_true {
+                    jsx_attr.value = None;
+                }
+            }
+        }
+    }
+
+    fn categorize_jsx_attr(&self, attr: &JSXAttrOrSpread) -> (u8, String) {
+        match attr {
+            JSXAttrOrSpread::JSXAttr(jsx_attr) => {
+                match &jsx_attr.name {
+                    JSXAttrName::Ident(ident) => {
+                        let name = ident.sym.to_string();
+                        // JSX attribute ordering follows React best practices:
+                        // 1. key - React needs this for reconciliation
+                        // 2. ref - Often accessed before render
+                        // 3. Regular props - Alphabetically for easy scanning
+                        // 4. Event handlers - Grouped together as they represent behavior
+                        // 5. Spread props - Last because they can override earlier props
+                        match name.as_str() {
+                            "key" => (0, name), // key always first
+                            "ref" => (1, name), // ref second
+                            s if s.starts_with("on")
+                                && s.len() > 2
+                                && s.chars().nth(2).unwrap().is_uppercase() =>
+                            {
+                                (3, name) // Event handlers grouped
+                            }
+                            _ => (2, name), // Regular props alphabetically
+                        }
+                    }
+                    _ => (2, String::new()),
+                }
+            }
+            JSXAttrOrSpread::SpreadElement(_) => (4, String::from("...")), // Spreads at the end
+        }
+    }
+}
+
+impl VisitMut for OrganizerVisitor {
+    fn visit_mut_object_lit(&mut self, obj: &mut ObjectLit) {
+        if self.rules.is_enabled(RuleGroup::ObjectProperties) {
+            self.sort_object_props(&mut obj.props);
+        }
+        obj.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_param(&mut self, param: &mut Param) {
+        // Sort object pattern destructuring in function parameters
+        if let Pat::Object(obj_pat) = &mut param.pat {
+            if self.rules.is_enabled(RuleGroup::Destructuring) {
+                self.sort_object_pattern_props(&mut obj_pat.props);
+            }
+        }
+        param.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_pat(&mut self, pat: &mut Pat) {
+        // Handle object patterns in other contexts (like arrow functions)
+        if let Pat::Object(obj_pat) = pat {
+            if self.rules.is_enabled(RuleGroup::Destructuring) {
+                self.sort_object_pattern_props(&mut obj_pat.props);
+            }
+        }
+        pat.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_class_decl(&mut self, class_decl: &mut ClassDecl) {
+        self.class_name = Some(class_decl.ident.clone());
+        class_decl.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_class_expr(&mut self, class_expr: &mut ClassExpr) {
+        self.class_name = class_expr.ident.clone();
+        class_expr.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_class(&mut self, class: &mut Class) {
+        // Taken here, so the classes nested in this one don't see it
+        let class_name = self.class_name.take();
+
+        // Sort class members according to the rules
+        if self.rules.is_enabled(RuleGroup::ClassMembers) {
+            self.sort_class_members(
+                &mut class.body,
+                LifecycleProfile::detect(class),
+                class_name.as_ref(),
+            );
+        }
+        class.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_ts_type(&mut self, ts_type: &mut TsType) {
+        if !self.rules.is_enabled(RuleGroup::TypeMembers) {
+            ts_type.visit_mut_children_with(self);
+            return;
+        }
+
+        if let TsType::TsUnionOrIntersectionType(union_or_intersection) = ts_type {
+            match union_or_intersection {
+                TsUnionOrIntersectionType::TsUnionType(union) => {
+                    self.sort_union_types(&mut union.types);
+                }
+                TsUnionOrIntersectionType::TsIntersectionType(intersection) => {
+                    self.sort_intersection_types(&mut intersection.types);
+                }
+            }
+        }
+        ts_type.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_ts_interface_body(&mut self, body: &mut TsInterfaceBody) {
+        if self.rules.is_enabled(RuleGroup::TypeMembers) {
+            self.sort_type_elements(&mut body.body);
+        }
+        body.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_ts_type_lit(&mut self, type_lit: &mut TsTypeLit) {
+        if self.rules.is_enabled(RuleGroup::TypeMembers) {
+            self.sort_type_elements(&mut type_lit.members);
+        }
+        type_lit.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_switch_stmt(&mut self, switch: &mut SwitchStmt) {
+        if self.rules.is_enabled(RuleGroup::SwitchCases) && has_independent_cases(&switch.cases) {
+            self.sort_switch_cases(&mut switch.cases);
+        }
+        switch.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_ts_enum_decl(&mut self, ts_enum: &mut TsEnumDecl) {
+        // Only sort if it's a string enum
+        if self.rules.is_enabled(RuleGroup::EnumMembers) && self.is_string_enum(&ts_enum.members) {
+            let written: Vec<_> = ts_enum.members.iter().map(|m| m.span.lo).collect();
+            self.sort_enum_members(&mut ts_enum.members);
+            if ts_enum.members.iter().map(|m| m.span.lo).ne(written) {
+                self.sorted_enums.push(ts_enum.id.sym.to_string());
+            }
+        }
+        ts_enum.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_call_expr(&mut self, call: &mut CallExpr) {
+        // Opt-in only: some teams rely on dependency order to mirror the order
+        // values are used in the callback.
+        if self.sort_hook_dependencies {
+            if let Some(deps) = self.hook_dependency_array(call) {
+                self.sort_hook_dependencies(deps);
+            }
+        }
+        call.visit_mut_children_with(self);
+    }
+
+    fn visit_mut_jsx_opening_element(&mut self, jsx_opening: &mut JSXOpeningElement) {
+        if self.rules.is_enabled(RuleGroup::JsxProps) {
+            self.normalize_jsx_boolean_attributes(&mut jsx_opening.attrs);
+            self.sort_jsx_attributes(&mut jsx_opening.attrs);
+        }
+        jsx_opening.visit_mut_children_with(self);
+    }
+
+    // TODO: Add more visit methods for other sortable elements
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::TypeScriptParser;
+
+    fn organize_source(source: &str) -> Result<Module> {
+        let parser = TypeScriptParser::new();
+        // Detect JSX and use .tsx extension if needed
+        let filename = if source.contains("<") && (source.contains("/>") || source.contains("</")) {
+            "test.tsx"
+        } else {
+            "test.ts"
+        };
+        let module = parser.parse(source, filename)?;
+        KrokOrganizer::new().organize(module)
+    }
+
+    #[test]
+    fn test_interface_and_type_literal_members_sorted() {
+        let source = r#"
+interface Handler {
+    zebra: string;
+    (event: string): void;
+    apple(): void;
+    [key: string]: unknown;
+    Mango?: number;
+}
+type Point = { y: number; x: number };
+"#;
+        let organized = organize_source(source).unwrap();
+
+        let ModuleItem::Stmt(Stmt::Decl(Decl::TsInterface(interface))) = &organized.body[0] else {
+            panic!("expected the interface first");
+        };
+        let visitor = OrganizerVisitor::new(false, RuleSet::all());
+        let keys: Vec<_> = interface
+            .body
+            .body
+            .iter()
+            .map(|member| visitor.get_type_element_key(member))
+            .collect();
+        assert_eq!(
+            keys,
+            [
+                (true, String::new()),
+                (true, String::new()),
+                (false, "apple".to_string()),
+                (false, "Mango".to_string()),
+                (false, "zebra".to_string()),
+            ]
+        );
+        assert!(matches!(
+            interface.body.body[0],
+            TsTypeElement::TsCallSignatureDecl(_)
+        ));
+
+        let ModuleItem::Stmt(Stmt::Decl(Decl::TsTypeAlias(alias))) = &organized.body[1] else {
+            panic!("expected the type alias second");
+        };
+        let TsType::TsTypeLit(literal) = &*alias.type_ann else {
+            panic!("expected a type literal");
+        };
+        let TsTypeElement::TsPropertySignature(first) = &literal.members[0] else {
+            panic!("expected a property");
+        };
+        assert!(matches!(&*first.key, Expr::Ident(ident) if ident.sym == "x"));
+    }
+
+    #[test]
+    fn test_directive_prologue_stays_first() {
+        let source = r#"
+'use client';
+'use strict';
+const b = 2;
+import React from 'react';
+const a = 1;
+'not a directive';
+"#;
+        let module = organize_source(source).unwrap();
+
+        assert_eq!(directive_prologue_len(&module.body), 2);
+        assert!(matches!(
+            module.body[2],
+            ModuleItem::ModuleDecl(ModuleDecl::Import(_))
+        ));
+    }
+
+    #[test]
+    fn test_change_log() {
+        let source = r#"
+import { z } from './utils';
+import React from 'react';
+import axios from 'axios';
+export const config = { retries: defaultRetries() };
+function defaultRetries() { return 3; }
+enum Level { Warn = 'warn', Error = 'error' }
+enum Done { A = 'a', B = 'b' }
+"#;
+        let module = TypeScriptParser::new().parse(source, "test.ts").unwrap();
+        let (_, change_log) = KrokOrganizer::new().organize_with_log(module).unwrap();
+
+        assert_eq!(
+            change_log,
+            ChangeLog {
+                regrouped_imports: 2,
+                hoisted_declarations: vec!["defaultRetries".to_string()],
+                sorted_enums: vec!["Level".to_string()],
+                diagnostics: Vec::new(),
+            }
+        );
+        assert!(!change_log.is_empty());
+
+        let module = TypeScriptParser::new()
+            .parse("import a from 'a';\nconst b = 1;\n", "test.ts")
+            .unwrap();
+        let (_, change_log) = KrokOrganizer::new().organize_with_log(module).unwrap();
+        assert!(change_log.is_empty());
+    }
+
+    #[test]
+    fn test_merged_declarations_keep_every_part() {
+        let source = r#"
+interface Zed { a: string }
+function build(): void;
+function build(retries?: number): void {}
+namespace build { export const version = 1; }
+interface Zed { b: number }
+interface Alpha {}
+"#;
+        let organized = organize_source(source).unwrap();
+
+        let names: Vec<_> = organized
+            .body
+            .iter()
+            .filter_map(KrokOrganizer::get_item_name)
+            .collect();
+        assert_eq!(names, ["Alpha", "build", "build", "build", "Zed", "Zed"]);
+
+        // Each name's parts stay in the order they were written
+        let written_at: Vec<_> = organized.body.iter().map(|item| item.span_lo()).collect();
+        assert!(written_at[1] < written_at[2] && written_at[2] < written_at[3]);
+        assert!(written_at[4] < written_at[5]);
+    }
+
+    #[test]
+    fn test_ambient_blocks_follow_the_imports_as_written() {
+        let source = r#"
+export function zeta() {}
+declare module 'express' { interface Request { user: string; id: number } }
+import express from 'express';
+declare global { type Mode = 'b' | 'a'; }
+export function alpha() {}
+"#;
+        let organized = organize_source(source).unwrap();
+
+        assert!(matches!(
+            organized.body[0],
+            ModuleItem::ModuleDecl(ModuleDecl::Import(_))
+        ));
+        let ambient: Vec<_> = organized.body[1..3]
+            .iter()
+            .map(|item| match item {
+                ModuleItem::Stmt(Stmt::Decl(Decl::TsModule(module))) => module.global,
+                _ => panic!("expected an ambient block, got {item:?}"),
+            })
+            .collect();
+        assert_eq!(ambient, [false, true]);
+        assert_eq!(
+            KrokOrganizer::get_item_name(&organized.body[3]).as_deref(),
+            Some("alpha")
+        );
+
+        // Their members stay as written too
+        let ModuleItem::Stmt(Stmt::Decl(Decl::TsModule(global))) = &organized.body[2] else {
+            unreachable!()
+        };
+        let Some(TsNamespaceBody::TsModuleBlock(block)) = &global.body else {
+            panic!("expected a block");
+        };
+        let ModuleItem::Stmt(Stmt::Decl(Decl::TsTypeAlias(alias))) = &block.body[0] else {
+            panic!("expected a type alias");
+        };
+        let TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsUnionType(union)) =
+            &*alias.type_ann
+        else {
+            panic!("expected a union");
+        };
+        let members: Vec<_> = union
+            .types
+            .iter()
+            .filter_map(|member| match &**member {
+                TsType::TsLitType(TsLitType {
+                    lit: TsLit::Str(s), ..
+                }) => Some(s.value.to_string()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(members, ["b", "a"]);
+    }
+
+    #[test]
+    fn test_calls_during_evaluation_order_what_the_callee_reads() {
+        let source = r#"
+const alpha = compute();
+const beta = () => compute();
+const gamma = (() => zeta + 1)();
+function compute() { return helper(); }
+function helper() { return zeta; }
+const zeta = 1;
+"#;
+        let module = TypeScriptParser::new().parse(source, "test.ts").unwrap();
+        let graph = DependencyAnalyzer::new().analyze(&module);
+        assert!(graph.depends_on("alpha", "compute"));
+        assert!(graph.depends_on("compute", "helper"));
+        // `beta` only calls it later, when `zeta` is long declared
+        assert!(!graph.depends_on("beta", "compute"));
+
+        let organized = KrokOrganizer::new().organize(module).unwrap();
+        let names: Vec<_> = organized
+            .body
+            .iter()
+            .filter_map(KrokOrganizer::get_item_name)
+            .collect();
+        let position = |name: &str| names.iter().position(|n| n == name).unwrap();
+
+        // `alpha` calls `compute` right away, which calls `helper`, which
+        // reads `zeta`
+        assert!(position("zeta") < position("helper"), "{names:?}");
+        assert!(position("helper") < position("compute"), "{names:?}");
+        assert!(position("compute") < position("alpha"), "{names:?}");
+        assert!(position("zeta") < position("gamma"), "{names:?}");
+    }
+
+    #[test]
+    fn test_dependency_cycles_are_diagnosed() {
+        let source = r#"
+const alpha = { next: () => beta };
+const beta = { next: () => alpha };
+function even(n: number): boolean { return n === 0 || odd(n - 1); }
+function odd(n: number): boolean { return n !== 0 && even(n - 1); }
+const gamma = 1;
+"#;
+        let module = TypeScriptParser::new().parse(source, "test.ts").unwrap();
+        let (_, change_log) = KrokOrganizer::new().organize_with_log(module).unwrap();
+
+        // Mutually recursive functions are fine in any order
+        assert_eq!(
+            change_log.diagnostics,
+            [Diagnostic::DependencyCycle {
+                declarations: vec!["alpha".to_string(), "beta".to_string()],
+            }]
+        );
+        assert_eq!(
+            change_log.diagnostics[0].to_string(),
+            "alpha -> beta -> alpha depend on each other in a cycle, so they can't all be ordered after what they use"
+        );
+    }
+
+    #[test]
+    fn test_organize_imports_grouped_and_sorted() {
+        let source = r#"
+import { z } from './utils';
+import React from 'react';
+import { Button } from '@ui/Button';
+import axios from 'axios';
+import { helper } from '../helper';
+"#;
+
+        let organized = organize_source(source).unwrap();
+
+        // Verify imports are in correct order
+        let imports: Vec<_> = organized
+            .body
+            .iter()
+            .filter_map(|item| match item {
+                ModuleItem::ModuleDecl(ModuleDecl::Import(import)) => Some(import),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(imports.len(), 5);
+        assert_eq!(imports[0].src.value, "axios");
+        assert_eq!(imports[1].src.value, "react");
+        assert_eq!(imports[2].src.value, "@ui/Button");
+        assert_eq!(imports[3].src.value, "../helper");
+        assert_eq!(imports[4].src.value, "./utils");
+    }
+
+    #[test]
+    fn test_organize_object_properties_sorted() {
+        let source = r#"
+const obj = {
+    zebra: 1,
+    apple: 2,
+    banana: 3,
+    cat: 4
+};
+"#;
+
+        let organized = organize_source(source).unwrap();
+
+        // Find the object literal
+        let obj_lit = organized
+            .body
+            .iter()
+            .find_map(|item| match item {
+                ModuleItem::Stmt(Stmt::Decl(Decl::Var(var_decl))) => {
+                    var_decl.decls.first().and_then(|decl| {
+                        decl.init.as_ref().and_then(|init| match &**init {
+                            Expr::Object(obj) => Some(obj),
+                            _ => None,
+                        })
+                    })
+                }
+                _ => None,
+            })
+            .unwrap();
+
+        // Verify properties are sorted
+        let keys: Vec<_> = obj_lit
+            .props
+            .iter()
+            .filter_map(|prop| match prop {
+                PropOrSpread::Prop(prop) => match prop.as_ref() {
+                    Prop::KeyValue(kv) => match &kv.key {
+                        PropName::Ident(ident) => Some(ident.sym.to_string()),
+                        _ => None,
+                    },
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(keys, vec!["apple", "banana", "cat", "zebra"]);
+    }
+
+    #[test]
+    fn test_imports_remain_at_top() {
+        let source = r#"
+const x = 1;
+import React from 'react';
+const y = 2;
+import { useState } from 'react';
+"#;
+
+        let organized = organize_source(source).unwrap();
+
+        // First two items should be imports
+        assert!(matches!(
+            &organized.body[0],
+            ModuleItem::ModuleDecl(ModuleDecl::Import(_))
+        ));
+        assert!(matches!(
+            &organized.body[1],
+            ModuleItem::ModuleDecl(ModuleDecl::Import(_))
+        ));
+
+        // Rest should be statements
+        assert!(matches!(&organized.body[2], ModuleItem::Stmt(_)));
+        assert!(matches!(&organized.body[3], ModuleItem::Stmt(_)));
+    }
+
+    #[test]
+    fn test_import_equals_sorted_and_export_assignment_last() {
+        let source = r#"
+export = api;
+const api = { helper };
+import helper = require('./helper');
+import path = require('path');
+"#;
+
+        let organized = organize_source(source).unwrap();
+
+        let require_paths: Vec<_> = organized.body[..2]
+            .iter()
+            .filter_map(|item| match item {
+                ModuleItem::ModuleDecl(ModuleDecl::TsImportEquals(import)) => {
+                    ImportAnalyzer::require_path(import)
+                }
+                _ => None,
+            })
+            .collect();
+        assert_eq!(require_paths, vec!["path", "./helper"]);
+
+        assert!(matches!(&organized.body[2], ModuleItem::Stmt(_)));
+        assert!(matches!(
+            organized.body.last(),
+            Some(ModuleItem::ModuleDecl(ModuleDecl::TsExportAssignment(_)))
+        ));
+    }
+
+    #[test]
+    fn test_re_export_order_kept_unless_sorting() {
+        let source = r#"
+export * from './zebra';
+export { Button } from '@ui/button';
+export * from './apple';
+"#;
+        let module = TypeScriptParser::new().parse(source, "test.ts").unwrap();
+
+        let paths = |module: &Module| -> Vec<String> {
+            module
+                .body
+                .iter()
+                .filter_map(|item| match item {
+                    ModuleItem::ModuleDecl(ModuleDecl::ExportAll(export)) => {
+                        Some(export.src.value.to_string())
+                    }
+                    ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(export)) => {
+                        export.src.as_ref().map(|src| src.value.to_string())
+                    }
+                    _ => None,
+                })
+                .collect()
+        };
+
+        let kept = KrokOrganizer::new().organize(module.clone()).unwrap();
+        assert_eq!(paths(&kept), vec!["./zebra", "@ui/button", "./apple"]);
+
+        let sorted = KrokOrganizer::new()
+            .with_sorted_re_exports(true)
+            .organize(module)
+            .unwrap();
+        assert_eq!(paths(&sorted), vec!["@ui/button", "./apple", "./zebra"]);
+    }
+
+    #[test]
+    fn test_comma_operator_statements_are_barriers() {
+        let source = r#"
+function zeta() {}
+function gamma() {}
+a = 1, b = 2;
+function alpha() {}
+const ready = (init(), true);
+function delta() {}
+function beta() {}
+"#;
+        let module = TypeScriptParser::new().parse(source, "test.ts").unwrap();
+        let organized = KrokOrganizer::new().organize(module).unwrap();
+
+        let labels: Vec<String> = organized
+            .body
+            .iter()
+            .map(|item| {
+                KrokOrganizer::get_item_name(item).unwrap_or_else(|| "<sequence>".to_string())
+            })
+            .collect();
+        assert_eq!(
+            labels,
+            vec![
+                "gamma",
+                "zeta",
+                "<sequence>",
+                "alpha",
+                "ready",
+                "beta",
+                "delta"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_class_evaluation_side_effects_are_barriers() {
+        let source = r#"
+function zeta() {}
+(class { static { register(); } });
+function delta() {}
+function alpha() {}
+(class { static ready = init(); });
+function gamma() {}
+function beta() {}
+"#;
+        let module = TypeScriptParser::new().parse(source, "test.ts").unwrap();
+        let organized = KrokOrganizer::new().organize(module).unwrap();
+
+        let labels: Vec<String> = organized
+            .body
+            .iter()
+            .map(|item| KrokOrganizer::get_item_name(item).unwrap_or_else(|| "<class>".to_string()))
+            .collect();
+        assert_eq!(
+            labels,
+            vec!["zeta", "<class>", "alpha", "delta", "<class>", "beta", "gamma"]
+        );
+
+        // Instance fields and methods wait for a `new` or a call
+        let idle = TypeScriptParser::new()
+            .parse(
+                "(class { value = init(); run() { register(); } });",
+                "test.ts",
+            )
+            .unwrap();
+        assert!(!KrokOrganizer::is_mutation_barrier(&idle.body[0]));
+    }
+
+    #[test]
+    fn test_screaming_case_constants_grouped() {
+        let source = r#"
+function build() {}
+const MAX_RETRIES = 3;
+const adapter = createAdapter();
+export function apply() {}
+export const API_URL = 'https://example.com';
+export const DEFAULT_OPTIONS = { retries: 3 };
+export const TIMEOUT_MS = 500;
+const BASE_DELAY = -100;
+"#;
+        let module = TypeScriptParser::new().parse(source, "test.ts").unwrap();
+
+        let names = |module: &Module| -> Vec<String> {
+            module
+                .body
+                .iter()
+                .filter_map(KrokOrganizer::get_item_name)
+                .collect()
+        };
+
+        let grouped = KrokOrganizer::new()
+            .with_grouped_constants(true)
+            .organize(module.clone())
+            .unwrap();
+        assert_eq!(
+            names(&grouped),
+            vec![
+                "API_URL",
+                "TIMEOUT_MS",
+                "apply",
+                "DEFAULT_OPTIONS",
+                "BASE_DELAY",
+                "MAX_RETRIES",
+                "adapter",
+                "build",
+            ]
+        );
+
+        let ungrouped = KrokOrganizer::new().organize(module).unwrap();
+        assert_eq!(
+            names(&ungrouped),
+            vec![
+                "API_URL",
+                "apply",
+                "DEFAULT_OPTIONS",
+                "TIMEOUT_MS",
+                "adapter",
+                "BASE_DELAY",
+                "build",
+                "MAX_RETRIES",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_function_destructured_params_sorted() {
+        let source = r#"
+function process({ zebra, apple, banana }: Options) {
+    return apple + banana + zebra;
+}
+"#;
+
+        let organized = organize_source(source).unwrap();
+
+        // Find the function declaration
+        let func_decl = organized
+            .body
+            .iter()
+            .find_map(|item| match item {
+                ModuleItem::Stmt(Stmt::Decl(Decl::Fn(fn_decl))) => Some(fn_decl),
+                _ => None,
+            })
+            .unwrap();
+
+        // Get the first parameter
+        let param = &func_decl.function.params[0];
+
+        // Verify it's an object pattern with sorted keys
+        match &param.pat {
+            Pat::Object(obj_pat) => {
+                let keys: Vec<_> = obj_pat
+                    .props
+                    .iter()
+                    .filter_map(|prop| match prop {
+                        ObjectPatProp::KeyValue(kv) => match &kv.key {
+                            PropName::Ident(ident) => Some(ident.sym.to_string()),
+                            _ => None,
+                        },
+                        ObjectPatProp::Assign(assign) => Some(assign.key.sym.to_string()),
+                        _ => None,
+                    })
+                    .collect();
+
+                assert_eq!(keys, vec!["apple", "banana", "zebra"]);
+            }
+            _ => panic!("Expected object pattern"),
+        }
+    }
+
+    #[test]
+    fn test_arrow_function_destructured_params_sorted() {
+        let source = r#"
+const process = ({ zebra, apple, banana }: Options) => {
+    return apple + banana + zebra;
+};
+"#;
+
+        let organized = organize_source(source).unwrap();
+
+        // Find the arrow function
+        let arrow_func = organized
+            .body
+            .iter()
+            .find_map(|item| match item {
+                ModuleItem::Stmt(Stmt::Decl(Decl::Var(var_decl))) => {
+                    var_decl.decls.first().and_then(|decl| {
+                        decl.init.as_ref().and_then(|init| match &**init {
+                            Expr::Arrow(arrow) => Some(arrow),
+                            _ => None,
+                        })
+                    })
+                }
+                _ => None,
+            })
+            .unwrap();
+
+        // Get the first parameter
+        let param = &arrow_func.params[0];
+
+        // Verify it's an object pattern with sorted keys
+        match param {
+            Pat::Object(obj_pat) => {
+                let keys: Vec<_> = obj_pat
+                    .props
+                    .iter()
+                    .filter_map(|prop| match prop {
+                        ObjectPatProp::KeyValue(kv) => match &kv.key {
+                            PropName::Ident(ident) => Some(ident.sym.to_string()),
+                            _ => None,
+                        },
+                        ObjectPatProp::Assign(assign) => Some(assign.key.sym.to_string()),
+                        _ => None,
+                    })
+                    .collect();
+
+                assert_eq!(keys, vec!["apple", "banana", "zebra"]);
+            }
+            _ => panic!("Expected object pattern"),
+        }
+    }
+
+    #[test]
+    fn test_function_mixed_params_preserved() {
+        let source = r#"
+function process(id: number, { zebra, apple, banana }: Options, callback: Function) {
+    return callback(id, apple + banana + zebra);
+}
+"#;
+
+        let organized = organize_source(source).unwrap();
+
+        // Find the function declaration
+        let func_decl = organized
+            .body
+            .iter()
+            .find_map(|item| match item {
+                ModuleItem::Stmt(Stmt::Decl(Decl::Fn(fn_decl))) => Some(fn_decl),
+                _ => None,
+            })
+            .unwrap();
+
+        // Verify parameter count
+        assert_eq!(func_decl.function.params.len(), 3);
+
+        // Verify middle parameter is sorted object pattern
+        match &func_decl.function.params[1].pat {
+            Pat::Object(obj_pat) => {
+                let keys: Vec<_> = obj_pat
+                    .props
+                    .iter()
+                    .filter_map(|prop| match prop {
+                        ObjectPatProp::KeyValue(kv) => match &kv.key {
+                            PropName::Ident(ident) => Some(ident.sym.to_string()),
+                            _ => None,
+                        },
+                        ObjectPatProp::Assign(assign) => Some(assign.key.sym.to_string()),
+                        _ => None,
+                    })
+                    .collect();
+
+                assert_eq!(keys, vec!["apple", "banana", "zebra"]);
+            }
+            _ => panic!("Expected object pattern"),
+        }
+    }
+
+    #[test]
+    fn test_function_nested_destructuring_sorted() {
+        let source = r#"
+function process({ config: { zebra, apple, banana }, data }: NestedOptions) {
+    return apple + banana + zebra;
+}
+"#;
+
+        let organized = organize_source(source).unwrap();
+
+        // Find the function declaration
+        let func_decl = organized
+            .body
+            .iter()
+            .find_map(|item| match item {
+                ModuleItem::Stmt(Stmt::Decl(Decl::Fn(fn_decl))) => Some(fn_decl),
+                _ => None,
+            })
+            .unwrap();
+
+        // Get the first parameter
+        let param = &func_decl.function.params[0];
+
+        // Verify outer object pattern has sorted keys
+        match &param.pat {
+            Pat::Object(obj_pat) => {
+                let outer_keys: Vec<_> = obj_pat
+                    .props
+                    .iter()
+                    .filter_map(|prop| match prop {
+                        ObjectPatProp::KeyValue(kv) => match &kv.key {
+                            PropName::Ident(ident) => Some(ident.sym.to_string()),
+                            _ => None,
+                        },
+                        ObjectPatProp::Assign(assign) => Some(assign.key.sym.to_string()),
+                        _ => None,
+                    })
+                    .collect();
+
+                assert_eq!(outer_keys, vec!["config", "data"]);
+
+                // Check nested object pattern
+                if let Some(ObjectPatProp::KeyValue(kv)) = obj_pat.props.first() {
+                    if let Pat::Object(nested_obj_pat) = kv.value.as_ref() {
+                        let inner_keys: Vec<_> = nested_obj_pat
+                            .props
+                            .iter()
+                            .filter_map(|prop| match prop {
+                                ObjectPatProp::KeyValue(kv) => match &kv.key {
+                                    PropName::Ident(ident) => Some(ident.sym.to_string()),
+                                    _ => None,
+                                },
+                                ObjectPatProp::Assign(assign) => Some(assign.key.sym.to_string()),
+                                _ => None,
+                            })
+                            .collect();
+
+                        assert_eq!(inner_keys, vec!["apple", "banana", "zebra"]);
+                    }
+                }
+            }
+            _ => panic!("Expected object pattern"),
+        }
+    }
+
+    #[test]
+    fn test_class_member_sorting_basic() {
+        let source = r#"
+class User {
+    private zebra: string;
+    public apple: number;
+    protected banana: boolean;
+    
+    constructor() {}
+    
+    private writeLog() {}
+    public getInfo() {}
+    protected checkAccess() {}
+}
+"#;
+
+        let organized = organize_source(source).unwrap();
+
+        // Find the class declaration
+        let class_decl = organized
+            .body
+            .iter()
+            .find_map(|item| match item {
+                ModuleItem::Stmt(Stmt::Decl(Decl::Class(class_decl))) => Some(class_decl),
+                _ => None,
+            })
+            .unwrap();
+
+        // Get member names in order
+        let members: Vec<String> = class_decl
+            .class
+            .body
+            .iter()
+            .filter_map(|member| match member {
+                ClassMember::ClassProp(prop) => {
+                    prop.key.as_ident().map(|ident| ident.sym.to_string())
+                }
+                ClassMember::Method(method) => {
+                    method.key.as_ident().map(|ident| ident.sym.to_string())
+                }
+                ClassMember::Constructor(_) => Some("constructor".to_string()),
+                _ => None,
+            })
+            .collect();
+
+        // Fields should be sorted alphabetically: apple, banana, zebra
+        // Then constructor
+        // Then methods sorted alphabetically: checkAccess, getInfo, writeLog
+        assert_eq!(
+            members,
+            vec![
+                "apple",
+                "banana",
+                "zebra",
+                "constructor",
+                "checkAccess",
+                "getInfo",
+                "writeLog"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_lifecycle_methods_in_framework_order() {
+        let source = r#"
+class Clock extends React.Component {
+    render() {}
+    tick() {}
+    componentWillUnmount() {}
+    componentDidMount() {}
+    constructor(props) { super(props); }
+    #format() {}
+}
+
+class Dashboard implements OnInit, OnDestroy {
+    ngOnDestroy() {}
+    load() {}
+    ngOnInit() {}
+    constructor() {}
+}
+
+class Plain {
+    render() {}
+    componentDidMount() {}
+}
+"#;
+        let organized = organize_source(source).unwrap();
+
+        let members: Vec<Vec<String>> = organized
+            .body
+            .iter()
+            .filter_map(|item| match item {
+                ModuleItem::Stmt(Stmt::Decl(Decl::Class(class_decl))) => Some(
+                    class_decl
+                        .class
+                        .body
+                        .iter()
+                        .filter_map(|member| match member {
+                            ClassMember::Method(method) => {
+                                method.key.as_ident().map(|ident| ident.sym.to_string())
+                            }
+                            ClassMember::PrivateMethod(method) => {
+                                Some(format!("#{}", method.key.name))
+                            }
+                            ClassMember::Constructor(_) => Some("constructor".to_string()),
+                            _ => None,
+                        })
+                        .collect(),
+                ),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(
+            members,
+            vec![
+                vec![
+                    "constructor",
+                    "componentDidMount",
+                    "componentWillUnmount",
+                    "tick",
+                    "#format",
+                    "render"
+                ],
+                vec!["constructor", "ngOnInit", "ngOnDestroy", "load"],
+                vec!["componentDidMount", "render"],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_disabled_rule_groups_are_skipped() {
+        let source = r#"
+function helper() {}
+export const config = { zebra: 1, apple: 2 };
+class Legacy {
+    zebra() {}
+    apple() {}
+}
+"#;
+
+        let parser = TypeScriptParser::new();
+        let module = parser.parse(source, "test.ts").unwrap();
+        let rules = RuleSet::all()
+            .disable(RuleGroup::ClassMembers)
+            .disable(RuleGroup::DeclarationOrder);
+        let organized = KrokOrganizer::new()
+            .with_rules(rules)
+            .organize(module)
+            .unwrap();
+
+        // Declarations keep their order
+        assert!(matches!(
+            &organized.body[0],
+            ModuleItem::Stmt(Stmt::Decl(Decl::Fn(_)))
+        ));
+
+        let class_decl = organized
+            .body
+            .iter()
+            .find_map(|item| match item {
+                ModuleItem::Stmt(Stmt::Decl(Decl::Class(class_decl))) => Some(class_decl),
+                _ => None,
+            })
+            .unwrap();
+        let methods: Vec<_> = class_decl
+            .class
+            .body
+            .iter()
+            .filter_map(|member| match member {
+                ClassMember::Method(method) => method.key.as_ident().map(|i| i.sym.to_string()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(methods, vec!["zebra", "apple"]);
+
+        // Rule groups that weren't disabled still apply
+        let mut object_keys = Vec::new();
+        for item in &organized.body {
+            if let ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export)) = item {
+                if let Decl::Var(var) = &export.decl {
+                    if let Some(Expr::Object(obj)) = var.decls[0].init.as_deref() {
+                        let visitor = OrganizerVisitor::new(false, RuleSet::all());
+                        object_keys.extend(obj.props.iter().map(|prop| visitor.get_prop_key(prop)));
+                    }
+                }
+            }
+        }
+        assert_eq!(object_keys, vec!["apple", "zebra"]);
+    }
+
+    #[test]
+    fn test_accessor_pairs_stay_together() {
+        let source = r#"
+class Form {
+    set name(value: string) {}
+    Name() {}
+    get name() { return ''; }
+    set #secret(value: string) {}
+    get #secret() { return ''; }
+    label() {}
+}
+"#;
+        let organized = organize_source(source).unwrap();
+
+        let ModuleItem::Stmt(Stmt::Decl(Decl::Class(class_decl))) = &organized.body[0] else {
+            panic!("expected the class");
+        };
+        let members: Vec<String> = class_decl
+            .class
+            .body
+            .iter()
+            .map(|member| match member {
+                ClassMember::Method(method) => {
+                    let name = method.key.as_ident().unwrap().sym.to_string();
+                    match method.kind {
+                        MethodKind::Getter => format!("get {name}"),
+                        MethodKind::Setter => format!("set {name}"),
+                        MethodKind::Method => name,
+                    }
+                }
+                ClassMember::PrivateMethod(method) => match method.kind {
+                    MethodKind::Getter => format!("get #{}", method.key.name),
+                    MethodKind::Setter => format!("set #{}", method.key.name),
+                    MethodKind::Method => format!("#{}", method.key.name),
+                },
+                _ => panic!("unexpected member {member:?}"),
+            })
+            .collect();
+
+        assert_eq!(
+            members,
+            [
+                "label",
+                "Name",
+                "get name",
+                "set name",
+                "get #secret",
+                "set #secret"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_decorated_members_keep_their_order() {
+        let source = r#"
+class HeroComponent {
+    @Input() zebra: string;
+    plain: number;
+    @Input() apple: string;
+    @Output() mango = new EventEmitter();
+    banana: boolean;
+
+    @HostListener('click') onClick() {}
+    save(@Body() body: Hero) {}
+    close() {}
+}
+"#;
+        let organized = organize_source(source).unwrap();
+
+        let ModuleItem::Stmt(Stmt::Decl(Decl::Class(class_decl))) = &organized.body[0] else {
+            panic!("expected the class");
+        };
+        let members: Vec<String> = class_decl
+            .class
+            .body
+            .iter()
+            .filter_map(|member| match member {
+                ClassMember::ClassProp(prop) => prop.key.as_ident().map(|i| i.sym.to_string()),
+                ClassMember::Method(method) => method.key.as_ident().map(|i| i.sym.to_string()),
+                _ => None,
+            })
+            .collect();
+
+        // The undecorated members sort around the decorated ones, which stay
+        // in the order they were written
+        assert_eq!(
+            members,
+            ["zebra", "banana", "apple", "plain", "mango", "close", "onClick", "save"]
+        );
+    }
+
+    #[test]
+    fn test_initialized_fields_keep_their_order() {
+        let source = r#"
+class Counter {
+    static zebra = 1;
+    static apple: number;
+    static #limit = Counter.zebra * 2;
+    static {
+        Counter.apple = Counter.#limit;
+    }
+    total = 0;
+    banana: string;
+    #step = this.total + 1;
+    average = this.#step / 2;
+}
+"#;
+        let organized = organize_source(source).unwrap();
+
+        let ModuleItem::Stmt(Stmt::Decl(Decl::Class(class_decl))) = &organized.body[0] else {
+            panic!("expected the class");
+        };
+        let members: Vec<String> = class_decl
+            .class
+            .body
+            .iter()
+            .map(|member| match member {
+                ClassMember::ClassProp(prop) => prop.key.as_ident().unwrap().sym.to_string(),
+                ClassMember::PrivateProp(prop) => format!("#{}", prop.key.name),
+                ClassMember::StaticBlock(_) => "static {}".to_string(),
+                _ => unreachable!(),
+            })
+            .collect();
+
+        // Initializers read the fields before them, so the initialized fields
+        // stay in written order, static and instance ones each, filling the
+        // places sorting gave the fields
+        assert_eq!(
+            members,
+            [
+                "zebra",
+                "apple",
+                "#limit",
+                "static {}",
+                "total",
+                "banana",
+                "#step",
+                "average"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_order_sensitive_initializers() {
+        // Whether the last member of the class must run where it was written
+        let order_sensitive = |source: &str| {
+            let module = crate::parser::TypeScriptParser::new()
+                .parse(source, "test.ts")
+                .unwrap();
+            let ModuleItem::Stmt(Stmt::Decl(Decl::Class(class_decl))) = &module.body[0] else {
+                panic!("expected the class");
+            };
+            let member = class_decl.class.body.last().unwrap();
+            is_order_sensitive(member, Some(&class_decl.ident))
+        };
+
+        assert!(!order_sensitive("class Counter { static a = 'zoo'; }"));
+        assert!(!order_sensitive(
+            "class Counter { static a = { max: 10 }; }"
+        ));
+        assert!(!order_sensitive("class Counter { a = () => this.b; }"));
+        assert!(!order_sensitive("class Counter { a: number; }"));
+
+        assert!(order_sensitive("class Counter { a = this.b; }"));
+        assert!(order_sensitive("class Counter { static a = Counter.b; }"));
+        assert!(order_sensitive("class Counter { #a = 1; b = Counter.#a; }"));
+        assert!(order_sensitive("class Counter { static a = create(); }"));
+        assert!(order_sensitive("class Counter { static { setup(); } }"));
+    }
+
+    #[test]
+    fn test_literal_initialized_fields_sort() {
+        let source = r#"
+class Palette {
+    static zoo = 'zoo';
+    static bar = 'bar';
+    primary = '#f00';
+    accent = { light: '#fff' };
+}
+"#;
+        let organized = organize_source(source).unwrap();
+
+        let ModuleItem::Stmt(Stmt::Decl(Decl::Class(class_decl))) = &organized.body[0] else {
+            panic!("expected the class");
+        };
+        let members: Vec<String> = class_decl
+            .class
+            .body
+            .iter()
+            .map(|member| match member {
+                ClassMember::ClassProp(prop) => prop.key.as_ident().unwrap().sym.to_string(),
+                _ => unreachable!(),
+            })
+            .collect();
+
+        // Nothing reads the fields while they're initialized
+        assert_eq!(members, ["bar", "zoo", "accent", "primary"]);
+    }
+
+    #[test]
+    fn test_independent_switch_cases_sorted() {
+        let source = r#"
+function label(kind: string) {
+    switch (kind) {
+        case 'zebra':
+            return 1;
+        case 'Mango': {
+            log(kind);
+            break;
+        }
+        case 'apple':
+            throw new Error(kind);
+        default:
+            return 0;
+    }
+}
+function fallthrough(kind: string) {
+    switch (kind) {
+        case 'b':
+        case 'a':
+            return 1;
+    }
+}
+"#;
+        let organized = organize_source(source).unwrap();
+
+        let tests = |index: usize| -> Vec<Option<String>> {
+            let ModuleItem::Stmt(Stmt::Decl(Decl::Fn(func))) = &organized.body[index] else {
+                panic!("expected a function");
+            };
+            let Some(Stmt::Switch(switch)) = func.function.body.as_ref().unwrap().stmts.first()
+            else {
+                panic!("expected a switch");
+            };
+            switch.cases.iter().map(switch_case_key).collect()
+        };
+        let some = |key: &str| Some(key.to_string());
+
+        assert_eq!(tests(0), [some("b"), some("a")]);
+        assert_eq!(
+            tests(1),
+            [some("apple"), some("Mango"), some("zebra"), None]
+        );
+    }
+
+    #[test]
+    fn test_object_props_sorted_between_spreads() {
+        let source = r#"
+const options = { zebra: 1, ...defaults, mango: 2, apple: 3, ...overrides, cat: 4 };
+"#;
+        let organized = organize_source(source).unwrap();
+
+        let ModuleItem::Stmt(Stmt::Decl(Decl::Var(var))) = &organized.body[0] else {
+            panic!("expected the const");
+        };
+        let Some(Expr::Object(object)) = var.decls[0].init.as_deref() else {
+            panic!("expected an object literal");
+        };
+        let visitor = OrganizerVisitor::new(false, RuleSet::all());
+        let keys: Vec<_> = object
+            .props
+            .iter()
+            .map(|prop| match prop {
+                PropOrSpread::Spread(spread) => match &*spread.expr {
+                    Expr::Ident(ident) => format!("...{}", ident.sym),
+                    _ => panic!("unexpected spread"),
+                },
+                prop => visitor.get_prop_key(prop),
+            })
+            .collect();
+
+        assert_eq!(
+            keys,
+            [
+                "zebra",
+                "...defaults",
+                "apple",
+                "mango",
+                "...overrides",
+                "cat"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_class_static_members_sorting() {
+        let source = r#"
+class Config {
+    static zebra = "z";
+    static apple = "a";
+    
+    instanceZebra = 100;
+    instanceApple = 200;
+    
+    static getZebra() { return this.zebra; }
+    static getApple() { return this.apple; }
+    
+    getInstanceData() { return this.instanceApple; }
+}
+"#;
+
+        let organized = organize_source(source).unwrap();
+
+        // Find the class
+        let class_decl = organized
+            .body
+            .iter()
+            .find_map(|item| match item {
+                ModuleItem::Stmt(Stmt::Decl(Decl::Class(class_decl))) => Some(class_decl),
+                _ => None,
+            })
+            .unwrap();
+
+        // Get members with static info
+        let members: Vec<(String, bool)> = class_decl
+            .class
+            .body
+            .iter()
+            .filter_map(|member| match member {
+                ClassMember::ClassProp(prop) => prop
+                    .key
+                    .as_ident()
+                    .map(|ident| (ident.sym.to_string(), prop.is_static)),
+                ClassMember::Method(method) => method
+                    .key
+                    .as_ident()
+                    .map(|ident| (ident.sym.to_string(), method.is_static)),
+                _ => None,
+            })
+            .collect();
+
+        // Order should be:
+        // 1. Public static fields (sorted): apple, zebra
+        // 2. Public static methods (sorted): getApple, getZebra
+        // 3. Public instance fields (sorted): instanceApple, instanceZebra
+        // 4. Public instance methods: getInstanceData
+        assert_eq!(
+            members,
+            vec![
+                ("apple".to_string(), true),
+                ("zebra".to_string(), true),
+                ("getApple".to_string(), true),
+                ("getZebra".to_string(), true),
+                ("instanceApple".to_string(), false),
+                ("instanceZebra".to_string(), false),
+                ("getInstanceData".to_string(), false),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_union_type_sorting() {
+        let source = r#"
+type Status = 'error' | 'success' | 'pending' | 'idle';
+type Size = 'xl' | 'sm' | 'lg' | 'md' | 'xs';
+"#;
+
+        let organized = organize_source(source).unwrap();
+
+        // Find the type aliases by name
+        let mut status_union = None;
+        let mut size_union = None;
+
+        for item in &organized.body {
+            if let ModuleItem::Stmt(Stmt::Decl(Decl::TsTypeAlias(ts_type))) = item {
+                if let TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsUnionType(
+                    union,
+                )) = ts_type.type_ann.as_ref()
+                {
+                    let union_members: Vec<String> = union
+                        .types
+                        .iter()
+                        .filter_map(|t| {
+                            if let TsType::TsLitType(lit) = t.as_ref() {
+                                if let TsLit::Str(s) = &lit.lit {
+                                    return Some(s.value.to_string());
+                                }
+                            }
+                            None
+                        })
+                        .collect();
+
+                    // Identify by name
+                    match ts_type.id.sym.as_ref() {
+                        "Status" => status_union = Some(union_members),
+                        "Size" => size_union = Some(union_members),
+                        _ => {}
+                    }
+                }
+            }
+        }
+
+        // Status type should be sorted: error, idle, pending, success
+        assert_eq!(
+            status_union.unwrap(),
+            vec!["error", "idle", "pending", "success"]
+        );
+        // Size type should be sorted: lg, md, sm, xl, xs
+        assert_eq!(size_union.unwrap(), vec!["lg", "md", "sm", "xl", "xs"]);
+    }
+
+    #[test]
+    fn test_intersection_type_sorting() {
+        let source = r#"
+type Combined = Writable & Timestamped & Identifiable & Versioned;
+"#;
+
+        let organized = organize_source(source).unwrap();
+
+        // Find the type alias with intersection
+        let ts_type = organized
+            .body
+            .iter()
+            .find_map(|item| match item {
+                ModuleItem::Stmt(Stmt::Decl(Decl::TsTypeAlias(ts_type))) => Some(ts_type),
+                _ => None,
+            })
+            .unwrap();
+
+        if let TsType::TsUnionOrIntersectionType(TsUnionOrIntersectionType::TsIntersectionType(
+            intersection,
+        )) = ts_type.type_ann.as_ref()
+        {
+            let members: Vec<String> = intersection
+                .types
+                .iter()
+                .filter_map(|t| {
+                    if let TsType::TsTypeRef(type_ref) = t.as_ref() {
+                        if let TsEntityName::Ident(ident) = &type_ref.type_name {
+                            return Some(ident.sym.to_string());
+                        }
+                    }
+                    None
+                })
+                .collect();
+
+            // Should be sorted: Identifiable, Timestamped, Versioned, Writable
+            assert_eq!(
+                members,
+                vec!["Identifiable", "Timestamped", "Versioned", "Writable"]
+            );
+        } else {
+            panic!("Expected intersection type");
+        }
+    }
+
+    #[test]
+    fn test_enum_member_sorting_string_enum() {
+        let source = r#"
+enum Status {
+    Pending = "pending",
+    Active = "active",
+    Disabled = "disabled",
+    Archived = "archived"
+}
+
+enum Color {
+    Red = "RED",
+    Blue = "BLUE",
+    Green = "GREEN",
+    Yellow = "YELLOW"
+}
+"#;
+
+        let organized = organize_source(source).unwrap();
+
+        // Find the enums
+        let enums: Vec<_> = organized
+            .body
+            .iter()
+            .filter_map(|item| match item {
+                ModuleItem::Stmt(Stmt::Decl(Decl::TsEnum(ts_enum))) => Some(ts_enum),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(enums.len(), 2);
+
+        // Find enums by name since they may be reordered
+        let status_enum = enums.iter().find(|e| e.id.sym == "Status").unwrap();
+        let color_enum = enums.iter().find(|e| e.id.sym == "Color").unwrap();
+
+        // Check Status enum members are sorted
+        let status_members: Vec<String> = status_enum
+            .members
+            .iter()
+            .map(|member| member.id.as_ident().unwrap().sym.to_string())
+            .collect();
+        assert_eq!(
+            status_members,
+            vec!["Active", "Archived", "Disabled", "Pending"]
+        );
+
+        // Check Color enum members are sorted
+        let color_members: Vec<String> = color_enum
+            .members
+            .iter()
+            .map(|member| member.id.as_ident().unwrap().sym.to_string())
+            .collect();
+        assert_eq!(color_members, vec!["Blue", "Green", "Red", "Yellow"]);
+    }
+
+    #[test]
+    fn test_enum_member_sorting_numeric_enum_preserved() {
+        let source = r#"
+enum Priority {
+    Low = 1,
+    Medium = 2,
+    High = 3,
+    Critical = 4
+}
+
+enum HttpStatus {
+    NotFound = 404,
+    OK = 200,
+    ServerError = 500,
+    BadRequest = 400
+}
+"#;
+
+        let organized = organize_source(source).unwrap();
+
+        // Find the enums
+        let enums: Vec<_> = organized
+            .body
+            .iter()
+            .filter_map(|item| match item {
+                ModuleItem::Stmt(Stmt::Decl(Decl::TsEnum(ts_enum))) => Some(ts_enum),
+                _ => None,
+            })
+            .collect();
+
+        assert_eq!(enums.len(), 2);
+
+        // Find enums by name since they may be reordered
+        let priority_enum = enums.iter().find(|e| e.id.sym == "Priority").unwrap();
+        let http_status_enum = enums.iter().find(|e| e.id.sym == "HttpStatus").unwrap();
+
+        // Check Priority enum members are NOT sorted (preserved original order)
+        let priority_members: Vec<String> = priority_enum
+            .members
+            .iter()
+            .map(|member| member.id.as_ident().unwrap().sym.to_string())
+            .collect();
+        assert_eq!(priority_members, vec!["Low", "Medium", "High", "Critical"]);
+
+        // Check HttpStatus enum members are NOT sorted (preserved original order)
+        let status_members: Vec<String> = http_status_enum
+            .members
+            .iter()
+            .map(|member| member.id.as_ident().unwrap().sym.to_string())
+            .collect();
+        assert_eq!(
+            status_members,
+            vec!["NotFound", "OK", "ServerError", "BadRequest"]
+        );
+    }
+
+    #[test]
+    fn test_enum_member_sorting_mixed_enum() {
+        let source = r#"
+enum Mixed {
+    First,
+    Second = 10,
+    Third,
+    Fourth = "fourth",
+    Fifth = "fifth"
+}
+"#;
+
+        let organized = organize_source(source).unwrap();
+
+        // Find the enum
+        let ts_enum = organized
+            .body
+            .iter()
+            .find_map(|item| match item {
+                ModuleItem::Stmt(Stmt::Decl(Decl::TsEnum(ts_enum))) => Some(ts_enum),
+                _ => None,
+            })
+            .unwrap();
+
+        // Mixed enums should not be sorted to preserve value assignments
+        let members: Vec<String> = ts_enum
+            .members
+            .iter()
+            .map(|member| member.id.as_ident().unwrap().sym.to_string())
+            .collect();
+        assert_eq!(members, vec!["First", "Second", "Third", "Fourth", "Fifth"]);
+    }
+
+    #[test]
+    fn test_jsx_property_sorting_basic() {
+        let source = r#"
+const Component = () => {
+    return (
+        <div 
+            className="container"
+            onClick={handleClick}
+            id="main"
+            style={styles}
+            key="unique"
+            ref={divRef}
+            data-testid="test"
+        />
+    );
+};
+"#;
+
+        let organized = organize_source(source).unwrap();
+
+        // Find the JSX element
+        let jsx_element = find_jsx_element(&organized);
+
+        // Get prop names in order
+        let prop_names: Vec<String> = jsx_element
+            .opening
+            .attrs
+            .iter()
+            .filter_map(|attr| match attr {
+                JSXAttrOrSpread::JSXAttr(jsx_attr) => match &jsx_attr.name {
+                    JSXAttrName::Ident(ident) => Some(ident.sym.to_string()),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect();
+
+        // key and ref should be first, then alphabetically sorted, then event handlers
+        assert_eq!(
+            prop_names,
+            vec![
+                "key",
+                "ref",
+                "className",
+                "data-testid",
+                "id",
+                "style",
+                "onClick"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_jsx_property_sorting_event_handlers() {
+        let source = r#"
+const Button = () => (
+    <button
+        type="submit"
+        onMouseEnter={handleEnter}
+        className="btn"
+        onClick={handleClick}
+        disabled={false}
+        onMouseLeave={handleLeave}
+        aria-label="Submit"
+        onChange={handleChange}
+        key="btn1"
+    />
+);
+"#;
+
+        let organized = organize_source(source).unwrap();
+
+        // Find the JSX element
+        let jsx_element = find_jsx_element(&organized);
+
+        // Get prop names in order
+        let prop_names: Vec<String> = jsx_element
+            .opening
+            .attrs
+            .iter()
+            .filter_map(|attr| match attr {
+                JSXAttrOrSpread::JSXAttr(jsx_attr) => match &jsx_attr.name {
+                    JSXAttrName::Ident(ident) => Some(ident.sym.to_string()),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect();
+
+        // key first, then alphabetically sorted with event handlers grouped
+        assert_eq!(
+            prop_names,
+            vec![
+                "key",
+                "aria-label",
+                "className",
+                "disabled",
+                "type",
+                "onChange",
+                "onClick",
+                "onMouseEnter",
+                "onMouseLeave"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_jsx_property_sorting_with_spread() {
+        let source = r#"
+const Card = (props) => (
+    <div
+        {...defaultProps}
+        className="card"
+        id={props.id}
+        {...props}
+        style={customStyle}
+        ref={cardRef}
+        key={props.key}
+    />
+);
+"#;
+
+        let organized = organize_source(source).unwrap();
+
+        // Find the JSX element
+        let jsx_element = find_jsx_element(&organized);
+
+        // Check attribute order - key/ref first, regular props sorted, spreads at end
+        let attrs: Vec<String> = jsx_element
+            .opening
+            .attrs
+            .iter()
+            .map(|attr| match attr {
+                JSXAttrOrSpread::JSXAttr(jsx_attr) => match &jsx_attr.name {
+                    JSXAttrName::Ident(ident) => ident.sym.to_string(),
+                    _ => "".to_string(),
+                },
+                JSXAttrOrSpread::SpreadElement(_) => "...spread".to_string(),
+            })
+            .collect();
+
+        assert_eq!(
+            attrs,
+            vec![
+                "key",
+                "ref",
+                "className",
+                "id",
+                "style",
+                "...spread",
+                "...spread"
+            ]
+        );
+    }
+
+    fn hook_dependencies(module: &Module) -> Vec<Vec<String>> {
+        #[derive(Default)]
+        struct ArrayCollector(Vec<Vec<String>>);
+
+        impl Visit for ArrayCollector {
+            fn visit_array_lit(&mut self, array: &ArrayLit) {
+                let names = array
+                    .elems
+                    .iter()
+                    .map(|elem| match elem.as_ref().map(|e| &*e.expr) {
+                        Some(Expr::Ident(ident)) => ident.sym.to_string(),
+                        Some(Expr::Member(_)) => "<member>".to_string(),
+                        _ => "<other>".to_string(),
+                    })
+                    .collect();
+                self.0.push(names);
+                array.visit_children_with(self);
+            }
+        }
+
+        let mut collector = ArrayCollector::default();
+        module.visit_with(&mut collector);
+        collector.0
+    }
+
+    #[test]
+    fn test_hook_dependencies_untouched_by_default() {
+        let source = "useEffect(() => {}, [zeta, alpha]);";
+        let organized = organize_source(source).unwrap();
+
+        assert_eq!(hook_dependencies(&organized), vec![vec!["zeta", "alpha"]]);
+    }
+
+    #[test]
+    fn test_hook_dependency_sorting_opt_in() {
+        let source = r#"
+useEffect(() => {}, [zeta, Alpha, beta, zeta]);
+React.useMemo(() => 1, [b, a]);
+useImperativeHandle(ref, () => ({}), [d, c]);
+useCallback(() => {}, [props.b, a]);
+useCustom(() => {}, [b, a]);
+const list = [b, a];
+"#;
+        let parser = TypeScriptParser::new();
+        let module = parser.parse(source, "test.ts").unwrap();
+        let organized = KrokOrganizer::new()
+            .with_sorted_hook_dependencies(true)
+            .organize(module)
+            .unwrap();
+
+        let arrays = hook_dependencies(&organized);
+        // Identifier-only deps are sorted case-insensitively, duplicates kept
+        assert!(arrays.contains(&vec![
+            "Alpha".into(),
+            "beta".into(),
+            "zeta".into(),
+            "zeta".into()
+        ]));
+        assert!(arrays.contains(&vec!["a".into(), "b".into()]));
+        assert!(arrays.contains(&vec!["c".into(), "d".into()]));
+        // Non-identifier deps, custom hooks and plain arrays keep their order
+        assert!(arrays.contains(&vec!["<member>".into(), "a".into()]));
+        assert_eq!(
+            arrays
+                .iter()
+                .filter(|a| **a == vec!["b".to_string(), "a".to_string()])
+                .count(),
+            2
+        );
+    }
+
+    #[test]
+    fn test_jsx_boolean_prop_normalization() {
+        let source = r#"
+const Field = (props) => (
+    <input required={true} disabled={false} readOnly={props.locked} />
+);
+"#;
+
+        let organized = organize_source(source).unwrap();
+        let jsx_element = find_jsx_element(&organized);
+
+        // Only the literal `true` collapses to shorthand
+        let shorthand: Vec<(String, bool)> = jsx_element
+            .opening
+            .attrs
+            .iter()
+            .filter_map(|attr| match attr {
+                JSXAttrOrSpread::JSXAttr(jsx_attr) => match &jsx_attr.name {
+                    JSXAttrName::Ident(ident) => {
+                        Some((ident.sym.to_string(), jsx_attr.value.is_none()))
+                    }
+                    _ => None,
+                },
+                JSXAttrOrSpread::SpreadElement(_) => None,
+            })
+            .collect();
+
+        assert_eq!(
+            shorthand,
+            vec![
+                ("disabled".to_string(), false),
+                ("readOnly".to_string(), false),
+                ("required".to_string(), true),
+            ]
+        );
+    }
+
+    fn find_jsx_element(module: &Module) -> &JSXElement {
+        for item in &module.body {
+            if let ModuleItem::Stmt(stmt) = item {
+                if let Some(jsx) = find_jsx_in_stmt(stmt) {
+                    return jsx;
+                }
+            }
+        }
+        panic!("No JSX element found");
+    }
+
+    fn find_jsx_in_stmt(stmt: &Stmt) -> Option<&JSXElement> {
+        match stmt {
+            Stmt::Decl(Decl::Var(var_decl)) => {
+                for decl in &var_decl.decls {
+                    if let Some(init) = &decl.init {
+                        if let Some(jsx) = find_jsx_in_expr(init) {
+                            return Some(jsx);
+                        }
+                    }
+                }
+            }
+            Stmt::Return(ret_stmt) => {
+                if let Some(arg) = &ret_stmt.arg {
+                    if let Some(jsx) = find_jsx_in_expr(arg) {
+                        return Some(jsx);
+                    }
+                }
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn find_jsx_in_expr(expr: &Expr) -> Option<&JSXElement> {
+        match expr {
+            Expr::JSXElement(jsx) => Some(jsx),
+            Expr::Paren(paren) => find_jsx_in_expr(&paren.expr),
+            Expr::Arrow(arrow) => match &*arrow.body {
+                BlockStmtOrExpr::Expr(expr) => find_jsx_in_expr(expr),
+                BlockStmtOrExpr::BlockStmt(block) => {
+                    for stmt in &block.stmts {
+                        if let Some(jsx) = find_jsx_in_stmt(stmt) {
+                            return Some(jsx);
+                        }
+                    }
+                    None
+                }
+            },
+            _ => None,
+        }
+    }
+
+    #[test]
+    fn test_export_detection_functions() {
+        let source = r#"
+export function publicFunc() {}
+function privateFunc() {}
+export const publicArrow = () => {};
+const privateArrow = () => {};
+"#;
+
+        let module = TypeScriptParser::new().parse(source, "test.ts").unwrap();
+        let mut analyzer = ExportAnalyzer::new();
+        let export_info = analyzer.analyze(&module);
+
+        assert!(export_info.is_exported("publicFunc"));
+        assert!(!export_info.is_exported("privateFunc"));
+        assert!(export_info.is_exported("publicArrow"));
+        assert!(!export_info.is_exported("privateArrow"));
+    }
+
+    #[test]
+    fn test_export_detection_classes() {
+        let source = r#"
+export class PublicClass {}
+class PrivateClass {}
+export interface PublicInterface {}
+interface PrivateInterface {}
+"#;
+
+        let module = TypeScriptParser::new().parse(source, "test.ts").unwrap();
+        let mut analyzer = ExportAnalyzer::new();
+        let export_info = analyzer.analyze(&module);
+
+        assert!(export_info.is_exported("PublicClass"));
+        assert!(!export_info.is_exported("PrivateClass"));
+        assert!(export_info.is_exported("PublicInterface"));
+        assert!(!export_info.is_exported("PrivateInterface"));
+    }
+
+    #[test]
+    fn test_export_detection_types_and_enums() {
+        let source = r#"
+export type PublicType = string;
+type PrivateType = number;
+export enum PublicEnum { A, B }
+enum PrivateEnum { X, Y }
+"#;
+
+        let module = TypeScriptParser::new().parse(source, "test.ts").unwrap();
+        let mut analyzer = ExportAnalyzer::new();
+        let export_info = analyzer.analyze(&module);
+
+        assert!(export_info.is_exported("PublicType"));
+        assert!(!export_info.is_exported("PrivateType"));
+        assert!(export_info.is_exported("PublicEnum"));
+        assert!(!export_info.is_exported("PrivateEnum"));
+    }
+
+    #[test]
+    fn test_export_detection_named_exports() {
+        let source = r#"
+const a = 1;
+const b = 2;
+function c() {}
+class D {}
+
+export { a, b as bee, c };
+"#;
+
+        let module = TypeScriptParser::new().parse(source, "test.ts").unwrap();
+        let mut analyzer = ExportAnalyzer::new();
+        let export_info = analyzer.analyze(&module);
+
+        assert!(export_info.is_exported("a"));
+        assert!(export_info.is_exported("b"));
+        assert!(export_info.is_exported("c"));
+        assert!(!export_info.is_exported("D"));
+    }
+
+    #[test]
+    fn test_export_detection_default_export() {
+        let source = r#"
+function myFunc() {}
+export default myFunc;
+
+const obj = { x: 1 };
+export { obj as default };
+"#;
+
+        let module = TypeScriptParser::new().parse(source, "test.ts").unwrap();
+        let mut analyzer = ExportAnalyzer::new();
+        let export_info = analyzer.analyze(&module);
+
+        assert!(export_info.is_exported("myFunc"));
+        assert!(export_info.is_exported("obj"));
+    }
+
+    #[test]
+    fn test_export_detection_namespaces_and_export_assignment() {
+        let source = r#"
+const helper = 1;
+function parse() {}
+const format = () => {};
+export namespace Api {
+    export const helper = 2;
+    const internal = 3;
+    export namespace V1 { export type Options = {}; }
+}
+namespace Internal.Deep { export const config = {}; }
+export = { parse, render: format };
+"#;
+
+        let module = TypeScriptParser::new().parse(source, "test.ts").unwrap();
+        let mut analyzer = ExportAnalyzer::new();
+        let export_info = analyzer.analyze(&module);
+
+        assert!(export_info.is_exported("Api"));
+        assert!(!export_info.is_exported("Internal"));
+        // Namespace members don't pass for the module's own
+        assert!(!export_info.is_exported("helper"));
+        assert!(export_info.is_exported_from("Api", "helper"));
+        assert!(!export_info.is_exported_from("Api", "internal"));
+        assert!(export_info.is_exported_from("Api.V1", "Options"));
+        assert!(export_info.is_exported_from("Internal.Deep", "config"));
+        // What `export =` hands out
+        assert!(export_info.is_exported("parse"));
+        assert!(export_info.is_exported("format"));
+    }
+
+    #[test]
+    fn test_export_prioritization_basic() {
+        let source = r#"
+function privateFunc() {
+    return "private";
+}
+
+export function publicFunc() {
+    return privateFunc();
+}
+
+const privateConst = 10;
+
+export const publicConst = privateConst + 5;
+"#;
+
+        let organized = organize_source(source).unwrap();
+
+        // Find all function and variable declarations
+        let mut declarations = Vec::new();
+        for item in &organized.body {
+            match item {
+                ModuleItem::Stmt(Stmt::Decl(Decl::Fn(fn_decl))) => {
+                    declarations.push(fn_decl.ident.sym.to_string());
+                }
+                ModuleItem::Stmt(Stmt::Decl(Decl::Var(var_decl))) => {
+                    for decl in &var_decl.decls {
+                        if let Pat::Ident(ident) = &decl.name {
+                            declarations.push(ident.id.sym.to_string());
+                        }
+                    }
+                }
+                ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export_decl)) => {
+                    match &export_decl.decl {
+                        Decl::Fn(fn_decl) => {
+                            declarations.push(fn_decl.ident.sym.to_string());
+                        }
+                        Decl::Var(var_decl) => {
+                            for decl in &var_decl.decls {
+                                if let Pat::Ident(ident) = &decl.name {
+                                    declarations.push(ident.id.sym.to_string());
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // With smart dependency analysis:
+        // - Only runtime dependencies are hoisted (privateConst)
+        // - Function declarations don't need hoisting (privateFunc can stay after)
+        // - Exported items are alphabetically sorted (publicConst, publicFunc)
+        // - Non-exported items follow (privateFunc)
+        assert_eq!(
+            declarations,
+            vec!["privateConst", "publicConst", "publicFunc", "privateFunc"]
+        );
+    }
+
+    #[test]
+    fn test_export_prioritization_preserves_dependencies() {
+        let source = r#"
+const helper = () => "help";
+
+export const publicFunc = () => helper();
+
+function util() {
+    return helper();
+}
+
+export function main() {
+    return util();
+}
+"#;
+
+        let organized = organize_source(source).unwrap();
+
+        // Helper should stay before publicFunc because publicFunc depends on it
+        // util should stay before main because main depends on it
+        let mut declarations = Vec::new();
+        for item in &organized.body {
+            match item {
+                ModuleItem::Stmt(Stmt::Decl(decl)) => match decl {
+                    Decl::Fn(fn_decl) => {
+                        declarations.push(fn_decl.ident.sym.to_string());
+                    }
+                    Decl::Var(var_decl) => {
+                        for decl in &var_decl.decls {
+                            if let Pat::Ident(ident) = &decl.name {
+                                declarations.push(ident.id.sym.to_string());
+                            }
+                        }
+                    }
+                    _ => {}
+                },
+                ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export_decl)) => {
+                    match &export_decl.decl {
+                        Decl::Fn(fn_decl) => {
+                            declarations.push(fn_decl.ident.sym.to_string());
+                        }
+                        Decl::Var(var_decl) => {
+                            for decl in &var_decl.decls {
+                                if let Pat::Ident(ident) = &decl.name {
+                                    declarations.push(ident.id.sym.to_string());
+                                }
+                            }
+                        }
+                        _ => {}
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // With smart dependency analysis:
+        // - helper (arrow function) must be before publicFunc (runtime dependency)
+        // - util is a function declaration, so it doesn't need to be before main
+        let helper_idx = declarations.iter().position(|s| s == "helper").unwrap();
+        let public_func_idx = declarations.iter().position(|s| s == "publicFunc").unwrap();
+
+        assert!(helper_idx < public_func_idx);
+
+        // Function declarations can be called before declaration, so util can appear after main
+    }
+
+    #[test]
+    fn test_export_prioritization_with_classes_and_types() {
+        let source = r#"
+interface PrivateInterface {
+    x: number;
+}
+
+export interface PublicInterface {
+    y: string;
+}
+
+class PrivateClass {
+    value = 10;
+}
+
+export class PublicClass extends PrivateClass {
+    extra = 20;
+}
+
+type PrivateType = string | number;
+
+export type PublicType = PrivateType | boolean;
+"#;
+
+        let organized = organize_source(source).unwrap();
+
+        // Collect all declaration names
+        let mut declarations = Vec::new();
+        for item in &organized.body {
+            match item {
+                ModuleItem::Stmt(Stmt::Decl(decl)) => match decl {
+                    Decl::Class(class_decl) => {
+                        declarations.push(class_decl.ident.sym.to_string());
+                    }
+                    Decl::TsInterface(interface) => {
+                        declarations.push(interface.id.sym.to_string());
+                    }
+                    Decl::TsTypeAlias(type_alias) => {
+                        declarations.push(type_alias.id.sym.to_string());
+                    }
+                    _ => {}
+                },
+                ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export_decl)) => {
+                    match &export_decl.decl {
+                        Decl::Class(class_decl) => {
+                            declarations.push(class_decl.ident.sym.to_string());
+                        }
+                        Decl::TsInterface(interface) => {
+                            declarations.push(interface.id.sym.to_string());
+                        }
+                        Decl::TsTypeAlias(type_alias) => {
+                            declarations.push(type_alias.id.sym.to_string());
+                        }
+                        _ => {}
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // Ensure dependencies are preserved
+        let private_class_idx = declarations
+            .iter()
+            .position(|s| s == "PrivateClass")
+            .unwrap();
+        let public_class_idx = declarations
+            .iter()
+            .position(|s| s == "PublicClass")
+            .unwrap();
+        let _private_type_idx = declarations
+            .iter()
+            .position(|s| s == "PrivateType")
+            .unwrap();
+        let _public_type_idx = declarations.iter().position(|s| s == "PublicType").unwrap();
+
+        // PublicClass extends PrivateClass (runtime dependency for prototype chain)
+        assert!(private_class_idx < public_class_idx);
+        // Type aliases can forward reference other types, so ordering is not required
+    }
+}
@@ -0,0 +1,300 @@
+use anyhow::{Context, Result};
+use serde::de::{self, Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use serde_json::{Map, Value};
+use std::fmt;
+
+/// Canonical top-level key order for package.json.
+///
+/// The order mirrors what npm itself writes and what most of the ecosystem
+/// (sort-package-json, yarn) converges on: identity first, then distribution
+/// metadata, then entry points, then scripts and dependency tables. Keys not in
+/// this list keep their original relative order after the known ones, so custom
+/// tool config blocks don't get shuffled around.
+const KEY_ORDER: &[&str] = &[
+    "name",
+    "version",
+    "private",
+    "description",
+    "keywords",
+    "homepage",
+    "bugs",
+    "repository",
+    "funding",
+    "license",
+    "author",
+    "contributors",
+    "workspaces",
+    "files",
+    "type",
+    "sideEffects",
+    "main",
+    "module",
+    "browser",
+    "types",
+    "typings",
+    "exports",
+    "imports",
+    "bin",
+    "man",
+    "directories",
+    "scripts",
+    "dependencies",
+    "devDependencies",
+    "peerDependencies",
+    "peerDependenciesMeta",
+    "optionalDependencies",
+    "bundleDependencies",
+    "overrides",
+    "resolutions",
+    "engines",
+    "os",
+    "cpu",
+    "packageManager",
+    "publishConfig",
+];
+
+/// Companion formatter that keeps package.json key order canonical.
+///
+/// This intentionally does the minimum: reorder keys, alphabetize scripts and
+/// dependency tables, and sort export subpaths. The document is written back
+/// pretty-printed with two-space indentation, so values keep their meaning but
+/// not necessarily their spelling: escapes and number formats come out in
+/// serde_json's canonical form.
+///
+/// A document with a key twice in one object is refused. package.json is
+/// consumed by several tools with slightly different parsing rules, and which
+/// of the two they keep is one of them; dropping either would be the formatter
+/// deciding for them.
+pub struct PackageJsonFormatter;
+
+impl PackageJsonFormatter {
+    pub fn format(content: &str) -> Result<String> {
+        let UniqueKeys(value) =
+            serde_json::from_str(content).context("Failed to parse package.json")?;
+
+        let Value::Object(root) = value else {
+            anyhow::bail!("package.json must contain a JSON object");
+        };
+
+        let organized = Self::organize_root(root);
+
+        let mut output = serde_json::to_string_pretty(&Value::Object(organized))
+            .context("Failed to serialize package.json")?;
+        output.push('\n');
+
+        Ok(output)
+    }
+
+    fn organize_root(mut root: Map<String, Value>) -> Map<String, Value> {
+        let mut organized = Map::new();
+
+        for key in KEY_ORDER {
+            if let Some(value) = root.remove(*key) {
+                let value = match *key {
+                    "scripts"
+                    | "dependencies"
+                    | "devDependencies"
+                    | "peerDependencies"
+                    | "peerDependenciesMeta"
+                    | "optionalDependencies"
+                    | "overrides"
+                    | "resolutions"
+                    | "engines" => Self::sort_keys(value),
+                    "exports" | "imports" => Self::sort_subpaths(value),
+                    _ => value,
+                };
+                organized.insert(key.to_string(), value);
+            }
+        }
+
+        // Unknown keys keep their original relative order at the end
+        organized.extend(root);
+        organized
+    }
+
+    fn sort_keys(value: Value) -> Value {
+        match value {
+            Value::Object(map) => {
+                let mut entries: Vec<_> = map.into_iter().collect();
+                entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+                Value::Object(entries.into_iter().collect())
+            }
+            other => other,
+        }
+    }
+
+    /// Sort subpath keys (`"."`, `"./utils"`, `"#internal"`) while leaving
+    /// condition keys (`"import"`, `"require"`, `"default"`) in their original order.
+    ///
+    /// Node resolves conditions in object order, first match wins, so sorting
+    /// them would silently change which file gets loaded.
+    fn sort_subpaths(value: Value) -> Value {
+        match value {
+            Value::Object(map) => {
+                let is_subpath_map = map.keys().all(|k| k.starts_with('.') || k.starts_with('#'));
+                if !is_subpath_map {
+                    return Value::Object(map);
+                }
+
+                let mut entries: Vec<_> = map.into_iter().collect();
+                // The root export always leads because it's the package's main entry
+                entries.sort_by(|(a, _), (b, _)| (a != ".").cmp(&(b != ".")).then(a.cmp(b)));
+                Value::Object(entries.into_iter().collect())
+            }
+            other => other,
+        }
+    }
+}
+
+/// A JSON value with no object, at any depth, holding a key twice.
+struct UniqueKeys(Value);
+
+impl<'de> Deserialize<'de> for UniqueKeys {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer
+            .deserialize_any(UniqueKeysVisitor)
+            .map(UniqueKeys)
+    }
+}
+
+struct UniqueKeysVisitor;
+
+impl<'de> Visitor<'de> for UniqueKeysVisitor {
+    type Value = Value;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("a JSON value")
+    }
+
+    fn visit_bool<E: de::Error>(self, value: bool) -> Result<Value, E> {
+        Ok(Value::Bool(value))
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Value, E> {
+        Ok(Value::from(value))
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Value, E> {
+        Ok(Value::from(value))
+    }
+
+    fn visit_f64<E: de::Error>(self, value: f64) -> Result<Value, E> {
+        Ok(Value::from(value))
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Value, E> {
+        Ok(Value::String(value.to_string()))
+    }
+
+    fn visit_string<E: de::Error>(self, value: String) -> Result<Value, E> {
+        Ok(Value::String(value))
+    }
+
+    fn visit_unit<E: de::Error>(self) -> Result<Value, E> {
+        Ok(Value::Null)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Value, A::Error> {
+        let mut items = Vec::new();
+        while let Some(UniqueKeys(item)) = seq.next_element()? {
+            items.push(item);
+        }
+        Ok(Value::Array(items))
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Value, A::Error> {
+        let mut object = Map::new();
+        while let Some(key) = map.next_key::<String>()? {
+            let UniqueKeys(value) = map.next_value()?;
+            if object.contains_key(&key) {
+                return Err(de::Error::custom(format!("duplicate key `{key}`")));
+            }
+            object.insert(key, value);
+        }
+        Ok(Value::Object(object))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_orders_top_level_keys() {
+        let input = r#"{"scripts":{"test":"jest"},"version":"1.0.0","custom":true,"name":"pkg"}"#;
+        let result = PackageJsonFormatter::format(input).unwrap();
+
+        let name = result.find("\"name\"").unwrap();
+        let version = result.find("\"version\"").unwrap();
+        let scripts = result.find("\"scripts\"").unwrap();
+        let custom = result.find("\"custom\"").unwrap();
+
+        assert!(name < version);
+        assert!(version < scripts);
+        assert!(scripts < custom);
+        assert!(result.ends_with("}\n"));
+    }
+
+    #[test]
+    fn test_sorts_scripts_alphabetically() {
+        let input = r#"{"scripts":{"test":"jest","build":"tsc","lint":"eslint ."}}"#;
+        let result = PackageJsonFormatter::format(input).unwrap();
+
+        let build = result.find("\"build\"").unwrap();
+        let lint = result.find("\"lint\"").unwrap();
+        let test = result.find("\"test\"").unwrap();
+
+        assert!(build < lint);
+        assert!(lint < test);
+    }
+
+    #[test]
+    fn test_sorts_export_subpaths_but_not_conditions() {
+        let input = r#"{"exports":{"./utils":"./utils.js",".":{"require":"./index.cjs","import":"./index.mjs"},"./alpha":"./alpha.js"}}"#;
+        let result = PackageJsonFormatter::format(input).unwrap();
+
+        let root = result.find("\".\"").unwrap();
+        let alpha = result.find("\"./alpha\"").unwrap();
+        let utils = result.find("\"./utils\"").unwrap();
+        assert!(root < alpha);
+        assert!(alpha < utils);
+
+        // Condition order is semantically significant and must be kept
+        let require = result.find("\"require\"").unwrap();
+        let import = result.find("\"import\"").unwrap();
+        assert!(require < import);
+    }
+
+    #[test]
+    fn test_rejects_non_object_root() {
+        assert!(PackageJsonFormatter::format("[]").is_err());
+    }
+
+    #[test]
+    fn test_rejects_duplicate_keys() {
+        let top_level = r#"{"name":"a","version":"1.0.0","name":"b"}"#;
+        let error = PackageJsonFormatter::format(top_level).unwrap_err();
+        assert!(
+            format!("{error:#}").contains("duplicate key `name`"),
+            "{error:#}"
+        );
+
+        let nested = r#"{"dependencies":{"react":"^18.0.0","react":"^19.0.0"}}"#;
+        assert!(PackageJsonFormatter::format(nested).is_err());
+
+        // The same key in different objects is fine
+        let separate =
+            r#"{"dependencies":{"react":"^18.0.0"},"devDependencies":{"react":"^18.0.0"}}"#;
+        assert!(PackageJsonFormatter::format(separate).is_ok());
+    }
+
+    #[test]
+    fn test_values_keep_their_meaning() {
+        let input = r#"{"version":"1.0.0","config":{"path":"a\/b","ratio":1.5,"list":[1,null,true]},"name":"pkg"}"#;
+        let result = PackageJsonFormatter::format(input).unwrap();
+
+        let reparsed: Value = serde_json::from_str(&result).unwrap();
+        assert_eq!(reparsed, serde_json::from_str::<Value>(input).unwrap());
+        assert!(result.starts_with("{\n  \"name\": \"pkg\""), "{result}");
+    }
+}
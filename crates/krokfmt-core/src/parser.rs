@@ -0,0 +1,391 @@
+use anyhow::{Context, Result};
+use swc_common::{comments::SingleThreadedComments, sync::Lrc, FileName, SourceMap};
+use swc_ecma_ast::Module;
+use swc_ecma_parser::{lexer::Lexer, Parser, StringInput, Syntax};
+
+use std::path::Path;
+
+use crate::logging::{debug, debug_span};
+
+/// Wrapper around SWC's TypeScript parser with our specific configuration.
+///
+/// We store source_map and comments as public fields because the formatter pipeline
+/// needs to pass them through to the code generator. This preserves comment positioning
+/// and source locations across the entire transformation.
+pub struct TypeScriptParser {
+    pub source_map: Lrc<SourceMap>,
+    pub comments: SingleThreadedComments,
+}
+
+/// A module parsed by [`TypeScriptParser::parse_disambiguated`].
+pub struct ParsedSource {
+    /// The parser that produced `module`; its source map and comments describe it
+    pub parser: TypeScriptParser,
+    pub module: Module,
+    /// Filename matching the syntax that actually parsed (`.tsx` after a JSX
+    /// fallback), for later stages that pick their syntax from the extension
+    pub filename: String,
+}
+
+/// Whether TypeScript is parsed with JSX, for embedders that know better
+/// than the filename.
+///
+/// JavaScript files are always parsed with JSX, whatever this says.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum JsxDetection {
+    /// From the extension, falling back to TSX when a `.ts` file only parses
+    /// with JSX
+    #[default]
+    Auto,
+    /// As TSX, whatever the extension
+    Always,
+    /// As plain TypeScript, with no fallback to TSX
+    Never,
+}
+
+impl Default for TypeScriptParser {
+    fn default() -> Self {
+        Self {
+            source_map: Lrc::new(SourceMap::default()),
+            comments: SingleThreadedComments::default(),
+        }
+    }
+}
+
+impl TypeScriptParser {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn parse(&self, source: &str, filename: &str) -> Result<Module> {
+        let _span = debug_span!("parse", file = filename).entered();
+
+        let fm = self.source_map.new_source_file(
+            Lrc::new(FileName::Custom(filename.to_string())),
+            source.to_string(),
+        );
+
+        let syntax = Self::syntax(filename);
+
+        // The lexer needs comment tracking enabled to preserve them through formatting.
+        // Without this, all comments would be stripped from the output.
+        let lexer = Lexer::new(
+            syntax,
+            Default::default(),
+            StringInput::from(&*fm),
+            Some(&self.comments),
+        );
+
+        let mut parser = Parser::new_from(lexer);
+
+        parser
+            .parse_module()
+            .map_err(|err| anyhow::anyhow!("Failed to parse {}: {:?}", filename, err))
+            .context("Failed to parse TypeScript module")
+    }
+
+    /// The syntax a file is parsed with, picked from its extension.
+    fn syntax(filename: &str) -> Syntax {
+        if is_javascript(filename) {
+            // JSX is on for every JavaScript file: without type assertions there's
+            // nothing for `<` to be confused with, and React projects put JSX in
+            // plain .js files all the time
+            return Syntax::Es(swc_ecma_parser::EsSyntax {
+                jsx: true,
+                decorators: true,
+                ..Default::default()
+            });
+        }
+
+        // TSX detection is file extension based - we chose this over content sniffing
+        // to avoid ambiguity and match common tooling behavior (webpack, tsc, etc).
+        Syntax::Typescript(swc_ecma_parser::TsSyntax {
+            tsx: filename.ends_with(".tsx"),
+            decorators: true,      // Always enabled since Angular/NestJS are popular
+            no_early_errors: true, // We want to format even partially invalid code
+            ..Default::default()
+        })
+    }
+
+    /// Parse `source`, falling back to TSX when a plain-TS parse fails only
+    /// because the file contains JSX.
+    ///
+    /// Sniffing the source for JSX-looking text isn't safe: `<T>(x)` is a type
+    /// assertion in `.ts` but the start of a JSX element in `.tsx`, so guessing
+    /// TSX changes what the code means. Instead the extension's syntax always
+    /// wins when it parses, and TSX is only used when TS fails and TSX succeeds,
+    /// i.e. when the failure was down to JSX. If both fail, the TS error is
+    /// reported since that's the syntax the file claims to be.
+    pub fn parse_disambiguated(source: &str, filename: &str) -> Result<ParsedSource> {
+        let parser = Self::new();
+        let ts_error = match parser.parse(source, filename) {
+            Ok(module) => {
+                return Ok(ParsedSource {
+                    parser,
+                    module,
+                    filename: filename.to_string(),
+                })
+            }
+            Err(err) => err,
+        };
+
+        if !Self::allows_jsx_fallback(filename) {
+            return Err(ts_error);
+        }
+
+        // A fresh parser, because the failed attempt already registered a source
+        // file and comments that would otherwise leak into the output
+        let tsx_parser = Self::new();
+        let tsx_filename = Path::new(filename)
+            .with_extension("tsx")
+            .to_string_lossy()
+            .into_owned();
+
+        match tsx_parser.parse(source, &tsx_filename) {
+            Ok(module) => {
+                debug!(file = filename, "parsed as TSX after TS parse failed");
+                Ok(ParsedSource {
+                    parser: tsx_parser,
+                    module,
+                    filename: tsx_filename,
+                })
+            }
+            Err(_) => Err(ts_error),
+        }
+    }
+
+    /// Parse `source` with JSX on or off as `jsx` says, or as
+    /// [`Self::parse_disambiguated`] decides for [`JsxDetection::Auto`].
+    ///
+    /// The resulting filename carries the extension of the syntax used, like
+    /// a `.ts` file falling back to TSX does.
+    pub fn parse_with_jsx(source: &str, filename: &str, jsx: JsxDetection) -> Result<ParsedSource> {
+        let with_extension = |extension| {
+            Path::new(filename)
+                .with_extension(extension)
+                .to_string_lossy()
+                .into_owned()
+        };
+        let filename = match jsx {
+            JsxDetection::Auto => return Self::parse_disambiguated(source, filename),
+            _ if is_javascript(filename) => return Self::parse_disambiguated(source, filename),
+            JsxDetection::Always => with_extension("tsx"),
+            JsxDetection::Never if filename.ends_with(".tsx") => with_extension("ts"),
+            JsxDetection::Never => filename.to_string(),
+        };
+
+        let parser = Self::new();
+        let module = parser.parse(source, &filename)?;
+        Ok(ParsedSource {
+            parser,
+            module,
+            filename,
+        })
+    }
+
+    /// Whether a file with this name could hold JSX despite not parsing as TSX.
+    ///
+    /// `.mts`, `.cts` and declaration files can never contain JSX, and `.tsx`
+    /// and JavaScript files were already parsed with JSX enabled.
+    fn allows_jsx_fallback(filename: &str) -> bool {
+        !(is_javascript(filename)
+            || filename.ends_with(".tsx")
+            || filename.ends_with(".mts")
+            || filename.ends_with(".cts")
+            || filename.ends_with(".d.ts"))
+    }
+}
+
+/// Whether `filename` names a JavaScript file, parsed without TypeScript
+/// syntax.
+fn is_javascript(filename: &str) -> bool {
+    matches!(
+        Path::new(filename).extension().and_then(|ext| ext.to_str()),
+        Some("js" | "jsx" | "mjs" | "cjs")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use swc_ecma_ast::*;
+
+    #[test]
+    fn test_parse_empty_file() {
+        let parser = TypeScriptParser::new();
+        let result = parser.parse("", "test.ts");
+        assert!(result.is_ok());
+        let module = result.unwrap();
+        assert_eq!(module.body.len(), 0);
+    }
+
+    #[test]
+    fn test_parse_simple_import() {
+        let parser = TypeScriptParser::new();
+        let source = r#"import { foo } from './bar';"#;
+        let result = parser.parse(source, "test.ts");
+        assert!(result.is_ok());
+
+        let module = result.unwrap();
+        assert_eq!(module.body.len(), 1);
+
+        match &module.body[0] {
+            ModuleItem::ModuleDecl(ModuleDecl::Import(_)) => {}
+            _ => panic!("Expected import declaration"),
+        }
+    }
+
+    #[test]
+    fn test_parse_multiple_imports() {
+        let parser = TypeScriptParser::new();
+        let source = r#"
+import React from 'react';
+import { useState } from 'react';
+import './styles.css';
+import type { Props } from './types';
+"#;
+        let result = parser.parse(source, "test.ts");
+        assert!(result.is_ok());
+
+        let module = result.unwrap();
+        assert_eq!(module.body.len(), 4);
+
+        // All items should be imports
+        for item in &module.body {
+            match item {
+                ModuleItem::ModuleDecl(ModuleDecl::Import(_)) => {}
+                _ => panic!("Expected only import declarations"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_exports() {
+        let parser = TypeScriptParser::new();
+        let source = r#"
+export const foo = 42;
+export function bar() {}
+export { baz } from './baz';
+export default class MyClass {}
+"#;
+        let result = parser.parse(source, "test.ts");
+        assert!(result.is_ok());
+
+        let module = result.unwrap();
+        assert_eq!(module.body.len(), 4);
+    }
+
+    #[test]
+    fn test_parse_typescript_specific_syntax() {
+        let parser = TypeScriptParser::new();
+        let source = r#"
+interface User {
+    name: string;
+    age: number;
+}
+
+type ID = string | number;
+
+enum Status {
+    Active,
+    Inactive
+}
+
+const user: User = { name: "John", age: 30 };
+"#;
+        let result = parser.parse(source, "test.ts");
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_parse_tsx_file() {
+        let parser = TypeScriptParser::new();
+        let source = r#"
+import React from 'react';
+
+interface Props {
+    title: string;
+}
+
+export const Component: React.FC<Props> = ({ title }) => {
+    return <div>{title}</div>;
+};
+"#;
+        let result = parser.parse(source, "test.tsx");
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_disambiguation_keeps_ts_type_assertions() {
+        // Valid TS that a JSX heuristic would mistake for an element
+        let source = "const value = <Foo>(bar);\nconst other = <T,>(x: T) => x;\n";
+        let parsed = TypeScriptParser::parse_disambiguated(source, "cast.ts").unwrap();
+
+        assert_eq!(parsed.filename, "cast.ts");
+        match &parsed.module.body[0] {
+            ModuleItem::Stmt(Stmt::Decl(Decl::Var(var))) => {
+                assert!(matches!(
+                    var.decls[0].init.as_deref(),
+                    Some(Expr::TsTypeAssertion(_))
+                ));
+            }
+            _ => panic!("Expected variable declaration"),
+        }
+    }
+
+    #[test]
+    fn test_disambiguation_falls_back_to_tsx_for_jsx() {
+        let source = "export const App = () => <div className=\"app\" />;\n";
+        let parsed = TypeScriptParser::parse_disambiguated(source, "app.ts").unwrap();
+
+        assert_eq!(parsed.filename, "app.tsx");
+    }
+
+    #[test]
+    fn test_disambiguation_reports_ts_error_when_both_fail() {
+        let result = TypeScriptParser::parse_disambiguated("import { foo from './bar';", "bad.ts");
+        assert!(result.is_err());
+
+        // Module-only extensions never fall back, even if TSX would parse
+        let result = TypeScriptParser::parse_disambiguated("const a = <div />;", "app.mts");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_parse_with_jsx_overrides_the_extension() {
+        let jsx = "const a = <div />;";
+        let parsed = TypeScriptParser::parse_with_jsx(jsx, "app.ts", JsxDetection::Always).unwrap();
+        assert_eq!(parsed.filename, "app.tsx");
+        assert!(TypeScriptParser::parse_with_jsx(jsx, "app.ts", JsxDetection::Never).is_err());
+
+        let assertion = "const n = <number>value;";
+        let parsed =
+            TypeScriptParser::parse_with_jsx(assertion, "app.tsx", JsxDetection::Never).unwrap();
+        assert_eq!(parsed.filename, "app.ts");
+
+        // JavaScript always has JSX
+        let parsed = TypeScriptParser::parse_with_jsx(jsx, "app.js", JsxDetection::Never).unwrap();
+        assert_eq!(parsed.filename, "app.js");
+    }
+
+    #[test]
+    fn test_parse_javascript_files() {
+        let source = "export const App = () => <div className=\"app\" />;\n";
+        for filename in ["app.js", "app.jsx", "app.mjs", "app.cjs"] {
+            let parsed = TypeScriptParser::parse_disambiguated(source, filename).unwrap();
+            assert_eq!(parsed.filename, filename);
+        }
+
+        // JavaScript files don't get TypeScript syntax, or a TSX fallback
+        let result = TypeScriptParser::parse_disambiguated("let a: number = 1;", "app.js");
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_parse_syntax_error() {
+        let parser = TypeScriptParser::new();
+        let source = r#"import { foo from './bar';"#; // Missing closing brace
+        let result = parser.parse(source, "test.ts");
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,291 @@
+//! [`format_typescript_with_options`](crate::format_typescript_with_options),
+//! one stage at a time.
+//!
+//! Each stage hands over what the next one needs, and the ASTs in between are
+//! public, so tools can run passes of their own on them:
+//!
+//! ```text
+//! parse -> organize -> reinsert_comments -> biome_format
+//! ```
+//!
+//! Running the stages back to back is exactly what the one-call functions do.
+
+use anyhow::{Context, Result};
+use std::path::Path;
+use swc_common::comments::SingleThreadedComments;
+use swc_ecma_ast::Module;
+
+use crate::biome_formatter::{BiomeFormatter, BiomeFormatterConfig, Formatted};
+use crate::comment_extractor::CommentExtractionResult;
+use crate::comment_formatter::{CommentFormatter, OrganizedModule};
+use crate::comment_reinserter::TrailingCommentTables;
+use crate::logging::warn;
+use crate::organizer::ChangeLog;
+use crate::parser::{JsxDetection, ParsedSource, TypeScriptParser};
+use crate::FormatOptions;
+
+/// A parsed source file, with its comments still attached.
+pub struct Parsed<'a> {
+    pub module: Module,
+    parser: TypeScriptParser,
+    source: &'a str,
+    filename: String,
+}
+
+impl Parsed<'_> {
+    /// The filename matching the syntax that parsed: `.tsx` when a `.ts` file
+    /// turned out to hold JSX.
+    pub fn filename(&self) -> &str {
+        &self.filename
+    }
+}
+
+/// An organized module, with its comments set aside until
+/// [`reinsert_comments`].
+///
+/// Comments go back to the nodes they were attached to by semantic hash. A
+/// pass that changes a node's content leaves that node without its comments.
+pub struct Organized<'a> {
+    pub module: Module,
+    /// What organizing regrouped, hoisted and sorted
+    pub change_log: ChangeLog,
+    comments: CommentExtractionResult,
+    inline_comments: SingleThreadedComments,
+    file_header: Vec<String>,
+    syntax_filename: &'static str,
+    formatter: CommentFormatter,
+    biome_config: BiomeFormatterConfig,
+    source: &'a str,
+    filename: String,
+}
+
+/// Code printed from an organized module, before Biome lays it out.
+pub struct Generated<'a> {
+    pub code: String,
+    biome_config: BiomeFormatterConfig,
+    source: &'a str,
+    filename: String,
+}
+
+/// Parse `source`, picking TS or TSX syntax from `filename` the way
+/// [`format_typescript`](crate::format_typescript) does.
+pub fn parse<'a>(source: &'a str, filename: &str) -> Result<Parsed<'a>> {
+    parse_with_jsx(source, filename, JsxDetection::Auto)
+}
+
+/// [`parse`], with JSX on or off whatever `filename` says, as
+/// [`FormatOptions::jsx`] has it.
+pub fn parse_with_jsx<'a>(
+    source: &'a str,
+    filename: &str,
+    jsx: JsxDetection,
+) -> Result<Parsed<'a>> {
+    let ParsedSource {
+        parser,
+        module,
+        filename,
+    } = TypeScriptParser::parse_with_jsx(source, filename, jsx)
+        .context("Failed to parse TypeScript code")?;
+
+    Ok(Parsed {
+        module,
+        parser,
+        source,
+        filename,
+    })
+}
+
+/// Sort imports, declarations, members and keys as `options` allow.
+pub fn organize<'a>(parsed: Parsed<'a>, options: &FormatOptions) -> Result<Organized<'a>> {
+    organize_with(parsed, options, true)
+}
+
+/// [`organize`], or with `reorganize` off only set the comments aside, as
+/// [`format_generated`](crate::format_generated) does.
+pub(crate) fn organize_with<'a>(
+    parsed: Parsed<'a>,
+    options: &FormatOptions,
+    reorganize: bool,
+) -> Result<Organized<'a>> {
+    let Parsed {
+        module,
+        parser,
+        source,
+        filename,
+    } = parsed;
+
+    let formatter = CommentFormatter::new(parser.source_map, parser.comments)
+        .with_reorganization(reorganize)
+        .with_import_profile(options.import_profile)
+        .with_project_layout(options.project_layout.clone())
+        .with_sorted_hook_dependencies(options.sort_hook_dependencies)
+        .with_react_first(options.react_first)
+        .with_grouped_type_imports(options.group_type_imports)
+        .with_grouped_constants(options.group_constants)
+        .with_sorted_re_exports(options.sort_re_exports)
+        .with_import_fixes(options.import_fixes.clone())
+        .with_unused_imports_removed(options.remove_unused_imports)
+        .with_import_group_banners(options.import_group_banners)
+        .with_rules(options.rules);
+    let OrganizedModule {
+        module,
+        change_log,
+        comments,
+        inline_comments,
+        file_header,
+        syntax_filename,
+    } = formatter
+        .organize(module, source)
+        .context("Failed to organize code")?;
+
+    // The organized code is always indented by codegen's rules, so the
+    // source's own indentation is worked out before it's gone
+    let mut biome_config = BiomeFormatterConfig::default();
+    if options.detect_indentation {
+        biome_config = biome_config.with_detected_indentation(source);
+    }
+
+    Ok(Organized {
+        module,
+        change_log,
+        comments,
+        inline_comments,
+        file_header,
+        syntax_filename,
+        formatter,
+        biome_config,
+        source,
+        filename,
+    })
+}
+
+/// Print the organized module and put its comments back.
+pub fn reinsert_comments(organized: Organized<'_>) -> Result<Generated<'_>> {
+    let Organized {
+        module,
+        change_log,
+        comments,
+        inline_comments,
+        file_header,
+        syntax_filename,
+        formatter,
+        biome_config,
+        source,
+        filename,
+    } = organized;
+
+    let organized = OrganizedModule {
+        module,
+        change_log,
+        comments,
+        inline_comments,
+        file_header,
+        syntax_filename,
+    };
+    let code = formatter
+        .generate(organized, source)
+        .context("Failed to reinsert comments")?;
+
+    Ok(Generated {
+        code,
+        biome_config,
+        source,
+        filename,
+    })
+}
+
+/// Lay the code out with Biome, keeping it as printed if Biome can't handle
+/// it.
+pub fn biome_format(generated: Generated<'_>) -> Result<String> {
+    let filename = generated.filename.clone();
+    let formatted = biome_format_or_fallback(generated)?;
+    if let Some(failure) = &formatted.fallback {
+        warn!(file = %filename, %failure, "Biome failed, keeping the organized code");
+    }
+    Ok(formatted.code)
+}
+
+/// [`biome_format`], handing back why Biome couldn't format the code instead
+/// of logging it, for callers that tell the user themselves.
+pub fn biome_format_or_fallback(generated: Generated<'_>) -> Result<Formatted> {
+    let mut formatted = BiomeFormatter::with_config(generated.biome_config)
+        .format_or_fallback(&generated.code, Path::new(&generated.filename))
+        .context("Failed to format with Biome")?;
+
+    // Biome sets trailing comments one space after their code; line up the
+    // ones the source had in columns again
+    let tables = TrailingCommentTables::detect(generated.source);
+    formatted.code = tables.reflow(formatted.code);
+    Ok(formatted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transformer::ImportFixes;
+    use swc_ecma_ast::{Decl, ModuleItem, Stmt};
+
+    #[test]
+    fn test_stages_match_format_typescript() {
+        let source = "// Helpers\nconst b = 2;\nimport { z, a } from 'lib';\nconst a = 1; // one\n";
+
+        let parsed = parse(source, "test.ts").unwrap();
+        let organized = organize(parsed, &FormatOptions::default()).unwrap();
+        let generated = reinsert_comments(organized).unwrap();
+        let formatted = biome_format(generated).unwrap();
+
+        assert_eq!(
+            formatted,
+            crate::format_typescript(source, "test.ts").unwrap()
+        );
+    }
+
+    #[test]
+    fn test_custom_pass_between_stages() {
+        let source = "export const b = 2;\nconst a = 1;\n";
+
+        let parsed = parse(source, "test.ts").unwrap();
+        let mut organized = organize(parsed, &FormatOptions::default()).unwrap();
+        // Drop every non-exported declaration
+        organized
+            .module
+            .body
+            .retain(|item| !matches!(item, ModuleItem::Stmt(Stmt::Decl(Decl::Var(_)))));
+        let generated = reinsert_comments(organized).unwrap();
+
+        assert!(generated.code.contains("export const b = 2"));
+        assert!(!generated.code.contains("const a = 1"));
+    }
+
+    #[test]
+    fn test_opt_in_options_reach_the_stages() {
+        let source = "import { b } from './lib';\nimport { a } from './lib';\nexport function f() {\n    return a + b;\n}\n";
+        let options = FormatOptions {
+            import_fixes: ImportFixes {
+                merge_duplicates: true,
+                ..ImportFixes::default()
+            },
+            detect_indentation: true,
+            ..FormatOptions::default()
+        };
+
+        let parsed = parse(source, "test.ts").unwrap();
+        let organized = organize(parsed, &options).unwrap();
+        let generated = reinsert_comments(organized).unwrap();
+        let formatted = biome_format_or_fallback(generated).unwrap();
+
+        assert!(formatted.fallback.is_none());
+        assert!(formatted.code.contains("{ a, b }"), "{}", formatted.code);
+        assert!(
+            formatted.code.contains("\n    return a + b;"),
+            "{}",
+            formatted.code
+        );
+    }
+
+    #[test]
+    fn test_jsx_in_ts_file_switches_filename() {
+        let parsed = parse("const a = <div />;\n", "view.ts").unwrap();
+        assert_eq!(parsed.filename(), "view.tsx");
+    }
+}
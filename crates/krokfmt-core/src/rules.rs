@@ -0,0 +1,146 @@
+use serde::Deserialize;
+use std::fmt;
+use std::str::FromStr;
+
+/// A group of related organizing rules that can be switched off as a unit.
+///
+/// Groups are coarse on purpose. They exist so large codebases can adopt krokfmt
+/// a directory at a time (see `[[override]]` in krokfmt.toml), not to turn
+/// krokfmt into a formatter with a knob for every opinion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum RuleGroup {
+    /// Reordering top-level declarations by visibility (FR2)
+    DeclarationOrder,
+    /// Sorting object literal properties (FR3.2)
+    ObjectProperties,
+    /// Sorting destructured object patterns, including parameters (FR3.1)
+    Destructuring,
+    /// Sorting class members (FR3.3)
+    ClassMembers,
+    /// Sorting union and intersection type members, and the members of
+    /// interfaces and type literals (FR3.4)
+    TypeMembers,
+    /// Sorting string enum members (FR3.5)
+    EnumMembers,
+    /// Sorting and normalizing JSX props (FR3.6)
+    JsxProps,
+    /// Sorting switch cases on string literals (FR3.8)
+    SwitchCases,
+}
+
+impl RuleGroup {
+    pub const ALL: [RuleGroup; 8] = [
+        RuleGroup::DeclarationOrder,
+        RuleGroup::ObjectProperties,
+        RuleGroup::Destructuring,
+        RuleGroup::ClassMembers,
+        RuleGroup::TypeMembers,
+        RuleGroup::EnumMembers,
+        RuleGroup::JsxProps,
+        RuleGroup::SwitchCases,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            RuleGroup::DeclarationOrder => "declaration-order",
+            RuleGroup::ObjectProperties => "object-properties",
+            RuleGroup::Destructuring => "destructuring",
+            RuleGroup::ClassMembers => "class-members",
+            RuleGroup::TypeMembers => "type-members",
+            RuleGroup::EnumMembers => "enum-members",
+            RuleGroup::JsxProps => "jsx-props",
+            RuleGroup::SwitchCases => "switch-cases",
+        }
+    }
+
+    fn bit(&self) -> u16 {
+        1 << (*self as u16)
+    }
+}
+
+impl FromStr for RuleGroup {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        RuleGroup::ALL
+            .into_iter()
+            .find(|group| group.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<_> = RuleGroup::ALL.iter().map(RuleGroup::name).collect();
+                format!(
+                    "unknown rule group '{s}' (expected one of: {})",
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+impl fmt::Display for RuleGroup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The rule groups in effect for a file. Everything is enabled by default.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RuleSet {
+    disabled: u16,
+}
+
+impl RuleSet {
+    pub fn all() -> Self {
+        Self { disabled: 0 }
+    }
+
+    /// Every rule group switched off, leaving only the imports organized, for
+    /// teams that want import sorting without declarations moving around.
+    pub fn imports_only() -> Self {
+        RuleGroup::ALL.into_iter().fold(Self::all(), Self::disable)
+    }
+
+    pub fn disable(mut self, group: RuleGroup) -> Self {
+        self.disabled |= group.bit();
+        self
+    }
+
+    pub fn is_enabled(&self, group: RuleGroup) -> bool {
+        self.disabled & group.bit() == 0
+    }
+}
+
+impl Default for RuleSet {
+    fn default() -> Self {
+        Self::all()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rule_set_disable() {
+        let rules = RuleSet::all().disable(RuleGroup::ClassMembers);
+
+        assert!(!rules.is_enabled(RuleGroup::ClassMembers));
+        assert!(RuleGroup::ALL
+            .iter()
+            .filter(|group| **group != RuleGroup::ClassMembers)
+            .all(|group| rules.is_enabled(*group)));
+    }
+
+    #[test]
+    fn test_imports_only_disables_every_group() {
+        let rules = RuleSet::imports_only();
+        assert!(RuleGroup::ALL.iter().all(|group| !rules.is_enabled(*group)));
+    }
+
+    #[test]
+    fn test_rule_group_names_round_trip() {
+        for group in RuleGroup::ALL {
+            assert_eq!(group.name().parse::<RuleGroup>(), Ok(group));
+        }
+        assert!("imports".parse::<RuleGroup>().is_err());
+    }
+}
@@ -0,0 +1,284 @@
+//! Formatting very large files a segment at a time.
+//!
+//! The regular pipeline holds the whole AST, the organized code, Biome's tree
+//! and the formatted code at once, which for a multi-megabyte generated file
+//! adds up to many times its size. Here the file is parsed once just to find
+//! where its top-level items start, that AST is dropped, and the file is
+//! formatted in segments of roughly equal size whose output is written out as
+//! soon as it's ready. Peak memory is then the source plus one segment's worth
+//! of pipeline.
+//!
+//! The price is that declarations are only organized within their segment.
+//! Imports are the exception: they're gathered from the whole file into a
+//! leading segment of their own, so they're still sorted and grouped as one.
+
+use anyhow::{Context, Result};
+use std::borrow::Cow;
+use std::io::{self, Write};
+use std::ops::Range;
+use swc_common::comments::Comments;
+use swc_common::{BytePos, Spanned};
+use swc_ecma_ast::*;
+
+use crate::parser::TypeScriptParser;
+
+/// A piece of the source that's formatted on its own.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Segment {
+    /// Byte ranges of the source making up the segment, in order
+    ranges: Vec<Range<usize>>,
+}
+
+impl Segment {
+    /// The segment's source text; only copied when it isn't contiguous.
+    pub fn text<'a>(&self, source: &'a str) -> Cow<'a, str> {
+        match self.ranges.as_slice() {
+            [range] => Cow::Borrowed(&source[range.clone()]),
+            ranges => Cow::Owned(ranges.iter().map(|range| &source[range.clone()]).collect()),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.ranges.iter().map(|range| range.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Split `source` into segments of at least `segment_bytes` (except the last),
+/// cut only where a top-level item starts.
+///
+/// All imports make up the first segment. Every other byte of the source ends
+/// up in exactly one of the following segments, leading comments staying with
+/// the item they precede.
+pub fn split_segments(source: &str, filename: &str, segment_bytes: usize) -> Result<Vec<Segment>> {
+    let parsed =
+        TypeScriptParser::parse_disambiguated(source, filename).context("Failed to parse file")?;
+    let offset = |pos: BytePos| parsed.parser.source_map.lookup_byte_offset(pos).pos.0 as usize;
+
+    let items: Vec<(bool, usize, usize)> = parsed
+        .module
+        .body
+        .iter()
+        .map(|item| {
+            let span = item.span();
+            let start = parsed
+                .parser
+                .comments
+                .get_leading(span.lo)
+                .iter()
+                .flatten()
+                .map(|comment| comment.span.lo)
+                .min()
+                .unwrap_or(span.lo);
+            let is_import = matches!(
+                item,
+                ModuleItem::ModuleDecl(ModuleDecl::Import(_) | ModuleDecl::TsImportEquals(_))
+            );
+            (
+                is_import,
+                line_start(source, offset(start)),
+                offset(span.hi),
+            )
+        })
+        .collect();
+    // Only the boundaries are needed from here on
+    drop(parsed);
+
+    let mut imports = Vec::new();
+    let mut cuts = vec![0];
+    for (index, &(is_import, start, end)) in items.iter().enumerate() {
+        if is_import {
+            // Take the rest of the line too (trailing comments, the newline)
+            // unless the next item starts on it
+            let next_start = items.get(index + 1).map_or(source.len(), |next| next.1);
+            let line_end = source[end..]
+                .find('\n')
+                .map_or(source.len(), |i| end + i + 1);
+            imports.push(start..line_end.min(next_start));
+        } else if start.saturating_sub(cuts[cuts.len() - 1]) >= segment_bytes {
+            cuts.push(start);
+        }
+    }
+    cuts.push(source.len());
+
+    let mut segments = vec![Segment {
+        ranges: imports.clone(),
+    }];
+    for window in cuts.windows(2) {
+        let mut ranges = Vec::new();
+        let mut cursor = window[0];
+        for import in imports
+            .iter()
+            .filter(|import| import.start >= window[0] && import.end <= window[1])
+        {
+            ranges.push(cursor..import.start);
+            cursor = import.end;
+        }
+        ranges.push(cursor..window[1]);
+        ranges.retain(|range| !range.is_empty());
+        segments.push(Segment { ranges });
+    }
+    segments.retain(|segment| !segment.is_empty());
+
+    Ok(segments)
+}
+
+/// Format `source` segment by segment with `format_segment`, streaming the
+/// results to `out` separated by blank lines.
+///
+/// `format_segment` is the caller's regular pipeline, so each segment gets
+/// every rule and option a whole file would, comments included.
+pub fn format_segmented<W: Write>(
+    source: &str,
+    filename: &str,
+    segment_bytes: usize,
+    out: &mut W,
+    mut format_segment: impl FnMut(&str) -> Result<String>,
+) -> Result<()> {
+    let mut first = true;
+    for segment in split_segments(source, filename, segment_bytes)? {
+        let text = segment.text(source);
+        if text.trim().is_empty() {
+            continue;
+        }
+
+        let formatted = format_segment(&text)?;
+        let formatted = formatted.trim_matches('\n');
+        if formatted.is_empty() {
+            continue;
+        }
+
+        if !first {
+            out.write_all(b"\n")?;
+        }
+        out.write_all(formatted.as_bytes())?;
+        out.write_all(b"\n")?;
+        first = false;
+    }
+
+    out.flush()?;
+    Ok(())
+}
+
+/// A writer that passes everything through to `inner` while checking it
+/// against `original`, so streamed output can tell whether it changed anything
+/// without being kept in memory.
+pub struct ChangeDetector<'a, W> {
+    inner: W,
+    original: &'a [u8],
+    written: usize,
+    changed: bool,
+}
+
+impl<'a, W: Write> ChangeDetector<'a, W> {
+    pub fn new(original: &'a str, inner: W) -> Self {
+        Self {
+            inner,
+            original: original.as_bytes(),
+            written: 0,
+            changed: false,
+        }
+    }
+
+    /// Whether what was written so far differs from the original, counting
+    /// output that stopped short of it as a change.
+    pub fn changed(&self) -> bool {
+        self.changed || self.written != self.original.len()
+    }
+
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for ChangeDetector<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        if !self.changed {
+            let expected = self.original.get(self.written..self.written + written);
+            self.changed = expected != Some(&buf[..written]);
+        }
+        self.written += written;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+fn line_start(source: &str, offset: usize) -> usize {
+    let line_start = source[..offset].rfind('\n').map_or(0, |i| i + 1);
+    if source[line_start..offset].trim().is_empty() {
+        line_start
+    } else {
+        offset
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts(source: &str, segment_bytes: usize) -> Vec<String> {
+        split_segments(source, "test.ts", segment_bytes)
+            .unwrap()
+            .iter()
+            .map(|segment| segment.text(source).into_owned())
+            .collect()
+    }
+
+    #[test]
+    fn test_imports_are_gathered_and_segments_cut_between_items() {
+        let source = r#"import { b } from './b';
+// About a
+const a = 1;
+import { a2 } from './a2'; // trailing
+const b = 2;
+const c = 3;
+"#;
+
+        assert_eq!(
+            texts(source, 20),
+            vec![
+                "import { b } from './b';\nimport { a2 } from './a2'; // trailing\n".to_string(),
+                "// About a\nconst a = 1;\n".to_string(),
+                "const b = 2;\nconst c = 3;\n".to_string(),
+            ]
+        );
+
+        // A segment size larger than the file leaves a single body segment
+        assert_eq!(texts(source, 1 << 20).len(), 2);
+    }
+
+    #[test]
+    fn test_format_segmented_streams_each_segment() {
+        let source = "import { z } from 'z';\nconst b = 1;\nconst a = 2;\n";
+        let mut out = Vec::new();
+        format_segmented(source, "test.ts", 1, &mut out, |segment| {
+            Ok(segment.to_uppercase())
+        })
+        .unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "IMPORT { Z } FROM 'Z';\n\nCONST B = 1;\n\nCONST A = 2;\n"
+        );
+    }
+
+    #[test]
+    fn test_change_detector() {
+        let mut same = ChangeDetector::new("abc", io::sink());
+        same.write_all(b"ab").unwrap();
+        assert!(same.changed());
+        same.write_all(b"c").unwrap();
+        assert!(!same.changed());
+
+        let mut different = ChangeDetector::new("abc", io::sink());
+        different.write_all(b"abd").unwrap();
+        assert!(different.changed());
+    }
+}
@@ -0,0 +1,847 @@
+use anyhow::Result;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+use std::ops::Range;
+use swc_common::Spanned;
+use swc_ecma_ast::*;
+use swc_ecma_visit::{Visit, VisitWith};
+
+use crate::parser::TypeScriptParser;
+
+/// Version of the semantic hash scheme.
+///
+/// Hashes are a public contract: review bots and other downstream tools persist
+/// them to recognize declarations that moved but didn't change. Any change that
+/// can alter the hash of an existing declaration (what gets fed to the hasher,
+/// in what order, or the hash function itself) must bump this number so
+/// consumers know to discard stored hashes instead of silently mismatching.
+///
+/// Version history:
+/// - 1: FNV-1a 64 over declaration kind, name, and signature (params, return
+///   type, superclass, extends). Bodies are deliberately excluded.
+/// - 2: Top-level expression statements hash the identifiers and literals they
+///   contain, instead of all sharing a single hash.
+pub const SEMANTIC_HASH_VERSION: u32 = 2;
+
+/// FNV-1a 64-bit hasher used for all semantic hashes.
+///
+/// We can't use std's `DefaultHasher` because its algorithm is explicitly
+/// unspecified and may change between Rust releases, which would break the
+/// stability promise above. FNV-1a is tiny, fast for short keys like
+/// identifiers, and fully specified. `usize` values are widened to 64 bits so
+/// hashes match between native and wasm32 builds.
+pub struct StableHasher {
+    state: u64,
+}
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        Self {
+            state: 0xcbf2_9ce4_8422_2325,
+        }
+    }
+}
+
+impl Hasher for StableHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.state ^= u64::from(*byte);
+            self.state = self.state.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+
+    fn write_usize(&mut self, value: usize) {
+        self.write_u64(value as u64);
+    }
+
+    fn finish(&self) -> u64 {
+        self.state
+    }
+}
+
+/// Compute semantic hashes for every top-level declaration in `source`.
+///
+/// Returns `(name, hash, span)` tuples in source order, where `span` is the
+/// byte range of the declaration within `source`. Names follow the same rules
+/// the formatter uses internally (e.g. `{a,b}` for destructuring), and hashes
+/// follow [`SEMANTIC_HASH_VERSION`]. Items that don't carry a semantic identity,
+/// such as control flow statements, are skipped.
+pub fn hash_source(source: &str) -> Result<Vec<(String, u64, Range<usize>)>> {
+    // Try plain TypeScript first; only fall back to TSX so generic arrow
+    // functions like `<T>(x: T) => x` keep their non-JSX meaning.
+    let parser = TypeScriptParser::new();
+    let module = match parser.parse(source, "source.ts") {
+        Ok(module) => module,
+        Err(_) => {
+            let parser = TypeScriptParser::new();
+            let module = parser.parse(source, "source.tsx")?;
+            return Ok(collect_hashes(&parser, &module));
+        }
+    };
+
+    Ok(collect_hashes(&parser, &module))
+}
+
+fn collect_hashes(parser: &TypeScriptParser, module: &Module) -> Vec<(String, u64, Range<usize>)> {
+    module
+        .body
+        .iter()
+        .filter_map(|item| {
+            let (hash, name) = SemanticHasher::hash_module_item(item)?;
+            let span = item.span();
+            let lo = parser.source_map.lookup_byte_offset(span.lo).pos.0 as usize;
+            let hi = parser.source_map.lookup_byte_offset(span.hi).pos.0 as usize;
+            Some((name, hash, lo..hi))
+        })
+        .collect()
+}
+
+/// Keys for the object and array literals of a module, which the comments
+/// dangling inside them are stored under.
+///
+/// A literal's shape ([`SemanticHasher::hash_object_lit`]) is shared by every
+/// `{}` and by both objects in `[{ id, name }, { id, name }]`, so the key also
+/// takes the owners the literal sits in (module item, class member, object
+/// property, JSX attribute) and which of the same-shaped literals under those
+/// owners it is. Owners are identified by hashes that survive sorting, so the
+/// key does too. Visitors [`enter`](Self::enter) and [`exit`](Self::exit) each
+/// owner and ask for a literal's key before visiting inside it.
+#[derive(Default)]
+pub struct LiteralKeys {
+    owners: Vec<u64>,
+    seen: HashMap<(u64, u64), usize>,
+}
+
+impl LiteralKeys {
+    pub fn enter(&mut self, owner: u64) {
+        self.owners.push(owner);
+    }
+
+    pub fn exit(&mut self) {
+        self.owners.pop();
+    }
+
+    /// The key of the next literal shaped `shape` under the current owners.
+    pub fn key(&mut self, shape: u64) -> u64 {
+        let mut hasher = StableHasher::default();
+        self.owners.hash(&mut hasher);
+        let path = hasher.finish();
+
+        let seen = self.seen.entry((path, shape)).or_default();
+        let occurrence = *seen;
+        *seen += 1;
+
+        let mut hasher = StableHasher::default();
+        "literal".hash(&mut hasher);
+        path.hash(&mut hasher);
+        shape.hash(&mut hasher);
+        occurrence.hash(&mut hasher);
+        hasher.finish()
+    }
+}
+
+/// Generates semantic hashes for AST nodes that are stable across transformations.
+/// These hashes identify nodes by their semantic properties rather than positions.
+///
+/// See [`SEMANTIC_HASH_VERSION`] for the stability guarantees of the produced hashes.
+#[derive(Default)]
+pub struct SemanticHasher {
+    /// Current hash being computed
+    current_hash: Option<u64>,
+}
+
+impl SemanticHasher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Generate a semantic hash for any AST node that might have comments
+    pub fn hash_node(node: &impl VisitWith<Self>) -> u64 {
+        let mut hasher = Self::new();
+        node.visit_with(&mut hasher);
+        hasher.current_hash.unwrap_or(0)
+    }
+
+    /// Generate hash for a module item
+    pub fn hash_module_item(item: &ModuleItem) -> Option<(u64, String)> {
+        match item {
+            ModuleItem::Stmt(stmt) => Self::hash_stmt(stmt),
+            ModuleItem::ModuleDecl(decl) => Self::hash_module_decl(decl),
+        }
+    }
+
+    fn hash_stmt(stmt: &Stmt) -> Option<(u64, String)> {
+        match stmt {
+            Stmt::Decl(decl) => Self::hash_decl(decl),
+            Stmt::Expr(expr_stmt) => {
+                let hash = Self::hash_expr_stmt(&expr_stmt.expr);
+                Some((hash, format!("expr_{hash:x}")))
+            }
+            _ => None,
+        }
+    }
+
+    /// Expression statements have no name, so they're identified by the names
+    /// and literals they mention. `registry.push(a)` and `registry.push(b)`
+    /// must hash apart, or comments on side-effect statements (which stay put as
+    /// reordering barriers) would all land on whichever was positioned last.
+    fn hash_expr_stmt(expr: &Expr) -> u64 {
+        let mut hasher = StableHasher::default();
+        "expr".hash(&mut hasher);
+        expr.visit_with(&mut ExprIdentity {
+            hasher: &mut hasher,
+        });
+        hasher.finish()
+    }
+
+    fn hash_module_decl(decl: &ModuleDecl) -> Option<(u64, String)> {
+        match decl {
+            ModuleDecl::Import(import) => {
+                let hash = Self::hash_import(import);
+                Some((hash, format!("import_{hash:x}")))
+            }
+            ModuleDecl::ExportDecl(export) => Self::hash_decl(&export.decl),
+            ModuleDecl::ExportNamed(export) => {
+                let hash = Self::hash_node(export);
+                Some((hash, format!("export_named_{hash:x}")))
+            }
+            ModuleDecl::ExportDefaultDecl(export) => {
+                let hash = Self::hash_node(&export.decl);
+                Some((hash, format!("export_default_{hash:x}")))
+            }
+            ModuleDecl::ExportDefaultExpr(export) => {
+                let hash = Self::hash_node(&export.expr);
+                Some((hash, format!("export_default_expr_{hash:x}")))
+            }
+            ModuleDecl::TsImportEquals(import) => {
+                let hash = Self::hash_import_equals(import);
+                Some((hash, format!("import_equals_{hash:x}")))
+            }
+            ModuleDecl::TsExportAssignment(_) => {
+                // A module has at most one `export =`, so the kind alone identifies it
+                let mut hasher = StableHasher::default();
+                "export_assignment".hash(&mut hasher);
+                let hash = hasher.finish();
+                Some((hash, format!("export_assignment_{hash:x}")))
+            }
+            _ => None,
+        }
+    }
+
+    fn hash_decl(decl: &Decl) -> Option<(u64, String)> {
+        match decl {
+            Decl::Fn(fn_decl) => {
+                let hash = Self::hash_function_decl(fn_decl);
+                Some((hash, fn_decl.ident.sym.to_string()))
+            }
+            Decl::Class(class_decl) => {
+                let hash = Self::hash_class_decl(class_decl);
+                Some((hash, class_decl.ident.sym.to_string()))
+            }
+            Decl::Var(var_decl) => {
+                let hash = Self::hash_var_decl(var_decl);
+                let name = var_decl
+                    .decls
+                    .first()
+                    .and_then(|d| Self::get_pat_name(&d.name))
+                    .unwrap_or_else(|| "var".to_string());
+                Some((hash, name))
+            }
+            Decl::TsInterface(interface) => {
+                let hash = Self::hash_interface(interface);
+                Some((hash, interface.id.sym.to_string()))
+            }
+            Decl::TsTypeAlias(alias) => {
+                let hash = Self::hash_type_alias(alias);
+                Some((hash, alias.id.sym.to_string()))
+            }
+            Decl::TsEnum(ts_enum) => {
+                let hash = Self::hash_enum(ts_enum);
+                Some((hash, ts_enum.id.sym.to_string()))
+            }
+            _ => None,
+        }
+    }
+
+    fn hash_import(import: &ImportDecl) -> u64 {
+        let mut hasher = StableHasher::default();
+        "import".hash(&mut hasher);
+        import.src.value.as_str().hash(&mut hasher);
+
+        // Sort specifiers for consistent hashing regardless of order
+        let mut spec_hashes: Vec<u64> = Vec::new();
+
+        for spec in &import.specifiers {
+            let mut spec_hasher = StableHasher::default();
+            match spec {
+                ImportSpecifier::Default(default) => {
+                    "default".hash(&mut spec_hasher);
+                    default.local.sym.as_str().hash(&mut spec_hasher);
+                }
+                ImportSpecifier::Named(named) => {
+                    "named".hash(&mut spec_hasher);
+                    named.local.sym.as_str().hash(&mut spec_hasher);
+                    if let Some(imported) = &named.imported {
+                        match imported {
+                            ModuleExportName::Ident(ident) => {
+                                ident.sym.as_str().hash(&mut spec_hasher)
+                            }
+                            ModuleExportName::Str(s) => s.value.as_str().hash(&mut spec_hasher),
+                        }
+                    }
+                }
+                ImportSpecifier::Namespace(ns) => {
+                    "namespace".hash(&mut spec_hasher);
+                    ns.local.sym.as_str().hash(&mut spec_hasher);
+                }
+            }
+            spec_hashes.push(spec_hasher.finish());
+        }
+
+        // Sort for consistent ordering
+        spec_hashes.sort();
+        for spec_hash in spec_hashes {
+            spec_hash.hash(&mut hasher);
+        }
+
+        hasher.finish()
+    }
+
+    fn hash_import_equals(import: &TsImportEqualsDecl) -> u64 {
+        let mut hasher = StableHasher::default();
+        "import_equals".hash(&mut hasher);
+        import.id.sym.as_str().hash(&mut hasher);
+        match &import.module_ref {
+            TsModuleRef::TsExternalModuleRef(module_ref) => {
+                module_ref.expr.value.as_str().hash(&mut hasher)
+            }
+            TsModuleRef::TsEntityName(entity) => Self::hash_entity_name(entity, &mut hasher),
+        }
+        hasher.finish()
+    }
+
+    fn hash_entity_name(entity: &TsEntityName, hasher: &mut StableHasher) {
+        match entity {
+            TsEntityName::Ident(ident) => ident.sym.as_str().hash(hasher),
+            TsEntityName::TsQualifiedName(qualified) => {
+                Self::hash_entity_name(&qualified.left, hasher);
+                qualified.right.sym.as_str().hash(hasher);
+            }
+        }
+    }
+
+    fn hash_function_decl(func: &FnDecl) -> u64 {
+        let mut hasher = StableHasher::default();
+        "function".hash(&mut hasher);
+        func.ident.sym.as_str().hash(&mut hasher);
+        Self::hash_function_signature(&func.function, &mut hasher);
+        hasher.finish()
+    }
+
+    fn hash_class_decl(class: &ClassDecl) -> u64 {
+        let mut hasher = StableHasher::default();
+        "class".hash(&mut hasher);
+        class.ident.sym.as_str().hash(&mut hasher);
+
+        // Include superclass in hash
+        if let Some(super_class) = &class.class.super_class {
+            if let Expr::Ident(ident) = super_class.as_ref() {
+                ident.sym.as_str().hash(&mut hasher);
+            }
+        }
+
+        hasher.finish()
+    }
+
+    fn hash_var_decl(var: &VarDecl) -> u64 {
+        let mut hasher = StableHasher::default();
+
+        match var.kind {
+            VarDeclKind::Const => "const".hash(&mut hasher),
+            VarDeclKind::Let => "let".hash(&mut hasher),
+            VarDeclKind::Var => "var".hash(&mut hasher),
+        }
+
+        // Hash all declarators
+        for decl in &var.decls {
+            if let Some(name) = Self::get_pat_name(&decl.name) {
+                name.hash(&mut hasher);
+            }
+        }
+
+        hasher.finish()
+    }
+
+    /// Generate hash for a single variable declarator
+    pub fn hash_var_declarator(name: &str) -> u64 {
+        let mut hasher = StableHasher::default();
+        "var_declarator".hash(&mut hasher);
+        name.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Generate hash for the shape of an object literal, which
+    /// [`LiteralKeys`] keys its dangling comments with.
+    ///
+    /// Only the set of keys is hashed, so the hash survives property sorting.
+    pub fn hash_object_lit(obj: &ObjectLit) -> u64 {
+        let mut keys: Vec<String> = obj
+            .props
+            .iter()
+            .map(|prop| match prop {
+                PropOrSpread::Spread(_) => "...".to_string(),
+                PropOrSpread::Prop(prop) => match prop.as_ref() {
+                    Prop::Shorthand(ident) => ident.sym.to_string(),
+                    Prop::KeyValue(kv) => Self::prop_name_key(&kv.key),
+                    Prop::Assign(assign) => assign.key.sym.to_string(),
+                    Prop::Getter(getter) => format!("get {}", Self::prop_name_key(&getter.key)),
+                    Prop::Setter(setter) => format!("set {}", Self::prop_name_key(&setter.key)),
+                    Prop::Method(method) => Self::prop_name_key(&method.key),
+                },
+            })
+            .collect();
+        keys.sort();
+
+        let mut hasher = StableHasher::default();
+        "object_lit".hash(&mut hasher);
+        keys.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Generate hash for the shape of an array literal, which [`LiteralKeys`]
+    /// keys its dangling comments with.
+    ///
+    /// Elements are hashed as a sorted multiset so the hash survives element
+    /// sorting (e.g. hook dependency arrays).
+    pub fn hash_array_lit(array: &ArrayLit) -> u64 {
+        let mut elems: Vec<String> = array
+            .elems
+            .iter()
+            .map(|elem| match elem {
+                None => "<hole>".to_string(),
+                Some(elem) if elem.spread.is_some() => "...".to_string(),
+                Some(elem) => match elem.expr.as_ref() {
+                    Expr::Ident(ident) => ident.sym.to_string(),
+                    Expr::Lit(Lit::Str(s)) => format!("{:?}", s.value.as_str()),
+                    Expr::Lit(Lit::Num(n)) => n.value.to_string(),
+                    _ => "<expr>".to_string(),
+                },
+            })
+            .collect();
+        elems.sort();
+
+        let mut hasher = StableHasher::default();
+        "array_lit".hash(&mut hasher);
+        elems.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// Generate hashes for the members of an interface body or type literal,
+    /// used to keep their comments with them.
+    ///
+    /// A member is hashed with its key and the keys of all its siblings, so
+    /// hashes survive member sorting while `id` in one interface and `id` in
+    /// another hash apart. Members sharing a key (overloads) are told apart
+    /// by which of them they are, which sorting keeps.
+    pub fn hash_type_elements(members: &[TsTypeElement]) -> Vec<u64> {
+        let keys: Vec<String> = members.iter().map(Self::type_element_key).collect();
+        let mut siblings = keys.clone();
+        siblings.sort();
+
+        keys.iter()
+            .enumerate()
+            .map(|(index, key)| {
+                let occurrence = keys[..index].iter().filter(|k| *k == key).count();
+                let mut hasher = StableHasher::default();
+                "type_element".hash(&mut hasher);
+                siblings.hash(&mut hasher);
+                key.hash(&mut hasher);
+                occurrence.hash(&mut hasher);
+                hasher.finish()
+            })
+            .collect()
+    }
+
+    fn type_element_key(member: &TsTypeElement) -> String {
+        let (prefix, key, computed) = match member {
+            TsTypeElement::TsCallSignatureDecl(_) => return "()".to_string(),
+            TsTypeElement::TsConstructSignatureDecl(_) => return "new()".to_string(),
+            TsTypeElement::TsIndexSignature(_) => return "[index]".to_string(),
+            TsTypeElement::TsPropertySignature(prop) => ("", &prop.key, prop.computed),
+            TsTypeElement::TsMethodSignature(method) => ("", &method.key, method.computed),
+            TsTypeElement::TsGetterSignature(getter) => ("get ", &getter.key, getter.computed),
+            TsTypeElement::TsSetterSignature(setter) => ("set ", &setter.key, setter.computed),
+        };
+        let key = match &**key {
+            _ if computed => "[computed]".to_string(),
+            Expr::Ident(ident) => ident.sym.to_string(),
+            Expr::Lit(Lit::Str(s)) => s.value.to_string(),
+            Expr::Lit(Lit::Num(n)) => n.value.to_string(),
+            _ => "[computed]".to_string(),
+        };
+        format!("{prefix}{key}")
+    }
+
+    fn prop_name_key(key: &PropName) -> String {
+        match key {
+            PropName::Ident(ident) => ident.sym.to_string(),
+            PropName::Str(s) => s.value.to_string(),
+            PropName::Num(n) => n.value.to_string(),
+            PropName::BigInt(b) => b.value.to_string(),
+            PropName::Computed(_) => "[computed]".to_string(),
+        }
+    }
+
+    fn hash_interface(interface: &TsInterfaceDecl) -> u64 {
+        let mut hasher = StableHasher::default();
+        "interface".hash(&mut hasher);
+        interface.id.sym.as_str().hash(&mut hasher);
+
+        // Include extends in hash
+        for extend in &interface.extends {
+            if let Expr::Ident(ident) = extend.expr.as_ref() {
+                ident.sym.as_str().hash(&mut hasher);
+            }
+        }
+
+        hasher.finish()
+    }
+
+    fn hash_type_alias(alias: &TsTypeAliasDecl) -> u64 {
+        let mut hasher = StableHasher::default();
+        "type".hash(&mut hasher);
+        alias.id.sym.as_str().hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn hash_enum(ts_enum: &TsEnumDecl) -> u64 {
+        let mut hasher = StableHasher::default();
+        "enum".hash(&mut hasher);
+        ts_enum.id.sym.as_str().hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn hash_function_signature(func: &Function, hasher: &mut StableHasher) {
+        // Include parameter count and types
+        func.params.len().hash(hasher);
+
+        for param in &func.params {
+            match &param.pat {
+                Pat::Ident(ident) => {
+                    ident.id.sym.as_str().hash(hasher);
+                    if let Some(type_ann) = &ident.type_ann {
+                        Self::hash_type_annotation(type_ann, hasher);
+                    }
+                }
+                Pat::Array(_) => "array_pattern".hash(hasher),
+                Pat::Object(_) => "object_pattern".hash(hasher),
+                Pat::Rest(_) => "rest_pattern".hash(hasher),
+                _ => "other_pattern".hash(hasher),
+            }
+        }
+
+        // Include return type
+        if let Some(return_type) = &func.return_type {
+            Self::hash_type_annotation(return_type, hasher);
+        }
+    }
+
+    fn hash_type_annotation(type_ann: &TsTypeAnn, hasher: &mut StableHasher) {
+        // Simplified type hashing - could be expanded
+        match type_ann.type_ann.as_ref() {
+            TsType::TsKeywordType(keyword) => {
+                format!("{:?}", keyword.kind).hash(hasher);
+            }
+            TsType::TsTypeRef(type_ref) => {
+                if let TsEntityName::Ident(ident) = &type_ref.type_name {
+                    ident.sym.as_str().hash(hasher);
+                }
+            }
+            _ => "complex_type".hash(hasher),
+        }
+    }
+
+    fn get_pat_name(pat: &Pat) -> Option<String> {
+        match pat {
+            Pat::Ident(ident) => Some(ident.id.sym.to_string()),
+            Pat::Object(obj) => {
+                // For object patterns, create a composite name
+                let names: Vec<String> = obj
+                    .props
+                    .iter()
+                    .filter_map(|prop| match prop {
+                        ObjectPatProp::Assign(assign) => Some(assign.key.sym.to_string()),
+                        ObjectPatProp::KeyValue(kv) => Self::get_pat_name(&kv.value),
+                        _ => None,
+                    })
+                    .collect();
+
+                if names.is_empty() {
+                    None
+                } else {
+                    Some(format!("{{{}}}", names.join(",")))
+                }
+            }
+            Pat::Array(arr) => {
+                let names: Vec<String> = arr
+                    .elems
+                    .iter()
+                    .filter_map(|elem| elem.as_ref().and_then(Self::get_pat_name))
+                    .collect();
+
+                if names.is_empty() {
+                    None
+                } else {
+                    Some(format!("[{}]", names.join(",")))
+                }
+            }
+            _ => None,
+        }
+    }
+
+    /// Generate hash for class members
+    pub fn hash_class_member(member: &ClassMember, class_name: &str) -> Option<(u64, String)> {
+        let mut hasher = StableHasher::default();
+        class_name.hash(&mut hasher);
+
+        match member {
+            ClassMember::Constructor(ctor) => {
+                "constructor".hash(&mut hasher);
+                ctor.params.len().hash(&mut hasher);
+                Some((hasher.finish(), "constructor".to_string()))
+            }
+            ClassMember::Method(method) => {
+                "method".hash(&mut hasher);
+                method.is_static.hash(&mut hasher);
+
+                let name = match &method.key {
+                    PropName::Ident(ident) => ident.sym.to_string(),
+                    PropName::Str(s) => s.value.to_string(),
+                    _ => return None,
+                };
+
+                name.hash(&mut hasher);
+                Self::hash_function_signature(&method.function, &mut hasher);
+
+                Some((hasher.finish(), name))
+            }
+            ClassMember::PrivateMethod(method) => {
+                "private_method".hash(&mut hasher);
+                method.is_static.hash(&mut hasher);
+                method.key.name.as_str().hash(&mut hasher);
+                Self::hash_function_signature(&method.function, &mut hasher);
+
+                Some((hasher.finish(), format!("#{}", method.key.name)))
+            }
+            ClassMember::ClassProp(prop) => {
+                "prop".hash(&mut hasher);
+                prop.is_static.hash(&mut hasher);
+
+                let name = match &prop.key {
+                    PropName::Ident(ident) => ident.sym.to_string(),
+                    PropName::Str(s) => s.value.to_string(),
+                    _ => return None,
+                };
+
+                name.hash(&mut hasher);
+                Some((hasher.finish(), name))
+            }
+            ClassMember::PrivateProp(prop) => {
+                "private_prop".hash(&mut hasher);
+                prop.is_static.hash(&mut hasher);
+                prop.key.name.as_str().hash(&mut hasher);
+
+                Some((hasher.finish(), format!("#{}", prop.key.name)))
+            }
+            _ => None,
+        }
+    }
+}
+
+// Implement Visit trait for completeness (though we mostly use specific functions)
+/// Feeds the identifiers and literals of an expression, in order, to a hasher.
+struct ExprIdentity<'a> {
+    hasher: &'a mut StableHasher,
+}
+
+impl Visit for ExprIdentity<'_> {
+    fn visit_ident(&mut self, ident: &Ident) {
+        ident.sym.as_str().hash(self.hasher);
+    }
+
+    fn visit_ident_name(&mut self, ident: &IdentName) {
+        ident.sym.as_str().hash(self.hasher);
+    }
+
+    fn visit_str(&mut self, s: &Str) {
+        s.value.as_str().hash(self.hasher);
+    }
+
+    fn visit_number(&mut self, n: &Number) {
+        n.value.to_bits().hash(self.hasher);
+    }
+
+    fn visit_bool(&mut self, b: &Bool) {
+        b.value.hash(self.hasher);
+    }
+}
+
+impl Visit for SemanticHasher {
+    fn visit_module(&mut self, module: &Module) {
+        let mut hasher = StableHasher::default();
+        "module".hash(&mut hasher);
+        module.body.len().hash(&mut hasher);
+        self.current_hash = Some(hasher.finish());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::TypeScriptParser;
+
+    fn parse_module(source: &str) -> Module {
+        let parser = TypeScriptParser::new();
+        parser.parse(source, "test.ts").unwrap()
+    }
+
+    #[test]
+    fn test_function_hash_stable() {
+        let source1 = "function foo(x: number): string { return x.toString(); }";
+        let source2 = "function foo(x: number): string { return String(x); }";
+
+        let module1 = parse_module(source1);
+        let module2 = parse_module(source2);
+
+        let hash1 = SemanticHasher::hash_module_item(&module1.body[0])
+            .unwrap()
+            .0;
+        let hash2 = SemanticHasher::hash_module_item(&module2.body[0])
+            .unwrap()
+            .0;
+
+        // Same signature = same hash despite different implementation
+        assert_eq!(hash1, hash2);
+    }
+
+    #[test]
+    fn test_function_hash_different_signatures() {
+        let source1 = "function foo(x: number): string { }";
+        let source2 = "function foo(x: string): string { }";
+
+        let module1 = parse_module(source1);
+        let module2 = parse_module(source2);
+
+        let hash1 = SemanticHasher::hash_module_item(&module1.body[0])
+            .unwrap()
+            .0;
+        let hash2 = SemanticHasher::hash_module_item(&module2.body[0])
+            .unwrap()
+            .0;
+
+        // Different signatures = different hashes
+        assert_ne!(hash1, hash2);
+    }
+
+    #[test]
+    fn test_class_hash_with_extends() {
+        let source1 = "class Foo extends Bar { }";
+        let source2 = "class Foo extends Baz { }";
+
+        let module1 = parse_module(source1);
+        let module2 = parse_module(source2);
+
+        let hash1 = SemanticHasher::hash_module_item(&module1.body[0])
+            .unwrap()
+            .0;
+        let hash2 = SemanticHasher::hash_module_item(&module2.body[0])
+            .unwrap()
+            .0;
+
+        // Different superclass = different hash
+        assert_ne!(hash1, hash2);
+    }
+
+    #[test]
+    fn test_import_hash() {
+        let source1 = "import { foo, bar } from './module';";
+        let source2 = "import { bar, foo } from './module';";
+        let source3 = "import { foo } from './module';";
+
+        let module1 = parse_module(source1);
+        let module2 = parse_module(source2);
+        let module3 = parse_module(source3);
+
+        let hash1 = SemanticHasher::hash_module_item(&module1.body[0])
+            .unwrap()
+            .0;
+        let hash2 = SemanticHasher::hash_module_item(&module2.body[0])
+            .unwrap()
+            .0;
+        let hash3 = SemanticHasher::hash_module_item(&module3.body[0])
+            .unwrap()
+            .0;
+
+        // Order doesn't matter for imports with same specifiers
+        assert_eq!(hash1, hash2);
+        // Different specifiers = different hash
+        assert_ne!(hash1, hash3);
+    }
+
+    #[test]
+    fn test_hash_scheme_is_pinned() {
+        // If this fails, the hash scheme changed: bump SEMANTIC_HASH_VERSION and
+        // update the expected value so downstream consumers are told.
+        let module = parse_module("function foo(x: number): string {}");
+        let (hash, _) = SemanticHasher::hash_module_item(&module.body[0]).unwrap();
+        assert_eq!(SEMANTIC_HASH_VERSION, 2);
+        assert_eq!(hash, 0x0812_f93b_72f5_3e3b);
+    }
+
+    #[test]
+    fn test_expression_statements_hash_apart() {
+        let module = parse_module("registry.push(a);\nregistry.push(b);\na = 1, b = 2;\n");
+        let hashes: Vec<_> = module
+            .body
+            .iter()
+            .map(|item| SemanticHasher::hash_module_item(item).unwrap().0)
+            .collect();
+
+        assert_ne!(hashes[0], hashes[1]);
+        assert_ne!(hashes[1], hashes[2]);
+
+        // Layout doesn't matter, only what the statement mentions
+        let reformatted = parse_module("registry\n  .push(a)");
+        assert_eq!(
+            SemanticHasher::hash_module_item(&reformatted.body[0])
+                .unwrap()
+                .0,
+            hashes[0]
+        );
+    }
+
+    #[test]
+    fn test_hash_source() {
+        let source = "import { a } from './a';\nexport function foo() {}\nconst bar = 1;\n";
+        let hashes = hash_source(source).unwrap();
+
+        assert_eq!(hashes.len(), 3);
+        assert_eq!(hashes[1].0, "foo");
+        assert_eq!(&source[hashes[1].2.clone()], "export function foo() {}");
+        assert_eq!(hashes[2].0, "bar");
+
+        // Moving a declaration doesn't change its hash
+        let moved = hash_source("const bar = 1;\nexport function foo() {}\n").unwrap();
+        assert_eq!(moved[1].1, hashes[1].1);
+        assert_eq!(moved[0].1, hashes[2].1);
+    }
+
+    #[test]
+    fn test_destructuring_pattern_names() {
+        let source = "const { foo, bar } = obj;";
+        let module = parse_module(source);
+
+        let (_, name) = SemanticHasher::hash_module_item(&module.body[0]).unwrap();
+        assert_eq!(name, "{foo,bar}");
+    }
+}
@@ -0,0 +1,119 @@
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::fmt;
+use std::str::FromStr;
+
+/// The part of a krokfmt version that determines its output.
+///
+/// This is the stability contract: patch releases never change how code is
+/// formatted, so a project formatted with 0.3.1 stays formatted under 0.3.7.
+/// Formatting changes only ship in a new minor release while we're on 0.x, and
+/// only in a new major release from 1.0 on. Keeping upgrades that reformat code
+/// rare and explicit is what makes `krokfmt migrate` a single, reviewable commit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub struct OutputVersion {
+    major: u64,
+    minor: u64,
+}
+
+impl OutputVersion {
+    /// The output version of this build of krokfmt.
+    pub fn current() -> Self {
+        env!("CARGO_PKG_VERSION")
+            .parse()
+            .expect("crate version is valid semver")
+    }
+
+    /// Whether formatting with `other` produces byte-identical output to `self`.
+    pub fn produces_same_output_as(&self, other: &OutputVersion) -> bool {
+        if self.major == 0 {
+            other.major == 0 && self.minor == other.minor
+        } else {
+            self.major == other.major
+        }
+    }
+}
+
+impl FromStr for OutputVersion {
+    type Err = anyhow::Error;
+
+    /// Accepts full versions (`0.3.1`), release lines (`0.3`), and an optional
+    /// leading `v`, since people copy these from tags as often as from Cargo.
+    fn from_str(s: &str) -> Result<Self> {
+        let trimmed = s.trim().trim_start_matches('v');
+        let mut parts = trimmed.split('.');
+
+        let mut next = |name: &str| -> Result<Option<u64>> {
+            parts
+                .next()
+                .map(|part| {
+                    // Ignore pre-release/build suffixes like `1.2.0-beta.1`
+                    let digits = part.split(['-', '+']).next().unwrap_or(part);
+                    digits
+                        .parse()
+                        .with_context(|| format!("Invalid {name} version in '{s}'"))
+                })
+                .transpose()
+        };
+
+        let Some(major) = next("major")? else {
+            bail!("Invalid krokfmt version '{s}'");
+        };
+        let minor = next("minor")?.unwrap_or(0);
+        next("patch")?;
+
+        Ok(Self { major, minor })
+    }
+}
+
+impl TryFrom<String> for OutputVersion {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> Result<Self> {
+        value.parse()
+    }
+}
+
+impl fmt::Display for OutputVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn version(s: &str) -> OutputVersion {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parses_common_version_spellings() {
+        assert_eq!(version("0.3.1"), version("0.3"));
+        assert_eq!(version("v0.3.1"), version("0.3"));
+        assert_eq!(version("1.2.0-beta.1"), version("1.2"));
+        assert_eq!(version("2").to_string(), "2.0");
+        assert!("".parse::<OutputVersion>().is_err());
+        assert!("0.x".parse::<OutputVersion>().is_err());
+    }
+
+    #[test]
+    fn test_output_stability_contract() {
+        // Patch releases never change output
+        assert!(version("0.3.1").produces_same_output_as(&version("0.3.9")));
+        // On 0.x, every minor release may
+        assert!(!version("0.3").produces_same_output_as(&version("0.4")));
+        // From 1.0 on, only major releases may
+        assert!(version("1.2").produces_same_output_as(&version("1.5")));
+        assert!(!version("1.2").produces_same_output_as(&version("2.0")));
+        assert!(!version("0.9").produces_same_output_as(&version("1.0")));
+    }
+
+    #[test]
+    fn test_current_version_is_valid() {
+        let current = OutputVersion::current();
+        assert!(current.produces_same_output_as(&current));
+    }
+}
@@ -1,3 +1,4 @@
+use krokfmt_core::diff::Hunk;
 use serde::{Deserialize, Serialize};
 use wasm_bindgen::prelude::*;
 
@@ -12,6 +13,15 @@ pub struct FormatResult {
     pub error: Option<String>,
 }
 
+#[derive(Serialize)]
+pub struct CheckResult {
+    pub success: bool,
+    /// Whether formatting would change anything
+    pub changed: bool,
+    pub hunks: Vec<Hunk>,
+    pub error: Option<String>,
+}
+
 #[wasm_bindgen]
 pub fn init_panic_hook() {
     console_error_panic_hook::set_once();
@@ -22,7 +32,7 @@ pub fn format_typescript(code: &str) -> String {
     init_panic_hook();
 
     // Use krokfmt to format the TypeScript code
-    let result = match krokfmt::format_typescript(code, "playground.ts") {
+    let result = match krokfmt_core::format_typescript(code, "playground.ts") {
         Ok(formatted) => FormatResult {
             success: true,
             formatted: Some(formatted),
@@ -45,6 +55,30 @@ pub fn format_typescript(code: &str) -> String {
     })
 }
 
+/// Report what formatting would change without producing the formatted code,
+/// so the playground can annotate the user's text instead of replacing it.
+#[wasm_bindgen]
+pub fn check_typescript(code: &str) -> Result<JsValue, JsValue> {
+    init_panic_hook();
+
+    let result = match krokfmt_core::check_typescript(code, "playground.ts") {
+        Ok(hunks) => CheckResult {
+            success: true,
+            changed: !hunks.is_empty(),
+            hunks,
+            error: None,
+        },
+        Err(err) => CheckResult {
+            success: false,
+            changed: false,
+            hunks: Vec::new(),
+            error: Some(format!("{err}")),
+        },
+    };
+
+    Ok(serde_wasm_bindgen::to_value(&result)?)
+}
+
 #[wasm_bindgen]
 pub fn get_version() -> String {
     env!("CARGO_PKG_VERSION").to_string()
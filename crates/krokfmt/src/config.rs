@@ -0,0 +1,418 @@
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::rules::{RuleGroup, RuleSet};
+use crate::versioning::OutputVersion;
+
+/// Name of the per-project configuration file.
+pub const CONFIG_FILE_NAME: &str = "krokfmt.toml";
+
+/// Every name a configuration file may have, in order of precedence.
+///
+/// `.krokrc.toml` and `.krokrc` hold the same TOML as `krokfmt.toml`, for
+/// projects that keep tool configuration in dotfiles.
+pub const CONFIG_FILE_NAMES: [&str; 3] = [CONFIG_FILE_NAME, INIT_FILE_NAME, ".krokrc"];
+
+/// Name of the config `krokfmt init` writes.
+pub const INIT_FILE_NAME: &str = ".krokrc.toml";
+
+/// Top-level keys accepted in `krokfmt.toml`. Must list every field of [`Config`].
+const KNOWN_KEYS: &[&str] = &[
+    "disable",
+    "exclude",
+    "include",
+    "override",
+    "version",
+    "workspaces",
+];
+
+/// Project configuration read from `krokfmt.toml`.
+///
+/// krokfmt stays opinionated about style, so this isn't a place for formatting
+/// knobs. It records facts about how a project uses krokfmt.
+#[derive(Debug, Default, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    /// krokfmt version whose output the project is formatted with. `--frozen`
+    /// refuses to run when the installed version would format differently.
+    pub version: Option<OutputVersion>,
+
+    /// Globs relative to the config of the files krokfmt formats; everything
+    /// when empty. Lets a project declare its scope once instead of on every
+    /// command line.
+    #[serde(default)]
+    pub include: Vec<String>,
+
+    /// Globs relative to the config of files left alone even when included.
+    #[serde(default)]
+    pub exclude: Vec<String>,
+
+    /// Rule groups switched off for the whole project, for codebases where an
+    /// opinion doesn't fit at all rather than just in some folders.
+    #[serde(default)]
+    pub disable: Vec<RuleGroup>,
+
+    /// Rule groups switched off for parts of the project, so legacy folders can
+    /// adopt krokfmt gradually instead of in one repo-wide reformat.
+    #[serde(default, rename = "override")]
+    pub overrides: Vec<Override>,
+
+    /// Package names of the monorepo's own packages, or prefixes like
+    /// `@myorg/*`, whose imports get a group of their own. For workspaces
+    /// krokfmt can't find in package.json, pnpm-workspace.yaml or tsconfig
+    /// paths.
+    #[serde(default)]
+    pub workspaces: Vec<String>,
+}
+
+/// An `[[override]]` section: rule groups to disable for matching files.
+///
+/// ```toml
+/// [[override]]
+/// files = ["legacy/**"]
+/// disable = ["class-members", "declaration-order"]
+/// ```
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Override {
+    /// Globs relative to the directory containing krokfmt.toml. A plain
+    /// directory path like `legacy` matches everything below it.
+    pub files: Vec<String>,
+    pub disable: Vec<RuleGroup>,
+}
+
+impl Override {
+    fn matches(&self, relative: &Path) -> bool {
+        matches_any(&self.files, relative)
+    }
+}
+
+/// Whether any of `patterns` matches `relative`, either as a glob or as a
+/// directory it's in.
+fn matches_any(patterns: &[String], relative: &Path) -> bool {
+    patterns.iter().any(|pattern| {
+        relative.starts_with(pattern)
+            || glob::Pattern::new(pattern).is_ok_and(|glob| glob.matches_path(relative))
+    })
+}
+
+impl Config {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read config: {}", path.display()))?;
+
+        Self::parse(&content).with_context(|| format!("Invalid config: {}", path.display()))
+    }
+
+    /// Parse and validate the contents of a `krokfmt.toml`.
+    ///
+    /// A typo'd key must never be silently ignored: whoever wrote it believes
+    /// krokfmt is honoring it. Unknown keys are reported with their line and the
+    /// closest known key; syntax and type errors come with toml's line and column.
+    pub fn parse(content: &str) -> Result<Self> {
+        let table: toml::Table = content.parse()?;
+
+        let unknown: Vec<_> = table
+            .keys()
+            .filter(|key| !KNOWN_KEYS.contains(&key.as_str()))
+            .map(|key| describe_unknown_key(content, key))
+            .collect();
+        if !unknown.is_empty() {
+            bail!("{}", unknown.join("\n"));
+        }
+
+        let config: Self = toml::from_str(content)?;
+        for pattern in config.overrides.iter().flat_map(|o| &o.files) {
+            glob::Pattern::new(pattern)
+                .with_context(|| format!("invalid glob `{pattern}` in [[override]]"))?;
+        }
+        for (key, patterns) in [("include", &config.include), ("exclude", &config.exclude)] {
+            for pattern in patterns {
+                glob::Pattern::new(pattern)
+                    .with_context(|| format!("invalid glob `{pattern}` in {key}"))?;
+            }
+        }
+
+        Ok(config)
+    }
+
+    /// Rule groups in effect for `file`, given the project `root` this config
+    /// belongs to. Project-wide `disable` and every matching override apply, so
+    /// they compose.
+    pub fn rules_for(&self, root: &Path, file: &Path) -> RuleSet {
+        let relative = file.strip_prefix(root).unwrap_or(file);
+
+        self.overrides
+            .iter()
+            .filter(|o| o.matches(relative))
+            .flat_map(|o| &o.disable)
+            .chain(&self.disable)
+            .fold(RuleSet::all(), |rules, group| rules.disable(*group))
+    }
+
+    /// Whether `file` is in the project's scope, given the directory `root`
+    /// the globs are relative to: matched by `include` (or there's none) and
+    /// not by `exclude`.
+    pub fn includes(&self, root: &Path, file: &Path) -> bool {
+        let relative = file.strip_prefix(root).unwrap_or(file);
+        (self.include.is_empty() || matches_any(&self.include, relative))
+            && !matches_any(&self.exclude, relative)
+    }
+
+    /// A commented starter config pinned to this krokfmt's version, as
+    /// `krokfmt init` writes it.
+    pub fn starter() -> String {
+        format!(
+            r#"# krokfmt configuration. Style isn't configurable; this file records
+# which files krokfmt owns and which version formats them.
+
+# The krokfmt version the project is formatted with. `krokfmt --frozen`
+# refuses to run when the installed version would format differently.
+version = "{version}"
+
+# Files krokfmt formats, as globs relative to this file. Empty means all.
+include = ["**/*"]
+
+# Files krokfmt leaves alone, even when included.
+exclude = ["dist/**", "build/**", "coverage/**", "**/*.d.ts"]
+
+# Rule groups to switch off for the whole project, such as "enum-members".
+disable = []
+"#,
+            version = OutputVersion::current()
+        )
+    }
+
+    /// Find the config that applies to `dir`: the nearest `krokfmt.toml`,
+    /// `.krokrc.toml` or `.krokrc` in it or any of its ancestors.
+    pub fn find(dir: &Path) -> Option<PathBuf> {
+        dir.ancestors().find_map(Self::in_dir)
+    }
+
+    /// The config file directly in `dir`, in the order of [`CONFIG_FILE_NAMES`].
+    pub fn in_dir(dir: &Path) -> Option<PathBuf> {
+        CONFIG_FILE_NAMES
+            .iter()
+            .map(|name| dir.join(name))
+            .find(|path| path.is_file())
+    }
+}
+
+fn describe_unknown_key(content: &str, key: &str) -> String {
+    let mut message = format!("unknown key `{key}`");
+
+    // `toml::Table` drops spans, so find the line by hand, either a `key = ...`
+    // assignment or a `[key]`/`[[key]]` header. A miss only costs the line number.
+    let line = content.lines().position(|line| {
+        let rest = line.trim_start().trim_start_matches(['[', '"', '\'']);
+        rest.strip_prefix(key).is_some_and(|after| {
+            after
+                .trim_start_matches(['"', '\''])
+                .trim_start()
+                .starts_with(['=', '.', ']'])
+        })
+    });
+    if let Some(line) = line {
+        message.push_str(&format!(" at line {}", line + 1));
+    }
+
+    match closest_key(key) {
+        Some(suggestion) => message.push_str(&format!(" (did you mean `{suggestion}`?)")),
+        None => message.push_str(&format!(" (expected one of: {})", KNOWN_KEYS.join(", "))),
+    }
+
+    message
+}
+
+/// The known key most similar to `key`, if any is close enough to be a typo.
+fn closest_key(key: &str) -> Option<&'static str> {
+    let key = key.to_lowercase();
+    KNOWN_KEYS
+        .iter()
+        .map(|known| (levenshtein(&key, known), *known))
+        .filter(|(distance, known)| *distance <= (known.len() / 3).max(1))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, known)| known)
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_load_version() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(CONFIG_FILE_NAME);
+        fs::write(&path, "version = \"0.1.4\"\n").unwrap();
+
+        let config = Config::load(&path).unwrap();
+        assert_eq!(config.version, Some("0.1".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_rejects_unknown_keys() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(CONFIG_FILE_NAME);
+        fs::write(&path, "indent = 4\n").unwrap();
+
+        assert!(Config::load(&path).is_err());
+    }
+
+    #[test]
+    fn test_suggests_closest_key() {
+        let err = Config::parse("\nverison = \"0.1\"\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unknown key `verison` at line 2 (did you mean `version`?)"
+        );
+
+        let err = Config::parse("indent = 4\n").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "unknown key `indent` at line 1 (expected one of: disable, exclude, include, override, version, workspaces)"
+        );
+    }
+
+    #[test]
+    fn test_reports_type_mismatch_line() {
+        let err = Config::parse("\nversion = 3\n").unwrap_err();
+        assert!(err.to_string().contains("line 2"), "{err}");
+    }
+
+    #[test]
+    fn test_find_walks_up() {
+        let temp_dir = TempDir::new().unwrap();
+        let nested = temp_dir.path().join("src/components");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(temp_dir.path().join(CONFIG_FILE_NAME), "").unwrap();
+
+        assert_eq!(
+            Config::find(&nested),
+            Some(temp_dir.path().join(CONFIG_FILE_NAME))
+        );
+    }
+
+    #[test]
+    fn test_find_krokrc() {
+        let temp_dir = TempDir::new().unwrap();
+        let nested = temp_dir.path().join("src");
+        fs::create_dir_all(&nested).unwrap();
+        fs::write(temp_dir.path().join(".krokrc"), "").unwrap();
+        assert_eq!(Config::find(&nested), Some(temp_dir.path().join(".krokrc")));
+
+        fs::write(temp_dir.path().join(CONFIG_FILE_NAME), "").unwrap();
+        assert_eq!(
+            Config::find(&nested),
+            Some(temp_dir.path().join(CONFIG_FILE_NAME))
+        );
+    }
+
+    #[test]
+    fn test_project_wide_disable() {
+        let config = Config::parse(
+            r#"
+disable = ["type-members"]
+
+[[override]]
+files = ["legacy/**"]
+disable = ["enum-members"]
+"#,
+        )
+        .unwrap();
+        let root = Path::new("/repo");
+
+        let rules = config.rules_for(root, Path::new("/repo/src/a.ts"));
+        assert!(!rules.is_enabled(RuleGroup::TypeMembers));
+        assert!(rules.is_enabled(RuleGroup::EnumMembers));
+
+        let rules = config.rules_for(root, Path::new("/repo/legacy/a.ts"));
+        assert!(!rules.is_enabled(RuleGroup::TypeMembers));
+        assert!(!rules.is_enabled(RuleGroup::EnumMembers));
+    }
+
+    #[test]
+    fn test_overrides_compose() {
+        let config = Config::parse(
+            r#"
+[[override]]
+files = ["legacy/**"]
+disable = ["class-members"]
+
+[[override]]
+files = ["legacy/old", "scripts/*.ts"]
+disable = ["declaration-order"]
+"#,
+        )
+        .unwrap();
+        let root = Path::new("/repo");
+
+        let rules = config.rules_for(root, Path::new("/repo/legacy/old/a.ts"));
+        assert!(!rules.is_enabled(RuleGroup::ClassMembers));
+        assert!(!rules.is_enabled(RuleGroup::DeclarationOrder));
+        assert!(rules.is_enabled(RuleGroup::ObjectProperties));
+
+        let rules = config.rules_for(root, Path::new("/repo/legacy/new/a.ts"));
+        assert!(!rules.is_enabled(RuleGroup::ClassMembers));
+        assert!(rules.is_enabled(RuleGroup::DeclarationOrder));
+
+        let rules = config.rules_for(root, Path::new("/repo/scripts/build.ts"));
+        assert!(!rules.is_enabled(RuleGroup::DeclarationOrder));
+
+        assert_eq!(
+            config.rules_for(root, Path::new("/repo/src/a.ts")),
+            RuleSet::all()
+        );
+    }
+
+    #[test]
+    fn test_include_and_exclude() {
+        let config = Config::parse(
+            "include = [\"src\", \"scripts/*.ts\"]\nexclude = [\"src/generated/**\"]\n",
+        )
+        .unwrap();
+        let root = Path::new("/repo");
+
+        assert!(config.includes(root, Path::new("/repo/src/app.ts")));
+        assert!(config.includes(root, Path::new("/repo/scripts/build.ts")));
+        assert!(!config.includes(root, Path::new("/repo/src/generated/api.ts")));
+        assert!(!config.includes(root, Path::new("/repo/test/app.test.ts")));
+        assert!(Config::default().includes(root, Path::new("/repo/test/app.test.ts")));
+    }
+
+    #[test]
+    fn test_starter_config() {
+        let config = Config::parse(&Config::starter()).unwrap();
+        assert_eq!(config.version, Some(OutputVersion::current()));
+
+        let root = Path::new("/repo");
+        assert!(config.includes(root, Path::new("/repo/src/app.ts")));
+        assert!(!config.includes(root, Path::new("/repo/dist/app.ts")));
+        assert!(!config.includes(root, Path::new("/repo/src/types.d.ts")));
+    }
+
+    #[test]
+    fn test_rejects_unknown_rule_group() {
+        let err = Config::parse("[[override]]\nfiles = [\"legacy\"]\ndisable = [\"imports\"]\n")
+            .unwrap_err();
+        assert!(err.to_string().contains("line 3"), "{err}");
+    }
+}
@@ -0,0 +1,200 @@
+//! Check-mode diffs of whole files, as unified diffs or JSON.
+//!
+//! The hunks themselves come from `krokfmt_core::diff`, re-exported here so
+//! `krokfmt::diff` still names everything it did before this module existed.
+
+use colored::Colorize;
+use serde::Serialize;
+use std::fmt::Write;
+use std::path::Path;
+
+pub use krokfmt_core::diff::*;
+
+use crate::file_handler::display_path;
+
+/// Output format of `--check --diff`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DiffFormat {
+    /// Unified diffs, as `diff -u` prints them and `git apply` reads them
+    #[default]
+    Text,
+    /// One object per changed file, for CI annotations and review bots
+    Json,
+}
+
+impl std::str::FromStr for DiffFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(DiffFormat::Text),
+            "json" => Ok(DiffFormat::Json),
+            other => Err(format!(
+                "unknown diff format '{other}' (expected 'text' or 'json')"
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for DiffFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DiffFormat::Text => write!(f, "text"),
+            DiffFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+/// What formatting would change in one file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileDiff {
+    /// The file as it's shown everywhere else in krokfmt's output
+    pub file: String,
+    /// Line ranges of every change, with the changed lines
+    pub hunks: Vec<Hunk>,
+    /// The same changes as a unified diff
+    pub patch: String,
+}
+
+impl FileDiff {
+    pub fn new(path: &Path, original: &str, formatted: &str) -> Self {
+        let file = display_path(path).replace('\\', "/");
+        let hunks = diff_hunks(original, formatted, DEFAULT_CONTEXT_LINES);
+        let patch = unified_diff(&file, original, formatted, &hunks);
+        Self { file, hunks, patch }
+    }
+}
+
+/// Render `hunks`, the diff of `original` against `formatted`, as a unified
+/// diff of `file`; empty if there are none.
+///
+/// The `a/` and `b/` prefixes and the missing-newline markers make the output
+/// something `git apply` accepts, not just something to read.
+pub fn unified_diff(file: &str, original: &str, formatted: &str, hunks: &[Hunk]) -> String {
+    if hunks.is_empty() {
+        return String::new();
+    }
+
+    // The last line of a side without a final newline gets a marker
+    let open_end =
+        |text: &str| (!text.is_empty() && !text.ends_with('\n')).then(|| text.lines().count());
+    let (old_open_end, new_open_end) = (open_end(original), open_end(formatted));
+    const NO_NEWLINE: &str = "\\ No newline at end of file\n";
+
+    let mut out = format!("--- a/{file}\n+++ b/{file}\n");
+    for hunk in hunks {
+        let _ = writeln!(
+            out,
+            "@@ -{} +{} @@",
+            range(hunk.old_start, hunk.old_lines),
+            range(hunk.new_start, hunk.new_lines)
+        );
+
+        let (mut old_line, mut new_line) = (hunk.old_start - 1, hunk.new_start - 1);
+        for line in &hunk.lines {
+            let (prefix, text, old_end, new_end) = match line {
+                DiffLine::Context(text) => {
+                    old_line += 1;
+                    new_line += 1;
+                    (' ', text, Some(old_line), Some(new_line))
+                }
+                DiffLine::Removed(text) => {
+                    old_line += 1;
+                    ('-', text, Some(old_line), None)
+                }
+                DiffLine::Added(text) => {
+                    new_line += 1;
+                    ('+', text, None, Some(new_line))
+                }
+            };
+            let _ = writeln!(out, "{prefix}{text}");
+            if (old_end.is_some() && old_end == old_open_end)
+                || (new_end.is_some() && new_end == new_open_end)
+            {
+                out.push_str(NO_NEWLINE);
+            }
+        }
+    }
+
+    out
+}
+
+/// Color a unified diff for a terminal: removed lines red, added lines green,
+/// hunk headers cyan.
+///
+/// `colored` leaves the text alone when stdout isn't a TTY or `NO_COLOR` is
+/// set, so what's piped into `git apply` or a file stays a plain patch.
+pub fn colorize_patch(patch: &str) -> String {
+    let mut out = String::with_capacity(patch.len());
+    for line in patch.split_inclusive('\n') {
+        let (text, newline) = match line.strip_suffix('\n') {
+            Some(text) => (text, "\n"),
+            None => (line, ""),
+        };
+        let colored = if text.starts_with("--- ") || text.starts_with("+++ ") {
+            text.bold()
+        } else if text.starts_with("@@") {
+            text.cyan()
+        } else if text.starts_with('-') {
+            text.red()
+        } else if text.starts_with('+') {
+            text.green()
+        } else {
+            text.normal()
+        };
+        let _ = write!(out, "{colored}{newline}");
+    }
+    out
+}
+
+/// A hunk range: an empty one names the line before it, as `diff -u` does.
+fn range(start: usize, lines: usize) -> String {
+    if lines == 0 {
+        format!("{},0", start - 1)
+    } else {
+        format!("{start},{lines}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_unified_diff() {
+        let original = "const  a = 1;\nconst b = 2;\n\nlet c;";
+        let formatted = "const a = 1;\nconst b = 2;\n\nlet c;\n";
+        let diff = FileDiff::new(&PathBuf::from("src/app.ts"), original, formatted);
+
+        assert_eq!(
+            diff.patch,
+            "--- a/src/app.ts\n+++ b/src/app.ts\n@@ -1,4 +1,4 @@\n-const  a = 1;\n+const a = 1;\n const b = 2;\n \n-let c;\n\\ No newline at end of file\n+let c;\n"
+        );
+        assert_eq!(diff.hunks.len(), 1);
+
+        let json = serde_json::to_value(&diff).unwrap();
+        assert_eq!(json["file"], "src/app.ts");
+        assert_eq!(json["hunks"][0]["old_start"], 1);
+        assert_eq!(json["hunks"][0]["new_lines"], 4);
+    }
+
+    #[test]
+    fn test_colorize_patch() {
+        colored::control::set_override(true);
+        let patch = "--- a/a.ts\n+++ b/a.ts\n@@ -1 +1 @@\n-let  a;\n+let a;\n";
+        let colorized = colorize_patch(patch);
+
+        assert!(colorized.contains(&format!("{}\n", "-let  a;".red())));
+        assert!(colorized.contains(&format!("{}\n", "+let a;".green())));
+        assert!(colorized.contains(&format!("{}\n", "@@ -1 +1 @@".cyan())));
+        assert!(colorized.contains(&format!("{}\n", "+++ b/a.ts".bold())));
+    }
+
+    #[test]
+    fn test_unchanged_files_have_no_patch() {
+        let diff = FileDiff::new(&PathBuf::from("a.ts"), "let a;\n", "let a;\n");
+        assert!(diff.hunks.is_empty());
+        assert_eq!(diff.patch, "");
+    }
+}
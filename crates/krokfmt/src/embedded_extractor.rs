@@ -0,0 +1,307 @@
+//! The `<script>` blocks of Vue and Svelte single-file components.
+//!
+//! Only the code in a component's scripts is formatted. The template and
+//! styles around them are kept byte for byte: a `<script>` block is cut out,
+//! formatted as a file of its own language, and spliced back in its place.
+
+use anyhow::{Context, Result};
+use std::ops::Range;
+
+/// The language a `<script>` block is written in, from its `lang` attribute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptLang {
+    JavaScript,
+    Jsx,
+    TypeScript,
+    Tsx,
+}
+
+impl ScriptLang {
+    /// The extension of a file in this language, which picks the syntax the
+    /// block is parsed with.
+    pub fn extension(self) -> &'static str {
+        match self {
+            ScriptLang::JavaScript => "js",
+            ScriptLang::Jsx => "jsx",
+            ScriptLang::TypeScript => "ts",
+            ScriptLang::Tsx => "tsx",
+        }
+    }
+
+    /// The language of a script with these attributes, or `None` for scripts
+    /// that aren't code krokfmt formats (`lang="coffee"`, JSON-LD and the
+    /// like).
+    fn from_attributes(attributes: &str) -> Option<Self> {
+        if let Some(kind) = attribute(attributes, "type") {
+            if !matches!(
+                kind.to_ascii_lowercase().as_str(),
+                "module" | "text/javascript" | "application/javascript" | "text/typescript"
+            ) {
+                return None;
+            }
+        }
+
+        match attribute(attributes, "lang").map(str::to_ascii_lowercase) {
+            None => Some(ScriptLang::JavaScript),
+            Some(lang) => match lang.as_str() {
+                "js" | "javascript" => Some(ScriptLang::JavaScript),
+                "jsx" => Some(ScriptLang::Jsx),
+                "ts" | "typescript" => Some(ScriptLang::TypeScript),
+                "tsx" => Some(ScriptLang::Tsx),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// A `<script>` block with code in it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScriptBlock {
+    /// Byte range of the code between the opening and closing tags
+    pub range: Range<usize>,
+    pub lang: ScriptLang,
+}
+
+/// The code of one `<script>` block, as it's handed over to be formatted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Script<'a> {
+    /// The code, without the indentation the block as a whole has
+    pub code: &'a str,
+    /// The component's filename with the extension of the block's language
+    /// added (`App.vue.ts`)
+    pub filename: &'a str,
+    /// 1-based line of the component the code starts on, to report lines in
+    /// the code by
+    pub first_line: usize,
+}
+
+/// Every `<script>` block of a component holding code krokfmt formats, in
+/// order.
+///
+/// Vue puts `<script>` and `<script setup>` side by side, and Svelte has a
+/// module script next to the instance one, so there can be several. Empty
+/// blocks, like `<script src="...">`, and commented-out ones are left out.
+pub fn script_blocks(source: &str) -> Vec<ScriptBlock> {
+    let mut blocks = Vec::new();
+    let mut pos = 0;
+
+    while let Some(offset) = source[pos..].find('<') {
+        let start = pos + offset;
+        let rest = &source[start..];
+
+        if rest.starts_with("<!--") {
+            pos = rest.find("-->").map_or(source.len(), |end| start + end + 3);
+            continue;
+        }
+        let is_script_tag = rest
+            .strip_prefix("<script")
+            .and_then(|after| after.chars().next())
+            .is_some_and(|next| next == '>' || next.is_whitespace());
+        if !is_script_tag {
+            pos = start + 1;
+            continue;
+        }
+
+        let Some(tag_end) = rest.find('>') else {
+            break;
+        };
+        let attributes = &rest["<script".len()..tag_end];
+        let content_start = start + tag_end + 1;
+        if attributes.ends_with('/') {
+            pos = content_start;
+            continue;
+        }
+        let Some(content_len) = source[content_start..].find("</script") else {
+            break;
+        };
+        let range = content_start..content_start + content_len;
+
+        if let Some(lang) = ScriptLang::from_attributes(attributes) {
+            if !source[range.clone()].trim().is_empty() {
+                blocks.push(ScriptBlock {
+                    range: range.clone(),
+                    lang,
+                });
+            }
+        }
+        pos = range.end;
+    }
+
+    blocks
+}
+
+/// Format the scripts of the component `filename` with `format`, leaving
+/// everything outside them untouched.
+///
+/// The result of `format` is indented as the block was and put on its own
+/// lines between the tags.
+pub fn format_script_blocks(
+    source: &str,
+    filename: &str,
+    mut format: impl FnMut(Script<'_>) -> Result<String>,
+) -> Result<String> {
+    let mut out = String::with_capacity(source.len());
+    let mut last = 0;
+
+    for block in script_blocks(source) {
+        let line = source[..block.range.start].matches('\n').count() + 1;
+        let script_filename = format!("{filename}.{}", block.lang.extension());
+        let content = &source[block.range.clone()];
+        let formatted = format_script(content, &script_filename, line, &mut format)
+            .with_context(|| format!("Failed to format the <script> block on line {line}"))?;
+
+        out.push_str(&source[last..block.range.start]);
+        out.push_str(&formatted);
+        last = block.range.end;
+    }
+
+    out.push_str(&source[last..]);
+    Ok(out)
+}
+
+/// Format the code of one block, starting on `line`, keeping its indentation
+/// and that of its closing tag.
+fn format_script(
+    content: &str,
+    filename: &str,
+    line: usize,
+    format: &mut impl FnMut(Script<'_>) -> Result<String>,
+) -> Result<String> {
+    // Whatever follows the last line break is the closing tag's indentation
+    let closing_indent = match content.rfind('\n') {
+        Some(newline) if content[newline + 1..].trim().is_empty() => &content[newline + 1..],
+        _ => "",
+    };
+
+    let lines: Vec<&str> = content.trim_end().lines().collect();
+    let indent = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| &line[..line.len() - line.trim_start_matches([' ', '\t']).len()])
+        .min_by_key(|indent| indent.len())
+        .unwrap_or("");
+
+    let blank_lines = lines
+        .iter()
+        .take_while(|line| line.trim().is_empty())
+        .count();
+    let mut code = String::with_capacity(content.len());
+    for line in &lines[blank_lines..] {
+        code.push_str(line.strip_prefix(indent).unwrap_or(line.trim_start()));
+        code.push('\n');
+    }
+
+    let formatted = format(Script {
+        code: &code,
+        filename,
+        first_line: line + blank_lines,
+    })?;
+
+    let mut out = String::with_capacity(formatted.len() + content.len());
+    out.push('\n');
+    for line in formatted.lines() {
+        if !line.is_empty() {
+            out.push_str(indent);
+        }
+        out.push_str(line);
+        out.push('\n');
+    }
+    out.push_str(closing_indent);
+    Ok(out)
+}
+
+/// The value of the attribute `name` among a tag's `attributes`: empty for
+/// one without a value, `None` without the attribute.
+fn attribute<'a>(attributes: &'a str, name: &str) -> Option<&'a str> {
+    let mut rest = attributes.trim_start();
+
+    while !rest.is_empty() {
+        let name_end = rest
+            .find(|c: char| c.is_whitespace() || c == '=')
+            .unwrap_or(rest.len());
+        let attribute_name = &rest[..name_end];
+        rest = rest[name_end..].trim_start();
+
+        let mut value = "";
+        if let Some(after) = rest.strip_prefix('=') {
+            let after = after.trim_start();
+            let (found, remainder) = match after.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let inner = &after[1..];
+                    let end = inner.find(quote).unwrap_or(inner.len());
+                    (&inner[..end], inner.get(end + 1..).unwrap_or(""))
+                }
+                _ => {
+                    let end = after.find(char::is_whitespace).unwrap_or(after.len());
+                    (&after[..end], &after[end..])
+                }
+            };
+            value = found;
+            rest = remainder.trim_start();
+        }
+
+        if attribute_name.eq_ignore_ascii_case(name) {
+            return Some(value);
+        }
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VUE: &str = "<template>\n  <div>{{ count }}</div>\n</template>\n\n<script setup lang=\"ts\">\nimport { z, a } from 'lib';\n</script>\n\n<style scoped>\ndiv { color: red; }\n</style>\n";
+
+    #[test]
+    fn test_script_blocks() {
+        let blocks = script_blocks(VUE);
+        assert_eq!(blocks.len(), 1);
+        assert_eq!(blocks[0].lang, ScriptLang::TypeScript);
+        assert_eq!(
+            &VUE[blocks[0].range.clone()],
+            "\nimport { z, a } from 'lib';\n"
+        );
+
+        let svelte = "<script context=\"module\">\nexport const prerender = true;\n</script>\n<!-- <script lang=\"ts\">old()</script> -->\n<script lang=\"coffee\">\nx = 1\n</script>\n<script src=\"./vendor.js\"></script>\n<script lang='tsx'>\nlet a = 1;\n</script>\n<h1>Hi</h1>\n";
+        let langs: Vec<_> = script_blocks(svelte)
+            .into_iter()
+            .map(|block| block.lang)
+            .collect();
+        assert_eq!(langs, [ScriptLang::JavaScript, ScriptLang::Tsx]);
+    }
+
+    #[test]
+    fn test_format_script_blocks_keeps_the_rest() {
+        let formatted = format_script_blocks(VUE, "App.vue", |script| {
+            assert_eq!(script.filename, "App.vue.ts");
+            assert_eq!(script.first_line, 6);
+            Ok(script.code.replace("z, a", "a, z"))
+        })
+        .unwrap();
+
+        assert_eq!(formatted, VUE.replace("z, a", "a, z"));
+    }
+
+    #[test]
+    fn test_indented_blocks_stay_indented() {
+        let source = "<div>\n  <script>\n    let b = 2;\n\n      let a = 1;\n  </script>\n</div>\n";
+        let formatted = format_script_blocks(source, "App.svelte", |script| {
+            assert_eq!(script.code, "let b = 2;\n\n  let a = 1;\n");
+            Ok("let a = 1;\n\nlet b = 2;\n".to_string())
+        })
+        .unwrap();
+
+        assert_eq!(
+            formatted,
+            "<div>\n  <script>\n    let a = 1;\n\n    let b = 2;\n  </script>\n</div>\n"
+        );
+    }
+
+    #[test]
+    fn test_errors_name_the_block() {
+        let error = format_script_blocks(VUE, "App.vue", |_| anyhow::bail!("bad")).unwrap_err();
+        assert!(format!("{error:#}").contains("line 5"), "{error:#}");
+    }
+}
@@ -1,7 +1,58 @@
 use anyhow::{Context, Result};
-use glob::glob;
+use glob::{glob, MatchOptions, Pattern};
+use std::borrow::Cow;
+use std::collections::HashSet;
 use std::fs;
-use std::path::{Path, PathBuf};
+use std::io::{self, BufWriter, Write};
+use std::path::{Component, Path, PathBuf};
+use std::sync::mpsc::SyncSender;
+use std::sync::{Condvar, Mutex};
+use std::time::SystemTime;
+
+use krokfmt_core::line_ending::to_lf;
+
+use crate::format_cache::FormatCache;
+use crate::ignore_files::IgnoreFiles;
+
+/// Where backups are kept, relative to the directory krokfmt runs in.
+pub const BACKUP_DIR: &str = ".krokfmt/backups";
+
+/// How many backups of each file are kept unless told otherwise.
+pub const DEFAULT_BACKUP_RETENTION: usize = 5;
+
+/// How formatted files are written back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WriteMode {
+    /// Overwrite the file where it is, keeping its inode and hard links
+    #[default]
+    InPlace,
+    /// Write a temporary file next to it, sync that to disk and rename it over
+    /// the original, so a crash leaves either the old or the new content
+    Atomic,
+}
+
+impl std::str::FromStr for WriteMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "in-place" => Ok(WriteMode::InPlace),
+            "atomic" => Ok(WriteMode::Atomic),
+            other => Err(format!(
+                "unknown write mode '{other}' (expected 'in-place' or 'atomic')"
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for WriteMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            WriteMode::InPlace => write!(f, "in-place"),
+            WriteMode::Atomic => write!(f, "atomic"),
+        }
+    }
+}
 
 /// Handles file system operations for the formatter.
 ///
@@ -10,69 +61,301 @@ use std::path::{Path, PathBuf};
 /// seen formatters corrupt files, so we default to safety over speed.
 pub struct FileHandler {
     backup_enabled: bool,
+    backup_dir: PathBuf,
+    backup_retention: usize,
+    write_mode: WriteMode,
+    io_throttle: Option<IoThrottle>,
+    respect_ignore_files: bool,
+    includes: Vec<Pattern>,
+    excludes: Vec<Pattern>,
+    cache: Option<FormatCache>,
 }
 
 impl FileHandler {
     pub fn new(backup_enabled: bool) -> Self {
-        Self { backup_enabled }
+        Self {
+            backup_enabled,
+            backup_dir: PathBuf::from(BACKUP_DIR),
+            backup_retention: DEFAULT_BACKUP_RETENTION,
+            write_mode: WriteMode::default(),
+            io_throttle: None,
+            respect_ignore_files: true,
+            includes: Vec::new(),
+            excludes: Vec::new(),
+            cache: None,
+        }
     }
 
-    /// Find all TypeScript files from the given paths.
+    /// Whether directory walks and glob expansion skip what `.gitignore` and
+    /// `.krokignore` files ignore; on by default.
+    ///
+    /// Build output and vendored code are usually ignored already, and walking
+    /// them is most of the time spent formatting a monorepo. Files named
+    /// explicitly are formatted either way.
+    pub fn with_ignore_files(mut self, respect: bool) -> Self {
+        self.respect_ignore_files = respect;
+        self
+    }
+
+    /// Only keep discovered files matching at least one of `patterns`.
+    pub fn with_includes(mut self, patterns: &[String]) -> Result<Self> {
+        self.includes = compile_patterns(patterns)?;
+        Ok(self)
+    }
+
+    /// Drop discovered files matching any of `patterns`, and skip directories
+    /// matching them without walking them.
+    ///
+    /// The patterns work like `!pattern` paths, for callers that keep their
+    /// exclusions apart from the paths to format.
+    pub fn with_excludes(mut self, patterns: &[String]) -> Result<Self> {
+        self.excludes = compile_patterns(patterns)?;
+        Ok(self)
+    }
+
+    /// Keep backups below `dir` instead of [`BACKUP_DIR`].
+    pub fn with_backup_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.backup_dir = dir.into();
+        self
+    }
+
+    /// Keep only the `retention` most recent backups of each file, deleting
+    /// older ones as new ones are made. Zero keeps every backup.
+    pub fn with_backup_retention(mut self, retention: usize) -> Self {
+        self.backup_retention = retention;
+        self
+    }
+
+    /// Write formatted files back the way `mode` says.
+    pub fn with_write_mode(mut self, mode: WriteMode) -> Self {
+        self.write_mode = mode;
+        self
+    }
+
+    /// Allow at most `max_concurrent` file reads and writes in flight at once.
+    ///
+    /// Formatting is CPU-bound, but on NFS-backed checkouts every worker thread
+    /// hitting the file server at once is what actually slows the run (and
+    /// everyone else on the share) down. Zero means no limit.
+    pub fn with_io_throttle(mut self, max_concurrent: usize) -> Self {
+        self.io_throttle = (max_concurrent > 0).then(|| IoThrottle::new(max_concurrent));
+        self
+    }
+
+    /// Consult `cache` for files already formatted in an earlier run.
+    ///
+    /// Reading a file is cheap next to parsing and printing it, so over a
+    /// large monorepo where few files changed, most of the run is skipped.
+    pub fn with_cache(mut self, cache: FormatCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Whether the cache knows `path`, holding `content`, to be formatted
+    /// with `settings` already. Always false without a cache.
+    pub fn is_cached(&self, path: &Path, content: &str, settings: &str) -> bool {
+        self.cache
+            .as_ref()
+            .is_some_and(|cache| cache.is_formatted(path, content, settings))
+    }
+
+    /// Record in the cache, if there is one, that `path` holding `content` is
+    /// formatted with `settings`.
+    pub fn record_formatted(&self, path: &Path, content: &str, settings: &str) {
+        if let Some(cache) = &self.cache {
+            cache.record_formatted(path, content, settings);
+        }
+    }
+
+    /// Write the cache, if there is one, for the next run.
+    pub fn save_cache(&self) -> Result<()> {
+        self.cache.as_ref().map_or(Ok(()), FormatCache::save)
+    }
+
+    /// Find all TypeScript, JavaScript and component files from the given paths.
     ///
     /// This handles three input types seamlessly:
     /// 1. Direct file paths - format that specific file
-    /// 2. Directories - recursively find all TS files
+    /// 2. Directories - recursively find all TS, JS, Vue and Svelte files
     /// 3. Glob patterns - for shell expansion like src/**/*.ts
     ///
+    /// Globs are expanded here rather than by the shell, so quoting them
+    /// behaves the same in every shell and on Windows, where nothing expands
+    /// them. Braces list alternatives (`src/**/*.{ts,tsx}`), and a pattern
+    /// starting with `!` excludes whatever it matches from the other paths.
+    ///
+    /// Directories and globs skip whatever `.gitignore` and `.krokignore` files
+    /// ignore, unless [`Self::with_ignore_files`] turned that off.
+    ///
     /// This flexibility was important for both CLI usage and editor integration.
     pub fn find_typescript_files(&self, paths: &[PathBuf]) -> Result<Vec<PathBuf>> {
+        self.find_files(paths, Self::is_source_file)
+    }
+
+    /// Find all package.json files from the given paths.
+    ///
+    /// Uses the exact same discovery rules as TypeScript files so that the
+    /// companion formatter never reaches into node_modules or hidden directories.
+    pub fn find_package_json_files(&self, paths: &[PathBuf]) -> Result<Vec<PathBuf>> {
+        self.find_files(paths, Self::is_package_json_file)
+    }
+
+    /// Send the files [`Self::find_typescript_files`] finds to `files` as
+    /// they're found, in the same order.
+    ///
+    /// Walking a large checkout on a slow disk takes long enough that
+    /// formatting shouldn't wait for it to finish: the receiving end can start
+    /// on the first files while the walk goes on. A bounded channel keeps the
+    /// walk from running far ahead. Stops early, without an error, once the
+    /// receiving end hangs up.
+    pub fn send_typescript_files(
+        &self,
+        paths: &[PathBuf],
+        files: &SyncSender<PathBuf>,
+    ) -> Result<()> {
+        self.visit_files(paths, Self::is_source_file, &mut |file| {
+            files.send(file).is_ok()
+        })
+    }
+
+    /// Send the files [`Self::find_package_json_files`] finds to `files` as
+    /// they're found, like [`Self::send_typescript_files`].
+    pub fn send_package_json_files(
+        &self,
+        paths: &[PathBuf],
+        files: &SyncSender<PathBuf>,
+    ) -> Result<()> {
+        self.visit_files(paths, Self::is_package_json_file, &mut |file| {
+            files.send(file).is_ok()
+        })
+    }
+
+    fn find_files(
+        &self,
+        paths: &[PathBuf],
+        is_match: fn(&Self, &Path) -> bool,
+    ) -> Result<Vec<PathBuf>> {
         let mut files = Vec::new();
+        self.visit_files(paths, is_match, &mut |file| {
+            files.push(file);
+            true
+        })?;
+        Ok(files)
+    }
+
+    /// Hand each file under `paths` matching `is_match` to `on_file` as soon
+    /// as it's found, until `on_file` returns false.
+    fn visit_files(
+        &self,
+        paths: &[PathBuf],
+        is_match: fn(&Self, &Path) -> bool,
+        on_file: &mut dyn FnMut(PathBuf) -> bool,
+    ) -> Result<()> {
+        // `!pattern` exclusions apply to every path, including the ones
+        // listed before them, so they're gathered before anything is walked
+        let mut exclusions = self.excludes.clone();
+        for exclusion in paths.iter().filter_map(|path| exclusion_pattern(path)) {
+            for pattern in expand_braces(exclusion) {
+                exclusions.push(
+                    Pattern::new(&pattern)
+                        .with_context(|| format!("Invalid exclusion pattern: !{exclusion}"))?,
+                );
+            }
+        }
+
+        // Overlapping patterns shouldn't format a file twice
+        let mut seen = HashSet::new();
+        let mut found = |file: PathBuf| {
+            if matches_any(&exclusions, &file)
+                || (!self.includes.is_empty() && !matches_any(&self.includes, &file))
+                || !seen.insert(file.clone())
+            {
+                return true;
+            }
+            on_file(file)
+        };
+        let mut ignore_files = IgnoreFiles::default();
 
         for path in paths {
-            if path.is_file() {
-                if self.is_typescript_file(path) {
-                    files.push(path.clone());
-                }
-            } else if path.is_dir() {
-                self.find_ts_files_in_dir(path, &mut files)?;
+            let more = if exclusion_pattern(path).is_some() {
+                true
+            } else if io_path(path).is_file() {
+                !is_match(self, path) || found(path.clone())
+            } else if io_path(path).is_dir() {
+                let root = IgnoreFiles::walk_root(path);
+                self.visit_dir(&root, path, is_match, &mut ignore_files, &mut found)?
             } else {
                 // Treat as glob pattern
                 let pattern = path.to_str().context("Invalid path")?;
-                for entry in glob(pattern).context("Failed to read glob pattern")? {
-                    let file = entry.context("Failed to process glob entry")?;
-                    if self.is_typescript_file(&file) {
-                        files.push(file);
+                let mut more = true;
+                'patterns: for pattern in expand_braces(pattern) {
+                    for entry in glob(&pattern).context("Failed to read glob pattern")? {
+                        let file = entry.context("Failed to process glob entry")?;
+                        if is_match(self, &file)
+                            && !(self.respect_ignore_files && ignore_files.is_ignored(&file, false))
+                            && !found(file)
+                        {
+                            more = false;
+                            break 'patterns;
+                        }
                     }
                 }
+                more
+            };
+            if !more {
+                break;
             }
         }
 
-        Ok(files)
+        Ok(())
     }
 
-    fn find_ts_files_in_dir(&self, dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
-        for entry in fs::read_dir(dir).context("Failed to read directory")? {
+    /// Walk `dir`, handing matching files to `found`; false once `found` asked
+    /// to stop.
+    fn visit_dir(
+        &self,
+        root: &Path,
+        dir: &Path,
+        is_match: fn(&Self, &Path) -> bool,
+        ignore_files: &mut IgnoreFiles,
+        found: &mut dyn FnMut(PathBuf) -> bool,
+    ) -> Result<bool> {
+        for entry in fs::read_dir(io_path(dir))
+            .with_context(|| format!("Failed to read directory: {}", display_path(dir)))?
+        {
             let entry = entry.context("Failed to read directory entry")?;
-            let path = entry.path();
+            // Joined onto `dir` rather than taken from the entry, which would
+            // carry the extended-length prefix into every path we report
+            let path = dir.join(entry.file_name());
+            let is_dir = io_path(&path).is_dir();
+            if self.respect_ignore_files && ignore_files.is_entry_ignored(root, dir, &path, is_dir)
+            {
+                continue;
+            }
 
-            if path.is_dir() {
-                // Skip node_modules and hidden directories. This hardcoded exclusion
-                // prevents accidentally formatting dependencies and build artifacts.
-                // We chose not to make this configurable to keep the tool simple.
+            if is_dir {
                 if let Some(name) = path.file_name() {
-                    let name_str = name.to_string_lossy();
-                    if name_str != "node_modules" && !name_str.starts_with('.') {
-                        self.find_ts_files_in_dir(&path, files)?;
+                    if !is_skipped_dir_name(&name.to_string_lossy())
+                        && !matches_any(&self.excludes, &path)
+                        && !self.visit_dir(root, &path, is_match, ignore_files, found)?
+                    {
+                        return Ok(false);
                     }
                 }
-            } else if self.is_typescript_file(&path) {
-                files.push(path);
+            } else if is_match(self, &path) && !found(path) {
+                return Ok(false);
             }
         }
-        Ok(())
+        Ok(true)
     }
 
-    fn is_typescript_file(&self, path: &Path) -> bool {
+    pub fn is_package_json_file(&self, path: &Path) -> bool {
+        path.file_name()
+            .map(|name| name == "package.json")
+            .unwrap_or(false)
+    }
+
+    pub fn is_typescript_file(&self, path: &Path) -> bool {
         // Support all TypeScript file extensions including the newer module variants
         // (.mts for ESM, .cts for CommonJS) introduced in TypeScript 4.5.
         path.extension()
@@ -81,39 +364,535 @@ impl FileHandler {
             .unwrap_or(false)
     }
 
+    pub fn is_javascript_file(&self, path: &Path) -> bool {
+        // The module variants too, so .mjs/.cjs configs and scripts in a mixed
+        // codebase are formatted alongside the TypeScript around them
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| matches!(ext, "js" | "jsx" | "mjs" | "cjs"))
+            .unwrap_or(false)
+    }
+
+    /// Whether `path` is a Vue or Svelte component, formatted a `<script>`
+    /// block at a time.
+    pub fn is_component_file(&self, path: &Path) -> bool {
+        path.extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| matches!(ext, "vue" | "svelte"))
+            .unwrap_or(false)
+    }
+
+    /// Whether `path` is a file krokfmt formats: TypeScript, JavaScript, or a
+    /// component with scripts in either.
+    pub fn is_source_file(&self, path: &Path) -> bool {
+        self.is_typescript_file(path)
+            || self.is_javascript_file(path)
+            || self.is_component_file(path)
+    }
+
     pub fn read_file(&self, path: &Path) -> Result<String> {
-        let content = fs::read_to_string(path)
-            .with_context(|| format!("Failed to read file: {}", path.display()))?;
+        let content = self.read_file_raw(path)?;
 
         // Normalize line endings to LF for consistent processing across platforms
         // This prevents issues with CRLF on Windows affecting comment position calculations
-        Ok(content.replace("\r\n", "\n").replace('\r', "\n"))
+        Ok(to_lf(&content).into_owned())
+    }
+
+    /// Read a file as it is, line breaks and all, to tell which ones its
+    /// formatted output should keep.
+    pub fn read_file_raw(&self, path: &Path) -> Result<String> {
+        let _permit = self.io_throttle.as_ref().map(IoThrottle::acquire);
+        fs::read_to_string(io_path(path))
+            .with_context(|| format!("Failed to read file: {}", display_path(path)))
     }
 
     pub fn write_file(&self, path: &Path, content: &str) -> Result<()> {
+        let _permit = self.io_throttle.as_ref().map(IoThrottle::acquire);
+
         // Backup first, write second. This ordering ensures we never lose the original
         // file if the write fails. The slight performance cost is worth the safety.
         if self.backup_enabled {
             self.create_backup(path)?;
         }
 
-        fs::write(path, content)
-            .with_context(|| format!("Failed to write file: {}", path.display()))
+        match self.write_mode {
+            WriteMode::InPlace => fs::write(io_path(path), content).map_err(anyhow::Error::from),
+            WriteMode::Atomic => write_atomically(path, content.as_bytes()),
+        }
+        .with_context(|| format!("Failed to write file: {}", display_path(path)))
+    }
+
+    /// Replace a file with what `write` streams out, without holding the new
+    /// content in memory.
+    ///
+    /// The output goes to a temporary file next to `path`, which replaces it
+    /// only once `write` has finished and reported a change; otherwise the
+    /// original is left untouched. Either way the file is replaced atomically,
+    /// as [`WriteMode::Atomic`] does. Returns what `write` reported.
+    pub fn write_file_streaming(
+        &self,
+        path: &Path,
+        write: impl FnOnce(&mut dyn Write) -> Result<bool>,
+    ) -> Result<bool> {
+        let _permit = self.io_throttle.as_ref().map(IoThrottle::acquire);
+
+        let target = resolve_symlink(path)?;
+        let temp_path = temp_path(&target);
+
+        let streamed = fs::File::create(io_path(&temp_path))
+            .map_err(anyhow::Error::from)
+            .and_then(|file| {
+                let mut out = BufWriter::new(file);
+                let changed = write(&mut out)?;
+                out.flush()?;
+                if changed {
+                    out.get_ref().sync_all()?;
+                }
+                Ok(changed)
+            })
+            .with_context(|| format!("Failed to write file: {}", display_path(path)));
+
+        let changed = match streamed {
+            Ok(changed) => changed,
+            Err(err) => {
+                let _ = fs::remove_file(io_path(&temp_path));
+                return Err(err);
+            }
+        };
+        if !changed {
+            fs::remove_file(io_path(&temp_path)).with_context(|| {
+                format!(
+                    "Failed to remove temporary file: {}",
+                    display_path(&temp_path)
+                )
+            })?;
+            return Ok(false);
+        }
+
+        if self.backup_enabled {
+            if let Err(err) = self.create_backup(path) {
+                let _ = fs::remove_file(io_path(&temp_path));
+                return Err(err);
+            }
+        }
+        replace_with(&temp_path, &target)
+            .with_context(|| format!("Failed to write file: {}", display_path(path)))?;
+
+        Ok(true)
+    }
+
+    /// The backups of `path`, oldest first.
+    pub fn backups(&self, path: &Path) -> Result<Vec<PathBuf>> {
+        let (dir, file_name) = self.backup_location(path);
+        let entries = match fs::read_dir(io_path(&dir)) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => {
+                return Err(err)
+                    .with_context(|| format!("Failed to read backups: {}", display_path(&dir)))
+            }
+        };
+
+        let mut backups: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|backup| {
+                backup
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .and_then(|name| name.strip_prefix(file_name.as_str()))
+                    .and_then(|rest| rest.strip_prefix('.'))
+                    .and_then(|rest| rest.strip_suffix(".bak"))
+                    .is_some_and(|stamp| {
+                        stamp.len() == STAMP_WIDTH && stamp.bytes().all(|b| b.is_ascii_digit())
+                    })
+            })
+            .collect();
+        // The fixed-width stamps sort by age
+        backups.sort();
+        Ok(backups)
     }
 
+    /// Put the most recent backup of `path` back in its place, and drop it
+    /// from the backups, so restoring again goes one backup further back.
+    /// Returns the backup restored, or `None` when `path` has none.
+    pub fn restore_backup(&self, path: &Path) -> Result<Option<PathBuf>> {
+        let Some(backup) = self.backups(path)?.pop() else {
+            return Ok(None);
+        };
+
+        let _permit = self.io_throttle.as_ref().map(IoThrottle::acquire);
+        fs::copy(io_path(&backup), io_path(path))
+            .with_context(|| format!("Failed to restore file: {}", display_path(path)))?;
+        fs::remove_file(io_path(&backup))
+            .with_context(|| format!("Failed to remove backup: {}", display_path(&backup)))?;
+
+        Ok(Some(backup))
+    }
+
+    /// Copy `path` into its backups, then drop the backups beyond the
+    /// retention limit.
+    ///
+    /// Backups keep the original name, extension included, with the time they
+    /// were made added: `test.ts` is backed up as `test.ts.<stamp>.bak`.
     fn create_backup(&self, path: &Path) -> Result<()> {
-        // Backup naming preserves the original extension for editor associations.
-        // test.ts becomes test.ts.bak, not test.bak, so editors still recognize it.
-        let backup_path = path.with_extension(format!(
-            "{}.bak",
-            path.extension().and_then(|ext| ext.to_str()).unwrap_or("")
+        let (dir, file_name) = self.backup_location(path);
+        fs::create_dir_all(io_path(&dir))
+            .with_context(|| format!("Failed to create backup: {}", display_path(&dir)))?;
+
+        let stamp = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_nanos();
+        let backup_path = dir.join(format!(
+            "{file_name}.{stamp:0width$}.bak",
+            width = STAMP_WIDTH
         ));
 
-        fs::copy(path, &backup_path)
-            .with_context(|| format!("Failed to create backup: {}", backup_path.display()))?;
+        fs::copy(io_path(path), io_path(&backup_path))
+            .with_context(|| format!("Failed to create backup: {}", display_path(&backup_path)))?;
+
+        if self.backup_retention > 0 {
+            let backups = self.backups(path)?;
+            let excess = backups.len().saturating_sub(self.backup_retention);
+            for old in &backups[..excess] {
+                fs::remove_file(io_path(old))
+                    .with_context(|| format!("Failed to remove backup: {}", display_path(old)))?;
+            }
+        }
 
         Ok(())
     }
+
+    /// The directory the backups of `path` are kept in, and their file name
+    /// before the stamp.
+    ///
+    /// The directory mirrors where the file is: its path relative to the
+    /// working directory, or its absolute path for files outside it. That
+    /// keeps files of the same name apart and the backups easy to find.
+    fn backup_location(&self, path: &Path) -> (PathBuf, String) {
+        let absolute = normalize(&std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf()));
+        let cwd = std::env::current_dir()
+            .map(|cwd| normalize(&cwd))
+            .unwrap_or_default();
+        let mirrored = absolute.strip_prefix(&cwd).unwrap_or(&absolute);
+
+        let mut dir = self.backup_dir.clone();
+        if let Some(parent) = mirrored.parent() {
+            dir.extend(
+                parent
+                    .components()
+                    .filter(|component| matches!(component, Component::Normal(_))),
+            );
+        }
+        let file_name = path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned();
+        (dir, file_name)
+    }
+}
+
+/// Digits of the nanosecond stamps in backup names, enough for any time
+/// `SystemTime` can tell.
+const STAMP_WIDTH: usize = 20;
+
+/// `path` with its `.` and `..` components resolved, without touching the
+/// filesystem.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+/// Replace `path` with `content` through a synced temporary file, so neither
+/// other processes nor a crash ever see it half-written.
+fn write_atomically(path: &Path, content: &[u8]) -> Result<()> {
+    let target = resolve_symlink(path)?;
+    let temp_path = temp_path(&target);
+
+    let written = fs::File::create(io_path(&temp_path)).and_then(|mut file| {
+        file.write_all(content)?;
+        file.sync_all()
+    });
+    if let Err(err) = written {
+        let _ = fs::remove_file(io_path(&temp_path));
+        return Err(err.into());
+    }
+
+    replace_with(&temp_path, &target)
+}
+
+/// The file a write to `path` lands in: renaming over a symlink would replace
+/// the link rather than the file it points to.
+fn resolve_symlink(path: &Path) -> Result<PathBuf> {
+    match fs::symlink_metadata(io_path(path)) {
+        Ok(metadata) if metadata.file_type().is_symlink() => Ok(fs::canonicalize(io_path(path))?),
+        _ => Ok(path.to_path_buf()),
+    }
+}
+
+/// Where the new content of `path` is written before it replaces the file;
+/// next to it, since a rename can't cross filesystems.
+fn temp_path(path: &Path) -> PathBuf {
+    let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(".krokfmt-tmp");
+    path.with_file_name(temp_name)
+}
+
+/// Rename the finished `temp` file over `target`, giving it `target`'s
+/// permissions and, on Unix, owner and group first. The temporary file is
+/// removed if that fails.
+fn replace_with(temp: &Path, target: &Path) -> Result<()> {
+    let replaced =
+        copy_permissions(target, temp).and_then(|()| fs::rename(io_path(temp), io_path(target)));
+    if let Err(err) = replaced {
+        let _ = fs::remove_file(io_path(temp));
+        return Err(err.into());
+    }
+
+    // The rename itself only survives a crash once the directory is synced
+    #[cfg(unix)]
+    {
+        let dir = target.parent().filter(|dir| !dir.as_os_str().is_empty());
+        if let Ok(dir) = fs::File::open(io_path(dir.unwrap_or(Path::new(".")))) {
+            let _ = dir.sync_all();
+        }
+    }
+    Ok(())
+}
+
+/// Give `to` the permissions, owner and group of `from`, if it exists.
+///
+/// Only root can give a file away, so replacing someone else's file fails here
+/// rather than leaving it owned by whoever ran krokfmt.
+fn copy_permissions(from: &Path, to: &Path) -> io::Result<()> {
+    let metadata = match fs::metadata(io_path(from)) {
+        Ok(metadata) => metadata,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(err) => return Err(err),
+    };
+
+    // Changing the owner clears setuid bits, so it goes first
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        let temp = fs::metadata(io_path(to))?;
+        if (temp.uid(), temp.gid()) != (metadata.uid(), metadata.gid()) {
+            std::os::unix::fs::chown(io_path(to), Some(metadata.uid()), Some(metadata.gid()))?;
+        }
+    }
+    fs::set_permissions(io_path(to), metadata.permissions())
+}
+
+/// The pattern of a `!pattern` exclusion, unless the path names a real file.
+fn exclusion_pattern(path: &Path) -> Option<&str> {
+    let pattern = path.to_str()?.strip_prefix('!')?;
+    if io_path(path).exists() {
+        return None;
+    }
+    Some(pattern.strip_prefix("./").unwrap_or(pattern))
+}
+
+/// Compile glob patterns, braces expanded, for [`matches_any`].
+fn compile_patterns(patterns: &[String]) -> Result<Vec<Pattern>> {
+    let mut compiled = Vec::new();
+    for pattern in patterns {
+        let trimmed = pattern.strip_prefix("./").unwrap_or(pattern);
+        for expanded in expand_braces(trimmed) {
+            compiled.push(
+                Pattern::new(&expanded).with_context(|| format!("Invalid pattern: {pattern}"))?,
+            );
+        }
+    }
+    Ok(compiled)
+}
+
+/// Whether discovery skips a directory with this name: node_modules and
+/// hidden directories like `.git`.
+///
+/// This hardcoded exclusion prevents accidentally formatting dependencies and
+/// build artifacts. We chose not to make it configurable to keep the tool
+/// simple.
+pub fn is_skipped_dir_name(name: &str) -> bool {
+    name == "node_modules" || name.starts_with('.')
+}
+
+/// Whether `path`, as it was found, matches any of `patterns`. `*` doesn't
+/// cross directories; `**` does.
+fn matches_any(patterns: &[Pattern], path: &Path) -> bool {
+    let options = MatchOptions {
+        require_literal_separator: true,
+        ..MatchOptions::new()
+    };
+    let path = path.strip_prefix(".").unwrap_or(path);
+    patterns
+        .iter()
+        .any(|pattern| pattern.matches_path_with(path, options))
+}
+
+/// Expand `{a,b}` alternatives the way shells do, nested braces included.
+///
+/// The glob crate has no brace syntax of its own. Braces without a comma are
+/// kept as written, since they're more likely part of a file name.
+fn expand_braces(pattern: &str) -> Vec<String> {
+    for (open, _) in pattern.match_indices('{') {
+        let mut depth = 0;
+        let mut commas = Vec::new();
+        let mut close = None;
+        for (i, c) in pattern[open..].char_indices() {
+            match c {
+                '{' => depth += 1,
+                '}' => {
+                    depth -= 1;
+                    if depth == 0 {
+                        close = Some(open + i);
+                        break;
+                    }
+                }
+                ',' if depth == 1 => commas.push(open + i),
+                _ => {}
+            }
+        }
+
+        let Some(close) = close else {
+            break;
+        };
+        if commas.is_empty() {
+            continue;
+        }
+
+        let (prefix, suffix) = (&pattern[..open], &pattern[close + 1..]);
+        let bounds: Vec<_> = std::iter::once(open)
+            .chain(commas)
+            .chain(std::iter::once(close))
+            .collect();
+        return bounds
+            .windows(2)
+            .flat_map(|window| {
+                let alternative = &pattern[window[0] + 1..window[1]];
+                expand_braces(&format!("{prefix}{alternative}{suffix}"))
+            })
+            .collect();
+    }
+
+    vec![pattern.to_string()]
+}
+
+/// Render a path for output: relative to the working directory when it's inside
+/// it, and with the platform's separator throughout.
+///
+/// Paths reach us from the command line, glob expansion and directory walks,
+/// which on Windows freely mix `/` and `\`. Rebuilding from components gives
+/// every message the same shape no matter how the file was found.
+pub fn display_path(path: &Path) -> String {
+    let path = dunce::simplified(path);
+    let relative = std::env::current_dir().ok().and_then(|cwd| {
+        let absolute = std::path::absolute(path).ok()?;
+        absolute
+            .strip_prefix(dunce::simplified(&cwd))
+            .ok()
+            .map(Path::to_path_buf)
+    });
+
+    let normalized: PathBuf = relative.as_deref().unwrap_or(path).components().collect();
+    if normalized.as_os_str().is_empty() {
+        ".".to_string()
+    } else {
+        normalized.display().to_string()
+    }
+}
+
+/// The form of `path` to hand to the file system.
+///
+/// Win32 rejects paths longer than `MAX_PATH` (260 characters) unless they use
+/// the `\\?\` extended-length form, which must be absolute and can't contain
+/// `/` or `..`. Deeply nested monorepo checkouts hit the limit easily, so long
+/// paths are converted; short ones are left alone so error messages from the OS
+/// still show what the user typed.
+#[cfg(windows)]
+pub fn io_path(path: &Path) -> Cow<'_, Path> {
+    const MAX_PATH: usize = 260;
+
+    let Some(raw) = path.to_str() else {
+        return Cow::Borrowed(path);
+    };
+    if raw.len() < MAX_PATH || raw.starts_with(r"\\?\") {
+        return Cow::Borrowed(path);
+    }
+
+    // `absolute` resolves `..` and `/` on Windows, as the prefix requires
+    let Some(absolute) = std::path::absolute(path)
+        .ok()
+        .and_then(|absolute| absolute.to_str().map(str::to_owned))
+    else {
+        return Cow::Borrowed(path);
+    };
+
+    let extended = match absolute.strip_prefix(r"\\") {
+        Some(unc) => format!(r"\\?\UNC\{unc}"),
+        None => format!(r"\\?\{absolute}"),
+    };
+    Cow::Owned(PathBuf::from(extended))
+}
+
+/// The form of `path` to hand to the file system; only Windows needs rewriting.
+#[cfg(not(windows))]
+pub fn io_path(path: &Path) -> Cow<'_, Path> {
+    Cow::Borrowed(path)
+}
+
+/// Counting semaphore bounding concurrent file I/O across rayon workers.
+struct IoThrottle {
+    available: Mutex<usize>,
+    released: Condvar,
+}
+
+impl IoThrottle {
+    fn new(permits: usize) -> Self {
+        Self {
+            available: Mutex::new(permits),
+            released: Condvar::new(),
+        }
+    }
+
+    fn acquire(&self) -> IoPermit<'_> {
+        let mut available = self.available.lock().unwrap_or_else(|e| e.into_inner());
+        while *available == 0 {
+            available = self
+                .released
+                .wait(available)
+                .unwrap_or_else(|e| e.into_inner());
+        }
+        *available -= 1;
+
+        IoPermit { throttle: self }
+    }
+}
+
+struct IoPermit<'a> {
+    throttle: &'a IoThrottle,
+}
+
+impl Drop for IoPermit<'_> {
+    fn drop(&mut self) {
+        let mut available = self
+            .throttle
+            .available
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        *available += 1;
+        self.throttle.released.notify_one();
+    }
 }
 
 #[cfg(test)]
@@ -136,6 +915,22 @@ mod tests {
         assert!(!handler.is_typescript_file(Path::new("test")));
     }
 
+    #[test]
+    fn test_is_source_file() {
+        let handler = FileHandler::new(false);
+
+        assert!(handler.is_source_file(Path::new("test.ts")));
+        assert!(handler.is_source_file(Path::new("test.js")));
+        assert!(handler.is_source_file(Path::new("test.jsx")));
+        assert!(handler.is_source_file(Path::new("test.mjs")));
+        assert!(handler.is_source_file(Path::new("test.cjs")));
+        assert!(handler.is_source_file(Path::new("App.vue")));
+        assert!(handler.is_source_file(Path::new("App.svelte")));
+
+        assert!(!handler.is_source_file(Path::new("test.json")));
+        assert!(!handler.is_source_file(Path::new("test.txt")));
+    }
+
     #[test]
     fn test_find_typescript_files_single_file() {
         let temp_dir = TempDir::new().unwrap();
@@ -143,7 +938,9 @@ mod tests {
         fs::write(&ts_file, "// test").unwrap();
 
         let handler = FileHandler::new(false);
-        let files = handler.find_typescript_files(&[ts_file.clone()]).unwrap();
+        let files = handler
+            .find_typescript_files(std::slice::from_ref(&ts_file))
+            .unwrap();
 
         assert_eq!(files.len(), 1);
         assert_eq!(files[0], ts_file);
@@ -155,10 +952,12 @@ mod tests {
         let ts_file1 = temp_dir.path().join("file1.ts");
         let ts_file2 = temp_dir.path().join("file2.tsx");
         let js_file = temp_dir.path().join("file3.js");
+        let text_file = temp_dir.path().join("notes.txt");
 
         fs::write(&ts_file1, "// test1").unwrap();
         fs::write(&ts_file2, "// test2").unwrap();
         fs::write(&js_file, "// test3").unwrap();
+        fs::write(&text_file, "notes").unwrap();
 
         let handler = FileHandler::new(false);
         let mut files = handler
@@ -166,10 +965,36 @@ mod tests {
             .unwrap();
         files.sort();
 
-        assert_eq!(files.len(), 2);
+        assert_eq!(files.len(), 3);
         assert!(files.contains(&ts_file1));
         assert!(files.contains(&ts_file2));
-        assert!(!files.contains(&js_file));
+        assert!(files.contains(&js_file));
+        assert!(!files.contains(&text_file));
+    }
+
+    #[test]
+    fn test_send_typescript_files() {
+        let temp_dir = TempDir::new().unwrap();
+        for name in ["a.ts", "b.ts", "c.ts"] {
+            fs::write(temp_dir.path().join(name), "// test").unwrap();
+        }
+        let paths = [temp_dir.path().to_path_buf(), temp_dir.path().join("a.ts")];
+        let handler = FileHandler::new(false);
+
+        let (sender, receiver) = std::sync::mpsc::sync_channel(8);
+        handler.send_typescript_files(&paths, &sender).unwrap();
+        drop(sender);
+        let mut sent: Vec<_> = receiver.into_iter().collect();
+        sent.sort();
+        let mut found = handler.find_typescript_files(&paths).unwrap();
+        found.sort();
+        assert_eq!(sent, found);
+        assert_eq!(sent.len(), 3);
+
+        // Once nobody's receiving, the walk stops instead of failing
+        let (sender, receiver) = std::sync::mpsc::sync_channel(0);
+        drop(receiver);
+        handler.send_typescript_files(&paths, &sender).unwrap();
     }
 
     #[test]
@@ -193,6 +1018,119 @@ mod tests {
         assert_eq!(files[0], ts_file);
     }
 
+    #[test]
+    fn test_ignore_files_and_excludes() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir(root.join(".git")).unwrap();
+        for dir in ["src/generated", "dist"] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        for name in [
+            "src/app.ts",
+            "src/generated/api.ts",
+            "src/app.stories.ts",
+            "dist/app.ts",
+        ] {
+            fs::write(root.join(name), "// test").unwrap();
+        }
+        fs::write(root.join(".gitignore"), "dist/\n").unwrap();
+        fs::write(root.join("src/.krokignore"), "*.stories.ts\n").unwrap();
+
+        let find = |handler: FileHandler| {
+            let mut files = handler
+                .find_typescript_files(&[root.to_path_buf()])
+                .unwrap();
+            files.sort();
+            files
+        };
+
+        assert_eq!(
+            find(FileHandler::new(false)),
+            vec![root.join("src/app.ts"), root.join("src/generated/api.ts")]
+        );
+        let excluded = FileHandler::new(false)
+            .with_excludes(&[format!("{}/**/generated", root.display())])
+            .unwrap();
+        assert_eq!(find(excluded), vec![root.join("src/app.ts")]);
+        assert_eq!(
+            find(FileHandler::new(false).with_ignore_files(false)).len(),
+            4
+        );
+
+        // Named explicitly, an ignored file is still formatted
+        let explicit = root.join("dist/app.ts");
+        assert_eq!(
+            FileHandler::new(false)
+                .find_typescript_files(std::slice::from_ref(&explicit))
+                .unwrap(),
+            vec![explicit]
+        );
+    }
+
+    #[test]
+    fn test_expand_braces() {
+        assert_eq!(
+            expand_braces("src/**/*.{ts,tsx}"),
+            vec!["src/**/*.ts", "src/**/*.tsx"]
+        );
+        assert_eq!(
+            expand_braces("{app,lib/{a,b}}/*.ts"),
+            vec!["app/*.ts", "lib/a/*.ts", "lib/b/*.ts"]
+        );
+        assert_eq!(expand_braces("src/{literal}.ts"), vec!["src/{literal}.ts"]);
+        assert_eq!(expand_braces("src/{open.ts"), vec!["src/{open.ts"]);
+    }
+
+    #[test]
+    fn test_glob_patterns_with_braces_and_exclusions() {
+        let temp_dir = TempDir::new().unwrap();
+        let src = temp_dir.path().join("src");
+        fs::create_dir_all(src.join("nested")).unwrap();
+        for name in ["app.ts", "view.tsx", "app.test.ts", "nested/util.ts"] {
+            fs::write(src.join(name), "// test").unwrap();
+        }
+
+        let root = temp_dir.path().to_str().unwrap();
+        let handler = FileHandler::new(false);
+        let mut files = handler
+            .find_typescript_files(&[
+                PathBuf::from(format!("{root}/src/**/*.{{ts,tsx}}")),
+                PathBuf::from(format!("{root}/src/*.ts")),
+                PathBuf::from(format!("!{root}/src/*.test.ts")),
+            ])
+            .unwrap();
+        files.sort();
+
+        assert_eq!(
+            files,
+            vec![
+                PathBuf::from(format!("{root}/src/app.ts")),
+                PathBuf::from(format!("{root}/src/nested/util.ts")),
+                PathBuf::from(format!("{root}/src/view.tsx")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_find_package_json_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let package_json = temp_dir.path().join("package.json");
+        let node_modules = temp_dir.path().join("node_modules");
+        fs::create_dir(&node_modules).unwrap();
+
+        fs::write(&package_json, "{}").unwrap();
+        fs::write(node_modules.join("package.json"), "{}").unwrap();
+        fs::write(temp_dir.path().join("app.ts"), "// app").unwrap();
+
+        let handler = FileHandler::new(false);
+        let files = handler
+            .find_package_json_files(&[temp_dir.path().to_path_buf()])
+            .unwrap();
+
+        assert_eq!(files, vec![package_json]);
+    }
+
     #[test]
     fn test_create_backup() {
         let temp_dir = TempDir::new().unwrap();
@@ -200,15 +1138,210 @@ mod tests {
         let original_content = "// original content";
         fs::write(&ts_file, original_content).unwrap();
 
-        let handler = FileHandler::new(true);
+        let handler = FileHandler::new(true).with_backup_dir(temp_dir.path().join("backups"));
         handler.write_file(&ts_file, "// new content").unwrap();
 
-        // Check backup was created
-        let backup_file = temp_dir.path().join("test.ts.bak");
-        assert!(backup_file.exists());
-        assert_eq!(fs::read_to_string(&backup_file).unwrap(), original_content);
+        // Check backup was created, away from the source
+        let backups = handler.backups(&ts_file).unwrap();
+        assert_eq!(backups.len(), 1);
+        assert!(backups[0].starts_with(temp_dir.path().join("backups")));
+        assert!(backups[0].to_string_lossy().ends_with(".bak"));
+        assert_eq!(fs::read_to_string(&backups[0]).unwrap(), original_content);
 
         // Check original file was updated
         assert_eq!(fs::read_to_string(&ts_file).unwrap(), "// new content");
     }
+
+    #[test]
+    fn test_backup_retention_and_restore() {
+        let temp_dir = TempDir::new().unwrap();
+        let ts_file = temp_dir.path().join("test.ts");
+        let other_file = temp_dir.path().join("test.ts.d.ts");
+        fs::write(&ts_file, "// 1").unwrap();
+        fs::write(&other_file, "// other").unwrap();
+        let handler = FileHandler::new(true)
+            .with_backup_dir(temp_dir.path().join("backups"))
+            .with_backup_retention(2);
+
+        for version in 2..=4 {
+            handler
+                .write_file(&ts_file, &format!("// {version}"))
+                .unwrap();
+        }
+        handler.write_file(&other_file, "// other 2").unwrap();
+
+        // Only the two most recent backups are kept, apart from other files'
+        let backups = handler.backups(&ts_file).unwrap();
+        let contents: Vec<_> = backups
+            .iter()
+            .map(|backup| fs::read_to_string(backup).unwrap())
+            .collect();
+        assert_eq!(contents, ["// 2", "// 3"]);
+
+        // Each restore goes one backup further back
+        assert_eq!(
+            handler.restore_backup(&ts_file).unwrap(),
+            Some(backups[1].clone())
+        );
+        assert_eq!(fs::read_to_string(&ts_file).unwrap(), "// 3");
+        handler.restore_backup(&ts_file).unwrap();
+        assert_eq!(fs::read_to_string(&ts_file).unwrap(), "// 2");
+        assert_eq!(handler.restore_backup(&ts_file).unwrap(), None);
+        assert_eq!(handler.backups(&other_file).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_write_file_streaming() {
+        let temp_dir = TempDir::new().unwrap();
+        let ts_file = temp_dir.path().join("test.ts");
+        fs::write(&ts_file, "const x = 1;").unwrap();
+        let handler = FileHandler::new(true).with_backup_dir(temp_dir.path().join("backups"));
+
+        let unchanged = handler
+            .write_file_streaming(&ts_file, |out| {
+                out.write_all(b"ignored")?;
+                Ok(false)
+            })
+            .unwrap();
+        assert!(!unchanged);
+        assert_eq!(fs::read_to_string(&ts_file).unwrap(), "const x = 1;");
+        assert!(handler.backups(&ts_file).unwrap().is_empty());
+
+        let changed = handler
+            .write_file_streaming(&ts_file, |out| {
+                out.write_all(b"const x = 2;\n")?;
+                Ok(true)
+            })
+            .unwrap();
+        assert!(changed);
+        assert_eq!(fs::read_to_string(&ts_file).unwrap(), "const x = 2;\n");
+        let backups = handler.backups(&ts_file).unwrap();
+        assert_eq!(fs::read_to_string(&backups[0]).unwrap(), "const x = 1;");
+        // The file and the backup directory, with no temporary file left
+        assert_eq!(fs::read_dir(temp_dir.path()).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn test_atomic_writes() {
+        let temp_dir = TempDir::new().unwrap();
+        let ts_file = temp_dir.path().join("test.ts");
+        fs::write(&ts_file, "const x = 1;").unwrap();
+        let handler = FileHandler::new(false).with_write_mode(WriteMode::Atomic);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&ts_file, fs::Permissions::from_mode(0o640)).unwrap();
+            let link = temp_dir.path().join("link.ts");
+            std::os::unix::fs::symlink(&ts_file, &link).unwrap();
+
+            // The link keeps pointing at the file, which keeps its mode
+            handler.write_file(&link, "const x = 2;\n").unwrap();
+            assert!(fs::symlink_metadata(&link)
+                .unwrap()
+                .file_type()
+                .is_symlink());
+            assert_eq!(fs::read_to_string(&link).unwrap(), "const x = 2;\n");
+            let mode = fs::metadata(&ts_file).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o640);
+        }
+
+        handler.write_file(&ts_file, "const x = 3;\n").unwrap();
+        assert_eq!(fs::read_to_string(&ts_file).unwrap(), "const x = 3;\n");
+        // Nothing but the file (and the link) is left behind
+        let expected_entries = if cfg!(unix) { 2 } else { 1 };
+        assert_eq!(
+            fs::read_dir(temp_dir.path()).unwrap().count(),
+            expected_entries
+        );
+    }
+
+    #[test]
+    fn test_display_path_relative_to_working_directory() {
+        let cwd = std::env::current_dir().unwrap();
+
+        assert_eq!(
+            display_path(&cwd.join("src").join("app.ts")),
+            Path::new("src").join("app.ts").display().to_string()
+        );
+        assert_eq!(
+            display_path(Path::new("./src/app.ts")),
+            Path::new("src").join("app.ts").display().to_string()
+        );
+        assert_eq!(display_path(&cwd), ".");
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_display_path_normalizes_separators() {
+        assert_eq!(
+            display_path(Path::new(r"C:\repo/src\app.ts")),
+            r"C:\repo\src\app.ts"
+        );
+        assert_eq!(
+            display_path(Path::new(r"\\?\C:\repo\app.ts")),
+            r"C:\repo\app.ts"
+        );
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_io_path_extends_long_paths() {
+        let short = Path::new(r"C:\repo\app.ts");
+        assert_eq!(io_path(short), short);
+
+        let long = format!(r"C:\repo/{}app.ts", "nested\\".repeat(40));
+        assert_eq!(
+            io_path(Path::new(&long)),
+            Path::new(&format!(r"\\?\C:\repo\{}app.ts", "nested\\".repeat(40)))
+        );
+
+        let unc = format!(r"\\server\share\{}app.ts", "nested\\".repeat(40));
+        assert_eq!(
+            io_path(Path::new(&unc)),
+            Path::new(&format!(
+                r"\\?\UNC\server\share\{}app.ts",
+                "nested\\".repeat(40)
+            ))
+        );
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_long_paths_are_discovered_and_backed_up() {
+        let temp_dir = TempDir::new().unwrap();
+        let mut dir = temp_dir.path().to_path_buf();
+        while dir.as_os_str().len() < 300 {
+            dir.push("deeply_nested_directory");
+        }
+        fs::create_dir_all(io_path(&dir)).unwrap();
+        let ts_file = dir.join("app.ts");
+        fs::write(io_path(&ts_file), "// original").unwrap();
+
+        // Backups mirror the file's long path below the backup directory
+        let handler = FileHandler::new(true).with_backup_dir(temp_dir.path().join(".backups"));
+        let files = handler
+            .find_typescript_files(&[temp_dir.path().to_path_buf()])
+            .unwrap();
+        assert_eq!(files, vec![ts_file.clone()]);
+
+        handler.write_file(&ts_file, "// formatted").unwrap();
+        assert_eq!(handler.read_file(&ts_file).unwrap(), "// formatted");
+        let backups = handler.backups(&ts_file).unwrap();
+        assert_eq!(
+            fs::read_to_string(io_path(&backups[0])).unwrap(),
+            "// original"
+        );
+    }
+
+    #[test]
+    fn test_io_throttle_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let ts_file = temp_dir.path().join("test.ts");
+
+        // Permits must be released, or the second operation would block forever
+        let handler = FileHandler::new(false).with_io_throttle(1);
+        handler.write_file(&ts_file, "// content").unwrap();
+        assert_eq!(handler.read_file(&ts_file).unwrap(), "// content");
+    }
 }
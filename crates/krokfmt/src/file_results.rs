@@ -0,0 +1,134 @@
+use anyhow::Result;
+use rayon::prelude::*;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc};
+
+use krokfmt_core::diff::{diff_hunks, Hunk, DEFAULT_CONTEXT_LINES};
+
+use crate::file_handler::FileHandler;
+
+/// What formatting one file produced.
+#[derive(Debug)]
+pub struct FileResult {
+    /// Position of the file in the list that was passed in, since results
+    /// arrive in the order they complete
+    pub index: usize,
+    pub path: PathBuf,
+    pub outcome: Result<FileOutcome>,
+}
+
+/// A file's content before and after formatting.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileOutcome {
+    pub original: String,
+    pub formatted: String,
+}
+
+impl FileOutcome {
+    pub fn changed(&self) -> bool {
+        self.original != self.formatted
+    }
+
+    /// What formatting changed, ready to show as a diff.
+    pub fn hunks(&self) -> Vec<Hunk> {
+        diff_hunks(&self.original, &self.formatted, DEFAULT_CONTEXT_LINES)
+    }
+}
+
+/// Per-file results, yielded as each file finishes formatting.
+///
+/// GUI wrappers and long-running hosts can show progress, and start rendering
+/// diffs, long before a large run is over. Nothing is written to disk; what to
+/// do with each result is up to the caller. Dropping the iterator early lets
+/// the remaining files finish in the background without their results being
+/// kept.
+pub struct FileResults {
+    receiver: mpsc::Receiver<FileResult>,
+}
+
+impl FileResults {
+    /// Start formatting `files` on rayon's pool with `format`, which receives
+    /// each file's path and content and returns the formatted content.
+    ///
+    /// `format` is the embedder's pipeline, so every option the CLI offers is
+    /// available; [`crate::format_typescript`] gives krokfmt's defaults.
+    pub fn start<F>(file_handler: Arc<FileHandler>, files: Vec<PathBuf>, format: F) -> Self
+    where
+        F: Fn(&Path, &str) -> Result<String> + Send + Sync + 'static,
+    {
+        let (sender, receiver) = mpsc::channel();
+
+        rayon::spawn(move || {
+            files
+                .into_par_iter()
+                .enumerate()
+                .for_each_with(sender, |sender, (index, path)| {
+                    let outcome = file_handler.read_file(&path).and_then(|original| {
+                        let formatted = format(&path, &original)?;
+                        Ok(FileOutcome {
+                            original,
+                            formatted,
+                        })
+                    });
+
+                    // A closed channel means the caller stopped listening
+                    let _ = sender.send(FileResult {
+                        index,
+                        path,
+                        outcome,
+                    });
+                });
+        });
+
+        Self { receiver }
+    }
+}
+
+impl Iterator for FileResults {
+    type Item = FileResult;
+
+    fn next(&mut self) -> Option<FileResult> {
+        self.receiver.recv().ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_every_file_yields_a_result() {
+        let temp_dir = TempDir::new().unwrap();
+        let formatted = temp_dir.path().join("formatted.ts");
+        let unformatted = temp_dir.path().join("unformatted.ts");
+        let missing = temp_dir.path().join("missing.ts");
+        fs::write(&formatted, "const a = 1;\n").unwrap();
+        fs::write(&unformatted, "const  a = 1;\n").unwrap();
+
+        let files = vec![formatted, unformatted, missing];
+        let mut results: Vec<_> = FileResults::start(
+            Arc::new(FileHandler::new(false)),
+            files.clone(),
+            |_, content| Ok(content.replace("  ", " ")),
+        )
+        .collect();
+        results.sort_by_key(|result| result.index);
+
+        assert_eq!(results.len(), 3);
+        for (result, file) in results.iter().zip(&files) {
+            assert_eq!(&result.path, file);
+        }
+
+        let outcome = results[0].outcome.as_ref().unwrap();
+        assert!(!outcome.changed());
+        assert!(outcome.hunks().is_empty());
+
+        let outcome = results[1].outcome.as_ref().unwrap();
+        assert!(outcome.changed());
+        assert_eq!(outcome.hunks().len(), 1);
+
+        assert!(results[2].outcome.is_err());
+    }
+}
@@ -0,0 +1,142 @@
+//! Remembers which files were already formatted, so repeated runs over a
+//! large checkout only format the files whose content changed since.
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::file_handler::{display_path, io_path};
+
+/// The cache file, in the directory krokfmt runs in.
+pub const CACHE_FILE_NAME: &str = ".krokfmt-cache";
+
+/// Hashes of files found already formatted, by absolute path.
+///
+/// A file is only recorded once a run finds it formatted, never right after
+/// writing it, so a file krokfmt wouldn't leave alone the second time is
+/// never skipped. Each hash covers the file's content and the settings it
+/// was formatted with: changing either formats the file again.
+#[derive(Debug)]
+pub struct FormatCache {
+    path: PathBuf,
+    files: Mutex<HashMap<PathBuf, u64>>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheFile {
+    /// The krokfmt that wrote the cache; any other version starts afresh,
+    /// since it may format differently
+    version: String,
+    files: HashMap<PathBuf, u64>,
+}
+
+impl FormatCache {
+    /// Read the cache at `path`. A missing or unreadable cache, or one from
+    /// another krokfmt version, is an empty one.
+    pub fn load(path: &Path) -> Self {
+        let files = fs::read_to_string(io_path(path))
+            .ok()
+            .and_then(|content| serde_json::from_str::<CacheFile>(&content).ok())
+            .filter(|cache| cache.version == env!("CARGO_PKG_VERSION"))
+            .map(|cache| cache.files)
+            .unwrap_or_default();
+
+        Self {
+            path: path.to_path_buf(),
+            files: Mutex::new(files),
+        }
+    }
+
+    /// Whether `content` is what `file` held when a run with the same
+    /// `settings` last found it formatted.
+    pub fn is_formatted(&self, file: &Path, content: &str, settings: &str) -> bool {
+        let hash = content_hash(content, settings);
+        self.lock().get(&cache_key(file)) == Some(&hash)
+    }
+
+    /// Record that `file` is formatted with `settings` as long as it holds
+    /// `content`.
+    pub fn record_formatted(&self, file: &Path, content: &str, settings: &str) {
+        let hash = content_hash(content, settings);
+        self.lock().insert(cache_key(file), hash);
+    }
+
+    /// Write the cache back to where it was loaded from.
+    pub fn save(&self) -> Result<()> {
+        let cache = CacheFile {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            files: self.lock().clone(),
+        };
+        let json = serde_json::to_string(&cache).context("Failed to serialize the cache")?;
+        fs::write(io_path(&self.path), json)
+            .with_context(|| format!("Failed to write cache: {}", display_path(&self.path)))
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<PathBuf, u64>> {
+        // A worker that panicked can't have left the map half-updated
+        self.files.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Files are keyed by absolute path, so the same file is found however the
+/// paths to format were spelled.
+fn cache_key(file: &Path) -> PathBuf {
+    std::path::absolute(file).unwrap_or_else(|_| file.to_path_buf())
+}
+
+/// Hashes only need to agree between runs of the same krokfmt build; one
+/// built differently just formats everything once more.
+fn content_hash(content: &str, settings: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    settings.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_cache_round_trip() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(CACHE_FILE_NAME);
+        let file = temp_dir.path().join("a.ts");
+
+        let cache = FormatCache::load(&path);
+        assert!(!cache.is_formatted(&file, "let a = 1;\n", "rules"));
+        cache.record_formatted(&file, "let a = 1;\n", "rules");
+        cache.save().unwrap();
+
+        let cache = FormatCache::load(&path);
+        assert!(cache.is_formatted(&file, "let a = 1;\n", "rules"));
+        // Changed content or settings format the file again
+        assert!(!cache.is_formatted(&file, "let a = 2;\n", "rules"));
+        assert!(!cache.is_formatted(&file, "let a = 1;\n", "other rules"));
+    }
+
+    #[test]
+    fn test_other_versions_and_broken_caches_are_empty() {
+        let temp_dir = TempDir::new().unwrap();
+        let path = temp_dir.path().join(CACHE_FILE_NAME);
+        let file = std::path::absolute(temp_dir.path().join("a.ts")).unwrap();
+
+        let mut files = HashMap::new();
+        files.insert(file.clone(), content_hash("let a = 1;\n", ""));
+        let old = CacheFile {
+            version: "0.0.0-old".to_string(),
+            files,
+        };
+        fs::write(&path, serde_json::to_string(&old).unwrap()).unwrap();
+        assert!(!FormatCache::load(&path).is_formatted(&file, "let a = 1;\n", ""));
+
+        fs::write(&path, "not json").unwrap();
+        assert!(!FormatCache::load(&path).is_formatted(&file, "let a = 1;\n", ""));
+    }
+}
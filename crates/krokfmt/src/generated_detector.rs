@@ -0,0 +1,272 @@
+use glob::{MatchOptions, Pattern};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use crate::file_handler::io_path;
+
+/// How much of each file we look at when sniffing for generated markers.
+///
+/// Generators put their banner at the top of the file, so there's no reason to
+/// read a multi-megabyte bundle just to decide we're going to skip it.
+const SNIFF_BYTES: usize = 4096;
+
+/// Header markers that generators use to say "don't hand-edit this".
+///
+/// `@generated` is the Facebook/Relay/protobuf convention and `DO NOT EDIT` is
+/// the Go convention that graphql-codegen, buf and friends have adopted too.
+const GENERATED_MARKERS: &[&str] = &["@generated", "DO NOT EDIT"];
+
+/// Decides whether a discovered file is generated (or binary) and should be skipped.
+///
+/// Formatting generated protobuf/graphql output is pure churn: the next codegen
+/// run reverts it and the diff noise buries real changes. We detect it the same
+/// two ways reviewers do: a marker in the file header, or a `linguist-generated`
+/// entry in `.gitattributes`.
+#[derive(Default)]
+pub struct GeneratedFileDetector {
+    /// Parsed `.gitattributes` per directory (`None` when the directory has none)
+    attributes: HashMap<PathBuf, Option<Vec<AttributeRule>>>,
+}
+
+impl GeneratedFileDetector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_generated(&mut self, path: &Path) -> bool {
+        Self::has_generated_header(path) || self.is_linguist_generated(path)
+    }
+
+    /// Sniff the file header for a generated marker or binary content.
+    ///
+    /// A NUL byte means the file isn't text at all, which happens when a build
+    /// step writes e.g. a compiled `.ts` asset next to the sources.
+    fn has_generated_header(path: &Path) -> bool {
+        let Ok(file) = File::open(io_path(path)) else {
+            return false;
+        };
+
+        let mut header = Vec::with_capacity(SNIFF_BYTES);
+        if file
+            .take(SNIFF_BYTES as u64)
+            .read_to_end(&mut header)
+            .is_err()
+        {
+            return false;
+        }
+
+        if header.contains(&0) {
+            return true;
+        }
+
+        let header = String::from_utf8_lossy(&header);
+        GENERATED_MARKERS
+            .iter()
+            .any(|marker| header.contains(marker))
+    }
+
+    /// Check the `linguist-generated` attribute from every `.gitattributes`
+    /// between the file and the repository root.
+    ///
+    /// Like git, deeper files take precedence over shallower ones and later lines
+    /// over earlier ones, so `-linguist-generated` can carve exceptions out of a
+    /// broader rule.
+    fn is_linguist_generated(&mut self, path: &Path) -> bool {
+        let absolute = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+        let Some(parent) = absolute.parent() else {
+            return false;
+        };
+
+        // Collect from the repository root downwards so the last match wins
+        let mut dirs: Vec<&Path> = Vec::new();
+        for dir in parent.ancestors() {
+            dirs.push(dir);
+            if dir.join(".git").exists() {
+                break;
+            }
+        }
+        dirs.reverse();
+
+        let mut generated = false;
+        for dir in dirs {
+            let Ok(relative) = absolute.strip_prefix(dir) else {
+                continue;
+            };
+            let relative = relative.to_string_lossy().replace('\\', "/");
+
+            for rule in self.rules_for(dir) {
+                if rule.matches(&relative) {
+                    generated = rule.generated;
+                }
+            }
+        }
+
+        generated
+    }
+
+    fn rules_for(&mut self, dir: &Path) -> &[AttributeRule] {
+        self.attributes
+            .entry(dir.to_path_buf())
+            .or_insert_with(|| {
+                std::fs::read_to_string(dir.join(".gitattributes"))
+                    .ok()
+                    .map(|content| parse_gitattributes(&content))
+            })
+            .as_deref()
+            .unwrap_or_default()
+    }
+}
+
+/// A `.gitattributes` line that sets or unsets `linguist-generated`.
+#[derive(Debug, PartialEq)]
+struct AttributeRule {
+    pattern: Pattern,
+    /// Whether the pattern is matched against the file name only
+    basename_only: bool,
+    generated: bool,
+}
+
+impl AttributeRule {
+    fn matches(&self, relative_path: &str) -> bool {
+        let options = MatchOptions {
+            require_literal_separator: true,
+            ..MatchOptions::new()
+        };
+
+        if self.basename_only {
+            let name = relative_path.rsplit('/').next().unwrap_or(relative_path);
+            self.pattern.matches_with(name, options)
+        } else {
+            self.pattern.matches_with(relative_path, options)
+        }
+    }
+}
+
+/// Parse the subset of `.gitattributes` we care about.
+///
+/// Only `linguist-generated` is extracted; every other attribute is ignored, as
+/// are lines we can't turn into a glob. Quoted patterns and macro attributes are
+/// rare enough in practice that we don't handle them.
+fn parse_gitattributes(content: &str) -> Vec<AttributeRule> {
+    let mut rules = Vec::new();
+
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let mut fields = line.split_whitespace();
+        let Some(raw_pattern) = fields.next() else {
+            continue;
+        };
+
+        let generated = fields.fold(None, |state, attr| match attr {
+            "linguist-generated" | "linguist-generated=true" => Some(true),
+            "-linguist-generated" | "!linguist-generated" | "linguist-generated=false" => {
+                Some(false)
+            }
+            _ => state,
+        });
+        let Some(generated) = generated else {
+            continue;
+        };
+
+        // Same anchoring rules as gitignore: a pattern without a slash matches
+        // the file name at any depth, otherwise it's relative to this directory.
+        let basename_only = !raw_pattern.contains('/');
+        let mut glob = raw_pattern.trim_start_matches('/').to_string();
+        if glob.ends_with('/') {
+            glob.push_str("**");
+        }
+
+        if let Ok(pattern) = Pattern::new(&glob) {
+            rules.push(AttributeRule {
+                pattern,
+                basename_only,
+                generated,
+            });
+        }
+    }
+
+    rules
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_detects_header_markers() {
+        let temp_dir = TempDir::new().unwrap();
+        let generated = temp_dir.path().join("schema.ts");
+        let codegen = temp_dir.path().join("types.ts");
+        let handwritten = temp_dir.path().join("app.ts");
+        fs::write(&generated, "// @generated by relay-compiler\nexport {};\n").unwrap();
+        fs::write(
+            &codegen,
+            "// Code generated by buf. DO NOT EDIT.\nexport {};\n",
+        )
+        .unwrap();
+        fs::write(&handwritten, "export const a = 1;\n").unwrap();
+
+        let mut detector = GeneratedFileDetector::new();
+        assert!(detector.is_generated(&generated));
+        assert!(detector.is_generated(&codegen));
+        assert!(!detector.is_generated(&handwritten));
+    }
+
+    #[test]
+    fn test_detects_binary_content() {
+        let temp_dir = TempDir::new().unwrap();
+        let binary = temp_dir.path().join("asset.ts");
+        fs::write(&binary, b"\x00\x01\x02binary").unwrap();
+
+        let mut detector = GeneratedFileDetector::new();
+        assert!(detector.is_generated(&binary));
+    }
+
+    #[test]
+    fn test_linguist_generated_attributes() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir(root.join(".git")).unwrap();
+        fs::create_dir_all(root.join("src/gql")).unwrap();
+        fs::write(
+            root.join(".gitattributes"),
+            "# codegen output\nsrc/gql/** linguist-generated=true\n*.pb.ts linguist-generated\n",
+        )
+        .unwrap();
+        // Nested files override the root, so this carves out an exception
+        fs::write(
+            root.join("src/gql/.gitattributes"),
+            "scalars.ts -linguist-generated\n",
+        )
+        .unwrap();
+
+        for name in [
+            "src/gql/types.ts",
+            "src/gql/scalars.ts",
+            "src/user.pb.ts",
+            "src/app.ts",
+        ] {
+            fs::write(root.join(name), "export {};\n").unwrap();
+        }
+
+        let mut detector = GeneratedFileDetector::new();
+        assert!(detector.is_generated(&root.join("src/gql/types.ts")));
+        assert!(detector.is_generated(&root.join("src/user.pb.ts")));
+        assert!(!detector.is_generated(&root.join("src/gql/scalars.ts")));
+        assert!(!detector.is_generated(&root.join("src/app.ts")));
+    }
+
+    #[test]
+    fn test_parse_ignores_unrelated_attributes() {
+        let rules = parse_gitattributes("*.ts text eol=lf\n*.png binary\n");
+        assert!(rules.is_empty());
+    }
+}
@@ -0,0 +1,249 @@
+use glob::{MatchOptions, Pattern};
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::file_handler::io_path;
+use crate::module_graph::normalize;
+use crate::project_context::project_root;
+
+/// Ignore files read in every directory, later ones taking precedence.
+pub const IGNORE_FILE_NAMES: [&str; 2] = [".gitignore", ".krokignore"];
+
+/// One line of an ignore file.
+#[derive(Debug, Clone)]
+struct IgnoreRule {
+    pattern: Pattern,
+    /// `!pattern`: re-includes what an earlier rule ignored
+    negated: bool,
+    /// `pattern/`: only matches directories
+    dir_only: bool,
+}
+
+/// The rules of a `.gitignore` or `.krokignore`, in gitignore syntax.
+///
+/// Patterns containing a `/` other than a trailing one are relative to the
+/// directory of the file; others match a name at any depth below it.
+#[derive(Debug, Clone)]
+pub struct IgnoreFile {
+    dir: PathBuf,
+    rules: Vec<IgnoreRule>,
+}
+
+impl IgnoreFile {
+    /// Parse the content of an ignore file found in `dir`. Lines that aren't
+    /// valid patterns are skipped, as git does.
+    pub fn parse(dir: &Path, content: &str) -> Self {
+        let rules = content
+            .lines()
+            .filter_map(|line| {
+                let line = line.trim_end();
+                if line.is_empty() || line.starts_with('#') {
+                    return None;
+                }
+
+                // `\!` and `\#` escape a literal first character
+                let (negated, line) = match line.strip_prefix('!') {
+                    Some(rest) => (true, rest),
+                    None => (false, line.strip_prefix('\\').unwrap_or(line)),
+                };
+                let (dir_only, line) = match line.strip_suffix('/') {
+                    Some(rest) => (true, rest),
+                    None => (false, line),
+                };
+
+                let pattern = if line.contains('/') {
+                    line.trim_start_matches('/').to_string()
+                } else {
+                    format!("**/{line}")
+                };
+                Some(IgnoreRule {
+                    pattern: Pattern::new(&pattern).ok()?,
+                    negated,
+                    dir_only,
+                })
+            })
+            .collect();
+
+        Self {
+            dir: dir.to_path_buf(),
+            rules,
+        }
+    }
+
+    /// Whether the last rule matching `path` ignores it (`Some(true)`) or
+    /// re-includes it (`Some(false)`); `None` if no rule matches.
+    fn matched(&self, path: &Path, is_dir: bool) -> Option<bool> {
+        let relative = path.strip_prefix(&self.dir).ok()?;
+        let options = MatchOptions {
+            require_literal_separator: true,
+            ..MatchOptions::new()
+        };
+
+        self.rules
+            .iter()
+            .rev()
+            .find(|rule| {
+                (is_dir || !rule.dir_only) && rule.pattern.matches_path_with(relative, options)
+            })
+            .map(|rule| !rule.negated)
+    }
+}
+
+/// The ignore files of a file tree, read as directories are reached.
+///
+/// Ignore files apply below the directory they're in, and are read up to the
+/// root of the git repository. Outside a repository they're read up to the
+/// project root (the nearest directory with a `krokfmt.toml`, `package.json`
+/// or `tsconfig.json`), or else only in the directories being searched (or a
+/// matched file's own directory), so nothing far up the tree ignores files by
+/// surprise.
+#[derive(Debug, Default)]
+pub struct IgnoreFiles {
+    by_dir: HashMap<PathBuf, Vec<IgnoreFile>>,
+}
+
+impl IgnoreFiles {
+    /// Whether `path` is ignored by the ignore files of its own directories.
+    ///
+    /// Like git, a file inside an ignored directory is ignored no matter what
+    /// the rules say about the file itself.
+    pub fn is_ignored(&mut self, path: &Path, is_dir: bool) -> bool {
+        let path = normalize(path);
+        let mut dirs: Vec<&Path> = path.ancestors().skip(1).collect();
+        dirs.reverse();
+        let root = ignore_root(&path)
+            .or_else(|| path.parent().map(Path::to_path_buf))
+            .unwrap_or_default();
+        dirs.retain(|dir| dir.starts_with(&root));
+
+        // Each directory between the root and the path can only be ignored by
+        // the ignore files above it
+        for (depth, dir) in dirs.iter().enumerate().skip(1) {
+            if self.matched_by(&dirs[..depth], dir, true) {
+                return true;
+            }
+        }
+        self.matched_by(&dirs, &path, is_dir)
+    }
+
+    /// The directory a walk of `dir` reads ignore files from: the root of
+    /// its repository or project, or `dir` itself outside both.
+    pub fn walk_root(dir: &Path) -> PathBuf {
+        let dir = normalize(dir);
+        ignore_root(&dir).unwrap_or(dir)
+    }
+
+    /// Whether the ignore files from `root` (see [`Self::walk_root`]) down to
+    /// `dir` ignore the entry `path` directly inside it.
+    ///
+    /// For directory walks, which have already checked every directory on the
+    /// way down and only need to look at each new entry.
+    pub fn is_entry_ignored(&mut self, root: &Path, dir: &Path, path: &Path, is_dir: bool) -> bool {
+        let (dir, path) = (normalize(dir), normalize(path));
+        let mut dirs: Vec<&Path> = dir
+            .ancestors()
+            .take_while(|ancestor| ancestor.starts_with(root))
+            .collect();
+        dirs.reverse();
+        self.matched_by(&dirs, &path, is_dir)
+    }
+
+    fn matched_by(&mut self, dirs: &[&Path], path: &Path, is_dir: bool) -> bool {
+        let mut ignored = false;
+        for dir in dirs {
+            for file in self.in_dir(dir) {
+                if let Some(matched) = file.matched(path, is_dir) {
+                    ignored = matched;
+                }
+            }
+        }
+        ignored
+    }
+
+    fn in_dir(&mut self, dir: &Path) -> &[IgnoreFile] {
+        self.by_dir.entry(dir.to_path_buf()).or_insert_with(|| {
+            IGNORE_FILE_NAMES
+                .iter()
+                .filter_map(|name| fs::read_to_string(io_path(&dir.join(name))).ok())
+                .map(|content| IgnoreFile::parse(dir, &content))
+                .collect()
+        })
+    }
+}
+
+/// The outermost directory whose ignore files apply to `path`: its
+/// repository's root, or its project's outside a repository.
+fn ignore_root(path: &Path) -> Option<PathBuf> {
+    repository_root(path).or_else(|| project_root(path).map(Path::to_path_buf))
+}
+
+/// The nearest directory containing `path` (or `path` itself) with a `.git`
+/// entry.
+fn repository_root(path: &Path) -> Option<PathBuf> {
+    path.ancestors()
+        .find(|dir| io_path(&dir.join(".git")).exists())
+        .map(Path::to_path_buf)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_gitignore_syntax() {
+        let root = Path::new("/repo");
+        let file = IgnoreFile::parse(
+            root,
+            "# Build output\ndist/\n*.generated.ts\n/top.ts\nsrc/legacy/**\n!src/legacy/keep.ts\n\n",
+        );
+        let matched = |path: &str, is_dir| file.matched(&root.join(path), is_dir);
+
+        assert_eq!(matched("dist", true), Some(true));
+        assert_eq!(matched("packages/a/dist", true), Some(true));
+        // Directory-only patterns don't match files
+        assert_eq!(matched("dist", false), None);
+        assert_eq!(matched("src/api.generated.ts", false), Some(true));
+        assert_eq!(matched("top.ts", false), Some(true));
+        assert_eq!(matched("src/top.ts", false), None);
+        assert_eq!(matched("src/legacy/old.ts", false), Some(true));
+        assert_eq!(matched("src/legacy/keep.ts", false), Some(false));
+        assert_eq!(matched("src/app.ts", false), None);
+    }
+
+    #[test]
+    fn test_ignored_directories_ignore_their_files() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir(root.join(".git")).unwrap();
+        fs::create_dir_all(root.join("packages/app/src")).unwrap();
+        fs::write(root.join(".gitignore"), "build/\n").unwrap();
+        fs::write(root.join("packages/app/.krokignore"), "src/vendor.ts\n").unwrap();
+
+        let mut ignore_files = IgnoreFiles::default();
+        assert!(ignore_files.is_ignored(&root.join("packages/app/build/out.ts"), false));
+        assert!(ignore_files.is_ignored(&root.join("packages/app/src/vendor.ts"), false));
+        assert!(!ignore_files.is_ignored(&root.join("packages/app/src/main.ts"), false));
+    }
+
+    #[test]
+    fn test_project_root_ignore_file_outside_a_repository() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::create_dir_all(root.join("src/dist")).unwrap();
+        fs::write(root.join("package.json"), "{}").unwrap();
+        fs::write(root.join(".krokignore"), "dist/\ncoverage/\n*.d.ts\n").unwrap();
+
+        // Searching below the project root still reads its .krokignore
+        let src = root.join("src");
+        let walk_root = IgnoreFiles::walk_root(&src);
+        assert_eq!(walk_root, normalize(root));
+
+        let mut ignore_files = IgnoreFiles::default();
+        assert!(ignore_files.is_entry_ignored(&walk_root, &src, &src.join("dist"), true));
+        assert!(ignore_files.is_entry_ignored(&walk_root, &src, &src.join("types.d.ts"), false));
+        assert!(!ignore_files.is_entry_ignored(&walk_root, &src, &src.join("app.ts"), false));
+        assert!(ignore_files.is_ignored(&root.join("coverage/report.ts"), false));
+    }
+}
@@ -0,0 +1,264 @@
+use anyhow::{Context, Result};
+use std::collections::{HashMap, HashSet};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use swc_ecma_ast::{ModuleDecl, ModuleItem};
+
+use krokfmt_core::line_ending::{to_lf, with_line_break, LineEnding};
+use krokfmt_core::parser::TypeScriptParser;
+use krokfmt_core::transformer::{ImportAnalyzer, ImportCategory};
+
+use crate::file_handler::{display_path, FileHandler};
+use crate::module_graph::{normalize, resolve_path};
+use crate::tsconfig::TsconfigPaths;
+
+/// An import that reaches a module through a relative path climbing out of
+/// its directory, while the rest of the project uses the module's alias.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AliasMismatch {
+    pub file: PathBuf,
+    /// 1-based line of the specifier
+    pub line: usize,
+    pub specifier: String,
+    /// The alias form the specifier should be rewritten to
+    pub canonical: String,
+    /// Byte range of the specifier's text, inside its quotes
+    range: Range<usize>,
+}
+
+/// Modules imported through different specifiers across a project.
+///
+/// `import '@app/lib/util'` in one file and `import '../../lib/util'` in
+/// another name the same module, which hides that fact from anyone searching
+/// for its users and splits it between two import groups. Only imports that
+/// climb out of their directory (`../`) are flagged, and only for modules the
+/// project already imports by alias somewhere: `./` imports of a neighbor are
+/// local by choice.
+#[derive(Debug, Default)]
+pub struct AliasReport {
+    pub mismatches: Vec<AliasMismatch>,
+}
+
+struct ModuleReference {
+    specifier: String,
+    range: Range<usize>,
+    line: usize,
+}
+
+impl AliasReport {
+    /// Find alias mismatches among `files`, each resolved with the aliases
+    /// `tsconfig_for` gives for it.
+    ///
+    /// Specifiers are only resolved to the given files, so modules outside them
+    /// are never flagged.
+    pub fn build(
+        file_handler: &FileHandler,
+        files: &[PathBuf],
+        tsconfig_for: impl Fn(&Path) -> Option<Arc<TsconfigPaths>>,
+    ) -> Result<Self> {
+        let known: HashMap<PathBuf, &PathBuf> =
+            files.iter().map(|file| (normalize(file), file)).collect();
+        let mut aliased_targets = HashSet::new();
+        let mut candidates = Vec::new();
+
+        for file in files {
+            let Some(tsconfig) = tsconfig_for(file) else {
+                continue;
+            };
+            let Some(dir) = normalize(file).parent().map(Path::to_path_buf) else {
+                continue;
+            };
+
+            let content = file_handler.read_file(file)?;
+            for reference in module_references(&content, file)? {
+                let specifier = &reference.specifier;
+                if ImportAnalyzer::categorize_import(specifier) == ImportCategory::Relative {
+                    if !specifier.starts_with("../") {
+                        continue;
+                    }
+                    let written = normalize(&dir.join(specifier));
+                    let Some(target) = resolve_path(&written, &known) else {
+                        continue;
+                    };
+                    let Some(canonical) = tsconfig.alias_for(&written) else {
+                        continue;
+                    };
+                    // The alias has to name the same file, which other
+                    // `paths` entries listed first could make it not do
+                    if resolve_alias(&tsconfig, &canonical, &known) != Some(target) {
+                        continue;
+                    }
+                    candidates.push((
+                        target,
+                        AliasMismatch {
+                            file: file.clone(),
+                            line: reference.line,
+                            specifier: reference.specifier,
+                            canonical,
+                            range: reference.range,
+                        },
+                    ));
+                } else if let Some(target) = resolve_alias(&tsconfig, specifier, &known) {
+                    aliased_targets.insert(target);
+                }
+            }
+        }
+
+        let mismatches = candidates
+            .into_iter()
+            .filter(|(target, _)| aliased_targets.contains(target))
+            .map(|(_, mismatch)| mismatch)
+            .collect();
+
+        Ok(Self { mismatches })
+    }
+
+    /// Rewrite every mismatch to its alias and run each changed file through
+    /// `format`, returning how many files were written.
+    ///
+    /// Formatting afterwards moves the rewritten imports into their new import
+    /// group, so the result is what krokfmt would have produced had the alias
+    /// been used all along. Files are written with the line breaks
+    /// `line_ending` picks for them.
+    pub fn apply(
+        &self,
+        file_handler: &FileHandler,
+        line_ending: LineEnding,
+        format: impl Fn(&Path, &str) -> Result<String>,
+    ) -> Result<usize> {
+        let mut by_file: HashMap<&PathBuf, Vec<&AliasMismatch>> = HashMap::new();
+        for mismatch in &self.mismatches {
+            by_file.entry(&mismatch.file).or_default().push(mismatch);
+        }
+
+        let written = by_file.len();
+        for (file, mut mismatches) in by_file {
+            let raw = file_handler.read_file_raw(file)?;
+            let line_break = line_ending.line_break(&raw);
+            let mut content = to_lf(&raw).into_owned();
+
+            // Back to front, so earlier ranges stay valid
+            mismatches.sort_by_key(|mismatch| std::cmp::Reverse(mismatch.range.start));
+            for mismatch in mismatches {
+                if content.get(mismatch.range.clone()) != Some(mismatch.specifier.as_str()) {
+                    anyhow::bail!(
+                        "{} changed since it was analyzed; run the analysis again",
+                        display_path(file)
+                    );
+                }
+                content.replace_range(mismatch.range.clone(), &mismatch.canonical);
+            }
+
+            let formatted = format(file, &content)
+                .with_context(|| format!("Failed to format {}", display_path(file)))?;
+            file_handler.write_file(file, &with_line_break(formatted, line_break))?;
+        }
+
+        Ok(written)
+    }
+}
+
+fn resolve_alias<'a>(
+    tsconfig: &TsconfigPaths,
+    specifier: &str,
+    known: &HashMap<PathBuf, &'a PathBuf>,
+) -> Option<&'a PathBuf> {
+    tsconfig
+        .resolve(specifier)
+        .iter()
+        .find_map(|candidate| resolve_path(candidate, known))
+}
+
+/// Every module specifier of `content`'s imports and re-exports.
+fn module_references(content: &str, file: &Path) -> Result<Vec<ModuleReference>> {
+    let parsed = TypeScriptParser::parse_disambiguated(content, &file.to_string_lossy())
+        .with_context(|| format!("Failed to parse {}", display_path(file)))?;
+    let offset = |pos| parsed.parser.source_map.lookup_byte_offset(pos).pos.0 as usize;
+
+    Ok(parsed
+        .module
+        .body
+        .iter()
+        .filter_map(|item| match item {
+            ModuleItem::ModuleDecl(ModuleDecl::Import(import)) => Some(&*import.src),
+            ModuleItem::ModuleDecl(ModuleDecl::ExportAll(export)) => Some(&*export.src),
+            ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(export)) => export.src.as_deref(),
+            _ => None,
+        })
+        .filter_map(|src| {
+            // Inside the quotes
+            let range = offset(src.span.lo) + 1..offset(src.span.hi) - 1;
+            let specifier = content.get(range.clone())?.to_string();
+            // Escapes would make the text differ from the value; leave those be
+            (specifier == src.value.to_string()).then(|| ModuleReference {
+                line: content[..range.start].matches('\n').count() + 1,
+                specifier,
+                range,
+            })
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_deep_relative_imports_of_aliased_modules() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = normalize(temp_dir.path());
+        let files: Vec<_> = [
+            ("src/lib/util.ts", "export const util = 1;\n"),
+            ("src/lib/other.ts", "export const other = 1;\n"),
+            ("src/app/main.ts", "import { util } from '@app/lib/util';\n"),
+            (
+                "src/app/pages/home.ts",
+                "import { other } from '../../lib/other';\nimport { util } from \"../../lib/util\";\nimport { main } from '../main';\n",
+            ),
+        ]
+        .iter()
+        .map(|(name, content)| {
+            let path = root.join(name);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(&path, content).unwrap();
+            path
+        })
+        .collect();
+        fs::write(
+            root.join("tsconfig.json"),
+            r#"{ "compilerOptions": { "paths": { "@app/*": ["src/*"] } } }"#,
+        )
+        .unwrap();
+
+        let tsconfig = Arc::new(
+            TsconfigPaths::load(&root.join("tsconfig.json"))
+                .unwrap()
+                .unwrap(),
+        );
+        let file_handler = FileHandler::new(false);
+        let report = AliasReport::build(&file_handler, &files, |_| Some(tsconfig.clone())).unwrap();
+
+        // `other` is never imported by alias and `main` is imported by
+        // nobody else, so only `util` is inconsistent
+        assert_eq!(report.mismatches.len(), 1);
+        let mismatch = &report.mismatches[0];
+        assert_eq!(mismatch.file, files[3]);
+        assert_eq!(mismatch.line, 2);
+        assert_eq!(mismatch.specifier, "../../lib/util");
+        assert_eq!(mismatch.canonical, "@app/lib/util");
+
+        let written = report
+            .apply(&file_handler, LineEnding::Auto, |_, content| {
+                Ok(content.to_string())
+            })
+            .unwrap();
+        assert_eq!(written, 1);
+        assert_eq!(
+            fs::read_to_string(&files[3]).unwrap(),
+            "import { other } from '../../lib/other';\nimport { util } from \"@app/lib/util\";\nimport { main } from '../main';\n"
+        );
+    }
+}
@@ -0,0 +1,315 @@
+// Installs the subscriber that turns krokfmt-core's `tracing` output into the
+// CLI's --verbose and --profile diagnostics. Without the `tracing` feature the
+// pipeline emits nothing, so there is nothing to install.
+
+use anyhow::Result;
+
+/// Install a global subscriber that reports pipeline diagnostics on stderr.
+///
+/// `verbose` prints krokfmt's events, `profile` prints how long each pipeline
+/// span took once it closes. Does nothing when neither is requested, so the
+/// common case pays no tracing overhead at all.
+pub fn init(verbose: bool, profile: bool) -> Result<()> {
+    #[cfg(feature = "tracing")]
+    if verbose || profile {
+        use anyhow::Context;
+
+        let subscriber = StderrSubscriber::new(Box::new(std::io::stderr()), verbose, profile);
+        tracing::subscriber::set_global_default(subscriber)
+            .context("Failed to install diagnostics subscriber")?;
+    }
+
+    #[cfg(not(feature = "tracing"))]
+    if verbose || profile {
+        eprintln!("krokfmt was built without the `tracing` feature; diagnostics are unavailable");
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "tracing")]
+pub use subscriber::StderrSubscriber;
+
+#[cfg(feature = "tracing")]
+mod subscriber {
+    use std::cell::RefCell;
+    use std::collections::HashMap;
+    use std::fmt::{self, Write as _};
+    use std::io::Write;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use std::sync::Mutex;
+    use std::time::{Duration, Instant};
+
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::{Event, Metadata, Subscriber};
+
+    /// Minimal line-oriented subscriber for krokfmt's own diagnostics.
+    ///
+    /// We only need two things — print events, and time spans — which doesn't
+    /// justify pulling in a full subscriber stack. Anything outside the krokfmt
+    /// target (swc and Biome are instrumented too) is filtered out because it
+    /// drowns the pipeline view in parser internals.
+    pub struct StderrSubscriber {
+        writer: Mutex<Box<dyn Write + Send>>,
+        verbose: bool,
+        profile: bool,
+        next_id: AtomicU64,
+        spans: Mutex<HashMap<u64, SpanTiming>>,
+    }
+
+    struct SpanTiming {
+        name: &'static str,
+        fields: String,
+        busy: Duration,
+        refs: usize,
+    }
+
+    thread_local! {
+        // The spans this thread is in and when it entered them. Rayon workers
+        // enter the same span concurrently, so when a span was entered is a
+        // per-thread fact, not one of the span's.
+        static ENTERED: RefCell<Vec<(u64, Instant)>> = const { RefCell::new(Vec::new()) };
+    }
+
+    impl StderrSubscriber {
+        pub fn new(writer: Box<dyn Write + Send>, verbose: bool, profile: bool) -> Self {
+            Self {
+                writer: Mutex::new(writer),
+                verbose,
+                profile,
+                next_id: AtomicU64::new(1),
+                spans: Mutex::new(HashMap::new()),
+            }
+        }
+
+        fn write_line(&self, line: &str) {
+            let mut writer = self.writer.lock().unwrap_or_else(|e| e.into_inner());
+            // Diagnostics are best effort; a closed stderr must not fail formatting
+            let _ = writeln!(writer, "{line}");
+        }
+    }
+
+    impl Subscriber for StderrSubscriber {
+        fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+            if !metadata.target().starts_with("krokfmt") {
+                return false;
+            }
+
+            if metadata.is_span() {
+                self.profile
+            } else {
+                self.verbose
+            }
+        }
+
+        fn new_span(&self, span: &Attributes<'_>) -> Id {
+            let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+
+            let mut fields = FieldWriter::default();
+            span.record(&mut fields);
+
+            let mut spans = self.spans.lock().unwrap_or_else(|e| e.into_inner());
+            spans.insert(
+                id,
+                SpanTiming {
+                    name: span.metadata().name(),
+                    fields: fields.fields,
+                    busy: Duration::ZERO,
+                    refs: 1,
+                },
+            );
+
+            Id::from_u64(id)
+        }
+
+        fn record(&self, span: &Id, values: &Record<'_>) {
+            let mut fields = FieldWriter::default();
+            values.record(&mut fields);
+
+            let mut spans = self.spans.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some(timing) = spans.get_mut(&span.into_u64()) {
+                timing.fields.push_str(&fields.fields);
+            }
+        }
+
+        fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            let mut fields = FieldWriter::default();
+            event.record(&mut fields);
+
+            let metadata = event.metadata();
+            self.write_line(&format!(
+                "{} {}: {}{}",
+                metadata.level(),
+                metadata.target(),
+                fields.message,
+                fields.fields
+            ));
+        }
+
+        fn enter(&self, span: &Id) {
+            ENTERED.with_borrow_mut(|entered| entered.push((span.into_u64(), Instant::now())));
+        }
+
+        fn exit(&self, span: &Id) {
+            let id = span.into_u64();
+            let entered_at = ENTERED.with_borrow_mut(|entered| {
+                let index = entered.iter().rposition(|(entered, _)| *entered == id)?;
+                Some(entered.remove(index).1)
+            });
+
+            if let Some(entered_at) = entered_at {
+                let mut spans = self.spans.lock().unwrap_or_else(|e| e.into_inner());
+                if let Some(timing) = spans.get_mut(&id) {
+                    timing.busy += entered_at.elapsed();
+                }
+            }
+        }
+
+        fn clone_span(&self, span: &Id) -> Id {
+            let mut spans = self.spans.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some(timing) = spans.get_mut(&span.into_u64()) {
+                timing.refs += 1;
+            }
+            span.clone()
+        }
+
+        fn try_close(&self, span: Id) -> bool {
+            let closed = {
+                let mut spans = self.spans.lock().unwrap_or_else(|e| e.into_inner());
+                let id = span.into_u64();
+                match spans.get_mut(&id) {
+                    Some(timing) if timing.refs > 1 => {
+                        timing.refs -= 1;
+                        None
+                    }
+                    Some(_) => spans.remove(&id),
+                    None => None,
+                }
+            };
+
+            match closed {
+                Some(timing) => {
+                    // Timings are reported on close, when the total busy time is known
+                    self.write_line(&format!(
+                        "{}{} took {:.2?}",
+                        timing.name, timing.fields, timing.busy
+                    ));
+                    true
+                }
+                None => false,
+            }
+        }
+    }
+
+    #[derive(Default)]
+    struct FieldWriter {
+        message: String,
+        fields: String,
+    }
+
+    impl Visit for FieldWriter {
+        fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+            if field.name() == "message" {
+                let _ = write!(self.message, "{value:?}");
+            } else {
+                let _ = write!(self.fields, " {}={:?}", field.name(), value);
+            }
+        }
+
+        fn record_str(&mut self, field: &Field, value: &str) {
+            if field.name() == "message" {
+                self.message.push_str(value);
+            } else {
+                let _ = write!(self.fields, " {}={}", field.name(), value);
+            }
+        }
+    }
+}
+
+#[cfg(all(test, feature = "tracing"))]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn capture(verbose: bool, profile: bool) -> String {
+        let buffer = SharedBuffer::default();
+        let subscriber = StderrSubscriber::new(Box::new(buffer.clone()), verbose, profile);
+
+        tracing::subscriber::with_default(subscriber, || {
+            crate::format_typescript("import b from 'b';\nimport a from 'a';\n", "test.ts")
+                .unwrap();
+        });
+
+        let output = buffer.0.lock().unwrap().clone();
+        String::from_utf8(output).unwrap()
+    }
+
+    #[test]
+    fn test_profile_reports_pipeline_span_timings() {
+        let output = capture(false, true);
+
+        assert!(output.contains("parse file=test.ts took"), "{output}");
+        assert!(output.contains("organize took"), "{output}");
+        assert!(output.contains("biome took"), "{output}");
+        assert!(output.contains("format file=test.ts took"), "{output}");
+        // Events are only shown in verbose mode
+        assert!(!output.contains("DEBUG"), "{output}");
+    }
+
+    #[test]
+    fn test_profile_sums_concurrent_enters_of_a_span() {
+        let buffer = SharedBuffer::default();
+        let subscriber = StderrSubscriber::new(Box::new(buffer.clone()), false, true);
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::debug_span!("work");
+            std::thread::scope(|scope| {
+                for _ in 0..2 {
+                    let span = span.clone();
+                    scope.spawn(move || {
+                        let _entered = span.enter();
+                        std::thread::sleep(std::time::Duration::from_millis(50));
+                    });
+                }
+            });
+        });
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let took = output
+            .lines()
+            .find_map(|line| line.strip_prefix("work took "))
+            .unwrap_or_else(|| panic!("{output}"));
+        let millis: f64 = match took.strip_suffix("ms") {
+            Some(millis) => millis.parse().unwrap(),
+            None => took.strip_suffix('s').unwrap().parse::<f64>().unwrap() * 1000.0,
+        };
+
+        // Both threads were busy in the span, so their time adds up
+        assert!(millis >= 100.0, "{output}");
+    }
+
+    #[test]
+    fn test_verbose_reports_pipeline_events() {
+        let output = capture(true, false);
+
+        assert!(output.contains("DEBUG krokfmt"), "{output}");
+        assert!(!output.contains(" took "), "{output}");
+    }
+}
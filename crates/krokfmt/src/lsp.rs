@@ -0,0 +1,389 @@
+//! A language server speaking just enough LSP to format documents.
+//!
+//! Editors keep the server running and hand it the open buffer, so formatting
+//! on save costs neither a process start nor a trip through the disk. Messages
+//! are JSON-RPC over stdio, framed by `Content-Length` headers.
+
+use anyhow::{bail, Context, Result};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
+
+use krokfmt_core::diff::{diff_hunks, DiffLine, Hunk};
+use krokfmt_core::node_selection::splice_lines;
+
+/// JSON-RPC error code for a method the server doesn't implement.
+const METHOD_NOT_FOUND: i64 = -32601;
+/// LSP error code for a valid request that failed, like formatting a buffer
+/// that doesn't parse.
+const REQUEST_FAILED: i64 = -32803;
+
+/// Full document sync: every change sends the whole buffer, which is all a
+/// whole-file formatter needs.
+const TEXT_DOCUMENT_SYNC_FULL: u8 = 1;
+
+/// Serves `textDocument/formatting` and `textDocument/rangeFormatting`,
+/// formatting buffers with `format(path, source)`.
+pub struct LanguageServer<F> {
+    format: F,
+    /// Open documents by URI, as the client last sent them
+    documents: HashMap<String, String>,
+    shutdown_requested: bool,
+}
+
+impl<F> LanguageServer<F>
+where
+    F: FnMut(&Path, &str) -> Result<String>,
+{
+    pub fn new(format: F) -> Self {
+        Self {
+            format,
+            documents: HashMap::new(),
+            shutdown_requested: false,
+        }
+    }
+
+    /// Answer messages from `input` on `output` until the client sends `exit`
+    /// or closes the stream.
+    ///
+    /// Returns whether `shutdown` came first; the protocol asks servers to
+    /// exit with 1 when it didn't.
+    pub fn run(mut self, mut input: impl BufRead, mut output: impl Write) -> Result<bool> {
+        while let Some(message) = read_message(&mut input)? {
+            if message["method"] == "exit" {
+                break;
+            }
+            if let Some(response) = self.handle(&message) {
+                write_message(&mut output, &response)?;
+            }
+        }
+        Ok(self.shutdown_requested)
+    }
+
+    /// The response to `message`, or `None` for notifications.
+    fn handle(&mut self, message: &Value) -> Option<Value> {
+        let method = message["method"].as_str().unwrap_or_default();
+        let params = &message["params"];
+
+        let Some(id) = message.get("id") else {
+            self.notify(method, params);
+            return None;
+        };
+
+        Some(match self.request(method, params) {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, error)) => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": code, "message": error },
+            }),
+        })
+    }
+
+    fn notify(&mut self, method: &str, params: &Value) {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+        match method {
+            "textDocument/didOpen" => {
+                let text = params["textDocument"]["text"].as_str().unwrap_or_default();
+                self.documents.insert(uri.to_string(), text.to_string());
+            }
+            "textDocument/didChange" => {
+                // With full sync the last change holds the whole buffer
+                let changes = params["contentChanges"].as_array();
+                if let Some(text) = changes.and_then(|changes| changes.last()?["text"].as_str()) {
+                    self.documents.insert(uri.to_string(), text.to_string());
+                }
+            }
+            "textDocument/didClose" => {
+                self.documents.remove(uri);
+            }
+            _ => {}
+        }
+    }
+
+    fn request(&mut self, method: &str, params: &Value) -> Result<Value, (i64, String)> {
+        match method {
+            "initialize" => Ok(json!({
+                "capabilities": {
+                    "textDocumentSync": TEXT_DOCUMENT_SYNC_FULL,
+                    "documentFormattingProvider": true,
+                    "documentRangeFormattingProvider": true,
+                },
+                "serverInfo": {
+                    "name": "krokfmt",
+                    "version": env!("CARGO_PKG_VERSION"),
+                },
+            })),
+            "shutdown" => {
+                self.shutdown_requested = true;
+                Ok(Value::Null)
+            }
+            "textDocument/formatting" => self.format_document(params, None),
+            "textDocument/rangeFormatting" => {
+                let range = &params["range"];
+                let lines = range["start"]["line"]
+                    .as_u64()
+                    .zip(range["end"]["line"].as_u64());
+                self.format_document(params, lines)
+            }
+            _ => Err((METHOD_NOT_FOUND, format!("Unknown method {method}"))),
+        }
+    }
+
+    /// The edits formatting the document needs, only for the statements
+    /// touching the 0-based, inclusive `lines` when given.
+    fn format_document(
+        &mut self,
+        params: &Value,
+        lines: Option<(u64, u64)>,
+    ) -> Result<Value, (i64, String)> {
+        let uri = params["textDocument"]["uri"].as_str().unwrap_or_default();
+        let source = self
+            .documents
+            .get(uri)
+            .ok_or_else(|| (REQUEST_FAILED, format!("{uri} is not open")))?;
+        let path = uri_to_path(uri);
+
+        let failed = |e: anyhow::Error| (REQUEST_FAILED, format!("{e:#}"));
+        let mut formatted = (self.format)(&path, source).map_err(failed)?;
+
+        // Statements are formatted as part of the whole file, then only those in
+        // range are taken over, where they were
+        if let Some((first, last)) = lines {
+            let filename = path.to_string_lossy();
+            let lines = first as usize + 1..=last as usize + 1;
+            formatted = splice_lines(source, &formatted, &filename, lines).map_err(failed)?;
+        }
+
+        let edits: Vec<Value> = diff_hunks(source, &formatted, 0)
+            .iter()
+            .map(text_edit)
+            .collect();
+        Ok(Value::Array(edits))
+    }
+}
+
+/// A hunk without context as an LSP `TextEdit` replacing whole lines, so no
+/// column ever needs converting to UTF-16.
+fn text_edit(hunk: &Hunk) -> Value {
+    let start = hunk.old_start - 1;
+    let end = start + hunk.old_lines;
+    let new_text: String = hunk
+        .lines
+        .iter()
+        .filter_map(|line| match line {
+            DiffLine::Added(text) => Some(format!("{text}\n")),
+            DiffLine::Context(_) | DiffLine::Removed(_) => None,
+        })
+        .collect();
+
+    json!({
+        "range": {
+            "start": { "line": start, "character": 0 },
+            "end": { "line": end, "character": 0 },
+        },
+        "newText": new_text,
+    })
+}
+
+/// The path of a `file://` URI, percent-decoded. Other schemes (unsaved
+/// buffers) keep the URI as the path, which still carries the extension.
+fn uri_to_path(uri: &str) -> PathBuf {
+    let Some(path) = uri.strip_prefix("file://") else {
+        return PathBuf::from(uri);
+    };
+
+    let mut bytes = Vec::with_capacity(path.len());
+    let mut rest = path.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        let decoded = (byte == b'%')
+            .then(|| tail.get(..2))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match decoded {
+            Some(decoded) => {
+                bytes.push(decoded);
+                rest = &tail[2..];
+            }
+            None => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+    let path = String::from_utf8_lossy(&bytes).into_owned();
+
+    // `file:///C:/src/app.ts` on Windows
+    match path.strip_prefix('/') {
+        Some(drive) if drive.as_bytes().get(1) == Some(&b':') => PathBuf::from(drive),
+        _ => PathBuf::from(path),
+    }
+}
+
+/// Read one message, or `None` once the client closed the stream.
+fn read_message(input: &mut impl BufRead) -> Result<Option<Value>> {
+    let mut length = None;
+    loop {
+        let mut header = String::new();
+        if input.read_line(&mut header)? == 0 {
+            return Ok(None);
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("Content-Length") {
+                length = Some(
+                    value
+                        .trim()
+                        .parse::<usize>()
+                        .context("Invalid Content-Length")?,
+                );
+            }
+        }
+    }
+
+    let Some(length) = length else {
+        bail!("Message without a Content-Length header");
+    };
+    let mut body = vec![0; length];
+    input
+        .read_exact(&mut body)
+        .context("Failed to read message")?;
+    serde_json::from_slice(&body)
+        .context("Invalid JSON-RPC message")
+        .map(Some)
+}
+
+fn write_message(output: &mut impl Write, message: &Value) -> Result<()> {
+    let body = serde_json::to_string(message)?;
+    write!(output, "Content-Length: {}\r\n\r\n{body}", body.len())?;
+    output.flush().context("Failed to write message")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(messages: &[Value]) -> Vec<u8> {
+        let mut input = Vec::new();
+        for message in messages {
+            write_message(&mut input, message).unwrap();
+        }
+        input
+    }
+
+    fn responses(output: &[u8]) -> Vec<Value> {
+        let mut output = output;
+        std::iter::from_fn(|| read_message(&mut output).unwrap()).collect()
+    }
+
+    fn collapse_spaces(_: &Path, source: &str) -> Result<String> {
+        Ok(source.replace("  ", " "))
+    }
+
+    #[test]
+    fn test_formatting_session() {
+        let uri = "file:///work/src/my%20app.ts";
+        let input = frame(&[
+            json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} }),
+            json!({ "jsonrpc": "2.0", "method": "initialized", "params": {} }),
+            json!({
+                "jsonrpc": "2.0",
+                "method": "textDocument/didOpen",
+                "params": { "textDocument": { "uri": uri, "text": "let x;\nconst  a = 1;\n" } },
+            }),
+            json!({
+                "jsonrpc": "2.0",
+                "id": 2,
+                "method": "textDocument/formatting",
+                "params": { "textDocument": { "uri": uri } },
+            }),
+            json!({ "jsonrpc": "2.0", "id": 3, "method": "shutdown" }),
+            json!({ "jsonrpc": "2.0", "method": "exit" }),
+        ]);
+
+        let mut output = Vec::new();
+        let server = LanguageServer::new(collapse_spaces);
+        assert!(server.run(input.as_slice(), &mut output).unwrap());
+
+        let responses = responses(&output);
+        assert_eq!(responses.len(), 3);
+        assert_eq!(
+            responses[0]["result"]["capabilities"]["documentFormattingProvider"],
+            true
+        );
+        assert_eq!(
+            responses[1]["result"],
+            json!([{
+                "range": {
+                    "start": { "line": 1, "character": 0 },
+                    "end": { "line": 2, "character": 0 },
+                },
+                "newText": "const a = 1;\n",
+            }])
+        );
+        assert_eq!(responses[2]["result"], Value::Null);
+    }
+
+    #[test]
+    fn test_range_formatting_only_touches_the_range() {
+        let mut server = LanguageServer::new(collapse_spaces);
+        server.documents.insert(
+            "file:///a.ts".to_string(),
+            "const  a = 1;\nlet x;\nconst  b = 2;\n".to_string(),
+        );
+
+        let edits = server
+            .request(
+                "textDocument/rangeFormatting",
+                &json!({
+                    "textDocument": { "uri": "file:///a.ts" },
+                    "range": {
+                        "start": { "line": 2, "character": 0 },
+                        "end": { "line": 2, "character": 4 },
+                    },
+                }),
+            )
+            .unwrap();
+        assert_eq!(edits.as_array().unwrap().len(), 1);
+        assert_eq!(edits[0]["range"]["start"]["line"], 2);
+    }
+
+    #[test]
+    fn test_failed_formatting_is_an_error_response() {
+        let mut server = LanguageServer::new(|_: &Path, _: &str| bail!("Failed to parse file"));
+        server
+            .documents
+            .insert("file:///a.ts".to_string(), "let".to_string());
+
+        let response = server
+            .handle(&json!({
+                "jsonrpc": "2.0",
+                "id": 7,
+                "method": "textDocument/formatting",
+                "params": { "textDocument": { "uri": "file:///a.ts" } },
+            }))
+            .unwrap();
+        assert_eq!(response["id"], 7);
+        assert_eq!(response["error"]["code"], REQUEST_FAILED);
+    }
+
+    #[test]
+    fn test_uri_to_path() {
+        assert_eq!(
+            uri_to_path("file:///work/my%20app/index.tsx"),
+            PathBuf::from("/work/my app/index.tsx")
+        );
+        assert_eq!(
+            uri_to_path("file:///C:/src/app.ts"),
+            PathBuf::from("C:/src/app.ts")
+        );
+        assert_eq!(
+            uri_to_path("untitled:Untitled-1.ts"),
+            PathBuf::from("untitled:Untitled-1.ts")
+        );
+    }
+}
@@ -1,12 +1,41 @@
 use anyhow::{Context, Result};
-use clap::Parser;
+use clap::{Args, Parser, Subcommand};
 use colored::Colorize;
 use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
 use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Arc};
 
 use krokfmt::{
-    biome_formatter::BiomeFormatter, comment_formatter::CommentFormatter,
-    file_handler::FileHandler, parser::TypeScriptParser,
+    config::{Config, INIT_FILE_NAME},
+    diff::{colorize_patch, DiffFormat, FileDiff},
+    embedded_extractor::format_script_blocks,
+    file_handler::{display_path, FileHandler, WriteMode, DEFAULT_BACKUP_RETENTION},
+    file_results::FileOutcome,
+    fingerprint::{semantic_changes, SemanticChange},
+    format_cache::{FormatCache, CACHE_FILE_NAME},
+    generated_detector::GeneratedFileDetector,
+    import_aliases::AliasReport,
+    invariants::{comment_changes, idempotency_violation, Violation},
+    line_ending::{to_lf, with_line_break, LineEnding},
+    logging,
+    lsp::LanguageServer,
+    module_graph::{GraphFormat, ModuleGraph},
+    node_selection::{splice_selected, NodeSelection},
+    organizer::ChangeLog,
+    package_json_formatter::PackageJsonFormatter,
+    pipeline,
+    project_context::{ProjectContext, ProjectContextCache},
+    report::{FileReport, Reordering, Reporter},
+    rules::RuleSet,
+    segmented::{format_segmented, ChangeDetector},
+    transformer::{ImportFixes, ImportProfile, ProjectLayout},
+    tsconfig::TsconfigPaths,
+    versioning::OutputVersion,
+    watcher::{FileWatcher, DEFAULT_DEBOUNCE},
+    workspaces::project_layout,
+    FormatOptions,
 };
 
 /// Command-line interface for krokfmt.
@@ -19,7 +48,11 @@ use krokfmt::{
 #[command(author = "krokorok")]
 #[command(version)]
 #[command(about = "A highly opinionated TypeScript code formatter", long_about = None)]
+#[command(args_conflicts_with_subcommands = true)]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
     #[arg(help = "Files or directories to format")]
     paths: Vec<PathBuf>,
 
@@ -33,6 +66,90 @@ struct Cli {
     )]
     check: bool,
 
+    // Adopting a formatter that reorders code is a leap of faith; this turns it
+    // into a CI-enforced guarantee that nothing it would change alters behavior.
+    #[arg(
+        long,
+        requires = "check",
+        help = "With --check, also fail if formatting would change a file's semantics"
+    )]
+    verify_semantics: bool,
+
+    // Reordering is only safe as long as krokfmt has no bugs. Every output is
+    // checked for the same semantics before it replaces a file, unless speed
+    // matters more than the safety net.
+    #[arg(
+        long,
+        help = "Write output without checking that it keeps each file's semantics"
+    )]
+    no_safety_check: bool,
+
+    // Shell aliases and wrapper scripts often bake in --no-safety-check; a
+    // single run that needs the guard can ask for it back.
+    #[arg(
+        long,
+        help = "Check that output keeps each file's semantics before writing it, even with --no-safety-check"
+    )]
+    safe: bool,
+
+    // A formatter that isn't idempotent or loses a comment makes every later
+    // run a surprise; CI is the place to catch that before it's committed.
+    #[arg(
+        long,
+        requires = "check",
+        help = "With --check, also fail if formatting isn't idempotent or drops or adds comments"
+    )]
+    strict: bool,
+
+    // Non-idempotent output is the bug users notice first: every run touches
+    // the same files again. A dedicated exit code lets CI tell it apart from
+    // files that simply need formatting.
+    #[arg(
+        long,
+        visible_alias = "verify-stable",
+        help = "Format each changed file twice and fail with exit code 3 if the passes differ"
+    )]
+    verify_idempotent: bool,
+
+    // A list of file names says that something's wrong, not what; CI logs and
+    // review bots need the actual change to be useful.
+    #[arg(
+        long,
+        requires = "check",
+        help = "With --check, print what would change as unified diffs"
+    )]
+    diff: bool,
+
+    #[arg(
+        long = "format",
+        value_name = "FORMAT",
+        requires = "diff",
+        default_value_t = DiffFormat::Text,
+        help = "Diff output format: text or json"
+    )]
+    diff_format: DiffFormat,
+
+    // CI systems and pre-commit frameworks want results they can parse, not
+    // colored text to scrape.
+    #[arg(
+        long,
+        value_name = "REPORTER",
+        default_value_t = Reporter::Text,
+        conflicts_with_all = ["diff", "stdout", "watch", "lsp", "stdin"],
+        help = "How to report results: text, or json for one JSON object per file and line"
+    )]
+    reporter: Reporter,
+
+    // Reordering is easier to trust once it says why things moved: which
+    // imports changed groups, what was hoisted for what used it, which enums
+    // were sorted.
+    #[arg(
+        long,
+        conflicts_with_all = ["diff", "stdout", "watch", "lsp", "stdin"],
+        help = "Explain what formatting would reorder in each file, without writing it"
+    )]
+    explain: bool,
+
     // stdout mode was added for editor integrations and quick previews.
     // Many editors expect formatters to output to stdout for real-time formatting.
     #[arg(
@@ -41,14 +158,472 @@ struct Cli {
     )]
     stdout: bool,
 
+    // Editors format unsaved buffers, which only exist in memory. Piping them
+    // through stdin keeps disk out of it entirely.
+    #[arg(
+        long,
+        conflicts_with = "paths",
+        help = "Read source from stdin and print the formatted result to stdout"
+    )]
+    stdin: bool,
+
+    // The buffer's path decides JSX parsing and which project's krokfmt.toml
+    // applies, so it's formatted exactly as it would be on disk.
+    #[arg(
+        long,
+        value_name = "PATH",
+        requires = "stdin",
+        help = "Path the stdin source is formatted as (default: stdin.ts)"
+    )]
+    stdin_filepath: Option<PathBuf>,
+
+    // Starting krokfmt on every save pays for process startup each time; a
+    // warm process only pays for the files that actually changed.
+    #[arg(
+        long,
+        conflicts_with_all = ["check", "stdout", "stdin"],
+        help = "Keep running and format files as they change"
+    )]
+    watch: bool,
+
+    // Editors that speak LSP can format on save through one long-lived
+    // process instead of shelling out for every buffer.
+    #[arg(
+        long,
+        conflicts_with_all = ["paths", "check", "stdout", "stdin", "watch"],
+        help = "Run a language server on stdio that formats open documents"
+    )]
+    lsp: bool,
+
     // Backups were made opt-out rather than opt-in because we've seen too many
     // formatters corrupt files due to parser bugs. Better safe than sorry.
-    #[arg(long, help = "Skip creating backups of original files")]
+    #[arg(long, global = true, help = "Skip creating backups of original files")]
     no_backup: bool,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "N",
+        default_value_t = DEFAULT_BACKUP_RETENTION,
+        help = "Backups to keep per file in .krokfmt/backups, newest first (0 keeps all)"
+    )]
+    backup_retention: usize,
+
+    // package.json is opt-in because it isn't TypeScript and some teams already
+    // run a dedicated tool for it. For everyone else, one invocation is nicer.
+    #[arg(
+        long,
+        global = true,
+        help = "Also normalize key order in package.json files found under the given paths"
+    )]
+    include_package_json: bool,
+
+    // Interop escape hatch for teams that keep an import-sorting lint rule enabled.
+    // Without a matching profile the linter and krokfmt would rewrite each other's
+    // output forever.
+    #[arg(
+        long,
+        global = true,
+        value_name = "PROFILE",
+        default_value_t = ImportProfile::Krokfmt,
+        help = "Import ordering profile: krokfmt or simple-import-sort"
+    )]
+    import_profile: ImportProfile,
+
+    // Opt-in because dependency order is sometimes used to mirror the order
+    // values appear in the callback, and sorting would fight that convention.
+    #[arg(
+        long,
+        global = true,
+        help = "Alphabetize identifier-only React hook dependency arrays (useEffect, useMemo, ...)"
+    )]
+    sort_hook_deps: bool,
+
+    // Classic-runtime React codebases often keep the React import first by
+    // convention, and alphabetical order would move it.
+    #[arg(
+        long,
+        global = true,
+        help = "Keep `import React from 'react'` first regardless of sort order"
+    )]
+    react_first: bool,
+
+    // Re-export order in barrel files is usually curated, so it's kept unless a
+    // file says `// krokfmt-sort-exports`; this applies that to every file.
+    #[arg(
+        long,
+        global = true,
+        help = "Sort re-export statements alphabetically within each category in every file"
+    )]
+    sort_exports: bool,
+
+    // Configuration-style constants (`const MAX_RETRIES = 3`) read best as one
+    // block, but pulling them out of alphabetical order is a matter of taste.
+    #[arg(
+        long,
+        global = true,
+        help = "Group SCREAMING_CASE primitive constants before other declarations"
+    )]
+    group_constants: bool,
+
+    // Type-only imports vanish from the output, so some teams read them as a
+    // block apart from the imports that run; others keep one order by source.
+    #[arg(
+        long,
+        global = true,
+        help = "Put type-only imports after the value imports of each group"
+    )]
+    group_type_imports: bool,
+
+    // Reindenting every line of a codebase drowns out the changes that matter,
+    // so repos on 4 spaces or tabs can keep what they have, file by file.
+    #[arg(
+        long,
+        global = true,
+        help = "Keep each file's existing indentation (2 or 4 spaces, or tabs) instead of 2 spaces"
+    )]
+    detect_indent: bool,
+
+    // Adopting krokfmt, or reviewing what it does, is easier one construct at a
+    // time. Everything outside the selected kinds is copied from the original
+    // byte for byte, rather than merely left unsorted.
+    #[arg(
+        long,
+        global = true,
+        value_name = "KINDS",
+        help = "Only format these top-level constructs: imports, exports, classes, functions, types, enums"
+    )]
+    only_nodes: Option<NodeSelection>,
+
+    // Many teams want sorted imports but find moving declarations around too
+    // invasive. This switches every rule group off, whatever krokfmt.toml says,
+    // so the flags opting into more sorting contradict it.
+    #[arg(
+        long,
+        global = true,
+        conflicts_with_all = ["sort_hook_deps", "sort_exports"],
+        help = "Only organize imports, keeping declarations and their contents in their original order"
+    )]
+    imports_only: bool,
+
+    // Hand-written group banners are always dropped because sorting would strand
+    // them above the wrong group. Regenerating them is opt-in since most
+    // codebases rely on blank lines alone.
+    #[arg(
+        long,
+        global = true,
+        help = "Label each import group with a banner comment like `// External`"
+    )]
+    import_group_banners: bool,
+
+    // Import hygiene fixes each change what an import means to some toolchain
+    // (a default import of a CommonJS module is undefined without
+    // esModuleInterop), so every one of them is opt-in and separate.
+    #[arg(
+        long,
+        global = true,
+        value_name = "MODULE",
+        value_delimiter = ',',
+        help = "Rewrite default imports of these CommonJS modules as namespace imports"
+    )]
+    namespace_import: Vec<String>,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Remove repeated side-effect imports of the same module"
+    )]
+    dedupe_side_effect_imports: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Turn `import {} from 'x'` into the side-effect import `import 'x'`"
+    )]
+    collapse_empty_imports: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Merge imports of the same module into one declaration"
+    )]
+    merge_duplicate_imports: bool,
+
+    // Files importing modules only for what loading them does can opt out
+    // with `// krokfmt-keep-unused-imports`
+    #[arg(
+        long,
+        global = true,
+        help = "Remove imported names the file never uses"
+    )]
+    remove_unused_imports: bool,
+
+    // Only the requires heading a file are converted, and .cjs/.cts files,
+    // which Node always loads as CommonJS, are left alone
+    #[arg(
+        long,
+        global = true,
+        help = "Turn top-level `const x = require('y')` into `import x from 'y'` where it's safe"
+    )]
+    convert_requires: bool,
+
+    // `./dir/index` -> `./dir` stops resolving under Node's own ESM loader,
+    // which doesn't look for index files, so this is for bundled code only
+    #[arg(
+        long,
+        global = true,
+        help = "Single-quote import paths, drop a trailing `/index` and collapse `..` segments"
+    )]
+    normalize_import_paths: bool,
+
+    // Multi-megabyte generated files can take many times their size in memory
+    // through the whole pipeline. Segmenting bounds that, at the cost of only
+    // organizing declarations within each segment, so it's opt-in.
+    #[arg(
+        long,
+        global = true,
+        value_name = "BYTES",
+        help = "Format files larger than this in segments of about this size, streaming the output"
+    )]
+    segment_threshold: Option<usize>,
+
+    // Generated code is skipped by default because reformatting it only creates
+    // churn that the next codegen run reverts. Some teams do commit hand-tuned
+    // "generated" files though, so this has to be overridable.
+    #[arg(
+        long,
+        global = true,
+        help = "Also format files marked @generated, DO NOT EDIT, or linguist-generated"
+    )]
+    include_generated: bool,
+
+    // Both flags surface the library's tracing output, so bug reports about odd
+    // comment placement or slow files can include what the pipeline actually did.
+    #[arg(
+        short,
+        long,
+        global = true,
+        help = "Print pipeline diagnostics to stderr"
+    )]
+    verbose: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Print how long each pipeline stage took to stderr"
+    )]
+    profile: bool,
+
+    // Using every core is right on a laptop, but on shared CI runners it starves
+    // whatever else is scheduled there. The env var lets CI set this once.
+    #[arg(
+        long,
+        global = true,
+        value_name = "N",
+        env = "KROKFMT_THREADS",
+        help = "Number of formatting threads (default: one per core)"
+    )]
+    threads: Option<usize>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "N",
+        help = "Limit concurrent file reads and writes, for network filesystems"
+    )]
+    io_throttle: Option<usize>,
+
+    // In-place writes keep hard links and inodes intact, which some file
+    // watchers and build tools rely on, so atomic replacement is opt-in
+    #[arg(
+        long,
+        global = true,
+        value_name = "MODE",
+        default_value_t = WriteMode::InPlace,
+        help = "How to write formatted files: in-place, or atomic to write a synced temporary file and rename it over the original"
+    )]
+    write_mode: WriteMode,
+
+    // Files keep the line endings they were written with, so a checkout made
+    // with Git's autocrlf doesn't come back as a wall of changed lines.
+    #[arg(
+        long,
+        value_name = "ENDING",
+        default_value_t = LineEnding::Auto,
+        help = "Line endings of formatted files: auto to keep each file's dominant one, lf or crlf"
+    )]
+    line_ending: LineEnding,
+
+    // Directory walks already skip what .gitignore and .krokignore ignore; this
+    // covers what's tracked but shouldn't be formatted, without an ignore file.
+    #[arg(
+        long,
+        global = true,
+        value_name = "GLOB",
+        help = "Skip files and directories matching a glob (repeatable)"
+    )]
+    exclude: Vec<String>,
+
+    // Sometimes the ignored files are exactly the ones to format, like checked
+    // in build output that's reformatted before a release.
+    #[arg(
+        long,
+        global = true,
+        help = "Don't skip files ignored by .gitignore or .krokignore"
+    )]
+    no_ignore: bool,
+
+    // Monorepos sometimes keep one shared config outside every package, and CI
+    // may want to try a config before committing it.
+    #[arg(
+        long,
+        global = true,
+        value_name = "PATH",
+        help = "Use this krokfmt.toml for every file instead of discovering one per project"
+    )]
+    config: Option<PathBuf>,
+
+    // CI pins the krokfmt version so that an unrelated toolchain bump can never
+    // sneak a repository-wide reformat into someone's feature branch.
+    #[arg(
+        long,
+        help = "Fail if this krokfmt would format differently than the version in krokfmt.toml"
+    )]
+    frozen: bool,
+
+    // Most files in a large monorepo haven't changed since the last run, and
+    // reading them is far cheaper than formatting them again.
+    #[arg(
+        long,
+        conflicts_with_all = ["watch", "stdin", "lsp"],
+        help = "Skip files found formatted by an earlier run, recorded in .krokfmt-cache"
+    )]
+    cache: bool,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Report, and optionally apply, the formatting changes introduced by
+    /// upgrading from an older krokfmt
+    Migrate(MigrateArgs),
+
+    /// Write a starter .krokrc.toml declaring which files krokfmt formats,
+    /// pinned to this version
+    Init {
+        #[arg(help = "Directory to write the config to (defaults to .)")]
+        dir: Option<PathBuf>,
+
+        #[arg(long, help = "Replace an existing config")]
+        force: bool,
+    },
+
+    /// Put files back the way they were before krokfmt last wrote them, from
+    /// their most recent backup in .krokfmt/backups
+    Restore {
+        #[arg(required = true, help = "Files to restore")]
+        paths: Vec<PathBuf>,
+    },
+
+    /// Inspect krokfmt.toml
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+
+    /// Report on how the files of a codebase relate to each other
+    Analyze {
+        #[command(subcommand)]
+        command: AnalyzeCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConfigCommand {
+    /// Validate krokfmt.toml and report every problem in it
+    Check {
+        #[arg(help = "Config file, or a directory to look for one in (defaults to .)")]
+        path: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+enum AnalyzeCommand {
+    /// Print the module import graph for Graphviz or other tooling
+    Graph(GraphArgs),
+
+    /// Find modules imported both by tsconfig path alias and by deep relative
+    /// path, and optionally rewrite the relative imports to the alias
+    Aliases(AliasArgs),
+}
+
+#[derive(Args)]
+struct GraphArgs {
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        default_value_t = GraphFormat::Dot,
+        help = "Output format: dot or json"
+    )]
+    format: GraphFormat,
+
+    #[arg(required = true, help = "Files or directories to analyze")]
+    paths: Vec<PathBuf>,
+}
+
+#[derive(Args)]
+struct AliasArgs {
+    #[arg(
+        long,
+        help = "Rewrite the affected imports instead of only listing them"
+    )]
+    apply: bool,
+
+    #[arg(required = true, help = "Files or directories to analyze")]
+    paths: Vec<PathBuf>,
+}
+
+#[derive(Args)]
+struct MigrateArgs {
+    #[arg(
+        long,
+        value_name = "VERSION",
+        help = "krokfmt version the code was formatted with"
+    )]
+    from: OutputVersion,
+
+    #[arg(long, help = "Rewrite the affected files instead of only listing them")]
+    apply: bool,
+
+    #[arg(required = true, help = "Files or directories to migrate")]
+    paths: Vec<PathBuf>,
 }
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
+    logging::init(cli.verbose, cli.profile)?;
+
+    match &cli.command {
+        Some(Command::Migrate(args)) => return run_migrate(&cli, args),
+        Some(Command::Init { dir, force }) => return run_init(dir.as_deref(), *force),
+        Some(Command::Restore { paths }) => return run_restore(&cli, paths),
+        Some(Command::Config {
+            command: ConfigCommand::Check { path },
+        }) => return run_config_check(path.as_deref()),
+        Some(Command::Analyze {
+            command: AnalyzeCommand::Graph(args),
+        }) => return run_graph(&cli, args),
+        Some(Command::Analyze {
+            command: AnalyzeCommand::Aliases(args),
+        }) => return run_aliases(&cli, args),
+        None => {}
+    }
+
+    if cli.stdin {
+        return run_stdin(&cli);
+    }
+    if cli.lsp {
+        return run_lsp(&cli);
+    }
 
     // Early exit with clear error - we chose to make this a hard error rather than
     // defaulting to current directory to prevent accidental mass reformatting.
@@ -57,49 +632,241 @@ fn main() -> Result<()> {
         std::process::exit(1);
     }
 
-    let file_handler = FileHandler::new(!cli.no_backup);
-    let files = file_handler.find_typescript_files(&cli.paths)?;
-
-    if files.is_empty() {
-        println!("{}", "No TypeScript files found".yellow());
-        return Ok(());
+    let mut file_handler = build_file_handler(&cli)?;
+    if cli.watch {
+        return run_watch(&cli, &file_handler);
     }
+    if cli.cache {
+        file_handler = file_handler.with_cache(FormatCache::load(Path::new(CACHE_FILE_NAME)));
+    }
+
+    // JSON goes to stdout and nothing may come before or after it
+    let json_diffs = cli.diff && cli.diff_format == DiffFormat::Json;
+    let json_report = cli.reporter == Reporter::Json;
+    let quiet = json_diffs || json_report;
 
-    println!("{} {} files", "Formatting".green(), files.len());
+    // Project-specific settings must come from each file's nearest project root,
+    // not from wherever krokfmt happened to be invoked. Running at a monorepo root
+    // touches many projects at once, so contexts are cached per directory.
+    let project_contexts = project_contexts(&cli);
+    let pool = thread_pool(&cli)?;
+    let (file_sender, discovered) = mpsc::sync_channel(DISCOVERY_BUFFER);
+    let mut jobs = Jobs::new(discovered, &project_contexts, &cli);
 
+    let mut diffs = Vec::new();
+    let mut file_count = 0;
     let mut had_changes = false;
     let mut had_errors = false;
+    let mut had_semantic_changes = false;
+    let mut had_strict_violations = false;
+    let mut had_idempotency_violations = false;
 
-    // Parallel processing was crucial for large codebases. We use rayon's work-stealing
-    // to handle varying file sizes efficiently - small files don't block large ones.
-    let results: Vec<_> = files
-        .par_iter()
-        .map(|file| process_file(&file_handler, file, &cli))
-        .collect();
+    // Formatting starts on the first files while the rest of the checkout is
+    // still being walked, and every file is reported as soon as it's done.
+    // Parallel processing was crucial for large codebases: rayon's work-stealing
+    // handles varying file sizes efficiently, so small files don't wait on large ones.
+    let walked = std::thread::scope(|scope| -> Result<Result<()>> {
+        let (file_handler, cli) = (&file_handler, &cli);
+        let walk = scope.spawn(move || -> Result<()> {
+            file_handler.send_typescript_files(&cli.paths, &file_sender)?;
+            if cli.include_package_json {
+                file_handler.send_package_json_files(&cli.paths, &file_sender)?;
+            }
+            Ok(())
+        });
 
-    // We collect results first, then report them sequentially to avoid jumbled output
-    // from parallel processing. The colored output helps users quickly scan results.
-    for (file, result) in files.iter().zip(results.iter()) {
-        match result {
-            Ok(changed) => {
-                if *changed {
-                    had_changes = true;
-                    // In check mode, changes are failures - we show red X to indicate
-                    // the file would be modified if we weren't in check mode.
-                    if cli.check {
-                        println!("{} {}", "✗".red(), file.display());
+        let (result_sender, results) = mpsc::channel();
+        let jobs = &mut jobs;
+        scope.spawn(move || {
+            pool.install(|| {
+                jobs.par_bridge()
+                    .for_each_with(result_sender, |results, (file, settings)| {
+                        let started = std::time::Instant::now();
+                        let result = process_file(file_handler, &file, &settings, cli);
+                        let duration = started.elapsed();
+
+                        let reordering = match &result {
+                            Ok(Processed {
+                                outcome: Some(outcome),
+                                ..
+                            }) if json_report && checks_whole_file(file_handler, &file) => {
+                                Reordering::between(
+                                    &outcome.original,
+                                    &outcome.formatted,
+                                    &file.to_string_lossy(),
+                                )
+                                .unwrap_or_default()
+                            }
+                            _ => Reordering::default(),
+                        };
+                        // Nobody's listening once reporting failed
+                        let _ = results.send((file, result, duration, reordering));
+                    })
+            })
+        });
+
+        // Results are reported one at a time, in the order files finish, so the
+        // output of parallel workers never gets jumbled. The colored output helps
+        // users quickly scan results.
+        for (file, result, duration, reordering) in results {
+            file_count += 1;
+            if json_report {
+                let outcome = match &result {
+                    Ok(processed) => Ok(processed.changed),
+                    Err(e) => Err(format!("{e:#}")),
+                };
+                let mut report = FileReport::new(&file, outcome, duration, reordering);
+                if let Ok(processed) = &result {
+                    report.explanation = processed.explanation.clone();
+                }
+                println!(
+                    "{}",
+                    serde_json::to_string(&report).context("Failed to serialize the report")?
+                );
+            }
+
+            match result {
+                Ok(processed) => {
+                    if processed.changed {
+                        had_changes = true;
+                        // In check mode, changes are failures - we show red X to indicate
+                        // the file would be modified if we weren't in check mode.
+                        match &processed.outcome {
+                            Some(outcome) if cli.diff => {
+                                let diff =
+                                    FileDiff::new(&file, &outcome.original, &outcome.formatted);
+                                if json_diffs {
+                                    diffs.push(diff);
+                                } else {
+                                    print!("{}", colorize_patch(&diff.patch));
+                                }
+                            }
+                            _ if quiet => {}
+                            _ if cli.explain => {
+                                println!("{} {}", "✗".red(), display_path(&file));
+                                if let Some(change_log) = &processed.explanation {
+                                    print_explanation(change_log);
+                                }
+                            }
+                            _ if cli.check => println!("{} {}", "✗".red(), display_path(&file)),
+                            _ => println!("{} {}", "✓".green(), display_path(&file)),
+                        }
+                    } else if !quiet {
+                        println!("{} {} (no changes)", "✓".green(), display_path(&file));
+                    }
+                }
+                Err(e) => {
+                    if let Some(SemanticChangeError(changes)) =
+                        e.downcast_ref::<SemanticChangeError>()
+                    {
+                        had_semantic_changes = true;
+                        for change in changes {
+                            eprintln!(
+                                "{} {}:{}: formatting would change semantics of {}",
+                                "✗".red(),
+                                display_path(&file),
+                                change.line,
+                                change.construct
+                            );
+                        }
+                    } else if let Some(IdempotencyError(violation)) =
+                        e.downcast_ref::<IdempotencyError>()
+                    {
+                        had_idempotency_violations = true;
+                        eprintln!("{} {}: {}", "✗".red(), display_path(&file), violation);
+                    } else if let Some(StrictCheckError(violations)) =
+                        e.downcast_ref::<StrictCheckError>()
+                    {
+                        had_strict_violations = true;
+                        for violation in violations {
+                            eprintln!("{} {}: {}", "✗".red(), display_path(&file), violation);
+                        }
                     } else {
-                        println!("{} {}", "✓".green(), file.display());
+                        had_errors = true;
+                        eprintln!("{} {}: {}", "✗".red(), display_path(&file), e);
                     }
-                } else {
-                    println!("{} {} (no changes)", "✓".green(), file.display());
                 }
             }
-            Err(e) => {
-                had_errors = true;
-                eprintln!("{} {}: {}", "✗".red(), file.display(), e);
-            }
         }
+
+        Ok(walk
+            .join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic)))
+    })?;
+
+    // A broken config is an error rather than a reason to fall back to
+    // defaults, and a project that can't be walked or read isn't formatted
+    walked?;
+    if let Some(error) = jobs.error {
+        return Err(error);
+    }
+
+    if jobs.generated > 0 && !quiet {
+        println!(
+            "{} {} generated files (use --include-generated to format them)",
+            "Skipped".yellow(),
+            jobs.generated
+        );
+    }
+    if file_count == 0 {
+        if !quiet {
+            println!("{}", "No TypeScript files found".yellow());
+        }
+        return Ok(());
+    }
+    if !quiet {
+        let verb = if cli.check || cli.explain {
+            "Checked"
+        } else {
+            "Formatted"
+        };
+        if jobs.roots.len() > 1 {
+            println!(
+                "\n{} {file_count} files across {} projects",
+                verb.green(),
+                jobs.roots.len()
+            );
+        } else {
+            println!("\n{} {file_count} files", verb.green());
+        }
+    }
+
+    // Files finish in no particular order, but the diffs shouldn't
+    diffs.sort_by(|a, b| a.file.cmp(&b.file));
+    if json_diffs {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&diffs).context("Failed to serialize the diffs")?
+        );
+    }
+
+    file_handler.save_cache()?;
+
+    // A semantic change is a krokfmt bug rather than a formatting need, so it
+    // gets its own exit code and is reported before anything else
+    if had_semantic_changes {
+        let message = if cli.check {
+            "Formatting would change the semantics of some files"
+        } else {
+            "Internal error: formatting would have changed the semantics of some files, \
+             so they were left as they were. Please report this as a krokfmt bug."
+        };
+        eprintln!("\n{}", message.red());
+        std::process::exit(2);
+    }
+    if had_strict_violations {
+        eprintln!(
+            "\n{}",
+            "Formatting isn't idempotent or doesn't retain comments in some files".red()
+        );
+        std::process::exit(2);
+    }
+    if had_idempotency_violations {
+        eprintln!(
+            "\n{}",
+            "Formatting some files twice gives different results".red()
+        );
+        std::process::exit(3);
     }
 
     // Exit codes matter for CI/CD integration. We use standard Unix conventions:
@@ -114,51 +881,1229 @@ fn main() -> Result<()> {
         std::process::exit(1);
     }
 
-    println!("\n{}", "All files formatted successfully".green());
+    if !quiet && !cli.explain {
+        println!("\n{}", "All files formatted successfully".green());
+    }
     Ok(())
 }
 
-/// Process a single TypeScript file through the parse-organize-format pipeline.
-///
-/// Returns true if the file was changed, false if it was already formatted.
-/// This boolean is crucial for check mode to determine exit codes.
-fn process_file(file_handler: &FileHandler, path: &Path, cli: &Cli) -> Result<bool> {
-    let content = file_handler.read_file(path)?;
-
-    // We need to clone source_map and comments before parsing because the parser
-    // consumes them. This allows the code generator to preserve comments and spans.
-    let parser = TypeScriptParser::new();
-    let source_map = parser.source_map.clone();
-    let comments = parser.comments.clone();
-    let module = parser
-        .parse(&content, path.to_str().unwrap_or("unknown.ts"))
-        .context("Failed to parse file")?;
+/// Print what organizing a file reordered, below its name.
+fn print_explanation(change_log: &ChangeLog) {
+    if change_log.is_empty() {
+        println!("    no imports regrouped, declarations hoisted or enums sorted");
+        return;
+    }
+    if change_log.regrouped_imports > 0 {
+        println!("    {} imports regrouped", change_log.regrouped_imports);
+    }
+    if !change_log.hoisted_declarations.is_empty() {
+        println!(
+            "    hoisted above what uses them: {}",
+            change_log.hoisted_declarations.join(", ")
+        );
+    }
+    if !change_log.sorted_enums.is_empty() {
+        println!(
+            "    enum members sorted: {}",
+            change_log.sorted_enums.join(", ")
+        );
+    }
+}
 
-    // Use selective comment preservation for organizing
-    let formatter = CommentFormatter::new(source_map, comments);
-    let organized_content = formatter
-        .format(module, &content)
-        .context("Failed to organize file")?;
+/// Format source read from stdin as if it were the file at `--stdin-filepath`,
+/// printing the result to stdout.
+///
+/// Nothing but the formatted code goes to stdout, so editors can replace the
+/// buffer with it directly. Errors go to stderr with a non-zero exit code and
+/// no output, leaving the buffer as it was.
+fn run_stdin(cli: &Cli) -> Result<()> {
+    let path = cli
+        .stdin_filepath
+        .clone()
+        .unwrap_or_else(|| PathBuf::from("stdin.ts"));
+    let raw =
+        std::io::read_to_string(std::io::stdin()).context("Failed to read source from stdin")?;
+    let line_break = cli.line_ending.line_break(&raw);
+    let content = to_lf(&raw).into_owned();
+
+    let file_handler = build_file_handler(cli)?;
+    let contexts = vec![project_contexts(cli).context_for(&path)];
+    let configs = load_configs(&contexts)?;
+    if cli.frozen {
+        check_frozen(&configs);
+    }
+    let settings = resolve_settings(std::slice::from_ref(&path), &contexts, &configs);
+
+    let formatted = match format_file(&file_handler, &path, &content, &settings[0], cli) {
+        Ok(formatted) => with_line_break(formatted, line_break),
+        Err(e) => {
+            eprintln!("{} {}: {:#}", "✗".red(), display_path(&path), e);
+            std::process::exit(1);
+        }
+    };
+
+    // The editor replaces its buffer with whatever comes out, so the output
+    // gets the same safety check as a write
+    if verifies_semantics(cli) && formatted != raw && checks_whole_file(&file_handler, &path) {
+        let filename = path.to_str().unwrap_or("stdin.ts");
+        let changes = semantic_changes(
+            &content,
+            &to_lf(&formatted),
+            filename,
+            &format_options(&path, &settings[0], cli),
+        )?;
+        if !changes.is_empty() {
+            for change in changes {
+                eprintln!(
+                    "{} {}:{}: internal error: formatting would change semantics of {}",
+                    "✗".red(),
+                    display_path(&path),
+                    change.line,
+                    change.construct
+                );
+            }
+            std::process::exit(2);
+        }
+    }
+
+    // With --check the result only decides the exit code
+    if cli.check {
+        if formatted != raw {
+            eprintln!("{} {}", "✗".red(), display_path(&path));
+            std::process::exit(1);
+        }
+        return Ok(());
+    }
+
+    let mut stdout = std::io::stdout().lock();
+    stdout
+        .write_all(formatted.as_bytes())
+        .and_then(|()| stdout.flush())
+        .context("Failed to write formatted output")
+}
+
+/// Serve formatting requests over LSP on stdio until the editor exits.
+///
+/// Each document is formatted as the file at its path would be, with the rules
+/// of its project read again per request so config edits apply right away.
+fn run_lsp(cli: &Cli) -> Result<()> {
+    let file_handler = build_file_handler(cli)?;
+    let project_contexts = project_contexts(cli);
+
+    let server = LanguageServer::new(|path: &Path, raw: &str| {
+        let contexts = vec![project_contexts.context_for(path)];
+        let configs = load_configs(&contexts)?;
+        let settings = resolve_settings(&[path.to_path_buf()], &contexts, &configs);
+        let line_break = cli.line_ending.line_break(raw);
+        let content = to_lf(raw);
+        let formatted = format_file(&file_handler, path, &content, &settings[0], cli)?;
+
+        // The editor applies the edits to the buffer, so they get the same
+        // safety check as a write
+        if verifies_semantics(cli) && formatted != content && checks_whole_file(&file_handler, path)
+        {
+            let changes = semantic_changes(
+                &content,
+                &formatted,
+                &path.to_string_lossy(),
+                &format_options(path, &settings[0], cli),
+            )?;
+            if !changes.is_empty() {
+                anyhow::bail!(
+                    "internal error: formatting would change semantics ({})",
+                    SemanticChangeError(changes)
+                );
+            }
+        }
+        Ok(with_line_break(formatted, line_break))
+    });
+
+    let stdin = std::io::stdin().lock();
+    let stdout = std::io::stdout().lock();
+    if !server.run(stdin, stdout)? {
+        // Exiting without a shutdown request first is an error under LSP
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Format the files under `cli.paths` as they change, until interrupted.
+///
+/// Only changed files are formatted, each batch on the same thread pool. Our
+/// own writes come back as changes too, but formatting them again finds
+/// nothing to do, so they're not reported.
+fn run_watch(cli: &Cli, file_handler: &FileHandler) -> Result<()> {
+    let pool = thread_pool(cli)?;
+    let project_contexts = project_contexts(cli);
+    let mut watcher = FileWatcher::new(&cli.paths, DEFAULT_DEBOUNCE)?;
+    let mut known = watched_files(file_handler, cli)?;
+    println!(
+        "{} {} files for changes (Ctrl-C to stop)",
+        "Watching".green(),
+        known.len()
+    );
+
+    loop {
+        let changed = watcher.next_batch()?;
+
+        // Discovery rules (globs, ignore files, generated files) decide what's
+        // formatted, so a new file is only picked up by discovering again
+        if changed
+            .iter()
+            .any(|path| !known.contains_key(path) && file_handler.is_source_file(path))
+        {
+            known = watched_files(file_handler, cli)?;
+        }
+        let files: Vec<PathBuf> = changed
+            .iter()
+            .filter_map(|path| known.get(path))
+            .filter(|file| file.is_file())
+            .cloned()
+            .collect();
+        if files.is_empty() {
+            continue;
+        }
+
+        // A config broken mid-edit shouldn't end the session
+        let contexts: Vec<_> = files
+            .iter()
+            .map(|file| project_contexts.context_for(file))
+            .collect();
+        let configs = match load_configs(&contexts) {
+            Ok(configs) => configs,
+            Err(e) => {
+                eprintln!("{} {:#}", "✗".red(), e);
+                continue;
+            }
+        };
+        let (files, contexts) = in_scope(files, contexts, &configs, cli);
+        let settings = resolve_settings(&files, &contexts, &configs);
+
+        let started = std::time::Instant::now();
+        let results: Vec<_> = pool.install(|| {
+            files
+                .par_iter()
+                .zip(settings.par_iter())
+                .map(|(file, settings)| process_file(file_handler, file, settings, cli))
+                .collect()
+        });
+        let mut formatted = 0;
+        for (file, result) in files.iter().zip(results) {
+            match result {
+                Ok(processed) if processed.changed => {
+                    formatted += 1;
+                    println!("{} {}", "✓".green(), display_path(file))
+                }
+                Ok(_) => {}
+                Err(e) => eprintln!("{} {}: {}", "✗".red(), display_path(file), e),
+            }
+        }
+
+        // How long a save takes to come back formatted is what a dev loop feels
+        if formatted > 0 {
+            println!(
+                "{} {} {} in {}ms",
+                "Formatted".green(),
+                formatted,
+                if formatted == 1 { "file" } else { "files" },
+                started.elapsed().as_millis()
+            );
+        }
+    }
+}
+
+/// The files discovery finds under `cli.paths`, keyed by absolute path, the
+/// form change events report them in.
+fn watched_files(file_handler: &FileHandler, cli: &Cli) -> Result<HashMap<PathBuf, PathBuf>> {
+    Ok(discover_files(file_handler, &cli.paths, cli)?
+        .into_iter()
+        .map(|file| {
+            let absolute = std::path::absolute(&file).unwrap_or_else(|_| file.clone());
+            (absolute, file)
+        })
+        .collect())
+}
+
+fn build_file_handler(cli: &Cli) -> Result<FileHandler> {
+    FileHandler::new(!cli.no_backup)
+        .with_io_throttle(cli.io_throttle.unwrap_or(0))
+        .with_backup_retention(cli.backup_retention)
+        .with_write_mode(cli.write_mode)
+        .with_ignore_files(!cli.no_ignore)
+        .with_excludes(&cli.exclude)
+}
+
+/// A dedicated pool sized by `--threads`, rather than rayon's global one, so the
+/// limit holds no matter what else in the process touches rayon first.
+fn thread_pool(cli: &Cli) -> Result<rayon::ThreadPool> {
+    rayon::ThreadPoolBuilder::new()
+        // Zero lets rayon pick, which means one thread per core
+        .num_threads(cli.threads.unwrap_or(0))
+        .thread_name(|index| format!("krokfmt-{index}"))
+        .build()
+        .context("Failed to start formatting threads")
+}
+
+/// Find the files to format under `paths`, skipping generated ones unless asked.
+fn discover_files(
+    file_handler: &FileHandler,
+    paths: &[PathBuf],
+    cli: &Cli,
+) -> Result<Vec<PathBuf>> {
+    let mut files = file_handler.find_typescript_files(paths)?;
+    if cli.include_package_json {
+        files.extend(file_handler.find_package_json_files(paths)?);
+    }
+
+    if !cli.include_generated {
+        let mut detector = GeneratedFileDetector::new();
+        let before = files.len();
+        files.retain(|file| !detector.is_generated(file));
+
+        let skipped = before - files.len();
+        if skipped > 0 {
+            println!(
+                "{} {} generated files (use --include-generated to format them)",
+                "Skipping".yellow(),
+                skipped
+            );
+        }
+    }
+
+    Ok(files)
+}
+
+/// How many discovered files may wait to be formatted before the walk pauses.
+const DISCOVERY_BUFFER: usize = 256;
+
+/// The files being discovered, each with the settings it's formatted with,
+/// as they're walked.
+///
+/// Drops the files generated code or their project's `include` and `exclude`
+/// leave out, loading each project's config the first time one of its files
+/// comes along. Planning stops at the first config that can't be loaded (or,
+/// with `--frozen`, isn't pinned to a matching version), so none of that
+/// project's files get formatted.
+struct Jobs<'a> {
+    /// `None` once planning failed, hanging up on the walk
+    discovered: Option<mpsc::IntoIter<PathBuf>>,
+    project_contexts: &'a ProjectContextCache,
+    cli: &'a Cli,
+    configs: HashMap<PathBuf, Config>,
+    /// Each project's layout, found along with its config
+    layouts: HashMap<PathBuf, ProjectLayout>,
+    detector: GeneratedFileDetector,
+    /// Generated files left out
+    generated: usize,
+    /// Roots of the projects files were formatted in
+    roots: HashSet<PathBuf>,
+    error: Option<anyhow::Error>,
+}
+
+impl<'a> Jobs<'a> {
+    fn new(
+        discovered: mpsc::Receiver<PathBuf>,
+        project_contexts: &'a ProjectContextCache,
+        cli: &'a Cli,
+    ) -> Self {
+        Self {
+            discovered: Some(discovered.into_iter()),
+            project_contexts,
+            cli,
+            configs: HashMap::new(),
+            layouts: HashMap::new(),
+            detector: GeneratedFileDetector::new(),
+            generated: 0,
+            roots: HashSet::new(),
+            error: None,
+        }
+    }
+
+    /// The settings `file` is formatted with, or `None` if it's left out.
+    fn plan(&mut self, file: &Path) -> Result<Option<FileSettings>> {
+        if !self.cli.include_generated && self.detector.is_generated(file) {
+            self.generated += 1;
+            return Ok(None);
+        }
+
+        let context = self.project_contexts.context_for(file);
+        if !self.configs.contains_key(&context.root) {
+            let config = match &context.config_path {
+                Some(path) => Config::load(path)?,
+                None => Config::default(),
+            };
+            if self.cli.frozen {
+                if let Some(violation) = frozen_violation(&config) {
+                    anyhow::bail!(
+                        "{}: {violation}\n{FROZEN_REQUIREMENT}",
+                        display_path(&context.root)
+                    );
+                }
+            }
+            let layout = project_layout(&context, &config);
+            self.layouts.insert(context.root.clone(), layout);
+            self.configs.insert(context.root.clone(), config);
+        }
+        let config = &self.configs[&context.root];
+
+        let absolute = std::path::absolute(file).unwrap_or_else(|_| file.to_path_buf());
+        if !self.cli.paths.iter().any(|path| path == file)
+            && !config.includes(config_base(&context), &absolute)
+        {
+            return Ok(None);
+        }
+
+        let settings = FileSettings {
+            rules: config.rules_for(config_base(&context), &absolute),
+            layout: self.layouts[&context.root].clone(),
+        };
+        self.roots.insert(context.root.clone());
+        Ok(Some(settings))
+    }
+}
+
+impl Iterator for Jobs<'_> {
+    type Item = (PathBuf, FileSettings);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let file = self.discovered.as_mut()?.next()?;
+            match self.plan(&file) {
+                Ok(Some(settings)) => return Some((file, settings)),
+                Ok(None) => {}
+                Err(error) => {
+                    self.error = Some(error);
+                    self.discovered = None;
+                    return None;
+                }
+            }
+        }
+    }
+}
+
+/// The project context cache for this run, pointing every project at
+/// `--config` when it's given.
+fn project_contexts(cli: &Cli) -> ProjectContextCache {
+    match &cli.config {
+        Some(config) => ProjectContextCache::new()
+            .with_config(std::path::absolute(config).unwrap_or_else(|_| config.clone())),
+        None => ProjectContextCache::new(),
+    }
+}
+
+/// Load and validate the krokfmt.toml of every project, keyed by project root.
+fn load_configs(contexts: &[Arc<ProjectContext>]) -> Result<HashMap<PathBuf, Config>> {
+    let mut configs = HashMap::new();
+
+    for context in contexts {
+        if configs.contains_key(&context.root) {
+            continue;
+        }
+
+        let config = match &context.config_path {
+            Some(path) => Config::load(path)?,
+            None => Config::default(),
+        };
+        configs.insert(context.root.clone(), config);
+    }
+
+    Ok(configs)
+}
+
+/// Drop the files their project's `include` and `exclude` leave out, along
+/// with their contexts. Files named on the command line are always kept.
+fn in_scope(
+    files: Vec<PathBuf>,
+    contexts: Vec<Arc<ProjectContext>>,
+    configs: &HashMap<PathBuf, Config>,
+    cli: &Cli,
+) -> (Vec<PathBuf>, Vec<Arc<ProjectContext>>) {
+    files
+        .into_iter()
+        .zip(contexts)
+        .filter(|(file, context)| {
+            let absolute = std::path::absolute(file).unwrap_or_else(|_| file.clone());
+            cli.paths.contains(file)
+                || configs[&context.root].includes(config_base(context), &absolute)
+        })
+        .unzip()
+}
+
+/// The directory a project's config globs are relative to.
+///
+/// That's the config's own directory, which `--config` may have put outside
+/// the project.
+fn config_base(context: &ProjectContext) -> &Path {
+    context
+        .config_path
+        .as_deref()
+        .and_then(Path::parent)
+        .unwrap_or(&context.root)
+}
+
+/// How one file is formatted, from its project's config and layout.
+#[derive(Debug, Clone)]
+struct FileSettings {
+    /// Rule groups in effect, from the project's `[[override]]` sections
+    rules: RuleSet,
+    /// The project's workspace packages and path aliases, which group imports
+    layout: ProjectLayout,
+}
+
+/// The settings each file is formatted with.
+fn resolve_settings(
+    files: &[PathBuf],
+    contexts: &[Arc<ProjectContext>],
+    configs: &HashMap<PathBuf, Config>,
+) -> Vec<FileSettings> {
+    let mut layouts: HashMap<&Path, ProjectLayout> = HashMap::new();
+
+    files
+        .iter()
+        .zip(contexts)
+        .map(|(file, context)| {
+            let config = &configs[&context.root];
+            let absolute = std::path::absolute(file).unwrap_or_else(|_| file.clone());
+            FileSettings {
+                rules: config.rules_for(config_base(context), &absolute),
+                layout: layouts
+                    .entry(&context.root)
+                    .or_insert_with(|| project_layout(context, config))
+                    .clone(),
+            }
+        })
+        .collect()
+}
+
+/// Refuse to format projects whose pinned krokfmt version formats differently.
+///
+/// Every project needs a pin: without one there's nothing to be frozen to, and
+/// silently passing would defeat the point of running with `--frozen` in CI.
+fn check_frozen(configs: &HashMap<PathBuf, Config>) {
+    let mut failed = false;
+
+    let mut roots: Vec<_> = configs.keys().collect();
+    roots.sort();
+
+    for root in roots {
+        if let Some(violation) = frozen_violation(&configs[root]) {
+            failed = true;
+            eprintln!("{} {}: {violation}", "✗".red(), display_path(root));
+        }
+    }
+
+    if failed {
+        eprintln!("\n{}", FROZEN_REQUIREMENT.red());
+        std::process::exit(1);
+    }
+}
+
+const FROZEN_REQUIREMENT: &str =
+    "--frozen requires a matching krokfmt version pinned in krokfmt.toml";
+
+/// Why `--frozen` refuses to format a project with `config`, if it does.
+fn frozen_violation(config: &Config) -> Option<String> {
+    let current = OutputVersion::current();
+    match config.version {
+        Some(pinned) if pinned.produces_same_output_as(&current) => None,
+        Some(pinned) => Some(format!(
+            "formatted with krokfmt {pinned}, but krokfmt {current} formats differently (run `krokfmt migrate --from {pinned}`)"
+        )),
+        None => Some("no `version` pinned in krokfmt.toml".to_string()),
+    }
+}
+
+/// Write a starter config to `dir`, refusing to replace one unless `force`.
+fn run_init(dir: Option<&Path>, force: bool) -> Result<()> {
+    let dir = dir.unwrap_or(Path::new("."));
+    if let Some(existing) = Config::in_dir(dir) {
+        if !force {
+            eprintln!(
+                "{} {} already exists (pass --force to replace it)",
+                "✗".red(),
+                display_path(&existing)
+            );
+            std::process::exit(1);
+        }
+    }
+
+    let path = dir.join(INIT_FILE_NAME);
+    std::fs::write(&path, Config::starter())
+        .with_context(|| format!("Failed to write {}", display_path(&path)))?;
+    println!("{} Created {}", "✓".green(), display_path(&path));
+    Ok(())
+}
+
+fn run_restore(cli: &Cli, paths: &[PathBuf]) -> Result<()> {
+    let file_handler = build_file_handler(cli)?;
+    let mut missing = false;
+
+    for path in paths {
+        match file_handler.restore_backup(path)? {
+            Some(_) => println!("{} Restored {}", "✓".green(), display_path(path)),
+            None => {
+                eprintln!("{} no backup of {}", "✗".red(), display_path(path));
+                missing = true;
+            }
+        }
+    }
+
+    if missing {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Validate a krokfmt.toml without formatting anything.
+///
+/// Meant for CI and for checking a config edit before it silently changes (or
+/// fails to change) how a whole repository is formatted.
+fn run_config_check(path: Option<&Path>) -> Result<()> {
+    let path = path.unwrap_or(Path::new("."));
+    let config_path = if path.is_dir() {
+        let absolute = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+        match Config::find(&absolute) {
+            Some(config_path) => config_path,
+            None => {
+                eprintln!(
+                    "{} no krokfmt.toml, .krokrc.toml or .krokrc found in {} or its parents",
+                    "✗".red(),
+                    display_path(path)
+                );
+                std::process::exit(1);
+            }
+        }
+    } else {
+        path.to_path_buf()
+    };
+
+    match Config::load(&config_path) {
+        Ok(_) => {
+            println!("{} {} is valid", "✓".green(), display_path(&config_path));
+            Ok(())
+        }
+        Err(e) => {
+            eprintln!("{} {e:#}", "✗".red());
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Print the import graph of the files under `args.paths` to stdout.
+///
+/// Generated files are included, since they're imported like any other file,
+/// and nothing else is printed so the output can be piped straight into `dot`.
+fn run_graph(cli: &Cli, args: &GraphArgs) -> Result<()> {
+    let file_handler = build_file_handler(cli)?;
+    let files = file_handler.find_typescript_files(&args.paths)?;
+
+    let graph = ModuleGraph::build(&file_handler, &files)?;
+    print!("{}", graph.render(args.format)?);
+    Ok(())
+}
+
+/// List imports that reach an aliased module by relative path, and rewrite
+/// them to the alias with `--apply`.
+///
+/// Each file is resolved with the `paths` of its own project's tsconfig.json,
+/// so a monorepo's packages keep their own aliases.
+fn run_aliases(cli: &Cli, args: &AliasArgs) -> Result<()> {
+    let file_handler = build_file_handler(cli)?;
+    let files = discover_files(&file_handler, &args.paths, cli)?;
+
+    let project_contexts = project_contexts(cli);
+    let contexts: Vec<_> = files
+        .iter()
+        .map(|file| project_contexts.context_for(file))
+        .collect();
+    let mut loaded: HashMap<PathBuf, Option<Arc<TsconfigPaths>>> = HashMap::new();
+    let mut tsconfigs = HashMap::new();
+    for (file, context) in files.iter().zip(&contexts) {
+        let Some(tsconfig_path) = &context.tsconfig_path else {
+            continue;
+        };
+        if !loaded.contains_key(tsconfig_path) {
+            let tsconfig = TsconfigPaths::load(tsconfig_path)?.map(Arc::new);
+            loaded.insert(tsconfig_path.clone(), tsconfig);
+        }
+        tsconfigs.insert(file.clone(), loaded[tsconfig_path].clone());
+    }
+
+    let report = AliasReport::build(&file_handler, &files, |file| {
+        tsconfigs.get(file).cloned().flatten()
+    })?;
+
+    for mismatch in &report.mismatches {
+        println!(
+            "{} {}:{}: '{}' -> '{}'",
+            "~".yellow(),
+            display_path(&mismatch.file),
+            mismatch.line,
+            mismatch.specifier,
+            mismatch.canonical
+        );
+    }
+
+    if report.mismatches.is_empty() {
+        println!(
+            "{} Every aliased module is imported by its alias",
+            "✓".green()
+        );
+    } else if args.apply {
+        let configs = load_configs(&contexts)?;
+        let settings: HashMap<_, _> = files
+            .iter()
+            .cloned()
+            .zip(resolve_settings(&files, &contexts, &configs))
+            .collect();
+        let written = report.apply(&file_handler, cli.line_ending, |file, content| {
+            let settings = &settings[file];
+            let formatted = format_typescript_file(file, content, settings, cli)?;
+            check_semantics(&file_handler, file, content, &formatted, settings, cli)?;
+            Ok(formatted)
+        })?;
+        println!(
+            "\nRewrote {} imports in {written} files",
+            report.mismatches.len()
+        );
+    } else {
+        println!(
+            "\n{} imports should use their alias. Run again with --apply to rewrite them.",
+            report.mismatches.len()
+        );
+    }
+
+    Ok(())
+}
 
-    // Apply Biome formatting as the final step
-    let biome_formatter = BiomeFormatter::new();
-    let formatted_content = biome_formatter
-        .format(&organized_content, path)
-        .context("Failed to format with Biome")?;
+/// Show which files an upgrade from `--from` reformats, and rewrite them with `--apply`.
+///
+/// We can't run the old formatter, but we don't need to: if the code is clean
+/// under the old version, everything the current version changes is exactly the
+/// difference between the two. Applying it on its own keeps the upgrade in one
+/// commit that reviewers can skim instead of mixing it into feature work.
+fn run_migrate(cli: &Cli, args: &MigrateArgs) -> Result<()> {
+    let current = OutputVersion::current();
+    if current.produces_same_output_as(&args.from) {
+        println!(
+            "{} krokfmt {} and {} produce identical output, nothing to migrate",
+            "✓".green(),
+            args.from,
+            current
+        );
+        return Ok(());
+    }
+
+    let file_handler = build_file_handler(cli)?;
+    let files = discover_files(&file_handler, &args.paths, cli)?;
+
+    let project_contexts = project_contexts(cli);
+    let contexts: Vec<_> = files
+        .iter()
+        .map(|file| project_contexts.context_for(file))
+        .collect();
+    let configs = load_configs(&contexts)?;
+    let settings = resolve_settings(&files, &contexts, &configs);
+
+    let results: Vec<_> = thread_pool(cli)?.install(|| {
+        files
+            .par_iter()
+            .zip(settings.par_iter())
+            .map(|(file, settings)| -> Result<Option<String>> {
+                let raw = file_handler.read_file_raw(file)?;
+                let line_break = cli.line_ending.line_break(&raw);
+                let content = to_lf(&raw).into_owned();
+                let formatted = format_file(&file_handler, file, &content, settings, cli)?;
+                // Rewritten files go through the same safety check as formatting
+                if args.apply && formatted != content {
+                    check_semantics(&file_handler, file, &content, &formatted, settings, cli)?;
+                }
+                let formatted = with_line_break(formatted, line_break);
+                Ok((formatted != raw).then_some(formatted))
+            })
+            .collect()
+    });
+
+    let mut changed = 0;
+    let mut had_errors = false;
+
+    for (file, result) in files.iter().zip(results) {
+        match result {
+            Ok(Some(formatted)) => {
+                changed += 1;
+                if args.apply {
+                    file_handler.write_file(file, &formatted)?;
+                }
+                println!("{} {}", "~".yellow(), display_path(file));
+            }
+            Ok(None) => {}
+            Err(e) => {
+                had_errors = true;
+                eprintln!("{} {}: {}", "✗".red(), display_path(file), e);
+            }
+        }
+    }
+
+    println!(
+        "\n{changed} of {} files change between krokfmt {} and {current}",
+        files.len(),
+        args.from
+    );
+
+    if args.apply && changed > 0 {
+        println!("Commit them on their own so the upgrade stays reviewable:");
+        println!(
+            "  git commit -am \"Reformat for krokfmt {current} (from {})\"",
+            args.from
+        );
+        println!("and set `version = \"{current}\"` in krokfmt.toml if you pin one.");
+    } else if changed > 0 {
+        println!("Run again with --apply to rewrite them.");
+    }
+
+    if had_errors {
+        eprintln!("\n{}", "Some files had errors".red());
+        std::process::exit(1);
+    }
+
+    Ok(())
+}
+
+/// What processing a file found.
+struct Processed {
+    /// Whether formatting changed the file; crucial for check mode to
+    /// determine exit codes
+    changed: bool,
+    /// The file before and after formatting, unless it was streamed
+    outcome: Option<FileOutcome>,
+    /// What organizing a changed file reordered, with `--explain`
+    explanation: Option<ChangeLog>,
+}
+
+/// Process a single TypeScript file through the parse-organize-format pipeline.
+fn process_file(
+    file_handler: &FileHandler,
+    path: &Path,
+    settings: &FileSettings,
+    cli: &Cli,
+) -> Result<Processed> {
+    let raw = file_handler.read_file_raw(path)?;
+
+    let cache_key = cache_settings(settings, cli);
+    if file_handler.is_cached(path, &raw, &cache_key) {
+        return Ok(Processed {
+            changed: false,
+            outcome: None,
+            explanation: None,
+        });
+    }
+
+    // Formatting works on LF throughout; the file's line breaks come back
+    // once it's done
+    let line_break = cli.line_ending.line_break(&raw);
+    let content = to_lf(&raw).into_owned();
+
+    // Semantic verification, diffs, node selection, unused import removal,
+    // require conversion and explanations need the whole file at once, so they
+    // keep formatting it in one piece, as do files whose line breaks change
+    if let Some(segment_bytes) = cli.segment_threshold {
+        if content.len() > segment_bytes
+            && content == raw
+            && line_break == "\n"
+            && checks_whole_file(file_handler, path)
+            && !cli.verify_semantics
+            && !cli.strict
+            && !cli.verify_idempotent
+            && !cli.diff
+            && cli.only_nodes.is_none()
+            && !cli.remove_unused_imports
+            && !cli.convert_requires
+            && !cli.explain
+        {
+            let changed =
+                process_file_segmented(file_handler, path, &content, segment_bytes, settings, cli)?;
+            if !changed {
+                file_handler.record_formatted(path, &raw, &cache_key);
+            }
+            return Ok(Processed {
+                changed,
+                outcome: None,
+                explanation: None,
+            });
+        }
+    }
+
+    let formatted_content = format_file(file_handler, path, &content, settings, cli)?;
 
     // Simple string comparison is sufficient here - we're not doing a semantic diff
-    // because any change, even whitespace, is a formatting change.
-    if content == formatted_content {
-        return Ok(false);
+    // because any change, even whitespace, is a formatting change. Line breaks
+    // are compared as the file would be written.
+    let unchanged = if line_break == "\n" {
+        raw == formatted_content
+    } else {
+        raw == formatted_content.replace('\n', line_break)
+    };
+    if unchanged {
+        file_handler.record_formatted(path, &raw, &cache_key);
+        return Ok(Processed {
+            changed: false,
+            outcome: None,
+            explanation: None,
+        });
     }
 
+    check_semantics(
+        file_handler,
+        path,
+        &content,
+        &formatted_content,
+        settings,
+        cli,
+    )?;
+
+    if cli.verify_idempotent {
+        let reformatted = format_file(file_handler, path, &formatted_content, settings, cli)
+            .context("Failed to format the formatted output again")?;
+        if let Some(violation) = idempotency_violation(&formatted_content, &reformatted) {
+            return Err(IdempotencyError(violation).into());
+        }
+    }
+
+    if cli.strict && checks_whole_file(file_handler, path) {
+        let filename = path.to_str().unwrap_or("unknown.ts");
+        let reformatted = format_file(file_handler, path, &formatted_content, settings, cli)
+            .context("Failed to format the formatted output again")?;
+        let mut violations: Vec<Violation> =
+            idempotency_violation(&formatted_content, &reformatted)
+                .into_iter()
+                .collect();
+        violations.extend(comment_changes(&content, &formatted_content, filename)?);
+        if !violations.is_empty() {
+            return Err(StrictCheckError(violations).into());
+        }
+    }
+
+    // package.json files aren't organized, and the scripts of components are
+    // organized one at a time, so only code files are explained
+    let explanation = if cli.explain && checks_whole_file(file_handler, path) {
+        Some(explain_file(path, &content, settings, cli)?)
+    } else {
+        None
+    };
+
     // Output handling is mutually exclusive: stdout for editor integration,
-    // file writing for normal operation, or neither for check mode.
+    // file writing for normal operation, or neither for check mode and
+    // explanations.
+    let formatted_content = with_line_break(formatted_content, line_break);
     if cli.stdout {
         println!("{formatted_content}");
-    } else if !cli.check {
+    } else if !cli.check && !cli.explain {
         file_handler.write_file(path, &formatted_content)?;
     }
 
-    Ok(true)
+    Ok(Processed {
+        changed: true,
+        outcome: Some(FileOutcome {
+            original: raw,
+            formatted: formatted_content,
+        }),
+        explanation,
+    })
+}
+
+/// Everything besides a file's content that decides how it's formatted, so
+/// the cache formats a file again once any of it changes.
+fn cache_settings(settings: &FileSettings, cli: &Cli) -> String {
+    format!(
+        "{:?} {} {:?} {:?} {} {} {} {} {} {} {:?} {} {:?} {} {} {} {} {} {} {} {:?}",
+        settings.rules,
+        cli.imports_only,
+        settings.layout,
+        cli.import_profile,
+        cli.sort_hook_deps,
+        cli.react_first,
+        cli.sort_exports,
+        cli.group_constants,
+        cli.group_type_imports,
+        cli.detect_indent,
+        cli.only_nodes,
+        cli.import_group_banners,
+        cli.namespace_import,
+        cli.dedupe_side_effect_imports,
+        cli.collapse_empty_imports,
+        cli.merge_duplicate_imports,
+        cli.remove_unused_imports,
+        cli.convert_requires,
+        cli.normalize_import_paths,
+        cli.line_ending,
+        cli.segment_threshold,
+    )
+}
+
+/// Process a very large file a segment at a time, streaming the output to its
+/// destination instead of building it in memory.
+fn process_file_segmented(
+    file_handler: &FileHandler,
+    path: &Path,
+    content: &str,
+    segment_bytes: usize,
+    settings: &FileSettings,
+    cli: &Cli,
+) -> Result<bool> {
+    let filename = path.to_str().unwrap_or("unknown.ts");
+    let format_into = |out: &mut dyn Write| -> Result<bool> {
+        let mut out = ChangeDetector::new(content, out);
+        format_segmented(content, filename, segment_bytes, &mut out, |segment| {
+            let formatted = format_typescript_file(path, segment, settings, cli)?;
+            // Segments parse on their own, so each one can be checked by itself
+            if verifies_semantics(cli) {
+                let changes = semantic_changes(
+                    segment,
+                    &formatted,
+                    filename,
+                    &format_options(path, settings, cli),
+                )?;
+                if !changes.is_empty() {
+                    return Err(SemanticChangeError(changes).into());
+                }
+            }
+            Ok(formatted)
+        })?;
+        Ok(out.changed())
+    };
+
+    if cli.stdout {
+        format_into(&mut std::io::stdout().lock())
+    } else if cli.check {
+        format_into(&mut std::io::sink())
+    } else {
+        file_handler.write_file_streaming(path, format_into)
+    }
+}
+
+/// Whether formatted output has to keep the semantics of its file: always
+/// with `--verify-semantics`, and before anything is written unless the
+/// safety check is turned off without `--safe`.
+fn verifies_semantics(cli: &Cli) -> bool {
+    cli.verify_semantics || (!cli.check && !cli.explain && (cli.safe || !cli.no_safety_check))
+}
+
+/// Fail if `formatted` changes what `content`'s code does, when the output
+/// has to keep the semantics of its file.
+fn check_semantics(
+    file_handler: &FileHandler,
+    path: &Path,
+    content: &str,
+    formatted: &str,
+    settings: &FileSettings,
+    cli: &Cli,
+) -> Result<()> {
+    if !verifies_semantics(cli) || !checks_whole_file(file_handler, path) {
+        return Ok(());
+    }
+    let changes = semantic_changes(
+        content,
+        formatted,
+        path.to_str().unwrap_or("unknown.ts"),
+        &format_options(path, settings, cli),
+    )?;
+    if !changes.is_empty() {
+        return Err(SemanticChangeError(changes).into());
+    }
+    Ok(())
+}
+
+/// Whether a file's output is checked as a whole after it's formatted:
+/// package.json files aren't code, and components are checked a script block
+/// at a time while they're formatted.
+fn checks_whole_file(file_handler: &FileHandler, path: &Path) -> bool {
+    !file_handler.is_package_json_file(path) && !file_handler.is_component_file(path)
+}
+
+/// Formatting a file would change what its code does.
+///
+/// Kept apart from other errors so it can be reported as its own class, with
+/// the location of every offending construct.
+#[derive(Debug)]
+struct SemanticChangeError(Vec<SemanticChange>);
+
+impl std::fmt::Display for SemanticChangeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let changes: Vec<_> = self.0.iter().map(ToString::to_string).collect();
+        write!(f, "{}", changes.join("; "))
+    }
+}
+
+impl std::error::Error for SemanticChangeError {}
+
+/// Formatting a file's output again changed it.
+#[derive(Debug)]
+struct IdempotencyError(Violation);
+
+impl std::fmt::Display for IdempotencyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl std::error::Error for IdempotencyError {}
+
+/// Formatting a file broke one of the guarantees `--strict` checks.
+#[derive(Debug)]
+struct StrictCheckError(Vec<Violation>);
+
+impl std::fmt::Display for StrictCheckError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let violations: Vec<_> = self.0.iter().map(ToString::to_string).collect();
+        write!(f, "{}", violations.join("; "))
+    }
+}
+
+impl std::error::Error for StrictCheckError {}
+
+/// Format a discovered file with the formatter for its kind.
+fn format_file(
+    file_handler: &FileHandler,
+    path: &Path,
+    content: &str,
+    settings: &FileSettings,
+    cli: &Cli,
+) -> Result<String> {
+    if file_handler.is_package_json_file(path) {
+        PackageJsonFormatter::format(content)
+    } else if file_handler.is_component_file(path) {
+        format_script_blocks(content, &path.to_string_lossy(), |script| {
+            let filename = script.filename;
+            let formatted =
+                format_typescript_file(Path::new(filename), script.code, settings, cli)?;
+            // The template around a script isn't code the check can parse, so
+            // each script is checked on its own
+            if verifies_semantics(cli) {
+                let mut changes = semantic_changes(
+                    script.code,
+                    &formatted,
+                    filename,
+                    &format_options(Path::new(filename), settings, cli),
+                )?;
+                if !changes.is_empty() {
+                    for change in &mut changes {
+                        change.line += script.first_line - 1;
+                    }
+                    return Err(SemanticChangeError(changes).into());
+                }
+            }
+            Ok(formatted)
+        })
+    } else {
+        format_typescript_file(path, content, settings, cli)
+    }
+}
+
+/// Whether `path` names a `<script>` block of a component (`App.vue.ts`),
+/// whose imports its template may use where the script can't see.
+fn is_component_script(path: &Path) -> bool {
+    path.file_stem()
+        .and_then(|stem| Path::new(stem).extension())
+        .is_some_and(|ext| ext == "vue" || ext == "svelte")
+}
+
+/// Whether `path` is one Node always loads as CommonJS, whatever package.json
+/// says.
+fn is_commonjs_file(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|ext| ext == "cjs" || ext == "cts")
+}
+
+/// Run TypeScript source through the parse-organize-format pipeline.
+fn format_typescript_file(
+    path: &Path,
+    content: &str,
+    settings: &FileSettings,
+    cli: &Cli,
+) -> Result<String> {
+    // A .ts file containing JSX only switches to TSX when the TS parse fails, so
+    // type assertions like `<T>(x)` keep their meaning.
+    let parsed = pipeline::parse(content, path.to_str().unwrap_or("unknown.ts"))
+        .context("Failed to parse file")?;
+    let syntax_filename = parsed.filename().to_string();
+
+    let organized = pipeline::organize(parsed, &format_options(path, settings, cli))
+        .context("Failed to organize file")?;
+    warn_about(path, &organized.change_log);
+    let generated = pipeline::reinsert_comments(organized).context("Failed to generate code")?;
+
+    let formatted = pipeline::biome_format_or_fallback(generated)?;
+    // Organizing already succeeded, so a gap in Biome's syntax support
+    // shouldn't cost the user that
+    if let Some(failure) = &formatted.fallback {
+        eprintln!(
+            "{} {}: left as organized, Biome couldn't format it: {failure}",
+            "Warning:".yellow(),
+            display_path(path)
+        );
+    }
+
+    match cli.only_nodes {
+        Some(selection) => splice_selected(content, &formatted.code, &syntax_filename, selection)
+            .context("Failed to restrict formatting to the selected constructs"),
+        None => Ok(formatted.code),
+    }
+}
+
+/// What organizing the TypeScript source `content` of `path` reorders, for
+/// `--explain`.
+fn explain_file(
+    path: &Path,
+    content: &str,
+    settings: &FileSettings,
+    cli: &Cli,
+) -> Result<ChangeLog> {
+    let parsed = pipeline::parse(content, path.to_str().unwrap_or("unknown.ts"))
+        .context("Failed to parse file")?;
+    let organized = pipeline::organize(parsed, &format_options(path, settings, cli))
+        .context("Failed to organize file")?;
+    warn_about(path, &organized.change_log);
+    Ok(organized.change_log)
+}
+
+/// Print the diagnostics organizing `path` logged as warnings.
+fn warn_about(path: &Path, change_log: &ChangeLog) {
+    for diagnostic in &change_log.diagnostics {
+        eprintln!(
+            "{} {}: {diagnostic}",
+            "Warning:".yellow(),
+            display_path(path)
+        );
+    }
+}
+
+/// How the file at `path` is formatted, as the command line and the file's
+/// settings say.
+fn format_options(path: &Path, settings: &FileSettings, cli: &Cli) -> FormatOptions {
+    FormatOptions {
+        rules: if cli.imports_only {
+            RuleSet::imports_only()
+        } else {
+            settings.rules
+        },
+        import_profile: cli.import_profile,
+        project_layout: settings.layout.clone(),
+        sort_hook_dependencies: cli.sort_hook_deps,
+        react_first: cli.react_first,
+        group_type_imports: cli.group_type_imports,
+        group_constants: cli.group_constants,
+        sort_re_exports: cli.sort_exports,
+        import_fixes: ImportFixes {
+            namespace_modules: cli.namespace_import.clone(),
+            dedupe_side_effects: cli.dedupe_side_effect_imports,
+            collapse_empty_specifiers: cli.collapse_empty_imports,
+            merge_duplicates: cli.merge_duplicate_imports,
+            convert_requires: cli.convert_requires && !is_commonjs_file(path),
+            normalize_paths: cli.normalize_import_paths,
+        },
+        remove_unused_imports: cli.remove_unused_imports && !is_component_script(path),
+        import_group_banners: cli.import_group_banners,
+        detect_indentation: cli.detect_indent,
+        ..FormatOptions::default()
+    }
 }
@@ -0,0 +1,383 @@
+use anyhow::{Context, Result};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::path::{Component, Path, PathBuf};
+
+use krokfmt_core::parser::TypeScriptParser;
+use krokfmt_core::transformer::{ImportAnalyzer, ImportCategory, ReExportAnalyzer};
+
+use crate::file_handler::{display_path, FileHandler};
+
+/// Extensions tried, in order, when resolving an extensionless relative import.
+const RESOLVE_EXTENSIONS: &[&str] = &["ts", "tsx", "mts", "cts", "d.ts", "js", "jsx", "mjs", "cjs"];
+
+/// Output format of `krokfmt analyze graph`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GraphFormat {
+    /// Graphviz, for `dot -Tsvg`
+    #[default]
+    Dot,
+    /// Nodes and edges as JSON, for bundler and custom tooling
+    Json,
+}
+
+impl std::str::FromStr for GraphFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "dot" => Ok(GraphFormat::Dot),
+            "json" => Ok(GraphFormat::Json),
+            other => Err(format!(
+                "unknown graph format '{other}' (expected 'dot' or 'json')"
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for GraphFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            GraphFormat::Dot => write!(f, "dot"),
+            GraphFormat::Json => write!(f, "json"),
+        }
+    }
+}
+
+/// What a node of the graph stands for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NodeKind {
+    /// One of the analyzed files
+    File,
+    /// Anything imported that isn't one of the analyzed files: packages, path
+    /// aliases we can't resolve without the bundler config, or missing files
+    Module,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GraphNode {
+    /// Path relative to the working directory for files, the import specifier
+    /// otherwise
+    pub id: String,
+    pub kind: NodeKind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GraphEdge {
+    pub from: String,
+    pub to: String,
+    /// The specifier as written in the source
+    pub specifier: String,
+    /// Import category: external, url, absolute or relative
+    pub category: &'static str,
+    /// Whether the edge is a re-export (`export * from`) rather than an import
+    pub re_export: bool,
+}
+
+/// The import graph of a set of files: which file imports what.
+///
+/// Relative specifiers are resolved the way TypeScript does for the common
+/// cases (extensionless paths, `.js` standing in for `.ts`, directory `index`
+/// files). Everything else is kept as the specifier, since resolving packages
+/// and path aliases would mean reimplementing the bundler.
+#[derive(Debug, Serialize)]
+pub struct ModuleGraph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+impl ModuleGraph {
+    pub fn build(file_handler: &FileHandler, files: &[PathBuf]) -> Result<Self> {
+        // Resolved imports are reported by the path the file was discovered
+        // under, so both ends of an edge name the file the same way
+        let known: HashMap<PathBuf, &PathBuf> =
+            files.iter().map(|file| (normalize(file), file)).collect();
+        let mut nodes = BTreeMap::new();
+        let mut edges = Vec::new();
+
+        for file in files {
+            let from = display_path(file);
+            nodes.insert(from.clone(), NodeKind::File);
+
+            let content = file_handler.read_file(file)?;
+            let filename = file.to_string_lossy();
+            let parsed = TypeScriptParser::parse_disambiguated(&content, &filename)
+                .with_context(|| format!("Failed to parse {from}"))?;
+
+            let imports = ImportAnalyzer::new()
+                .analyze(&parsed.module)
+                .into_iter()
+                .map(|import| (import.path, import.category, false));
+            let re_exports = ReExportAnalyzer::new()
+                .analyze(&parsed.module)
+                .into_iter()
+                .map(|re_export| (re_export.path, re_export.category, true));
+
+            for (specifier, category, re_export) in imports.chain(re_exports) {
+                let (to, kind) = match resolve(file, &specifier, &known) {
+                    Some(target) => (display_path(target), NodeKind::File),
+                    None => (specifier.clone(), NodeKind::Module),
+                };
+                nodes.entry(to.clone()).or_insert(kind);
+
+                edges.push(GraphEdge {
+                    from: from.clone(),
+                    to,
+                    specifier,
+                    category: category_label(&category),
+                    re_export,
+                });
+            }
+        }
+
+        let mut nodes: Vec<_> = nodes
+            .into_iter()
+            .map(|(id, kind)| GraphNode { id, kind })
+            .collect();
+        // Files first so a rendered graph starts with the codebase itself
+        nodes.sort_by(|a, b| (a.kind, &a.id).cmp(&(b.kind, &b.id)));
+
+        Ok(Self { nodes, edges })
+    }
+
+    pub fn render(&self, format: GraphFormat) -> Result<String> {
+        match format {
+            GraphFormat::Dot => Ok(self.to_dot()),
+            GraphFormat::Json => {
+                serde_json::to_string_pretty(self).context("Failed to serialize the graph")
+            }
+        }
+    }
+
+    fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph imports {\n");
+
+        for node in &self.nodes {
+            let shape = match node.kind {
+                NodeKind::File => "ellipse",
+                NodeKind::Module => "box",
+            };
+            let _ = writeln!(dot, "  {} [shape={shape}];", quote(&node.id));
+        }
+
+        for edge in &self.edges {
+            let style = if edge.re_export { ", style=dashed" } else { "" };
+            let _ = writeln!(
+                dot,
+                "  {} -> {} [label={}{style}];",
+                quote(&edge.from),
+                quote(&edge.to),
+                quote(edge.category)
+            );
+        }
+
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+fn category_label(category: &ImportCategory) -> &'static str {
+    match category {
+        ImportCategory::External => "external",
+        ImportCategory::Url => "url",
+        ImportCategory::Workspace => "workspace",
+        ImportCategory::Absolute => "absolute",
+        ImportCategory::Relative => "relative",
+    }
+}
+
+/// Resolve a relative specifier to one of the `known` files.
+fn resolve<'a>(
+    importer: &Path,
+    specifier: &str,
+    known: &HashMap<PathBuf, &'a PathBuf>,
+) -> Option<&'a PathBuf> {
+    if ImportAnalyzer::categorize_import(specifier) != ImportCategory::Relative {
+        return None;
+    }
+
+    resolve_path(&normalize(&importer.parent()?.join(specifier)), known)
+}
+
+/// Resolve a module path, as written but made absolute, to one of the `known`
+/// files.
+pub(crate) fn resolve_path<'a>(
+    base: &Path,
+    known: &HashMap<PathBuf, &'a PathBuf>,
+) -> Option<&'a PathBuf> {
+    let mut candidates = vec![base.to_path_buf()];
+
+    // ESM-style TypeScript imports name the emitted `.js` file
+    let written = base.to_string_lossy();
+    for (emitted, source) in [
+        (".js", ".ts"),
+        (".jsx", ".tsx"),
+        (".mjs", ".mts"),
+        (".cjs", ".cts"),
+    ] {
+        if let Some(stem) = written.strip_suffix(emitted) {
+            candidates.push(PathBuf::from(format!("{stem}{source}")));
+        }
+    }
+
+    for extension in RESOLVE_EXTENSIONS {
+        let mut with_extension = base.as_os_str().to_os_string();
+        with_extension.push(format!(".{extension}"));
+        candidates.push(PathBuf::from(with_extension));
+    }
+    for extension in RESOLVE_EXTENSIONS {
+        candidates.push(base.join(format!("index.{extension}")));
+    }
+
+    candidates
+        .into_iter()
+        .find_map(|candidate| known.get(&candidate).copied())
+}
+
+/// Absolute path with `.` and `..` resolved lexically, so the same file reached
+/// through different specifiers compares equal.
+pub(crate) fn normalize(path: &Path) -> PathBuf {
+    let absolute = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    let mut normalized = PathBuf::new();
+    for component in absolute.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// Quote a DOT identifier; Windows paths make the backslash escape matter.
+fn quote(id: &str) -> String {
+    format!("\"{}\"", id.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    fn build(files: &[(&str, &str)]) -> (TempDir, ModuleGraph) {
+        let temp_dir = TempDir::new().unwrap();
+        let paths: Vec<_> = files
+            .iter()
+            .map(|(name, content)| {
+                let path = temp_dir.path().join(name);
+                fs::create_dir_all(path.parent().unwrap()).unwrap();
+                fs::write(&path, content).unwrap();
+                path
+            })
+            .collect();
+
+        let graph = ModuleGraph::build(&FileHandler::new(false), &paths).unwrap();
+        (temp_dir, graph)
+    }
+
+    fn edges(graph: &ModuleGraph, root: &Path) -> Vec<(String, String, &'static str)> {
+        let short = |id: &str| {
+            let root = display_path(root);
+            id.strip_prefix(&root)
+                .map(|rest| rest.trim_start_matches(['/', '\\']).replace('\\', "/"))
+                .unwrap_or_else(|| id.to_string())
+        };
+        graph
+            .edges
+            .iter()
+            .map(|edge| (short(&edge.from), short(&edge.to), edge.category))
+            .collect()
+    }
+
+    #[test]
+    fn test_relative_imports_resolve_to_files() {
+        let (temp_dir, graph) = build(&[
+            (
+                "src/app.ts",
+                "import React from 'react';\nimport { api } from './api.js';\nimport { Button } from './ui';\nimport '@app/setup';\n",
+            ),
+            ("src/api.ts", "export * from '../shared/types';\nexport const api = 1;\n"),
+            ("src/ui/index.tsx", "export const Button = () => <button />;\n"),
+            ("shared/types.ts", "export type Id = string;\n"),
+        ]);
+
+        assert_eq!(
+            edges(&graph, temp_dir.path()),
+            vec![
+                ("src/app.ts".to_string(), "react".to_string(), "external"),
+                (
+                    "src/app.ts".to_string(),
+                    "src/api.ts".to_string(),
+                    "relative"
+                ),
+                (
+                    "src/app.ts".to_string(),
+                    "src/ui/index.tsx".to_string(),
+                    "relative"
+                ),
+                (
+                    "src/app.ts".to_string(),
+                    "@app/setup".to_string(),
+                    "absolute"
+                ),
+                (
+                    "src/api.ts".to_string(),
+                    "shared/types.ts".to_string(),
+                    "relative"
+                ),
+            ]
+        );
+        assert!(graph.edges[4].re_export);
+
+        let kinds: Vec<_> = graph.nodes.iter().map(|node| node.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                NodeKind::File,
+                NodeKind::File,
+                NodeKind::File,
+                NodeKind::File,
+                NodeKind::Module,
+                NodeKind::Module,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_render_dot_and_json() {
+        let graph = ModuleGraph {
+            nodes: vec![
+                GraphNode {
+                    id: "src\\app.ts".to_string(),
+                    kind: NodeKind::File,
+                },
+                GraphNode {
+                    id: "react".to_string(),
+                    kind: NodeKind::Module,
+                },
+            ],
+            edges: vec![GraphEdge {
+                from: "src\\app.ts".to_string(),
+                to: "react".to_string(),
+                specifier: "react".to_string(),
+                category: "external",
+                re_export: false,
+            }],
+        };
+
+        assert_eq!(
+            graph.render(GraphFormat::Dot).unwrap(),
+            "digraph imports {\n  \"src\\\\app.ts\" [shape=ellipse];\n  \"react\" [shape=box];\n  \"src\\\\app.ts\" -> \"react\" [label=\"external\"];\n}\n"
+        );
+
+        let json: serde_json::Value =
+            serde_json::from_str(&graph.render(GraphFormat::Json).unwrap()).unwrap();
+        assert_eq!(json["nodes"][1]["kind"], "module");
+        assert_eq!(json["edges"][0]["category"], "external");
+    }
+}
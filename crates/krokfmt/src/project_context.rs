@@ -0,0 +1,158 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use crate::config::{Config, CONFIG_FILE_NAME, INIT_FILE_NAME};
+
+/// Files whose presence marks a directory as a project root.
+///
+/// krokfmt.toml (or .krokrc.toml or .krokrc) is an explicit marker, package.json covers JS/TS
+/// packages (including each package in a workspace), and tsconfig.json covers
+/// TypeScript projects that aren't npm packages.
+const ROOT_MARKERS: &[&str] = &[
+    CONFIG_FILE_NAME,
+    INIT_FILE_NAME,
+    ".krokrc",
+    "package.json",
+    "tsconfig.json",
+];
+
+/// Everything krokfmt knows about the project a file belongs to.
+///
+/// A single invocation can span several projects (running at a monorepo root is
+/// the common case), and each of them may have its own configuration, path
+/// aliases, and workspace layout. Anything that depends on "the project" must be
+/// resolved from this context rather than computed once for the whole run.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectContext {
+    /// Nearest ancestor directory containing a root marker
+    pub root: PathBuf,
+    /// The project's package.json, if it has one
+    pub package_json_path: Option<PathBuf>,
+    /// The project's tsconfig.json, if it has one
+    pub tsconfig_path: Option<PathBuf>,
+    /// The project's krokfmt.toml or .krokrc, if it has one, or the config
+    /// every project was told to use instead
+    pub config_path: Option<PathBuf>,
+}
+
+/// The nearest directory containing `dir` (or `dir` itself) with a root
+/// marker.
+pub fn project_root(dir: &Path) -> Option<&Path> {
+    dir.ancestors()
+        .find(|ancestor| ROOT_MARKERS.iter().any(|m| ancestor.join(m).is_file()))
+}
+
+impl ProjectContext {
+    fn discover(dir: &Path) -> Self {
+        // Loose files outside any project act as their own root so they still
+        // get a context, just one with no project files attached.
+        let root = project_root(dir).unwrap_or(dir).to_path_buf();
+
+        let existing = |name: &str| Some(root.join(name)).filter(|path| path.is_file());
+
+        Self {
+            package_json_path: existing("package.json"),
+            tsconfig_path: existing("tsconfig.json"),
+            config_path: Config::in_dir(&root),
+            root,
+        }
+    }
+}
+
+/// Per-directory cache of project contexts.
+///
+/// Discovery walks up the directory tree and touches the filesystem, so we do
+/// it once per directory rather than once per file. The cache is shared across
+/// rayon workers, hence the mutex; lookups are cheap compared to formatting.
+#[derive(Default)]
+pub struct ProjectContextCache {
+    contexts: Mutex<HashMap<PathBuf, Arc<ProjectContext>>>,
+    config_override: Option<PathBuf>,
+}
+
+impl ProjectContextCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Give every project `config` instead of the config it would find.
+    ///
+    /// Project roots are still discovered, since they matter for more than
+    /// configuration; only where the rules come from changes.
+    pub fn with_config(mut self, config: PathBuf) -> Self {
+        self.config_override = Some(config);
+        self
+    }
+
+    /// Get the context for the project containing `file`.
+    pub fn context_for(&self, file: &Path) -> Arc<ProjectContext> {
+        let absolute = std::path::absolute(file).unwrap_or_else(|_| file.to_path_buf());
+        let dir = absolute
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| absolute.clone());
+
+        let mut contexts = self.contexts.lock().unwrap_or_else(|e| e.into_inner());
+        contexts
+            .entry(dir.clone())
+            .or_insert_with(|| {
+                let mut context = ProjectContext::discover(&dir);
+                if let Some(config) = &self.config_override {
+                    context.config_path = Some(config.clone());
+                }
+                Arc::new(context)
+            })
+            .clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_nearest_root_per_package() {
+        let temp_dir = TempDir::new().unwrap();
+        let monorepo = temp_dir.path();
+        let app = monorepo.join("packages/app");
+        let lib = monorepo.join("packages/lib/src");
+        fs::create_dir_all(&app).unwrap();
+        fs::create_dir_all(&lib).unwrap();
+
+        fs::write(monorepo.join("package.json"), "{}").unwrap();
+        fs::write(app.join("package.json"), "{}").unwrap();
+        fs::write(monorepo.join("packages/lib/tsconfig.json"), "{}").unwrap();
+
+        let cache = ProjectContextCache::new();
+
+        let app_context = cache.context_for(&app.join("index.ts"));
+        assert_eq!(app_context.root, app);
+        assert_eq!(
+            app_context.package_json_path,
+            Some(app.join("package.json"))
+        );
+        assert_eq!(app_context.tsconfig_path, None);
+
+        let lib_context = cache.context_for(&lib.join("index.ts"));
+        assert_eq!(lib_context.root, monorepo.join("packages/lib"));
+        assert_eq!(lib_context.package_json_path, None);
+
+        let root_context = cache.context_for(&monorepo.join("scripts.ts"));
+        assert_eq!(root_context.root, monorepo);
+    }
+
+    #[test]
+    fn test_contexts_are_cached_per_directory() {
+        let temp_dir = TempDir::new().unwrap();
+        fs::write(temp_dir.path().join("package.json"), "{}").unwrap();
+
+        let cache = ProjectContextCache::new();
+        let a = cache.context_for(&temp_dir.path().join("a.ts"));
+        let b = cache.context_for(&temp_dir.path().join("b.ts"));
+
+        assert!(Arc::ptr_eq(&a, &b));
+    }
+}
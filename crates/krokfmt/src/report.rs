@@ -0,0 +1,187 @@
+//! Machine-readable results of a formatting run, one JSON object per file.
+
+use anyhow::Result;
+use serde::Serialize;
+use std::path::Path;
+use std::time::Duration;
+
+use krokfmt_core::fingerprint::fingerprint;
+use krokfmt_core::organizer::ChangeLog;
+use krokfmt_core::parser::TypeScriptParser;
+use swc_ecma_ast::{ModuleDecl, ModuleItem};
+
+use crate::file_handler::display_path;
+
+/// How the results of a run are reported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Reporter {
+    /// Colored lines for people at a terminal
+    #[default]
+    Text,
+    /// One JSON object per line and file, for CI systems and pre-commit
+    /// frameworks
+    Json,
+}
+
+impl std::str::FromStr for Reporter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Reporter::Text),
+            "json" => Ok(Reporter::Json),
+            other => Err(format!(
+                "unknown reporter '{other}' (expected 'text' or 'json')"
+            )),
+        }
+    }
+}
+
+impl std::fmt::Display for Reporter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Reporter::Text => write!(f, "text"),
+            Reporter::Json => write!(f, "json"),
+        }
+    }
+}
+
+/// What formatting did to one file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileReport {
+    /// The file as it's shown everywhere else in krokfmt's output
+    pub path: String,
+    /// Whether formatting changed the file (or would, with `--check`)
+    pub changed: bool,
+    /// Why the file couldn't be formatted, if it couldn't
+    pub error: Option<String>,
+    pub duration_ms: u64,
+    /// Top-level imports that had to move
+    pub reordered_imports: usize,
+    /// Other top-level statements that had to move
+    pub reordered_declarations: usize,
+    /// What organizing reordered, with `--explain`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explanation: Option<ChangeLog>,
+}
+
+impl FileReport {
+    pub fn new(
+        path: &Path,
+        outcome: Result<bool, String>,
+        duration: Duration,
+        reordering: Reordering,
+    ) -> Self {
+        let (changed, error) = match outcome {
+            Ok(changed) => (changed, None),
+            Err(error) => (false, Some(error)),
+        };
+        Self {
+            path: display_path(path).replace('\\', "/"),
+            changed,
+            error,
+            duration_ms: duration.as_millis() as u64,
+            reordered_imports: reordering.imports,
+            reordered_declarations: reordering.declarations,
+            explanation: None,
+        }
+    }
+}
+
+/// How many top-level items formatting moved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Reordering {
+    pub imports: usize,
+    pub declarations: usize,
+}
+
+impl Reordering {
+    /// Count the top-level imports and other statements of `original` that
+    /// moved in `formatted`.
+    ///
+    /// Items are matched by semantic fingerprint, so sorting inside an item
+    /// doesn't count as moving it. Of the items of each kind, those outside
+    /// the longest sequence still in the original order are the ones that
+    /// moved: putting the last import first moves one import, not all of them.
+    pub fn between(original: &str, formatted: &str, filename: &str) -> Result<Self> {
+        let before = TypeScriptParser::parse_disambiguated(original, filename)?;
+        let after = TypeScriptParser::parse_disambiguated(formatted, filename)?;
+        let (before_imports, before_rest) = fingerprints(&before.module.body);
+        let (after_imports, after_rest) = fingerprints(&after.module.body);
+
+        Ok(Self {
+            imports: before_imports.len() - common_subsequence_len(&before_imports, &after_imports),
+            declarations: before_rest.len() - common_subsequence_len(&before_rest, &after_rest),
+        })
+    }
+}
+
+/// The fingerprints of the imports and of the other items, each in order.
+fn fingerprints(items: &[ModuleItem]) -> (Vec<String>, Vec<String>) {
+    let (imports, rest): (Vec<_>, Vec<_>) = items
+        .iter()
+        .partition(|item| matches!(item, ModuleItem::ModuleDecl(ModuleDecl::Import(_))));
+    (
+        imports.into_iter().map(fingerprint).collect(),
+        rest.into_iter().map(fingerprint).collect(),
+    )
+}
+
+/// Length of the longest common subsequence of `a` and `b`.
+fn common_subsequence_len(a: &[String], b: &[String]) -> usize {
+    let mut previous = vec![0; b.len() + 1];
+    let mut current = vec![0; b.len() + 1];
+    for x in a {
+        for (j, y) in b.iter().enumerate() {
+            current[j + 1] = if x == y {
+                previous[j] + 1
+            } else {
+                previous[j + 1].max(current[j])
+            };
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_reordering_counts_moved_items() {
+        let original = "import { b } from './b';\nimport { a } from './a';\nconst zebra = { y: 1, x: 2 };\nconst apple = 1;\nconst mango = 2;\n";
+        let formatted = "import { a } from './a';\nimport { b } from './b';\nconst apple = 1;\nconst mango = 2;\nconst zebra = { x: 2, y: 1 };\n";
+
+        assert_eq!(
+            Reordering::between(original, formatted, "a.ts").unwrap(),
+            Reordering {
+                imports: 1,
+                declarations: 1
+            }
+        );
+        assert_eq!(
+            Reordering::between(original, original, "a.ts").unwrap(),
+            Reordering::default()
+        );
+    }
+
+    #[test]
+    fn test_file_report_json() {
+        let report = FileReport::new(
+            &PathBuf::from("src/app.ts"),
+            Err("Failed to parse file".to_string()),
+            Duration::from_millis(12),
+            Reordering::default(),
+        );
+        let json = serde_json::to_value(&report).unwrap();
+
+        assert_eq!(json["path"], "src/app.ts");
+        assert_eq!(json["changed"], false);
+        assert_eq!(json["error"], "Failed to parse file");
+        assert_eq!(json["duration_ms"], 12);
+        assert_eq!(json["reordered_imports"], 0);
+        assert!(json.get("explanation").is_none());
+    }
+}
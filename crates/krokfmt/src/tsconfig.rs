@@ -0,0 +1,371 @@
+use anyhow::{bail, Context, Result};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::file_handler::display_path;
+use crate::module_graph::normalize;
+
+/// How many `extends` hops to follow before assuming a cycle.
+const MAX_EXTENDS_DEPTH: usize = 16;
+
+/// The path aliases of a tsconfig.json (`compilerOptions.paths`), resolved the
+/// way the TypeScript compiler resolves them.
+///
+/// Only what's needed to map specifiers to paths and back is read. `extends`
+/// is followed for relative paths; configs extended from packages are skipped,
+/// since finding them would mean reimplementing node's resolution.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TsconfigPaths {
+    /// Directory `paths` targets are relative to: `baseUrl`, or the directory
+    /// of the config declaring `paths` when there's none
+    base_dir: PathBuf,
+    /// `paths` entries in declaration order, like `@app/*` to `["src/*"]`
+    aliases: Vec<(String, Vec<String>)>,
+}
+
+impl TsconfigPaths {
+    /// Read the aliases of the tsconfig at `path`, or `None` if it has none.
+    pub fn load(path: &Path) -> Result<Option<Self>> {
+        let mut base_url = None;
+        let mut paths = None;
+        Self::load_into(path, &mut base_url, &mut paths, 0)?;
+
+        Ok(paths.map(|(paths_dir, aliases): (PathBuf, _)| Self {
+            base_dir: base_url.unwrap_or(paths_dir),
+            aliases,
+        }))
+    }
+
+    /// Fill in whatever `path` or the configs it extends set, nearest first.
+    fn load_into(
+        path: &Path,
+        base_url: &mut Option<PathBuf>,
+        paths: &mut Option<(PathBuf, Vec<(String, Vec<String>)>)>,
+        depth: usize,
+    ) -> Result<()> {
+        if depth > MAX_EXTENDS_DEPTH {
+            bail!(
+                "tsconfig extends chain is too deep at {}",
+                display_path(path)
+            );
+        }
+
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read {}", display_path(path)))?;
+        let config: Value = serde_json::from_str(&strip_jsonc(&content))
+            .with_context(|| format!("Failed to parse {}", display_path(path)))?;
+        let dir = path.parent().unwrap_or(Path::new("."));
+
+        let options = &config["compilerOptions"];
+        if base_url.is_none() {
+            if let Some(url) = options["baseUrl"].as_str() {
+                *base_url = Some(normalize(&dir.join(url)));
+            }
+        }
+        if paths.is_none() {
+            if let Some(entries) = options["paths"].as_object() {
+                let aliases = entries
+                    .iter()
+                    .map(|(pattern, targets)| {
+                        let targets = targets
+                            .as_array()
+                            .into_iter()
+                            .flatten()
+                            .filter_map(Value::as_str)
+                            .map(str::to_string)
+                            .collect();
+                        (pattern.clone(), targets)
+                    })
+                    .collect();
+                *paths = Some((normalize(dir), aliases));
+            }
+        }
+
+        // TypeScript 5 accepts a list, later entries taking precedence
+        let extends: Vec<&str> = match &config["extends"] {
+            Value::String(extends) => vec![extends.as_str()],
+            Value::Array(extends) => extends.iter().rev().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        for extends in extends {
+            if !extends.starts_with('.') {
+                continue;
+            }
+            // Like TypeScript, `./base` also finds `./base.json`
+            let mut parent = dir.join(extends);
+            if !parent.is_file() {
+                let mut with_json = parent.into_os_string();
+                with_json.push(".json");
+                parent = PathBuf::from(with_json);
+            }
+            Self::load_into(&parent, base_url, paths, depth + 1)?;
+        }
+
+        Ok(())
+    }
+
+    /// The paths `specifier` may refer to, in the order TypeScript tries them,
+    /// without extensions resolved. Empty if no alias matches.
+    pub fn resolve(&self, specifier: &str) -> Vec<PathBuf> {
+        self.aliases
+            .iter()
+            .filter_map(|(pattern, targets)| {
+                let captured = match pattern.split_once('*') {
+                    Some((prefix, suffix)) => {
+                        specifier.strip_prefix(prefix)?.strip_suffix(suffix)?
+                    }
+                    None if specifier == pattern => "",
+                    None => return None,
+                };
+                Some(targets.iter().map(move |target| {
+                    normalize(&self.base_dir.join(target.replacen('*', captured, 1)))
+                }))
+            })
+            .flatten()
+            .collect()
+    }
+
+    /// The shortest alias specifier for `path`, a module path as written but
+    /// made absolute.
+    pub fn alias_for(&self, path: &Path) -> Option<String> {
+        let path = forward_slashes(path);
+
+        self.aliases
+            .iter()
+            .flat_map(|(pattern, targets)| {
+                targets.iter().filter_map(move |target| {
+                    let target = forward_slashes(&normalize(&self.base_dir.join(target)));
+                    match (pattern.split_once('*'), target.split_once('*')) {
+                        (Some((prefix, suffix)), Some((target_prefix, target_suffix))) => {
+                            let captured = path
+                                .strip_prefix(target_prefix)?
+                                .strip_suffix(target_suffix)
+                                .filter(|captured| !captured.is_empty())?;
+                            Some(format!("{prefix}{captured}{suffix}"))
+                        }
+                        (None, None) if path == target => Some(pattern.clone()),
+                        _ => None,
+                    }
+                })
+            })
+            .min_by_key(String::len)
+    }
+
+    /// Every alias pattern but catch-all `*` ones, which map bare specifiers
+    /// and make no alias of them.
+    pub fn alias_patterns(&self) -> Vec<String> {
+        self.aliases
+            .iter()
+            .map(|(pattern, _)| pattern)
+            .filter(|pattern| !pattern.starts_with('*'))
+            .cloned()
+            .collect()
+    }
+
+    /// The alias patterns all of whose targets lie outside `dir`.
+    ///
+    /// Aliases into the project itself (`@/*` to `src/*`) are shortcuts for
+    /// relative paths. In a monorepo, aliases leading out of the project
+    /// (`@myorg/ui` to `../ui/src`) import the workspace's other packages.
+    /// Catch-all `*` aliases and aliases into node_modules are neither.
+    pub fn aliases_outside(&self, dir: &Path) -> Vec<String> {
+        let dir = normalize(dir);
+        self.aliases
+            .iter()
+            .filter(|(pattern, targets)| {
+                !pattern.starts_with('*')
+                    && !targets.is_empty()
+                    && targets.iter().all(|target| {
+                        let literal = target.split('*').next().unwrap_or_default();
+                        !literal.contains("node_modules")
+                            && !normalize(&self.base_dir.join(literal)).starts_with(&dir)
+                    })
+            })
+            .map(|(pattern, _)| pattern.clone())
+            .collect()
+    }
+}
+
+fn forward_slashes(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// tsconfig.json is JSON with comments and trailing commas; strip both so it
+/// parses as plain JSON.
+fn strip_jsonc(source: &str) -> String {
+    let mut stripped = String::with_capacity(source.len());
+    let mut chars = source.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            stripped.push(c);
+            match c {
+                '\\' => stripped.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                stripped.push(c);
+            }
+            ('/', Some('/')) => {
+                for c in chars.by_ref() {
+                    if c == '\n' {
+                        stripped.push(c);
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = ' ';
+                for c in chars.by_ref() {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+            }
+            (',', _) => {
+                // A comma is trailing when the next significant character
+                // closes the object or array
+                let rest: String = chars.clone().collect();
+                let next = strip_leading_trivia(&rest);
+                if !next.starts_with('}') && !next.starts_with(']') {
+                    stripped.push(c);
+                }
+            }
+            _ => stripped.push(c),
+        }
+    }
+
+    stripped
+}
+
+fn strip_leading_trivia(mut rest: &str) -> &str {
+    loop {
+        rest = rest.trim_start();
+        if let Some(comment) = rest.strip_prefix("//") {
+            rest = comment.split_once('\n').map_or("", |(_, after)| after);
+        } else if let Some(comment) = rest.strip_prefix("/*") {
+            rest = comment.split_once("*/").map_or("", |(_, after)| after);
+        } else {
+            return rest;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn test_strip_jsonc() {
+        let source = r#"{
+  // Line comment
+  "a": "http://not/a/comment", /* block */
+  "b": [1, 2,],
+}"#;
+        let value: Value = serde_json::from_str(&strip_jsonc(source)).unwrap();
+        assert_eq!(value["a"], "http://not/a/comment");
+        assert_eq!(value["b"], serde_json::json!([1, 2]));
+    }
+
+    #[test]
+    fn test_aliases_resolve_both_ways() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = normalize(temp_dir.path());
+        fs::write(
+            root.join("tsconfig.base.json"),
+            r#"{
+  "compilerOptions": {
+    "baseUrl": ".",
+    "paths": {
+      "@app/*": ["src/*"],
+      "@ui/*": ["src/components/ui/*"],
+      "@config": ["src/config/index.ts"],
+    },
+  },
+}"#,
+        )
+        .unwrap();
+        fs::write(
+            root.join("tsconfig.json"),
+            r#"{ "extends": "./tsconfig.base", "compilerOptions": { "strict": true } }"#,
+        )
+        .unwrap();
+
+        let paths = TsconfigPaths::load(&root.join("tsconfig.json"))
+            .unwrap()
+            .unwrap();
+
+        assert_eq!(
+            paths.resolve("@app/lib/util"),
+            vec![root.join("src/lib/util")]
+        );
+        assert_eq!(
+            paths.resolve("@config"),
+            vec![root.join("src/config/index.ts")]
+        );
+        assert!(paths.resolve("react").is_empty());
+
+        assert_eq!(
+            paths.alias_for(&root.join("src/lib/util")),
+            Some("@app/lib/util".to_string())
+        );
+        // The most specific alias gives the shortest specifier
+        assert_eq!(
+            paths.alias_for(&root.join("src/components/ui/button")),
+            Some("@ui/button".to_string())
+        );
+        assert_eq!(
+            paths.alias_for(&root.join("src/config/index.ts")),
+            Some("@config".to_string())
+        );
+        assert_eq!(paths.alias_for(&root.join("scripts/build")), None);
+    }
+
+    #[test]
+    fn test_aliases_outside_the_project() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = normalize(temp_dir.path());
+        let app = root.join("packages/app");
+        fs::create_dir_all(&app).unwrap();
+        fs::write(
+            app.join("tsconfig.json"),
+            r#"{
+  "compilerOptions": {
+    "paths": {
+      "@/*": ["./src/*"],
+      "@myorg/ui": ["../ui/src/index.ts"],
+      "@myorg/utils/*": ["../utils/src/*"],
+      "*": ["../../types/*"],
+      "legacy": ["../../node_modules/legacy-fork"],
+    },
+  },
+}"#,
+        )
+        .unwrap();
+
+        let paths = TsconfigPaths::load(&app.join("tsconfig.json"))
+            .unwrap()
+            .unwrap();
+        assert_eq!(paths.aliases_outside(&app), ["@myorg/ui", "@myorg/utils/*"]);
+    }
+
+    #[test]
+    fn test_config_without_paths() {
+        let temp_dir = TempDir::new().unwrap();
+        let tsconfig = temp_dir.path().join("tsconfig.json");
+        fs::write(&tsconfig, r#"{ "compilerOptions": { "baseUrl": "src" } }"#).unwrap();
+
+        assert_eq!(TsconfigPaths::load(&tsconfig).unwrap(), None);
+    }
+}
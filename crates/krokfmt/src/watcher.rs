@@ -0,0 +1,280 @@
+use anyhow::{Context, Result};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+use crate::file_handler::is_skipped_dir_name;
+
+/// How long changes have to stop arriving before a batch is formatted.
+///
+/// Editors save through temporary files and `git checkout` touches hundreds of
+/// files at once; waiting out the burst formats each file once, in parallel.
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// Changed paths gathered until no new change has arrived for a while.
+#[derive(Debug)]
+pub struct Debouncer {
+    delay: Duration,
+    pending: Vec<PathBuf>,
+    seen: HashSet<PathBuf>,
+    last_change: Option<Instant>,
+}
+
+impl Debouncer {
+    pub fn new(delay: Duration) -> Self {
+        Self {
+            delay,
+            pending: Vec::new(),
+            seen: HashSet::new(),
+            last_change: None,
+        }
+    }
+
+    /// Record that `paths` changed at `now`, pushing the batch back.
+    pub fn add(&mut self, paths: impl IntoIterator<Item = PathBuf>, now: Instant) {
+        for path in paths {
+            if self.seen.insert(path.clone()) {
+                self.pending.push(path);
+            }
+        }
+        self.last_change = Some(now);
+    }
+
+    /// How long until the pending batch is ready, or `None` with nothing
+    /// pending.
+    pub fn timeout(&self, now: Instant) -> Option<Duration> {
+        self.last_change
+            .map(|last_change| (last_change + self.delay).saturating_duration_since(now))
+    }
+
+    /// Take the pending paths, in the order they first changed, once changes
+    /// have stopped for the debounce delay.
+    pub fn take_ready(&mut self, now: Instant) -> Option<Vec<PathBuf>> {
+        if self.timeout(now)? > Duration::ZERO {
+            return None;
+        }
+
+        self.last_change = None;
+        self.seen.clear();
+        Some(std::mem::take(&mut self.pending))
+    }
+}
+
+/// Watches files and directories for changes, handing them over in debounced
+/// batches.
+///
+/// The process stays up between batches, so a change costs only formatting
+/// the files it touched rather than starting krokfmt from scratch.
+pub struct FileWatcher {
+    // Dropping the watcher stops the events
+    _watcher: RecommendedWatcher,
+    events: mpsc::Receiver<notify::Result<notify::Event>>,
+    debouncer: Debouncer,
+    /// Absolute paths being watched
+    roots: Vec<PathBuf>,
+}
+
+impl FileWatcher {
+    /// Watch `paths` as the formatter takes them: directories recursively,
+    /// files on their own, and globs through the directory they start in.
+    /// `!pattern` exclusions aren't watched.
+    pub fn new(paths: &[PathBuf], debounce: Duration) -> Result<Self> {
+        let (sender, events) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(sender).context("Failed to watch files")?;
+        let mut roots = Vec::new();
+
+        for path in paths {
+            let Some(root) = watch_root(path) else {
+                continue;
+            };
+            let mode = if root.is_dir() {
+                RecursiveMode::Recursive
+            } else {
+                RecursiveMode::NonRecursive
+            };
+            // Absolute, so events report absolute paths on every platform
+            let root = std::path::absolute(&root).unwrap_or(root);
+            watcher
+                .watch(&root, mode)
+                .with_context(|| format!("Failed to watch {}", root.display()))?;
+            roots.push(root);
+        }
+
+        Ok(Self {
+            _watcher: watcher,
+            events,
+            debouncer: Debouncer::new(debounce),
+            roots,
+        })
+    }
+
+    /// Block until files were created or modified and changes then stopped
+    /// for the debounce delay, returning their absolute paths.
+    pub fn next_batch(&mut self) -> Result<Vec<PathBuf>> {
+        loop {
+            let now = Instant::now();
+            if let Some(batch) = self.debouncer.take_ready(now) {
+                return Ok(batch);
+            }
+
+            let event = match self.debouncer.timeout(now) {
+                Some(timeout) => match self.events.recv_timeout(timeout) {
+                    Ok(event) => event,
+                    Err(RecvTimeoutError::Timeout) => continue,
+                    Err(RecvTimeoutError::Disconnected) => anyhow::bail!("File watcher stopped"),
+                },
+                None => self.events.recv().context("File watcher stopped")?,
+            };
+
+            let event = event.context("Failed to watch files")?;
+            if matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                // `npm install` and git write thousands of files that discovery
+                // skips anyway; they shouldn't even start a batch
+                let paths: Vec<PathBuf> = event
+                    .paths
+                    .into_iter()
+                    .filter(|path| !in_skipped_dir(&self.roots, path))
+                    .collect();
+                if !paths.is_empty() {
+                    self.debouncer.add(paths, Instant::now());
+                }
+            }
+        }
+    }
+}
+
+/// Whether `path` lies in a directory discovery skips (node_modules, `.git`
+/// and other hidden directories) below the watched root containing it.
+///
+/// Only directories below the root count, so a project checked out under a
+/// hidden directory is still watched.
+fn in_skipped_dir(roots: &[PathBuf], path: &Path) -> bool {
+    let Some(relative) = roots
+        .iter()
+        .filter_map(|root| path.strip_prefix(root).ok())
+        .min_by_key(|relative| relative.components().count())
+    else {
+        return false;
+    };
+
+    let mut dirs = relative.components();
+    dirs.next_back();
+    dirs.any(|dir| is_skipped_dir_name(&dir.as_os_str().to_string_lossy()))
+}
+
+/// What to watch for a path given on the command line: the path itself if it
+/// exists, or for a glob, the deepest existing directory before its first
+/// wildcard.
+fn watch_root(path: &Path) -> Option<PathBuf> {
+    if path.exists() {
+        return Some(path.to_path_buf());
+    }
+
+    let pattern = path.to_str()?;
+    if pattern.starts_with('!') {
+        return None;
+    }
+
+    let literal: PathBuf = path
+        .components()
+        .take_while(|component| {
+            !component
+                .as_os_str()
+                .to_string_lossy()
+                .contains(['*', '?', '[', '{'])
+        })
+        .collect();
+    literal
+        .ancestors()
+        .find(|ancestor| ancestor.is_dir())
+        .map(|ancestor| {
+            if ancestor.as_os_str().is_empty() {
+                PathBuf::from(".")
+            } else {
+                ancestor.to_path_buf()
+            }
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_debouncer_waits_for_changes_to_stop() {
+        let start = Instant::now();
+        let delay = Duration::from_millis(100);
+        let mut debouncer = Debouncer::new(delay);
+        assert_eq!(debouncer.timeout(start), None);
+        assert_eq!(debouncer.take_ready(start), None);
+
+        debouncer.add([PathBuf::from("b.ts"), PathBuf::from("a.ts")], start);
+        debouncer.add(
+            [PathBuf::from("a.ts"), PathBuf::from("c.ts")],
+            start + Duration::from_millis(60),
+        );
+
+        // The second change pushed the batch back
+        assert_eq!(debouncer.take_ready(start + delay), None);
+        assert_eq!(
+            debouncer.timeout(start + delay),
+            Some(Duration::from_millis(60))
+        );
+
+        assert_eq!(
+            debouncer.take_ready(start + Duration::from_millis(160)),
+            Some(vec![
+                PathBuf::from("b.ts"),
+                PathBuf::from("a.ts"),
+                PathBuf::from("c.ts"),
+            ])
+        );
+        assert_eq!(debouncer.timeout(start + Duration::from_millis(160)), None);
+    }
+
+    #[test]
+    fn test_skipped_dirs_are_not_watched() {
+        let roots = [PathBuf::from("/home/me/.projects/app")];
+
+        assert!(!in_skipped_dir(
+            &roots,
+            Path::new("/home/me/.projects/app/src/a.ts")
+        ));
+        assert!(!in_skipped_dir(
+            &roots,
+            Path::new("/home/me/.projects/app/.eslintrc.ts")
+        ));
+        assert!(in_skipped_dir(
+            &roots,
+            Path::new("/home/me/.projects/app/node_modules/lib/index.ts")
+        ));
+        assert!(in_skipped_dir(
+            &roots,
+            Path::new("/home/me/.projects/app/.git/index")
+        ));
+        assert!(!in_skipped_dir(
+            &roots,
+            Path::new("/elsewhere/node_modules/a.ts")
+        ));
+    }
+
+    #[test]
+    fn test_watch_root() {
+        let temp_dir = tempfile::TempDir::new().unwrap();
+        let root = temp_dir.path();
+        std::fs::create_dir(root.join("src")).unwrap();
+
+        assert_eq!(watch_root(root), Some(root.to_path_buf()));
+        assert_eq!(
+            watch_root(&root.join("src/**/*.{ts,tsx}")),
+            Some(root.join("src"))
+        );
+        assert_eq!(
+            watch_root(&root.join("missing/*.ts")),
+            Some(root.to_path_buf())
+        );
+        assert_eq!(watch_root(Path::new("!src/*.test.ts")), None);
+    }
+}
@@ -0,0 +1,211 @@
+//! The packages of the monorepo a project belongs to and the aliases of its
+//! tsconfig.json, which decide how krokfmt groups imports by name.
+
+use glob::Pattern;
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use krokfmt_core::transformer::{PathAliases, ProjectLayout, WorkspacePackages};
+
+use crate::config::Config;
+use crate::file_handler::io_path;
+use crate::project_context::ProjectContext;
+use crate::tsconfig::TsconfigPaths;
+
+/// The layout of the project at `context`, configured with `config`.
+///
+/// tsconfig.json files that can't be read are skipped, like a project without
+/// one: its imports are then grouped by their specifiers alone.
+pub fn project_layout(context: &ProjectContext, config: &Config) -> ProjectLayout {
+    let tsconfig = context
+        .tsconfig_path
+        .as_deref()
+        .and_then(|path| TsconfigPaths::load(path).ok().flatten());
+
+    ProjectLayout {
+        workspace: workspace_packages(context, config, tsconfig.as_ref()),
+        aliases: PathAliases::new(tsconfig.map(|t| t.alias_patterns()).unwrap_or_default()),
+    }
+}
+
+/// The workspace packages of the project at `context`, whose `tsconfig`
+/// aliases are given.
+///
+/// They're found in three places, all of which count:
+/// - the `workspaces` of the nearest package.json declaring any, or the
+///   `packages` of a pnpm-workspace.yaml, by the names of the packages there
+/// - the project's tsconfig.json `paths` aliases leading out of the project
+/// - the `workspaces` listed in krokfmt.toml
+///
+/// Workspace files that can't be read are skipped: without them the packages
+/// are simply grouped with the other external ones.
+fn workspace_packages(
+    context: &ProjectContext,
+    config: &Config,
+    tsconfig: Option<&TsconfigPaths>,
+) -> WorkspacePackages {
+    let mut patterns = config.workspaces.clone();
+
+    if let Some((root, globs)) = context.root.ancestors().find_map(workspace_globs) {
+        patterns.extend(package_names(&root, &globs));
+    }
+
+    if let Some(tsconfig) = tsconfig {
+        patterns.extend(tsconfig.aliases_outside(&context.root));
+    }
+
+    WorkspacePackages::new(patterns)
+}
+
+/// The package directory globs of the workspace rooted at `dir`, if it's the
+/// root of one.
+fn workspace_globs(dir: &Path) -> Option<(PathBuf, Vec<String>)> {
+    if let Ok(content) = fs::read_to_string(io_path(&dir.join("package.json"))) {
+        if let Ok(package) = serde_json::from_str::<Value>(&content) {
+            // Either a list, or Yarn's `{ "packages": [...] }`
+            let workspaces = match &package["workspaces"] {
+                Value::Object(workspaces) => workspaces.get("packages"),
+                workspaces => Some(workspaces),
+            };
+            if let Some(Value::Array(globs)) = workspaces {
+                let globs = globs.iter().filter_map(Value::as_str).map(str::to_string);
+                return Some((dir.to_path_buf(), globs.collect()));
+            }
+        }
+    }
+
+    let content = fs::read_to_string(io_path(&dir.join("pnpm-workspace.yaml"))).ok()?;
+    Some((dir.to_path_buf(), pnpm_package_globs(&content)))
+}
+
+/// The `packages` list of a pnpm-workspace.yaml, read line by line rather than
+/// with a YAML parser, since it's only ever a list of quoted or bare globs.
+fn pnpm_package_globs(content: &str) -> Vec<String> {
+    let mut globs = Vec::new();
+    let mut in_packages = false;
+
+    for line in content.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        if !line.starts_with([' ', '\t', '-']) {
+            in_packages = trimmed == "packages:";
+            continue;
+        }
+        if let Some(item) = trimmed.strip_prefix('-').filter(|_| in_packages) {
+            let item = item.split(" #").next().unwrap_or_default().trim();
+            globs.push(item.trim_matches(['"', '\'']).to_string());
+        }
+    }
+
+    globs
+}
+
+/// The names of the packages matched by the workspace `globs` below `root`.
+/// `!glob` exclusions leave packages out.
+fn package_names(root: &Path, globs: &[String]) -> Vec<String> {
+    let base = Pattern::escape(&root.to_string_lossy());
+    let excluded: Vec<Pattern> = globs
+        .iter()
+        .filter_map(|glob| glob.strip_prefix('!'))
+        .filter_map(|glob| Pattern::new(&format!("{base}/{}", glob.trim_end_matches('/'))).ok())
+        .collect();
+
+    globs
+        .iter()
+        .filter(|glob| !glob.starts_with('!'))
+        .filter_map(|glob| {
+            glob::glob(&format!(
+                "{base}/{}/package.json",
+                glob.trim_end_matches('/')
+            ))
+            .ok()
+        })
+        .flatten()
+        .flatten()
+        .filter(|manifest| {
+            let dir = manifest.parent().unwrap_or(manifest);
+            // `packages/**` reaches into each package's installed dependencies
+            !dir.components().any(|c| c.as_os_str() == "node_modules")
+                && !excluded.iter().any(|pattern| pattern.matches_path(dir))
+        })
+        .filter_map(|manifest| {
+            let content = fs::read_to_string(io_path(&manifest)).ok()?;
+            let package: Value = serde_json::from_str(&content).ok()?;
+            package["name"].as_str().map(str::to_string)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn write_package(dir: &Path, content: &str) {
+        fs::create_dir_all(dir).unwrap();
+        fs::write(dir.join("package.json"), content).unwrap();
+    }
+
+    #[test]
+    fn test_package_json_workspaces() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        write_package(
+            root,
+            r#"{ "private": true, "workspaces": ["packages/*", "!packages/scratch"] }"#,
+        );
+        write_package(&root.join("packages/app"), r#"{ "name": "@myorg/app" }"#);
+        write_package(&root.join("packages/ui"), r#"{ "name": "@myorg/ui" }"#);
+        write_package(&root.join("packages/scratch"), r#"{ "name": "scratch" }"#);
+
+        let context = ProjectContext {
+            root: root.join("packages/app"),
+            package_json_path: Some(root.join("packages/app/package.json")),
+            tsconfig_path: None,
+            config_path: None,
+        };
+        let config = Config {
+            workspaces: vec!["legacy-utils".to_string()],
+            ..Config::default()
+        };
+
+        assert_eq!(
+            project_layout(&context, &config).workspace.patterns(),
+            ["@myorg/app", "@myorg/ui", "legacy-utils"]
+        );
+    }
+
+    #[test]
+    fn test_tsconfig_aliases() {
+        let temp_dir = TempDir::new().unwrap();
+        let root = temp_dir.path();
+        fs::write(
+            root.join("tsconfig.json"),
+            r#"{ "compilerOptions": { "paths": { "@ui/*": ["src/ui/*"], "*": ["types/*"] } } }"#,
+        )
+        .unwrap();
+
+        let context = ProjectContext {
+            root: root.to_path_buf(),
+            package_json_path: None,
+            tsconfig_path: Some(root.join("tsconfig.json")),
+            config_path: None,
+        };
+        let layout = project_layout(&context, &Config::default());
+
+        assert_eq!(layout.aliases.patterns(), ["@ui/*"]);
+        assert!(layout.workspace.is_empty());
+    }
+
+    #[test]
+    fn test_pnpm_package_globs() {
+        let content = "packages:\n  # The apps\n  - 'apps/*'\n  - \"packages/**\" # and libraries\n  - '!**/test/**'\ncatalog:\n  - react\n";
+        assert_eq!(
+            pnpm_package_globs(content),
+            ["apps/*", "packages/**", "!**/test/**"]
+        );
+    }
+}
@@ -0,0 +1,11 @@
+// FR1.8: simple-import-sort interop profile
+import { helper } from './helper';
+import './polyfills';
+import { api } from '../api';
+import { readFile } from 'node:fs/promises';
+import { Button } from '@/components/Button';
+import React from 'react';
+import { parse } from '@babel/parser';
+import { config } from '~/config';
+
+export const app = helper(api, config, Button, React, parse, readFile);
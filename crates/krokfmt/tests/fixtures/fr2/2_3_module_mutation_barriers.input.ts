@@ -0,0 +1,24 @@
+// FR2.3: Top-level side effects act as reordering barriers
+const registry: Handler[] = [];
+
+export function zebraHandler() {
+    return 'zebra';
+}
+
+function appleHandler() {
+    return 'apple';
+}
+
+registry.push(zebraHandler);
+registry.push(appleHandler);
+
+// Declarations after the barrier must stay after it
+export const snapshot = [...registry];
+
+const count = registry.length;
+
+export function describe() {
+    return `${count} handlers`;
+}
+
+'use strict';
@@ -0,0 +1,8 @@
+// FR3.6: Literal `true` props collapse to shorthand, everything else stays explicit
+export const Form = ({ isReady }: { isReady: boolean }) => (
+    <form noValidate={true}>
+        <input required={true} disabled={false} type="text" />
+        <input readOnly={isReady} autoFocus={!isReady} hidden />
+        <button type="submit" disabled={isReady === false}>Submit</button>
+    </form>
+);
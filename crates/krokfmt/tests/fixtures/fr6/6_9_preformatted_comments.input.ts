@@ -0,0 +1,36 @@
+// FR6.9: ASCII diagrams and tables in block comments are preserved byte-for-byte
+
+/*
+ * Request lifecycle:
+ *
+ *   +----------+      +-----------+      +----------+
+ *   | Incoming | ---> | Validator | ---> | Handler  |
+ *   +----------+      +-----------+      +----------+
+ *                           |
+ *                           v
+ *                     +-----------+
+ *                     |  Reject   |
+ *                     +-----------+ */
+export function handle(request: Request) {
+    return request;
+}
+
+/*
+  | Status | Meaning     | Retry |
+  |--------|-------------|:-----:|
+  | 200    | OK          |  no   |
+  | 429    | Rate limit  |  yes  |
+  | 503    | Unavailable |  yes  |
+*/
+export const RETRYABLE = [429, 503];
+
+/*
+    ┌─────────┐
+    │  cache  │
+    └────┬────┘
+         │
+    ┌────┴────┐
+    │  store  │
+    └─────────┘
+*/
+export class Store {}
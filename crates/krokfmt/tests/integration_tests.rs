@@ -61,6 +61,58 @@ const App = () => {
     assert!(result.contains("return <div>Hello</div>"));
 }
 
+#[test]
+fn test_re_exports_are_grouped_like_imports() {
+    let input = r#"
+export * from 'lodash';
+export type { Config } from 'config-lib';
+export { api } from '@services/api';
+export type { User } from './types';
+export { helper } from './helper';
+const local = 1;
+"#;
+
+    let result = organize_code(input);
+
+    // Type-only re-exports take part in the groups instead of reading as
+    // type aliases
+    assert!(
+        result.contains(
+            "export * from 'lodash';\nexport type { Config } from 'config-lib';\n\nexport { api } from '@services/api';\n\nexport type { User } from './types';\nexport { helper } from './helper';\n\nconst local = 1;"
+        ),
+        "{result}"
+    );
+}
+
+#[test]
+fn test_blank_lines_never_land_inside_template_literals() {
+    let input = r#"
+export const TEMPLATE = `
+class Fake {
+import nothing from 'here';
+`;
+
+export class Widget {
+    label = `
+static field
+`;
+    constructor() {}
+}
+"#;
+
+    let result = organize_code(input);
+
+    // Separators come from the declarations themselves, so code-like lines
+    // in strings don't start one
+    assert!(
+        result.contains("`\nclass Fake {\nimport nothing from 'here';\n`"),
+        "{result}"
+    );
+    assert!(result.contains("`\nstatic field\n`"), "{result}");
+    // The field and the constructor are separate member groups
+    assert!(result.contains("`;\n\n    constructor"), "{result}");
+}
+
 #[test]
 fn test_object_property_sorting() {
     let input = r#"
@@ -235,3 +287,450 @@ const obj = {
     assert!(result.contains("apple: 2"));
     assert!(result.contains("zebra: 1"));
 }
+
+#[test]
+fn test_ts_type_assertions_are_not_parsed_as_jsx() {
+    let input = "const value = <Foo>(bar);\nconst view = <Bar>baz;\n";
+    let result = krokfmt::format_typescript(input, "cast.ts").unwrap();
+
+    // Under TSX these would be JSX elements (or a parse error), not assertions
+    assert!(result.contains("<Foo>bar"), "{result}");
+    assert!(result.contains("<Bar>baz"), "{result}");
+}
+
+#[test]
+fn test_jsx_in_ts_file_falls_back_to_tsx() {
+    let input = "export const App = () => <div className=\"app\" />;\n";
+    let result = krokfmt::format_typescript(input, "app.ts").unwrap();
+
+    assert!(result.contains("<div className=\"app\" />"), "{result}");
+}
+
+#[test]
+fn test_javascript_files_are_organized() {
+    let input = "import { z, a } from 'lib';\nexport const App = () => <div className=\"app\" />;\nconst config = { b: 2, a: 1 };\n";
+    let result = krokfmt::format_typescript(input, "app.js").unwrap();
+
+    assert!(result.contains("import { a, z }"), "{result}");
+    assert!(result.contains("<div className=\"app\" />"), "{result}");
+    assert!(
+        result.find("a: 1").unwrap() < result.find("b: 2").unwrap(),
+        "{result}"
+    );
+}
+
+#[test]
+fn test_vue_script_blocks_are_formatted_in_place() {
+    use krokfmt::embedded_extractor::format_script_blocks;
+
+    let template = "<template>\n  <Button :label=\"label\" />\n</template>\n\n";
+    let style = "\n<style scoped>\n.button {   color: red }\n</style>\n";
+    let script = "<script setup lang=\"ts\">\nimport { z, a } from 'lib';\nconst props = { b: 2, a: 1 };\n</script>\n";
+    let input = format!("{template}{script}{style}");
+    let result = format_script_blocks(&input, "Button.vue", |script| {
+        krokfmt::format_typescript(script.code, script.filename)
+    })
+    .unwrap();
+
+    assert!(result.starts_with(&template), "{result}");
+    assert!(result.ends_with(&format!("</script>\n{style}")), "{result}");
+    assert!(result.contains("import { a, z }"), "{result}");
+}
+
+#[test]
+fn test_literal_raw_text_preserved() {
+    let input = r#"
+const zebra = 'it\'s "quoted"';
+const apple = { million: 1_000_000, mask: 0xFF, flags: 0b1010, big: 1E10, mode: 0o755 };
+"#;
+
+    let result = organize_code(input);
+
+    // Reordering must not rewrite literals whose values it never touched
+    for raw in [
+        "1_000_000",
+        "0xFF",
+        "0b1010",
+        "1E10",
+        "0o755",
+        r#"'it\'s "quoted"'"#,
+    ] {
+        assert!(result.contains(raw), "{raw} missing from:\n{result}");
+    }
+}
+
+#[test]
+fn test_literal_raw_text_survives_formatting() {
+    let input = r#"const zebra = 'it\'s "quoted"';
+const apple = { million: 1_000_000, mask: 0xff, big: 1e10, flags: 0b1010 };
+"#;
+
+    let result = krokfmt::format_typescript(input, "literals.ts").unwrap();
+
+    // Biome lowercases `0xFF` and `1E10`, so these are already in its
+    // spelling; anything else here changing means a literal was respelled
+    for raw in ["1_000_000", "0xff", "1e10", "0b1010", r#"'it\'s "quoted"'"#] {
+        assert!(result.contains(raw), "{raw} missing from:\n{result}");
+    }
+}
+
+#[test]
+fn test_typescript_interop_module_syntax() {
+    let input = r#"import { b } from './b';
+
+// The public surface
+export = api;
+
+// Pulled in for its CommonJS default
+import fs = require('fs');
+import Alias = Foo.Bar;
+
+const api = { b, fs };
+"#;
+    let result = krokfmt::format_typescript(input, "interop.ts").unwrap();
+
+    let require_pos = result.find("import fs = require(").expect(&result);
+    let relative_pos = result.find("import { b } from").expect(&result);
+    let alias_pos = result.find("import Alias = Foo.Bar").expect(&result);
+    let export_pos = result.find("export = api;").expect(&result);
+
+    // `import =` sorts with the other imports, `export =` ends the module
+    assert!(require_pos < relative_pos, "{result}");
+    assert!(relative_pos < alias_pos, "{result}");
+    assert!(alias_pos < export_pos, "{result}");
+    assert!(result.trim_end().ends_with("export = api;"), "{result}");
+
+    // Comments travel with both statements
+    let comment_pos = result
+        .find("// Pulled in for its CommonJS default")
+        .expect(&result);
+    assert!(comment_pos < require_pos, "{result}");
+    let surface_pos = result.find("// The public surface").expect(&result);
+    assert!(
+        alias_pos < surface_pos && surface_pos < export_pos,
+        "{result}"
+    );
+}
+
+#[test]
+fn test_format_generated_never_reorders() {
+    let input = r#"import { z } from './z';
+import   axios from 'axios';
+const zeta = { b: 1, a: 2 };
+export   function alpha() { return zeta; }
+class Model { save() {} constructor() {} }
+"#;
+    let result = krokfmt::format_generated(input, "generated.ts").unwrap();
+
+    let positions: Vec<usize> = [
+        "import { z }",
+        "import axios",
+        "const zeta",
+        "b: 1",
+        "a: 2",
+        "export function alpha",
+        "save()",
+        "constructor()",
+    ]
+    .iter()
+    .map(|needle| {
+        result
+            .find(needle)
+            .unwrap_or_else(|| panic!("{needle}:\n{result}"))
+    })
+    .collect();
+
+    assert!(positions.windows(2).all(|w| w[0] < w[1]), "{result}");
+}
+
+#[test]
+fn test_file_pragmas_stay_on_top() {
+    let input = r#"#!/usr/bin/env node
+// @ts-nocheck
+/* eslint-disable no-console */
+
+// The schemas come first
+import { z } from 'zod';
+import a from 'a';
+"#;
+    let result = krokfmt::format_typescript(input, "cli.ts").unwrap();
+
+    assert!(
+        result.starts_with(
+            "#!/usr/bin/env node\n// @ts-nocheck\n/* eslint-disable no-console */\n\nimport a from \"a\";\n"
+        ),
+        "{result}"
+    );
+    assert!(
+        result.contains("// The schemas come first\nimport { z } from \"zod\";"),
+        "{result}"
+    );
+    assert_eq!(
+        krokfmt::format_typescript(&result, "cli.ts").unwrap(),
+        result
+    );
+}
+
+#[test]
+fn test_directives_stay_above_imports() {
+    let input = r#"'use client';
+import { Button } from './button';
+import { useState } from 'react';
+
+export function Counter() {
+  const [count] = useState(0);
+  return Button(count);
+}
+"#;
+    let result = krokfmt::format_typescript(input, "counter.ts").unwrap();
+
+    assert!(
+        result.starts_with("\"use client\";\n\nimport { useState } from \"react\";\n"),
+        "{result}"
+    );
+    assert_eq!(
+        krokfmt::format_typescript(&result, "counter.ts").unwrap(),
+        result
+    );
+}
+
+#[test]
+fn test_factory_based_test_files_keep_generics() {
+    let input = r#"import { h } from 'preact';
+import React from 'react';
+
+const render = <P,>(component: (props: P) => unknown, props: P) => component(props);
+const wrap = <T>(value: T) => ({ value });
+
+it('renders', () => {
+  const tree = React.createElement('ul', null, h('li', { key: 'a' }, 'A'));
+  expect(render(() => tree, {})).toEqual(wrap(tree).value);
+});
+"#;
+
+    for filename in ["list.spec.ts", "list.test.ts"] {
+        let result = krokfmt::format_typescript(input, filename).unwrap();
+
+        // Parsed as TS, so the generic arrows survive instead of becoming JSX
+        assert!(result.contains("<T>(value: T) =>"), "{result}");
+        assert!(result.contains("React.createElement(\"ul\""), "{result}");
+        assert!(result.contains("h(\"li\""), "{result}");
+    }
+}
+
+#[test]
+fn test_format_typescript_cow_borrows_formatted_source() {
+    use std::borrow::Cow;
+
+    let input = "import   b from 'b';\nimport a from 'a';\n";
+    let formatted = krokfmt::format_typescript_cow(input, "input.ts").unwrap();
+    assert!(matches!(formatted, Cow::Owned(_)), "{formatted}");
+
+    let again = krokfmt::format_typescript_cow(&formatted, "input.ts").unwrap();
+    assert!(matches!(again, Cow::Borrowed(_)), "{again}");
+    assert!(krokfmt::check_typescript(&formatted, "input.ts")
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn test_format_typescript_with_options_skips_disabled_rules() {
+    use krokfmt::rules::{RuleGroup, RuleSet};
+    use krokfmt::FormatOptions;
+
+    let input = "export enum Color {\n  Red = \"red\",\n  Blue = \"blue\",\n}\n";
+
+    let sorted = krokfmt::format_typescript(input, "input.ts").unwrap();
+    assert!(
+        sorted.find("Blue").unwrap() < sorted.find("Red").unwrap(),
+        "{sorted}"
+    );
+
+    let options = FormatOptions {
+        rules: RuleSet::all().disable(RuleGroup::EnumMembers),
+        ..FormatOptions::default()
+    };
+    let kept = krokfmt::format_typescript_with_options(input, "input.ts", &options).unwrap();
+    assert_eq!(kept, input);
+}
+
+#[test]
+fn test_imports_only_keeps_declarations_as_written() {
+    use krokfmt::rules::RuleSet;
+    use krokfmt::FormatOptions;
+
+    let input = "import { z } from './z';\nimport { a } from 'a';\n\nconst internal = { zebra: 1, apple: 2 };\nexport const api = internal;\n";
+    let options = FormatOptions {
+        rules: RuleSet::imports_only(),
+        ..FormatOptions::default()
+    };
+    let formatted = krokfmt::format_typescript_with_options(input, "input.ts", &options).unwrap();
+
+    let position = |text: &str| formatted.find(text).unwrap();
+    assert!(position("from 'a'") < position("from './z'"), "{formatted}");
+    assert!(
+        position("const internal") < position("export const api"),
+        "{formatted}"
+    );
+    assert!(position("zebra") < position("apple"), "{formatted}");
+}
+
+#[test]
+fn test_format_typescript_with_options_fits_the_output() {
+    use krokfmt::parser::JsxDetection;
+    use krokfmt::{FormatOptions, LineEnding};
+
+    let input = "const b = 2;\r\nconst a = `x\r\ny`;\r\n";
+    let format = |options: FormatOptions| {
+        krokfmt::format_typescript_with_options(input, "input.ts", &options).unwrap()
+    };
+
+    assert_eq!(
+        format(FormatOptions::default()),
+        "const a = `x\ny`;\nconst b = 2;\n"
+    );
+    assert_eq!(
+        format(FormatOptions {
+            line_ending: LineEnding::Auto,
+            final_newline: false,
+            ..FormatOptions::default()
+        }),
+        "const a = `x\r\ny`;\r\nconst b = 2;"
+    );
+
+    // Without the fallback to TSX, a `.ts` file holding JSX fails to parse
+    let jsx = "const el = <div />;\n";
+    let never = FormatOptions {
+        jsx: JsxDetection::Never,
+        ..FormatOptions::default()
+    };
+    assert!(krokfmt::format_typescript_with_options(jsx, "input.ts", &never).is_err());
+    assert!(krokfmt::format_typescript(jsx, "input.ts").is_ok());
+}
+
+#[test]
+fn test_verify_idempotent() {
+    let input = "import { b, a } from './lib';\n// About x\nconst x = { z: 1, y: 2 };\n";
+    assert_eq!(krokfmt::verify_idempotent(input, "input.ts").unwrap(), None);
+}
+
+#[test]
+fn test_verify_idempotent_with_options() {
+    use krokfmt::rules::{RuleGroup, RuleSet};
+    use krokfmt::FormatOptions;
+
+    let input = "export enum Color {\n  Red = \"red\",\n  Blue = \"blue\",\n}\nconst b = 1;\nconst a = 2;\n";
+    let options = FormatOptions {
+        rules: RuleSet::all()
+            .disable(RuleGroup::EnumMembers)
+            .disable(RuleGroup::DeclarationOrder),
+        ..FormatOptions::default()
+    };
+    assert_eq!(
+        krokfmt::verify_idempotent_with_options(input, "input.ts", &options).unwrap(),
+        None
+    );
+}
+
+#[test]
+fn test_format_typescript_range_leaves_the_rest_untouched() {
+    let input = "const  b = 2;\nconst config = { zebra: 1, apple: 2 };\nconst  a = 1;\n";
+    let formatted = krokfmt::format_typescript_range(input, "input.ts", 2, 2).unwrap();
+
+    assert!(formatted.starts_with("const  b = 2;\n"), "{formatted}");
+    assert!(formatted.ends_with("\nconst  a = 1;\n"), "{formatted}");
+    assert!(
+        formatted.find("apple").unwrap() < formatted.find("zebra").unwrap(),
+        "{formatted}"
+    );
+}
+
+#[test]
+fn test_class_member_fixture_passes_the_safety_check() {
+    // The snapshot tests stop short of the CLI's semantic safety check, which
+    // is what refuses to write a file whose class fields initialize in a
+    // different order
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("3_3_class_members.ts");
+    std::fs::copy("tests/fixtures/fr3/3_3_class_members.input.ts", &path).unwrap();
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_krokfmt"))
+        .arg(&path)
+        .output()
+        .unwrap();
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{stderr}");
+    assert!(!stderr.contains("semantics"), "{stderr}");
+
+    let formatted = std::fs::read_to_string(&path).unwrap();
+    let position = |text: &str| formatted.find(text).unwrap();
+    // Nothing reads the fields while they're initialized, so they all sort
+    assert!(
+        position("static bar") < position("static zoo"),
+        "{formatted}"
+    );
+    assert!(
+        position("#privateApple = ") < position("#privateZebra = "),
+        "{formatted}"
+    );
+    assert!(
+        position("apple: number") < position("zebra: string"),
+        "{formatted}"
+    );
+}
+
+#[test]
+fn test_changing_a_formatting_flag_formats_cached_files_again() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("config.ts");
+    let source = "const config = { zebra: 1, apple: 2 };\n";
+    std::fs::write(&path, source).unwrap();
+
+    let run = |args: &[&str]| {
+        let output = std::process::Command::new(env!("CARGO_BIN_EXE_krokfmt"))
+            .current_dir(dir.path())
+            .args(args)
+            .arg("config.ts")
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    };
+
+    // Already formatted with imports only, so the file is recorded as is
+    run(&["--cache", "--imports-only"]);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), source);
+
+    // Sorting the object too isn't what the cache saw, so it's formatted again
+    run(&["--cache"]);
+    let formatted = std::fs::read_to_string(&path).unwrap();
+    assert!(
+        formatted.find("apple").unwrap() < formatted.find("zebra").unwrap(),
+        "{formatted}"
+    );
+}
+
+#[test]
+fn test_safe_is_accepted_alongside_no_safety_check() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("config.ts");
+    std::fs::write(&path, "const config = { zebra: 1, apple: 2 };\n").unwrap();
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_krokfmt"))
+        .args(["--no-safety-check", "--safe"])
+        .arg(&path)
+        .output()
+        .unwrap();
+
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{stderr}");
+    let formatted = std::fs::read_to_string(&path).unwrap();
+    assert!(
+        formatted.find("apple").unwrap() < formatted.find("zebra").unwrap(),
+        "{formatted}"
+    );
+}
@@ -1,8 +1,10 @@
 use insta::assert_snapshot;
-use krokfmt::{comment_formatter::CommentFormatter, parser::TypeScriptParser};
+use krokfmt::{
+    comment_formatter::CommentFormatter, parser::TypeScriptParser, transformer::ImportProfile,
+};
 use std::fs;
 
-fn format_code(input: &str) -> String {
+fn format_code(input: &str, import_profile: ImportProfile, sort_exports: bool) -> String {
     let parser = TypeScriptParser::new();
     let source_map = parser.source_map.clone();
     let comments = parser.comments.clone();
@@ -12,7 +14,9 @@ fn format_code(input: &str) -> String {
         "test.ts"
     };
     let module = parser.parse(input, filename).unwrap();
-    let formatter = CommentFormatter::new(source_map, comments);
+    let formatter = CommentFormatter::new(source_map, comments)
+        .with_import_profile(import_profile)
+        .with_sorted_re_exports(sort_exports);
     formatter.format(module, input).unwrap()
 }
 
@@ -21,13 +25,32 @@ fn test_fixture(fixture_path: &str) {
 }
 
 fn test_fixture_with_extension(fixture_path: &str, extension: &str) {
+    test_fixture_with_options(fixture_path, extension, ImportProfile::default());
+}
+
+/// For fixtures written before re-export order was kept by default, which
+/// document the `// krokfmt-sort-exports` behavior.
+fn test_fixture_with_sorted_exports(fixture_path: &str) {
+    test_fixture_with_all_options(fixture_path, "ts", ImportProfile::default(), true);
+}
+
+fn test_fixture_with_options(fixture_path: &str, extension: &str, import_profile: ImportProfile) {
+    test_fixture_with_all_options(fixture_path, extension, import_profile, false);
+}
+
+fn test_fixture_with_all_options(
+    fixture_path: &str,
+    extension: &str,
+    import_profile: ImportProfile,
+    sort_exports: bool,
+) {
     let input_path = format!("tests/fixtures/{fixture_path}.input.{extension}");
     let input = fs::read_to_string(&input_path)
         .unwrap_or_else(|_| panic!("Failed to read fixture: {input_path}"))
         .replace("\r\n", "\n") // Normalize CRLF to LF for consistent testing across platforms
         .replace('\r', "\n"); // Also handle old Mac CR line endings
 
-    let output = format_code(&input);
+    let output = format_code(&input, import_profile, sort_exports);
 
     // Use the fixture path as the snapshot name
     assert_snapshot!(fixture_path, output);
@@ -97,22 +120,31 @@ fn test_fr1_5_group_separation() {
 
 #[test]
 fn test_fr1_6_syntax_preservation() {
-    test_fixture("fr1/1_6_syntax_preservation");
+    test_fixture_with_sorted_exports("fr1/1_6_syntax_preservation");
 }
 
 #[test]
 fn test_fr1_7_re_export_organization() {
-    test_fixture("fr1/1_7_re_export_organization");
+    test_fixture_with_sorted_exports("fr1/1_7_re_export_organization");
 }
 
 #[test]
 fn test_fr1_7_re_export_sorting() {
-    test_fixture("fr1/1_7_re_export_sorting");
+    test_fixture_with_sorted_exports("fr1/1_7_re_export_sorting");
 }
 
 #[test]
 fn test_fr1_7_re_export_with_comments() {
-    test_fixture("fr1/1_7_re_export_with_comments");
+    test_fixture_with_sorted_exports("fr1/1_7_re_export_with_comments");
+}
+
+#[test]
+fn test_fr1_8_simple_import_sort_profile() {
+    test_fixture_with_options(
+        "fr1/1_8_simple_import_sort_profile",
+        "ts",
+        ImportProfile::SimpleImportSort,
+    );
 }
 
 // FR2: Member Visibility Ordering Tests
@@ -178,6 +210,11 @@ fn test_fr2_3_forward_references() {
     test_fixture("fr2/2_3_forward_references");
 }
 
+#[test]
+fn test_fr2_3_module_mutation_barriers() {
+    test_fixture("fr2/2_3_module_mutation_barriers");
+}
+
 #[test]
 fn test_fr2_4_visibility_grouping() {
     test_fixture("fr2/2_4_visibility_grouping");
@@ -200,7 +237,7 @@ fn test_fr2_4_case_insensitive_visibility() {
 
 #[test]
 fn test_fr2_1_export_detection_edge_cases() {
-    test_fixture("fr2/2_1_export_detection_edge_cases");
+    test_fixture_with_sorted_exports("fr2/2_1_export_detection_edge_cases");
 }
 
 #[test]
@@ -285,6 +322,11 @@ fn test_fr3_6_jsx_properties() {
     test_fixture("fr3/3_6_jsx_properties");
 }
 
+#[test]
+fn test_fr3_6_jsx_boolean_props() {
+    test_fixture_with_extension("fr3/3_6_jsx_boolean_props", "tsx");
+}
+
 // FR6: Comment Handling Tests
 
 #[test]
@@ -333,6 +375,27 @@ fn test_fr6_6_complex_comments() {
     test_fixture("fr6/6_6_complex_comments");
 }
 
+#[test]
+fn test_fr6_9_preformatted_comments() {
+    let input = fs::read_to_string("tests/fixtures/fr6/6_9_preformatted_comments.input.ts")
+        .unwrap()
+        .replace("\r\n", "\n");
+    let output = format_code(&input, ImportProfile::default(), false);
+
+    // Diagrams and tables only line up as written, so rather than snapshotting
+    // the whole file, check each block comment survives byte for byte
+    let mut rest = input.as_str();
+    while let Some(start) = rest.find("/*") {
+        let end = start + rest[start..].find("*/").unwrap() + 2;
+        let comment = &rest[start..end];
+        assert!(
+            output.contains(comment),
+            "comment was altered:\n{comment}\n\noutput:\n{output}"
+        );
+        rest = &rest[end..];
+    }
+}
+
 // FR7: Visual Separation Tests
 
 #[test]
@@ -153,8 +153,8 @@ fn build_wasm(sh: &Shell, release: bool, force: bool) -> Result<()> {
         let src_files = [
             "crates/krokfmt-playground/src",
             "crates/krokfmt-playground/Cargo.toml",
-            "crates/krokfmt/src", // Also check krokfmt since playground depends on it
-            "crates/krokfmt/Cargo.toml",
+            "crates/krokfmt-core/src", // Also check krokfmt-core since playground depends on it
+            "crates/krokfmt-core/Cargo.toml",
         ];
 
         let mut needs_rebuild = false;